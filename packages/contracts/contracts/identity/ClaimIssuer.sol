@@ -21,6 +21,9 @@ contract ClaimIssuer is Identity, IClaimIssuer {
     //                           STORAGE
     // =============================================================
 
+    /// @notice Public values layout version ZK claims are decoded with
+    uint32 public constant OUTPUT_VERSION = 4;
+
     /// @notice Mapping of revoked claim signatures
     mapping(bytes => bool) private _revokedClaims;
 
@@ -165,12 +168,31 @@ contract ClaimIssuer is Identity, IClaimIssuer {
 
         // Decode public values to verify they match the claim
         (
+            uint32 version,
             address subject,
             uint256 proofClaimTopic,
             ,  // credentialHash
             ,  // issuedAt
-            uint256 expiresAt
-        ) = abi.decode(publicValues, (address, uint256, bytes32, uint256, uint256));
+            uint256 expiresAt,
+            uint64 chainId,
+            address verifyingContract,
+            uint64 asOf
+        ) = abi.decode(publicValues, (uint32, address, uint256, bytes32, uint256, uint256, uint64, address, uint64));
+
+        // Proofs in another layout can't be read as this one
+        if (version != OUTPUT_VERSION) {
+            return false;
+        }
+
+        // Proofs bound to another chain were meant for another deployment
+        if (chainId != 0 && chainId != block.chainid) {
+            return false;
+        }
+
+        // Proofs bound to another contract were meant for another dapp
+        if (verifyingContract != address(0) && verifyingContract != address(this)) {
+            return false;
+        }
 
         // Verify the proof is for the correct identity and topic
         if (subject != address(_identity) || proofClaimTopic != claimTopic) {
@@ -182,6 +204,11 @@ contract ClaimIssuer is Identity, IClaimIssuer {
             return false;
         }
 
+        // Proofs checked as of a future time could pass age predicates early
+        if (asOf > block.timestamp) {
+            return false;
+        }
+
         // Verify the ZK proof
         try sp1Verifier.verifyProof(zkProgramVKey, publicValues, proofBytes) {
             return true;
@@ -15,6 +15,9 @@ contract SP1CredentialVerifier is Ownable, ReentrancyGuard {
     //                           STORAGE
     // =============================================================
 
+    /// @notice Public values layout version this contract decodes
+    uint32 public constant OUTPUT_VERSION = 4;
+
     /// @notice The SP1 verifier contract
     ISP1Verifier public sp1Verifier;
 
@@ -36,6 +39,12 @@ contract SP1CredentialVerifier is Ownable, ReentrancyGuard {
     /// @notice Mapping of credential hash to verification timestamp
     mapping(bytes32 => uint256) public credentialTimestamps;
 
+    /// @notice Oldest a proof's as-of time may be, in seconds (0 means no limit)
+    uint256 public maxProofAge;
+
+    /// @notice CCIP-Read (EIP-3668) gateway URLs serving proofs by subject
+    string[] public proofGatewayUrls;
+
     // =============================================================
     //                          STRUCTS
     // =============================================================
@@ -70,6 +79,8 @@ contract SP1CredentialVerifier is Ownable, ReentrancyGuard {
     event SP1VerifierUpdated(address indexed oldVerifier, address indexed newVerifier);
     event ProgramVKeyUpdated(bytes32 indexed oldKey, bytes32 indexed newKey);
     event ExpirationTimeUpdated(uint256 oldTime, uint256 newTime);
+    event MaxProofAgeUpdated(uint256 oldAge, uint256 newAge);
+    event ProofGatewayUrlsUpdated(string[] urls);
 
     // =============================================================
     //                           ERRORS
@@ -83,6 +94,18 @@ contract SP1CredentialVerifier is Ownable, ReentrancyGuard {
     error CredentialNotFound();
     error CredentialExpired();
     error InvalidPublicValues();
+    error UnsupportedOutputVersion(uint32 version);
+    error WrongChain(uint64 chainId);
+    error WrongVerifyingContract(address verifyingContract);
+    error ProofFromFuture(uint64 asOf);
+    error ProofTooOld(uint64 asOf);
+    error OffchainLookup(
+        address sender,
+        string[] urls,
+        bytes callData,
+        bytes4 callbackFunction,
+        bytes extraData
+    );
 
     // =============================================================
     //                        CONSTRUCTOR
@@ -145,6 +168,27 @@ contract SP1CredentialVerifier is Ownable, ReentrancyGuard {
         emit ExpirationTimeUpdated(oldTime, _expirationTime);
     }
 
+    /**
+     * @notice Sets how old a proof's as-of time may be
+     * @param _maxProofAge Maximum age in seconds (0 for no limit)
+     */
+    function setMaxProofAge(uint256 _maxProofAge) external onlyOwner {
+        uint256 oldAge = maxProofAge;
+        maxProofAge = _maxProofAge;
+
+        emit MaxProofAgeUpdated(oldAge, _maxProofAge);
+    }
+
+    /**
+     * @notice Sets the CCIP-Read gateways proofs are fetched from
+     * @param urls URL templates with `{sender}` and `{data}` placeholders
+     */
+    function setProofGatewayUrls(string[] calldata urls) external onlyOwner {
+        proofGatewayUrls = urls;
+
+        emit ProofGatewayUrlsUpdated(urls);
+    }
+
     // =============================================================
     //                    VERIFICATION
     // =============================================================
@@ -171,15 +215,27 @@ contract SP1CredentialVerifier is Ownable, ReentrancyGuard {
         }
 
         // Decode public values
-        // Expected format: (address subject, uint256 credentialType, bytes32 credentialHash, uint256 issuedAt, uint256 expiresAt)
+        // Expected format: (uint32 version, address subject, uint256 credentialType, bytes32 credentialHash, uint256 issuedAt, uint256 expiresAt, uint64 chainId, address verifyingContract, uint64 asOf)
         (
+            uint32 version,
             address subject,
             uint256 credentialType,
             bytes32 credHash,
             uint256 issuedAt,
-            uint256 expiresAt
-        ) = abi.decode(publicValues, (address, uint256, bytes32, uint256, uint256));
-
+            uint256 expiresAt,
+            uint64 chainId,
+            address verifyingContract,
+            uint64 asOf
+        ) = abi.decode(publicValues, (uint32, address, uint256, bytes32, uint256, uint256, uint64, address, uint64));
+
+        if (version != OUTPUT_VERSION) revert UnsupportedOutputVersion(version);
+        if (chainId != 0 && chainId != block.chainid) revert WrongChain(chainId);
+        if (verifyingContract != address(0) && verifyingContract != address(this)) {
+            revert WrongVerifyingContract(verifyingContract);
+        }
+        // The circuit checked expiry, predicates and revocation as of asOf
+        if (asOf > block.timestamp) revert ProofFromFuture(asOf);
+        if (maxProofAge != 0 && block.timestamp - asOf > maxProofAge) revert ProofTooOld(asOf);
         if (subject == address(0)) revert InvalidPublicValues();
         if (verifiedCredentials[credHash]) revert CredentialAlreadyVerified();
 
@@ -230,13 +286,89 @@ contract SP1CredentialVerifier is Ownable, ReentrancyGuard {
 
         // Decode and store (same as verifyCredential)
         (
+            uint32 version,
             address subject,
             uint256 credentialType,
             bytes32 credHash,
             uint256 issuedAt,
-            uint256 expiresAt
-        ) = abi.decode(publicValues, (address, uint256, bytes32, uint256, uint256));
+            uint256 expiresAt,
+            uint64 chainId,
+            address verifyingContract,
+            uint64 asOf
+        ) = abi.decode(publicValues, (uint32, address, uint256, bytes32, uint256, uint256, uint64, address, uint64));
+
+        if (version != OUTPUT_VERSION) revert UnsupportedOutputVersion(version);
+        if (chainId != 0 && chainId != block.chainid) revert WrongChain(chainId);
+        if (verifyingContract != address(0) && verifyingContract != address(this)) {
+            revert WrongVerifyingContract(verifyingContract);
+        }
+        // The circuit checked expiry, predicates and revocation as of asOf
+        if (asOf > block.timestamp) revert ProofFromFuture(asOf);
+        if (maxProofAge != 0 && block.timestamp - asOf > maxProofAge) revert ProofTooOld(asOf);
+        if (subject == address(0)) revert InvalidPublicValues();
+        if (verifiedCredentials[credHash]) revert CredentialAlreadyVerified();
+
+        usedProofs[proofHash] = true;
+        verifiedCredentials[credHash] = true;
+        credentialTimestamps[credHash] = block.timestamp;
+        userCredentials[subject].push(credHash);
+
+        credentials[credHash] = VerifiedCredential({
+            credentialHash: credHash,
+            subject: subject,
+            credentialType: credentialType,
+            issuedAt: issuedAt,
+            expiresAt: expiresAt,
+            isValid: true
+        });
+
+        emit CredentialVerified(credHash, subject, credentialType, block.timestamp);
+
+        return credHash;
+    }
+
+    /**
+     * @notice Verifies a credential proof that commits only the keccak256 of its outputs
+     * @dev The proof's public values shrink to 32 bytes; the outputs are re-hashed here
+     * @param outputs The ABI-encoded outputs the proof's digest commits to
+     * @param proofBytes The SP1 proof bytes
+     * @return credentialHash The hash of the verified credential
+     */
+    function verifyCredentialDigest(
+        bytes calldata outputs,
+        bytes calldata proofBytes
+    ) external nonReentrant returns (bytes32 credentialHash) {
+        bytes32 proofHash = keccak256(abi.encodePacked(outputs, proofBytes));
+        if (usedProofs[proofHash]) revert ProofAlreadyUsed();
+
+        bytes memory publicValues = abi.encodePacked(keccak256(outputs));
+        try sp1Verifier.verifyProof(programVKey, publicValues, proofBytes) {
+            // Proof is valid
+        } catch {
+            revert ProofVerificationFailed();
+        }
 
+        // Decode and store (same as verifyCredential)
+        (
+            uint32 version,
+            address subject,
+            uint256 credentialType,
+            bytes32 credHash,
+            uint256 issuedAt,
+            uint256 expiresAt,
+            uint64 chainId,
+            address verifyingContract,
+            uint64 asOf
+        ) = abi.decode(outputs, (uint32, address, uint256, bytes32, uint256, uint256, uint64, address, uint64));
+
+        if (version != OUTPUT_VERSION) revert UnsupportedOutputVersion(version);
+        if (chainId != 0 && chainId != block.chainid) revert WrongChain(chainId);
+        if (verifyingContract != address(0) && verifyingContract != address(this)) {
+            revert WrongVerifyingContract(verifyingContract);
+        }
+        // The circuit checked expiry, predicates and revocation as of asOf
+        if (asOf > block.timestamp) revert ProofFromFuture(asOf);
+        if (maxProofAge != 0 && block.timestamp - asOf > maxProofAge) revert ProofTooOld(asOf);
         if (subject == address(0)) revert InvalidPublicValues();
         if (verifiedCredentials[credHash]) revert CredentialAlreadyVerified();
 
@@ -259,6 +391,42 @@ contract SP1CredentialVerifier is Ownable, ReentrancyGuard {
         return credHash;
     }
 
+    /**
+     * @notice Verifies a subject's credential with a proof fetched off-chain
+     * @dev Always reverts with OffchainLookup (EIP-3668); a CCIP-Read client
+     * fetches the proof from the gateways and calls verifyCredentialCallback
+     * @param subject The subject whose proof the gateway serves
+     */
+    function verifyCredentialOffchain(address subject) external view returns (bytes32) {
+        revert OffchainLookup(
+            address(this),
+            proofGatewayUrls,
+            abi.encodeWithSignature("credentialProof(address)", subject),
+            this.verifyCredentialCallback.selector,
+            abi.encode(subject)
+        );
+    }
+
+    /**
+     * @notice CCIP-Read callback verifying the proof a gateway returned
+     * @param response The gateway response, abi.encode(publicValues, proofBytes)
+     * @param extraData The subject the proof was requested for
+     * @return credentialHash The hash of the verified credential
+     */
+    function verifyCredentialCallback(
+        bytes calldata response,
+        bytes calldata extraData
+    ) external returns (bytes32 credentialHash) {
+        (bytes memory publicValues, bytes memory proofBytes) = abi.decode(response, (bytes, bytes));
+        address subject = abi.decode(extraData, (address));
+
+        // Same checks and storage as a direct submission
+        credentialHash = this.verifyCredential(publicValues, proofBytes);
+
+        // The gateway must not answer with another subject's proof
+        if (credentials[credentialHash].subject != subject) revert InvalidPublicValues();
+    }
+
     /**
      * @notice Revokes a verified credential
      * @param credentialHash The hash of the credential to revoke
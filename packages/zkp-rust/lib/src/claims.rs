@@ -0,0 +1,27 @@
+//! Credential data format v2: typed TLV claims
+//!
+//! Each claim is a `type (BE u16) || length (BE u32) || value` record
+//! behind a version and claim count:
+//!
+//! ```text
+//! version = 2 (BE u32) || claim_count (BE u32) || claim*
+//! ```
+//!
+//! The program parses it and the issuer CLI, samples and browser bindings
+//! encode it with [`encode`].
+
+/// Version tag of the typed TLV format
+pub const FORMAT_V2: u32 = 2;
+
+/// Encodes `(claim type, value)` claims as v2 credential data
+pub fn encode(claims: &[(u16, Vec<u8>)]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&FORMAT_V2.to_be_bytes());
+    data.extend_from_slice(&(claims.len() as u32).to_be_bytes());
+    for (claim_type, value) in claims {
+        data.extend_from_slice(&claim_type.to_be_bytes());
+        data.extend_from_slice(&(value.len() as u32).to_be_bytes());
+        data.extend_from_slice(value);
+    }
+    data
+}
@@ -0,0 +1,133 @@
+//! EIP-712 typed-data digests for wallet-signed credentials
+//!
+//! Issuers signing from MetaMask, Safe or other Ethereum wallets sign
+//! `keccak256(0x1901 || domainSeparator || hashStruct(credential))`. The
+//! circuit rebuilds that digest from the credential fields and the host
+//! tools sign it, both with these functions, so the type strings and
+//! encoding can't drift apart.
+
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::{CredentialInput, Eip712Domain};
+
+/// EIP-712 type string of the signing domain
+pub const DOMAIN_TYPE: &[u8] =
+    b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+
+/// EIP-712 type string of the signed credential struct
+pub const CREDENTIAL_TYPE: &[u8] = b"Credential(address subject,uint32 credentialType,bytes credentialData,uint64 issuedAt,uint64 expiresAt)";
+
+/// Computes keccak256 of `data`
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(data);
+    let mut hash = [0u8; 32];
+    hasher.finalize(&mut hash);
+    hash
+}
+
+/// ABI-encodes an unsigned integer as a 32-byte word
+fn word_u64(value: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// ABI-encodes an address as a 32-byte word
+fn word_address(address: &[u8; 20]) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(address);
+    word
+}
+
+/// Computes the domain separator for `domain`
+pub fn domain_separator(domain: &Eip712Domain) -> [u8; 32] {
+    let mut encoded = Vec::with_capacity(5 * 32);
+    encoded.extend_from_slice(&keccak256(DOMAIN_TYPE));
+    encoded.extend_from_slice(&keccak256(domain.name.as_bytes()));
+    encoded.extend_from_slice(&keccak256(domain.version.as_bytes()));
+    encoded.extend_from_slice(&word_u64(domain.chain_id));
+    encoded.extend_from_slice(&word_address(&domain.verifying_contract));
+    keccak256(&encoded)
+}
+
+/// Computes `hashStruct` of the credential
+pub fn credential_struct_hash(input: &CredentialInput) -> [u8; 32] {
+    let mut encoded = Vec::with_capacity(6 * 32);
+    encoded.extend_from_slice(&keccak256(CREDENTIAL_TYPE));
+    encoded.extend_from_slice(&word_address(&input.subject));
+    encoded.extend_from_slice(&word_u64(input.credential_type as u64));
+    encoded.extend_from_slice(&keccak256(&input.credential_data));
+    encoded.extend_from_slice(&word_u64(input.issued_at));
+    encoded.extend_from_slice(&word_u64(input.expires_at));
+    keccak256(&encoded)
+}
+
+/// Computes the digest signed for a domain separator and struct hash
+pub fn typed_data_digest(domain_separator: &[u8; 32], struct_hash: &[u8; 32]) -> [u8; 32] {
+    let mut encoded = Vec::with_capacity(2 + 2 * 32);
+    encoded.extend_from_slice(&[0x19, 0x01]);
+    encoded.extend_from_slice(domain_separator);
+    encoded.extend_from_slice(struct_hash);
+    keccak256(&encoded)
+}
+
+/// Computes the EIP-191 `personal_sign` digest of `message`
+///
+/// keccak256 over `"\x19Ethereum Signed Message:\n" || len(message) ||
+/// message`, which is how wallets sign anything that isn't typed data.
+pub fn personal_sign_digest(message: &[u8]) -> [u8; 32] {
+    let mut prefixed = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    prefixed.extend_from_slice(message);
+    keccak256(&prefixed)
+}
+
+/// Computes the digest the issuer's wallet signed
+pub fn credential_digest(input: &CredentialInput, domain: &Eip712Domain) -> [u8; 32] {
+    typed_data_digest(&domain_separator(domain), &credential_struct_hash(input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_sol_types::{eip712_domain, sol, SolStruct};
+
+    sol! {
+        struct Credential {
+            address subject;
+            uint32 credentialType;
+            bytes credentialData;
+            uint64 issuedAt;
+            uint64 expiresAt;
+        }
+    }
+
+    #[test]
+    fn test_digest_matches_typed_data() {
+        let input = crate::tests::sample();
+        let domain = Eip712Domain {
+            name: "Credence".into(),
+            version: "1".into(),
+            chain_id: 5003,
+            verifying_contract: [0x44; 20],
+        };
+        let typed = Credential {
+            subject: input.subject.into(),
+            credentialType: input.credential_type,
+            credentialData: input.credential_data.clone().into(),
+            issuedAt: input.issued_at,
+            expiresAt: input.expires_at,
+        };
+        let typed_domain = eip712_domain! {
+            name: "Credence",
+            version: "1",
+            chain_id: 5003,
+            verifying_contract: [0x44; 20].into(),
+        };
+
+        assert_eq!(
+            credential_digest(&input, &domain),
+            typed.eip712_signing_hash(&typed_domain).0
+        );
+    }
+}
@@ -0,0 +1,203 @@
+//! Credential input files for the host-side binaries
+//!
+//! A [`CredentialInput`] can be written as JSON or TOML (picked by the
+//! `.toml` extension) with its fields as serde names them. Byte fields
+//! take `0x`-prefixed hex strings as well as arrays of numbers, so
+//! signatures and keys can be pasted as-is:
+//!
+//! ```toml
+//! subject = "0x1234567890123456789012345678901234567890"
+//! credential_type = 2
+//! credential_data = "0x0000000100000000"
+//! credential_format = "Binary"
+//! signature = "0x..."
+//! ```
+//!
+//! Errors name the offending field, and fields the input doesn't have are
+//! rejected rather than ignored, so a misspelt optional field can't
+//! silently drop a check.
+
+use std::fmt;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::CredentialInput;
+
+/// Syntax of a credential file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    /// JSON, with `null` for absent optional fields
+    Json,
+    /// TOML, with absent optional fields left out
+    Toml,
+}
+
+impl FileFormat {
+    /// TOML for `.toml` files, JSON otherwise
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => FileFormat::Toml,
+            _ => FileFormat::Json,
+        }
+    }
+}
+
+/// Why a credential file couldn't be read, and where
+#[derive(Debug)]
+pub struct FileError {
+    /// Dotted path of the offending field (empty for the whole file)
+    pub field: String,
+    /// What was wrong with it
+    pub message: String,
+}
+
+impl FileError {
+    fn new(field: impl Into<String>, message: impl fmt::Display) -> Self {
+        FileError {
+            field: field.into(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.field.as_str() {
+            "" | "." => write!(f, "{}", self.message),
+            field => write!(f, "{}: {}", field, self.message),
+        }
+    }
+}
+
+impl std::error::Error for FileError {}
+
+/// Reads a credential from a JSON or TOML file
+pub fn load_credential(path: &Path) -> Result<CredentialInput, FileError> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|error| FileError::new("", format!("Can't read {}: {error}", path.display())))?;
+    parse_credential(&text, FileFormat::from_path(path))
+}
+
+/// Parses a credential, with byte fields as hex strings or arrays
+pub fn parse_credential(text: &str, format: FileFormat) -> Result<CredentialInput, FileError> {
+    let mut value = match format {
+        FileFormat::Json => {
+            serde_json::from_str(text).map_err(|error| FileError::new("", error))?
+        }
+        FileFormat::Toml => {
+            let toml: toml::Value =
+                toml::from_str(text).map_err(|error| FileError::new("", error))?;
+            serde_json::to_value(toml).map_err(|error| FileError::new("", error))?
+        }
+    };
+    decode_hex(&mut value, "")?;
+
+    let credential: CredentialInput = serde_path_to_error::deserialize(&value)
+        .map_err(|error| FileError::new(error.path().to_string(), error.inner()))?;
+    let known = serde_json::to_value(&credential).map_err(|error| FileError::new("", error))?;
+    match unknown_field(&value, &known, "") {
+        Some(field) => Err(FileError::new(field, "Unknown field")),
+        None => Ok(credential),
+    }
+}
+
+/// Replaces `0x` hex strings with arrays of their bytes
+fn decode_hex(value: &mut Value, path: &str) -> Result<(), FileError> {
+    match value {
+        Value::String(text) => {
+            if let Some(digits) = text.strip_prefix("0x") {
+                let bytes = hex::decode(digits)
+                    .map_err(|error| FileError::new(path, format!("Invalid hex: {error}")))?;
+                *value = Value::from(bytes);
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                decode_hex(item, &format!("{path}[{index}]"))?;
+            }
+        }
+        Value::Object(fields) => {
+            for (name, field) in fields.iter_mut() {
+                decode_hex(field, &join(path, name))?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// First field of `value` that deserializing didn't keep in `known`
+fn unknown_field(value: &Value, known: &Value, path: &str) -> Option<String> {
+    match (value, known) {
+        (Value::Object(fields), Value::Object(known)) => {
+            fields
+                .iter()
+                .find_map(|(name, field)| match known.get(name) {
+                    Some(known) => unknown_field(field, known, &join(path, name)),
+                    None => Some(join(path, name)),
+                })
+        }
+        (Value::Array(items), Value::Array(known)) => {
+            items
+                .iter()
+                .zip(known)
+                .enumerate()
+                .find_map(|(index, (item, known))| {
+                    unknown_field(item, known, &format!("{path}[{index}]"))
+                })
+        }
+        _ => None,
+    }
+}
+
+fn join(path: &str, name: &str) -> String {
+    match path {
+        "" => name.to_string(),
+        _ => format!("{path}.{name}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML: &str = r#"
+        subject = "0x1234567890123456789012345678901234567890"
+        credential_type = 2
+        credential_data = "0x0000000200000000"
+        credential_format = "Binary"
+        signature = "0xabcd"
+        issuer_pubkey = [2, 3]
+        signature_scheme = "Secp256k1"
+        hash_algorithm = "Sha256"
+        issuer_cert_chain = []
+        claim_proofs = []
+        sparse_claim_proofs = []
+        verifier_nonce = "0x0000000000000000000000000000000000000000000000000000000000000000"
+        predicates = []
+        revealed_claims = []
+        timestamp_granularity = "Exact"
+        chain_id = 11155111
+        issued_at = 1700000000
+        expires_at = 0
+        current_time = 1700000100
+    "#;
+
+    #[test]
+    fn test_parses_hex_fields() {
+        let credential = parse_credential(TOML, FileFormat::Toml).unwrap();
+        assert_eq!(credential.subject[..2], [0x12, 0x34]);
+        assert_eq!(credential.signature, vec![0xab, 0xcd]);
+        assert_eq!(credential.issuer_pubkey, vec![2, 3]);
+        assert_eq!(credential.chain_id, Some(11_155_111));
+
+        let short = TOML.replace("0x1234567890", "0x");
+        let error = parse_credential(&short, FileFormat::Toml).unwrap_err();
+        assert_eq!(error.field, "subject");
+
+        let misspelt = TOML.replace("chain_id", "chainid");
+        let error = parse_credential(&misspelt, FileFormat::Toml).unwrap_err();
+        assert_eq!(error.to_string(), "chainid: Unknown field");
+    }
+}
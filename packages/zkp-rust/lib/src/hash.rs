@@ -0,0 +1,120 @@
+//! Hash suites of the credential hash and signing digests
+//!
+//! The program binds credentials with these functions and the host tools
+//! sign and hash with them too, so the suites can't drift apart between
+//! the circuit, the CLIs and the browser bindings.
+
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+use light_poseidon::{Poseidon, PoseidonHasher};
+use sha2::{Digest, Sha256};
+use tiny_keccak::{Hasher, Keccak, Sha3};
+
+use crate::HashAlgorithm;
+
+/// Hashes the concatenation of `parts` with the selected algorithm
+pub fn hash_parts(algorithm: HashAlgorithm, parts: &[&[u8]]) -> [u8; 32] {
+    match algorithm {
+        HashAlgorithm::Sha256 => {
+            let mut hasher = Sha256::new();
+            for part in parts {
+                hasher.update(part);
+            }
+            hasher.finalize().into()
+        }
+        HashAlgorithm::Keccak256 => {
+            let mut hasher = Keccak::v256();
+            for part in parts {
+                hasher.update(part);
+            }
+            let mut hash = [0u8; 32];
+            hasher.finalize(&mut hash);
+            hash
+        }
+        HashAlgorithm::Sha3_256 => {
+            let mut hasher = Sha3::v256();
+            for part in parts {
+                hasher.update(part);
+            }
+            let mut hash = [0u8; 32];
+            hasher.finalize(&mut hash);
+            hash
+        }
+        HashAlgorithm::Blake3 => {
+            let mut hasher = blake3::Hasher::new();
+            for part in parts {
+                hasher.update(part);
+            }
+            hasher.finalize().into()
+        }
+        HashAlgorithm::Poseidon => {
+            let elements: Vec<Fr> = parts.iter().map(|part| poseidon_bytes(part)).collect();
+            let mut hasher =
+                Poseidon::<Fr>::new_circom(elements.len()).expect("Unsupported Poseidon arity");
+            let hash = hasher.hash(&elements).expect("Poseidon hash failed");
+            field_to_bytes(hash)
+        }
+    }
+}
+
+/// Computes the credential hash
+///
+/// The preimage is `subject || credential_type (BE) || credential_data ||
+/// issuer_pubkey`, i.e. `abi.encodePacked(address, uint32, bytes, bytes)`,
+/// so a contract can recompute the keccak256 variant directly. With
+/// Poseidon each of the four parts is absorbed as its own field element.
+pub fn credential_hash(
+    algorithm: HashAlgorithm,
+    subject: &[u8; 20],
+    credential_type: u32,
+    credential_data: &[u8],
+    issuer_pubkey: &[u8],
+) -> [u8; 32] {
+    hash_parts(
+        algorithm,
+        &[
+            subject,
+            &credential_type.to_be_bytes(),
+            credential_data,
+            issuer_pubkey,
+        ],
+    )
+}
+
+/// Digests a message for ECDSA signing under the selected suite
+///
+/// Poseidon outputs a field element meant for other circuits rather than a
+/// signing digest, so Poseidon suites sign a SHA-256 digest instead. The
+/// canonical message names the suite, so such a signature can't be
+/// presented under the SHA-256 suite or the other way round.
+pub fn message_digest(algorithm: HashAlgorithm, message: &[u8]) -> [u8; 32] {
+    match algorithm {
+        HashAlgorithm::Poseidon => hash_parts(HashAlgorithm::Sha256, &[message]),
+        _ => hash_parts(algorithm, &[message]),
+    }
+}
+
+/// Absorbs arbitrary bytes into a single BN254 field element
+///
+/// The bytes are split into 31-byte big-endian chunks (always below the
+/// field modulus) and chained as `acc = Poseidon(acc, chunk)` starting from
+/// zero, then the byte length is absorbed last so inputs differing only in
+/// trailing zeros don't collide. Circom's `Poseidon(2)` reproduces this.
+fn poseidon_bytes(data: &[u8]) -> Fr {
+    let mut hasher = Poseidon::<Fr>::new_circom(2).expect("Poseidon(2) parameters");
+    let mut acc = Fr::from(0u64);
+    for chunk in data.chunks(31) {
+        let element = Fr::from_be_bytes_mod_order(chunk);
+        acc = hasher.hash(&[acc, element]).expect("Poseidon hash failed");
+    }
+    hasher
+        .hash(&[acc, Fr::from(data.len() as u64)])
+        .expect("Poseidon hash failed")
+}
+
+/// Serializes a field element as 32 big-endian bytes
+fn field_to_bytes(element: Fr) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&element.into_bigint().to_bytes_be());
+    bytes
+}
@@ -0,0 +1,1362 @@
+//! Types shared by the credential verifier program and its host tooling
+//!
+//! The prover's stdin carries a `ProgramInput` encoded with [`encode`] and
+//! the program decodes it with [`decode`]. Both sides use these
+//! definitions and borsh's fixed layout (little-endian integers, `u32`
+//! length prefixes, one-byte enum tags in declaration order), so the host
+//! encoding and in-circuit decoding can't drift apart.
+//!
+//! Enum variants and struct fields may therefore only be reordered
+//! together with the program.
+//!
+//! The program's public values go the other way, to contracts, and are
+//! ABI-encoded with the [`CredentialOutput`], [`BatchOutputs`] and
+//! [`PolicyOutputs`] layouts so Solidity can `abi.decode` them, or replaced
+//! by their keccak256 for `ProgramInput::Digest`. Solana and Move programs
+//! get them as [`NativeOutputs`] instead, borsh- or BCS-encoded. Each
+//! layout starts with [`OUTPUT_VERSION`], so they can change without
+//! existing verifiers misreading new values.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+pub mod claims;
+pub mod eip712;
+#[cfg(feature = "files")]
+pub mod file;
+pub mod hash;
+pub mod message;
+
+/// High bit marking a `credential_type` as a bitmask of base types
+///
+/// A plain credential type names one attestation (1 = KYC, 2 = Accredited,
+/// ...). With this flag set the low bits instead carry a set of them, bit
+/// `t - 1` standing for type `t`, so a single credential can attest e.g.
+/// KYC and AML together (`CREDENTIAL_TYPE_BITMASK | 0b10001`).
+pub const CREDENTIAL_TYPE_BITMASK: u32 = 1 << 31;
+
+/// Names of the well-known credential types, as the host tooling accepts
+/// them and as they are exported to Solidity
+pub const CREDENTIAL_TYPES: &[(&str, u32)] = &[
+    ("kyc", 1),
+    ("accredited", 2),
+    ("qualified", 3),
+    ("institutional", 4),
+    ("aml", 5),
+];
+
+/// Prefix of the type name a JWT, SD-JWT, mDL or linked-data credential
+/// declares its `credential_type` with, followed by the well-known name of
+/// the type or else its decimal value (`urn:credence:type:kyc`,
+/// `urn:credence:type:2147483665`)
+pub const CREDENTIAL_TYPE_URN: &str = "urn:credence:type:";
+
+/// Type name a credential in a signed JSON or CBOR format declares for
+/// `credential_type`
+pub fn credential_type_name(credential_type: u32) -> String {
+    let known = CREDENTIAL_TYPES
+        .iter()
+        .find(|(_, known)| *known == credential_type);
+    match known {
+        Some((name, _)) => format!("{CREDENTIAL_TYPE_URN}{name}"),
+        None => format!("{CREDENTIAL_TYPE_URN}{credential_type}"),
+    }
+}
+
+/// Whether a signed type name declares exactly `credential_type`
+///
+/// Formats whose issuers sign their own payload rather than the canonical
+/// message bind the committed type this way, so a prover can't commit a
+/// genuine credential under another type.
+pub fn declares_credential_type(name: &str, credential_type: u32) -> bool {
+    name == credential_type_name(credential_type)
+}
+
+/// Expands a credential type into the base types it attests
+///
+/// A plain type expands to itself; a bitmask with no bits set expands to
+/// nothing.
+pub fn base_credential_types(credential_type: u32) -> Vec<u32> {
+    if credential_type & CREDENTIAL_TYPE_BITMASK == 0 {
+        return vec![credential_type];
+    }
+    (0..31)
+        .filter(|bit| credential_type & (1 << bit) != 0)
+        .map(|bit| bit + 1)
+        .collect()
+}
+
+/// Signature scheme used by the issuer
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum SignatureScheme {
+    /// ECDSA over secp256k1 (Ethereum-native issuers)
+    Secp256k1,
+    /// ECDSA over NIST P-256 / secp256r1 (government eID, mDL, passports)
+    Secp256r1,
+    /// BLS over BLS12-381 (min-pk: 48-byte G1 pubkey, 96-byte G2 signature)
+    Bls12381,
+    /// RSA-2048 PKCS#1 v1.5 with SHA-256 (pubkey is the 256-byte modulus, e = 65537)
+    Rsa2048Pkcs1v15,
+    /// RSA-2048 PSS with SHA-256 and a 32-byte salt (pubkey as above)
+    Rsa2048Pss,
+    /// secp256k1 wallet signature (65-byte `r || s || v`) over an EIP-712
+    /// digest; the pubkey may be SEC1-encoded or a 20-byte address
+    Eip712,
+    /// BBS+ over BLS12-381 (96-byte G2 pubkey, 112-byte signature) for
+    /// multi-message selective-disclosure credentials
+    BbsPlus,
+    /// HMAC-SHA256 tag (32 bytes) from a symmetric issuer key; the pubkey
+    /// field holds the key commitment and the key itself is `hmac_key`
+    HmacSha256,
+    /// RSA-2048 blind signature (RFC 9474 randomized PSS, SHA-256); the
+    /// signature is the 32-byte message randomizer followed by the
+    /// unblinded signature, so the issuer never saw the credential
+    Rsa2048Blind,
+    /// AnonCreds CL signature; the pubkey is the credential definition's
+    /// primary key JSON and the holder's link secret is `link_secret`
+    AnonCredsCl,
+}
+
+/// Encoding of `credential_data`
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum CredentialFormat {
+    /// Versioned binary claims, signed via the canonical signing message
+    Binary,
+    /// Compact ES256K JWS carrying its own signature, subject and timestamps
+    Jwt,
+    /// W3C Verifiable Credential JSON document carrying its own subject
+    /// and validity period, signed via the canonical signing message
+    W3cVc,
+    /// SD-JWT (ES256K issuer JWT plus disclosures); only the names of the
+    /// disclosed claims are made public
+    SdJwt,
+    /// ISO 18013-5 mDL `IssuerSigned` CBOR with an ES256 COSE_Sign1; only
+    /// the identifiers of the presented elements are made public
+    Mdl,
+    /// Hyperledger AnonCreds credential JSON with a CL signature over its
+    /// attributes
+    AnonCreds,
+    /// W3C credential with an `ecdsa-rdfc-2019` linked-data proof: JSON
+    /// holding the host-expanded document and proof configuration as
+    /// N-Quads, which the circuit canonicalizes with URDNA2015
+    LdProof,
+}
+
+/// Signed message vector of a BBS+ credential
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct BbsInput {
+    /// All signed messages; the first must be the canonical signing message
+    pub messages: Vec<Vec<u8>>,
+    /// Indices of the messages revealed in the public output (ascending)
+    pub disclosed: Vec<u32>,
+}
+
+/// Co-signature set for threshold m-of-n issuance
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct IssuerQuorum {
+    /// Minimum number of valid signatures (m)
+    pub threshold: u32,
+    /// Declared signer keys (n), all using the credential's scheme
+    pub pubkeys: Vec<Vec<u8>>,
+    /// Signatures, ordered by strictly increasing key index
+    pub signatures: Vec<QuorumSignature>,
+}
+
+impl IssuerQuorum {
+    /// Whether the declared keys are n distinct keys
+    ///
+    /// Keys must share one encoding length and differ byte for byte, so a
+    /// key can't be listed twice, even once compressed and once not, to
+    /// count its signature twice towards the threshold.
+    pub fn has_distinct_keys(&self) -> bool {
+        let Some(first) = self.pubkeys.first() else {
+            return true;
+        };
+        let mut keys: Vec<&[u8]> = self.pubkeys.iter().map(Vec::as_slice).collect();
+        keys.sort_unstable();
+        keys.windows(2).all(|pair| pair[0] != pair[1])
+            && self.pubkeys.iter().all(|key| key.len() == first.len())
+    }
+}
+
+/// A single co-signature in an `IssuerQuorum`
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct QuorumSignature {
+    /// Index into `IssuerQuorum::pubkeys`
+    pub key_index: u32,
+    /// Signature by that key over the credential message
+    pub signature: Vec<u8>,
+}
+
+/// Hash suite for the committed credential hash and ECDSA message digests
+///
+/// The discriminant is committed as `hash_suite`, so variants must only
+/// ever be appended.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum HashAlgorithm {
+    /// SHA-256 (default)
+    Sha256,
+    /// keccak256, matching `keccak256(abi.encodePacked(...))` in Solidity
+    Keccak256,
+    /// Circom-compatible Poseidon over BN254, for consumption by other
+    /// ZK circuits (the hash is a field element in big-endian bytes)
+    Poseidon,
+    /// SHA3-256 (FIPS 202)
+    Sha3_256,
+    /// BLAKE3 with a 32-byte output
+    Blake3,
+}
+
+/// Granularity the committed issuance and expiry times are rounded down to
+///
+/// Exact times can fingerprint a credential on-chain; expiry is still
+/// checked exactly in the circuit. The discriminant is committed as
+/// `timestamp_granularity`, so variants must only ever be appended.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum TimestampGranularity {
+    /// Exact seconds (default)
+    Exact,
+    /// Whole UTC days
+    Day,
+    /// Whole weeks counted from the Unix epoch
+    Week,
+    /// Whole expiry epochs of the program's fixed length (30 days)
+    Epoch,
+}
+
+/// Revocation registry a credential was checked against
+///
+/// The discriminant is committed as `revocation_method`, so variants must
+/// only ever be appended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RevocationMethod {
+    /// Not checked
+    None,
+    /// Merkle non-inclusion in a sorted revocation list
+    MerkleList,
+    /// Unset bit in a W3C StatusList2021 credential
+    StatusList2021,
+    /// Non-membership in an RSA accumulator
+    Accumulator,
+}
+
+impl RevocationMethod {
+    /// Returns the method with the committed discriminant `value`
+    pub fn from_u8(value: u8) -> Option<Self> {
+        [
+            Self::None,
+            Self::MerkleList,
+            Self::StatusList2021,
+            Self::Accumulator,
+        ]
+        .into_iter()
+        .find(|method| *method as u8 == value)
+    }
+}
+
+/// Status category a credential was checked for
+///
+/// Revocation is permanent while a suspension can be lifted. The
+/// discriminant is committed as `status_purpose`, so variants must only
+/// ever be appended.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+pub enum StatusPurpose {
+    /// Not checked; never valid for a status list entry
+    None,
+    /// Checked against a revocation registry
+    Revocation,
+    /// Checked against a StatusList2021 suspension list
+    Suspension,
+}
+
+impl StatusPurpose {
+    /// Returns the purpose with the committed discriminant `value`
+    pub fn from_u8(value: u8) -> Option<Self> {
+        [Self::None, Self::Revocation, Self::Suspension]
+            .into_iter()
+            .find(|purpose| *purpose as u8 == value)
+    }
+}
+
+/// EIP-712 signing domain used by wallet-signed credentials
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Eip712Domain {
+    /// Domain name (e.g., "Credence")
+    pub name: String,
+    /// Domain version
+    pub version: String,
+    /// Chain the issuer signed for
+    pub chain_id: u64,
+    /// Contract the signature is scoped to
+    pub verifying_contract: [u8; 20],
+}
+
+/// Authorization of a delegate issuer by the previous key in a chain
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Delegation {
+    /// Key being authorized, in the credential's signature scheme
+    pub delegate_pubkey: Vec<u8>,
+    /// Credential types the delegate may issue (and delegate further)
+    pub credential_types: Vec<u32>,
+    /// How many further delegations the delegate may grant (0 = issue only)
+    pub max_depth: u32,
+    /// Expiration timestamp of the authorization (0 for no expiration)
+    pub expires_at: u64,
+    /// Signature by the delegating key over the delegation message
+    pub signature: Vec<u8>,
+}
+
+/// Chain of delegations from a root issuer to the credential's issuer
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct DelegationChain {
+    /// Key of the root issuer that granted the first delegation
+    pub root_pubkey: Vec<u8>,
+    /// Delegations, root first; the last delegate is `issuer_pubkey`
+    pub links: Vec<Delegation>,
+}
+
+/// An issuer key listed in a key registry
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct RegistryKey {
+    /// Key in the credential's signature scheme
+    pub pubkey: Vec<u8>,
+    /// When the key was retired (0 while in use); credentials it signed
+    /// are rejected from then on
+    pub retired_at: u64,
+}
+
+/// An issuer's keys, signed by its long-term root key so signing keys can
+/// rotate without invalidating outstanding credentials
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct KeyRegistry {
+    /// Long-term root key of the issuer
+    pub root_pubkey: Vec<u8>,
+    /// Registry version, increased on every change
+    pub version: u32,
+    /// Current and past signing keys
+    pub keys: Vec<RegistryKey>,
+    /// Signature by the root key over the registry message
+    pub signature: Vec<u8>,
+}
+
+/// Value type of a claim schema field
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+pub enum FieldType {
+    /// UTF-8 text; `min`/`max` bound the length in bytes
+    Utf8,
+    /// Big-endian unsigned integer of 1 to 8 bytes; `min`/`max` bound the
+    /// value
+    Uint,
+    /// Opaque bytes; `min`/`max` bound the length
+    Bytes,
+}
+
+/// A field of a claim schema
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct SchemaField {
+    /// Human-readable field name (e.g., "kycLevel")
+    pub name: String,
+    /// Registered v2 claim type carrying the field
+    pub claim_type: u16,
+    /// How the claim value is interpreted
+    pub field_type: FieldType,
+    /// Whether the claim must be present
+    pub required: bool,
+    /// Inclusive lower bound (see `FieldType`)
+    pub min: u64,
+    /// Inclusive upper bound (see `FieldType`)
+    pub max: u64,
+}
+
+/// Issuer-registered structure of v2 credential data
+///
+/// The circuit commits the hash of the schema's borsh encoding, so a
+/// registry only needs to map that hash to the issuer and the schema.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct ClaimSchema {
+    /// Schema name (e.g., "credence.kyc")
+    pub name: String,
+    /// Schema version, bumped on any change to `fields`
+    pub version: u32,
+    /// Declared fields; claims of any other type are rejected
+    pub fields: Vec<SchemaField>,
+}
+
+impl ClaimSchema {
+    /// Canonical encoding the schema hash is computed over
+    pub fn to_bytes(&self) -> Vec<u8> {
+        borsh::to_vec(self).expect("Vec writes are infallible")
+    }
+}
+
+/// Inclusion proof of one claim under a v4 credential's Merkle root
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct ClaimProof {
+    /// Registered claim type, as in v2 data
+    pub claim_type: u16,
+    /// Claim value
+    pub value: Vec<u8>,
+    /// Leaf position; bit i selects whether `siblings[i]` is on the left
+    pub index: u32,
+    /// Sibling hashes from the leaf up to the root
+    pub siblings: Vec<[u8; 32]>,
+}
+
+/// Inclusion or absence proof of one key in a v5 credential's sparse
+/// Merkle tree
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct SparseClaimProof {
+    /// Claim key, as in v3 data
+    pub key: String,
+    /// Tagged v3 value (`tag || value`), or `None` to prove the key absent
+    pub value: Option<Vec<u8>>,
+    /// Bit i (LSB first) set if the sibling at height i is non-empty
+    pub occupied: [u8; 32],
+    /// Non-empty sibling hashes from the leaf up to the root
+    pub siblings: Vec<[u8; 32]>,
+}
+
+/// Path placing the issuer key in a trusted-issuer Merkle tree
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct IssuerMembership {
+    /// Leaf position; bit i selects whether `siblings[i]` is on the left
+    pub index: u32,
+    /// Sibling hashes from the leaf up to the root
+    pub siblings: Vec<[u8; 32]>,
+}
+
+/// Neighbouring entries of a sorted sanctions list that the subject falls
+/// strictly between
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct SanctionsExclusion {
+    /// Snapshot of the list the proof was built against
+    pub list_version: u32,
+    /// Leaf position of `lower`; `upper` is the next leaf
+    pub index: u32,
+    /// Greatest listed address below the subject
+    pub lower: [u8; 20],
+    /// Sibling hashes from `lower` up to the root
+    pub lower_siblings: Vec<[u8; 32]>,
+    /// Least listed address above the subject
+    pub upper: [u8; 20],
+    /// Sibling hashes from `upper` up to the root
+    pub upper_siblings: Vec<[u8; 32]>,
+}
+
+/// Neighbouring entries of an issuer's sorted revocation list that the
+/// credential hash falls strictly between
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct RevocationExclusion {
+    /// Snapshot of the list the proof was built against
+    pub list_version: u32,
+    /// Leaf position of `lower`; `upper` is the next leaf
+    pub index: u32,
+    /// Greatest revoked credential hash below the credential's
+    pub lower: [u8; 32],
+    /// Sibling hashes from `lower` up to the root
+    pub lower_siblings: Vec<[u8; 32]>,
+    /// Least revoked credential hash above the credential's
+    pub upper: [u8; 32],
+    /// Sibling hashes from `upper` up to the root
+    pub upper_siblings: Vec<[u8; 32]>,
+}
+
+/// Non-membership witness for an issuer's RSA revocation accumulator
+///
+/// Integers are big-endian.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct AccumulatorWitness {
+    /// Snapshot of the accumulator the witness was computed against
+    pub version: u32,
+    /// RSA modulus `N` of the accumulator
+    pub modulus: Vec<u8>,
+    /// Accumulator value over the revoked credentials' primes
+    pub accumulator: Vec<u8>,
+    /// Witness coefficient `a`, less than the credential's prime
+    pub a: Vec<u8>,
+    /// Witness element `d`
+    pub d: Vec<u8>,
+}
+
+/// Issuer-signed publication time of the revocation registry root, with
+/// the oldest the verifier accepts
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct RevocationFreshness {
+    /// When the issuer published the registry root
+    pub published_at: u64,
+    /// Issuer's signature over the freshness message
+    pub signature: Vec<u8>,
+    /// Oldest the root may be at the committed as-of time, in seconds,
+    /// chosen by the verifier
+    pub max_age: u64,
+}
+
+/// A credential's entry in an issuer-signed W3C StatusList2021 credential
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct StatusListEntry {
+    /// `statusListCredential`: URL of the status list credential
+    pub list_url: String,
+    /// `statusListIndex`: position of the credential's bit in the list
+    pub index: u64,
+    /// `statusPurpose` of the entry and its list
+    pub purpose: StatusPurpose,
+    /// Snapshot of the list the proof was built against
+    pub list_version: u32,
+    /// The status list credential's JSON document, whose `id` is
+    /// `list_url`
+    pub list_credential: Vec<u8>,
+    /// Issuer's signature over the status list credential
+    pub list_signature: Vec<u8>,
+}
+
+/// Key and path placing the subject in a ring of eligible holders
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct RingMembership {
+    /// Secp256k1 secret key controlling the subject address
+    pub subject_key: [u8; 32],
+    /// Vote or other event the linkability tag is scoped to
+    pub event: [u8; 32],
+    /// Leaf position; bit i selects whether `siblings[i]` is on the left
+    pub index: u32,
+    /// Sibling hashes from the leaf up to the root
+    pub siblings: Vec<[u8; 32]>,
+}
+
+/// WebAuthn assertion from the passkey a credential is bound to
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct DeviceAssertion {
+    /// Authenticator data the passkey signed
+    pub authenticator_data: Vec<u8>,
+    /// Client data JSON whose SHA-256 the passkey signed
+    pub client_data_json: Vec<u8>,
+    /// ES256 signature, DER or 64-byte `r || s`
+    pub signature: Vec<u8>,
+}
+
+/// Verifier-supplied condition on a private claim, of which only the
+/// result is made public
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum Predicate {
+    /// The subject's date of birth is at least this many years before
+    /// `current_time`
+    AgeAtLeast(u32),
+    /// A numeric claim, named as in key-value credentials (e.g.,
+    /// `netWorth`), lies within `[min, max]`
+    ///
+    /// For amount claims `unit` is the currency and the bounds are in units
+    /// of `10^-precision`; integer claims take no unit and precision 0.
+    InRange {
+        claim: String,
+        unit: Option<[u8; 3]>,
+        precision: u8,
+        min: u64,
+        max: u64,
+    },
+    /// A string claim (e.g., `residenceCountry`) is on an allow list,
+    /// given as the SHA-256 hashes of its entries in ascending order
+    InSet {
+        claim: String,
+        members: Vec<[u8; 32]>,
+    },
+    /// The credential was issued at least this many days before the
+    /// committed as-of time (commit coarse timestamps to hide the exact date)
+    IssuedAtLeastDaysAgo(u32),
+}
+
+impl Predicate {
+    /// Canonical encoding the predicates hash is computed over
+    pub fn to_bytes(&self) -> Vec<u8> {
+        borsh::to_vec(self).expect("Vec writes are infallible")
+    }
+}
+
+/// Holder secret and verifier scope for a scoped nullifier
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct NullifierInput {
+    /// Verifier-chosen context (e.g., a hash of contract and action)
+    pub scope: [u8; 32],
+    /// Holder secret whose commitment the issuer signed
+    pub secret: [u8; 32],
+}
+
+/// Credential input data (private to the prover)
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct CredentialInput {
+    /// The subject's Ethereum address (20 bytes as hex string)
+    pub subject: [u8; 20],
+    /// The credential type (e.g., 1=KYC, 2=Accredited, etc.), or a
+    /// bitmask of them (see `CREDENTIAL_TYPE_BITMASK`)
+    pub credential_type: u32,
+    /// Raw credential data (contains claims and metadata)
+    pub credential_data: Vec<u8>,
+    /// How `credential_data` is encoded
+    pub credential_format: CredentialFormat,
+    /// Issuer's signature over the canonical signing message
+    pub signature: Vec<u8>,
+    /// Issuer's public key
+    pub issuer_pubkey: Vec<u8>,
+    /// Scheme the issuer signed with
+    pub signature_scheme: SignatureScheme,
+    /// Signing domain, required when `signature_scheme` is `Eip712`
+    pub eip712_domain: Option<Eip712Domain>,
+    /// Hash suite for the credential hash and ECDSA message digest
+    pub hash_algorithm: HashAlgorithm,
+    /// Message vector, required when `signature_scheme` is `BbsPlus`
+    pub bbs: Option<BbsInput>,
+    /// Threshold co-signatures; when set they replace `signature` and
+    /// `issuer_pubkey` for verification
+    pub quorum: Option<IssuerQuorum>,
+    /// Private issuer key, required when `signature_scheme` is `HmacSha256`
+    pub hmac_key: Option<Vec<u8>>,
+    /// Holder link secret (big-endian), required for AnonCreds credentials
+    pub link_secret: Option<Vec<u8>>,
+    /// DER certificate chain for the issuer key, leaf first and root last
+    /// (empty when the issuer key is trusted directly)
+    pub issuer_cert_chain: Vec<Vec<u8>>,
+    /// Issuer ECVRF proof over the signing message (P-256 issuers only)
+    pub vrf_proof: Option<Vec<u8>>,
+    /// Schema the v2 claims must conform to (binary credentials only)
+    pub claim_schema: Option<ClaimSchema>,
+    /// Delegations authorizing `issuer_pubkey` on behalf of a root issuer
+    pub delegation: Option<DelegationChain>,
+    /// Claims checked against a v4 credential's signed Merkle root; the
+    /// other claims stay private
+    pub claim_proofs: Vec<ClaimProof>,
+    /// Keys proven present or absent in a v5 credential's sparse Merkle
+    /// tree
+    pub sparse_claim_proofs: Vec<SparseClaimProof>,
+    /// Holder secret and scope to derive a nullifier from; the issuer's
+    /// signing message then covers the secret's commitment
+    pub nullifier: Option<NullifierInput>,
+    /// Verifier-supplied challenge committed in the output, so the proof
+    /// answers one verification request (zero if none)
+    pub verifier_nonce: [u8; 32],
+    /// When set, the output commits `H(subject || salt)` in place of the
+    /// subject address, which the subject later opens to the contract
+    pub subject_salt: Option<[u8; 32]>,
+    /// When set, the output commits the root of the trusted-issuer tree
+    /// this places the issuer in, instead of identifying the issuer
+    pub issuer_membership: Option<IssuerMembership>,
+    /// Conditions on the claims to evaluate; the output commits their hash
+    /// and results but not the claims (at most 32)
+    pub predicates: Vec<Predicate>,
+    /// Positions of the v2 or v3 claims to reveal; the output commits their
+    /// salted hashes and only a root over the rest
+    pub revealed_claims: Vec<u32>,
+    /// Issuer-chosen seed of the per-claim salts, required to reveal
+    /// claims; the issuer's signing message covers a commitment to it
+    pub disclosure_seed: Option<[u8; 32]>,
+    /// When set, the output commits the root and version of a sanctions
+    /// list shown not to contain the subject
+    pub sanctions_exclusion: Option<SanctionsExclusion>,
+    /// When set, the output replaces the subject with the pseudonym
+    /// `H(holder_secret || verifier_id)`, using the nullifier's secret
+    pub verifier_id: Option<[u8; 32]>,
+    /// Granularity of the committed issuance and expiry times
+    pub timestamp_granularity: TimestampGranularity,
+    /// Verifier-supplied blind; when set, the output commits
+    /// `H(credential_hash || blind)` in place of the credential hash
+    pub credential_hash_blind: Option<[u8; 32]>,
+    /// When set, the output replaces the subject with the root of a ring
+    /// of eligible holders and a per-event linkability tag
+    pub ring: Option<RingMembership>,
+    /// Subject's EIP-191 signature over the verifier nonce, proving the
+    /// prover controls the subject address (65-byte `r || s || v`)
+    pub holder_signature: Option<Vec<u8>>,
+    /// SEC1 P-256 key of the passkey the credential is bound to; the
+    /// issuer's signing message covers a commitment to it
+    pub device_pubkey: Option<Vec<u8>>,
+    /// Passkey assertion over the verifier nonce, required for
+    /// device-bound credentials
+    pub device_assertion: Option<DeviceAssertion>,
+    /// Hash of a fresh biometric capture; the issuer's signing message
+    /// covers a commitment to the enrolled template's hash, so the two
+    /// must match
+    pub biometric_template_hash: Option<[u8; 32]>,
+    /// Serial number of a single-use credential (a voucher or ticket); the
+    /// signing message covers it and only its hash is committed
+    pub serial_number: Option<[u8; 32]>,
+    /// When set, the output commits the root of the issuer's revocation
+    /// list, shown not to contain the credential hash
+    pub revocation_exclusion: Option<RevocationExclusion>,
+    /// When set, the output commits the hash of the status list showing
+    /// the credential unrevoked; the signing message covers the entry
+    pub status_list: Option<StatusListEntry>,
+    /// When set, the output commits the hash of an issuer's revocation
+    /// accumulator, shown not to contain the credential
+    pub accumulator_witness: Option<AccumulatorWitness>,
+    /// Root-signed registry listing `issuer_pubkey` as a non-retired key;
+    /// the output then commits the root key like a delegation root
+    pub key_registry: Option<KeyRegistry>,
+    /// When set, the revocation registry root must have been published
+    /// within the verifier's max age, which the output commits
+    pub revocation_freshness: Option<RevocationFreshness>,
+    /// Path placing the accountable issuer key (the root key of a
+    /// delegation chain or key registry, else `issuer_pubkey`) in an
+    /// on-chain issuer registry; the output commits the registry's root
+    pub issuer_registry: Option<IssuerMembership>,
+    /// Chain ID of the verifier the proof is for, committed so it can't be
+    /// replayed on another chain (unbound if None)
+    pub chain_id: Option<u64>,
+    /// Verifier contract the proof is for, committed so another dapp's
+    /// contract can't accept it (unbound if None)
+    pub verifying_contract: Option<[u8; 20]>,
+    /// Issuance timestamp
+    pub issued_at: u64,
+    /// Expiration timestamp (0 for no expiration)
+    pub expires_at: u64,
+    /// Current timestamp for verification
+    pub current_time: u64,
+}
+
+/// Credentials to verify in one proof
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum ProgramInput {
+    /// A single credential, committed as one output
+    Single(Box<CredentialInput>),
+    /// Several credentials of one subject (e.g., KYC and accreditation),
+    /// committed as one output per credential
+    Batch(Vec<CredentialInput>),
+    /// A batch evaluated against a policy, committed as the policy hash
+    /// and whether it was satisfied, followed by the batch
+    Policy(PolicyInput),
+    /// Any other input, committed as just the keccak256 of its public
+    /// values; the contract is passed the values and re-hashes them
+    Digest(Box<ProgramInput>),
+    /// Any other input but a digest, committed as borsh-encoded
+    /// [`NativeOutputs`] for Solana programs instead of ABI-encoded
+    Solana(Box<ProgramInput>),
+    /// Any other input but a digest, committed as BCS-encoded
+    /// [`NativeOutputs`] for Move programs on Aptos and Sui
+    Move(Box<ProgramInput>),
+}
+
+/// Boolean expression over credential types, supplied by the verifier
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum Policy {
+    /// Satisfied by a verified credential of this type
+    Credential(u32),
+    /// Satisfied when every sub-policy is (true when empty)
+    All(Vec<Policy>),
+    /// Satisfied when any sub-policy is (false when empty)
+    Any(Vec<Policy>),
+}
+
+impl Policy {
+    /// Canonical encoding the policy hash is computed over
+    pub fn to_bytes(&self) -> Vec<u8> {
+        borsh::to_vec(self).expect("Vec writes are infallible")
+    }
+}
+
+/// Credentials evaluated against a verifier policy
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct PolicyInput {
+    /// Policy the credentials should satisfy
+    pub policy: Policy,
+    /// Credentials of one subject, each of which must verify
+    pub credentials: Vec<CredentialInput>,
+}
+
+/// Encodes the program input for the prover's stdin
+pub fn encode(input: &ProgramInput) -> Vec<u8> {
+    borsh::to_vec(input).expect("Vec writes are infallible")
+}
+
+/// Decodes the program input from the prover's stdin
+///
+/// Fails on unknown enum tags, truncated input or trailing bytes.
+pub fn decode(bytes: &[u8]) -> borsh::io::Result<ProgramInput> {
+    borsh::from_slice(bytes)
+}
+
+/// Version of the public value layouts, their first word
+///
+/// Bump it whenever the structs below change, and keep a decoder for the
+/// old layout on the host side.
+pub const OUTPUT_VERSION: u32 = 4;
+
+alloy_sol_types::sol! {
+    /// Public output of one credential: the program's `PublicOutput`, with
+    /// the issuer, holder and status fields grouped into static structs
+    /// that encode inline
+    ///
+    /// A single credential commits it as function parameters, i.e.
+    /// `abi.encode(version, subject, credentialType, credentialHash, ...)`,
+    /// so contracts can decode just the leading fields. Outputs in a batch
+    /// carry the version too, so each can be decoded on its own.
+    #[derive(Debug, Default, PartialEq, Eq)]
+    struct CredentialOutput {
+        uint32 version;
+        address subject;
+        uint32 credentialType;
+        bytes32 credentialHash;
+        uint64 issuedAt;
+        uint64 expiresAt;
+        uint64 chainId;
+        address verifyingContract;
+        uint64 asOf;
+        uint8 hashSuite;
+        uint8 timestampGranularity;
+        uint64 expiryEpoch;
+        bytes32 credentialHashBlind;
+        bytes32 disclosureHash;
+        bytes32 schemaHash;
+        bytes32 predicatesHash;
+        uint32 predicateResults;
+        bytes32 withheldClaimsRoot;
+        IssuerOutput issuer;
+        HolderOutput holder;
+        StatusOutput status;
+        bytes32[] revealedClaims;
+    }
+
+    /// How the issuer was identified or vouched for
+    #[derive(Debug, Default, PartialEq, Eq)]
+    struct IssuerOutput {
+        address issuerAddress;
+        bytes32 quorumHash;
+        bytes32 hmacKeyCommitment;
+        bytes32 rootCaHash;
+        bytes32 vrfOutputHash;
+        bytes32 rootIssuerHash;
+        uint32 keyRegistryVersion;
+        bytes32 trustedIssuersRoot;
+        bytes32 issuerRegistryRoot;
+    }
+
+    /// Nullifier, subject hiding and holder binding values
+    #[derive(Debug, Default, PartialEq, Eq)]
+    struct HolderOutput {
+        bytes32 nullifierScope;
+        bytes32 nullifier;
+        bytes32 verifierNonce;
+        bytes32 subjectCommitment;
+        bytes32 pseudonym;
+        bytes32 ringRoot;
+        bytes32 ringEvent;
+        bytes32 ringTag;
+        bool holderBound;
+        bytes32 deviceRpIdHash;
+        bool biometricBound;
+    }
+
+    /// Sanctions screening, single use and revocation checks
+    #[derive(Debug, Default, PartialEq, Eq)]
+    struct StatusOutput {
+        bytes32 sanctionsRoot;
+        uint32 sanctionsListVersion;
+        bytes32 serialHash;
+        uint8 revocationMethod;
+        bytes32 revocationRoot;
+        uint32 revocationVersion;
+        uint64 revocationMaxAge;
+        uint8 statusPurpose;
+    }
+
+    /// Public values of a batch, committed as `abi.encode(version, outputs)`
+    #[derive(Debug, PartialEq, Eq)]
+    struct BatchOutputs {
+        uint32 version;
+        CredentialOutput[] outputs;
+    }
+
+    /// Public values of a policy evaluation, committed as
+    /// `abi.encode(version, policyHash, policySatisfied, outputs)`
+    #[derive(Debug, PartialEq, Eq)]
+    struct PolicyOutputs {
+        uint32 version;
+        bytes32 policyHash;
+        bool policySatisfied;
+        CredentialOutput[] outputs;
+    }
+}
+
+/// Public output of one credential, as the program computes it
+///
+/// Contracts receive it in the [`CredentialOutput`] layout, and Solana and
+/// Move programs in [`NativeOutputs`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct PublicOutput {
+    /// The subject's address (zero when hidden)
+    pub subject: [u8; 20],
+    /// The credential type
+    pub credential_type: u32,
+    /// Hash of the credential for uniqueness, blinded when a blind is set
+    pub credential_hash: [u8; 32],
+    /// When the credential was issued, rounded down to the granularity
+    pub issued_at: u64,
+    /// When the credential expires, rounded down to the granularity
+    pub expires_at: u64,
+    /// Hash of the selectively disclosed BBS+ messages, or of the SD-JWT
+    /// claim names or mDL element identifiers (zero if none)
+    pub disclosure_hash: [u8; 32],
+    /// Hash of the m-of-n signer policy (zero for single-issuer credentials)
+    pub quorum_hash: [u8; 32],
+    /// Ethereum address of a secp256k1 issuer (zero for other schemes)
+    pub issuer_address: [u8; 20],
+    /// Commitment to the HMAC issuer key (zero for signature schemes)
+    pub hmac_key_commitment: [u8; 32],
+    /// SHA-256 of the root CA certificate (zero without a chain)
+    pub root_ca_hash: [u8; 32],
+    /// Issuer VRF output for the credential (zero without a VRF proof)
+    pub vrf_output_hash: [u8; 32],
+    /// `HashAlgorithm` discriminant that bound the credential
+    pub hash_suite: u8,
+    /// Hash of the claim schema the data was checked against (zero if none)
+    pub schema_hash: [u8; 32],
+    /// Hash of the root issuer of a delegation chain or key registry (zero
+    /// if none)
+    pub root_issuer_hash: [u8; 32],
+    /// Verifier scope the nullifier is bound to (zero if none)
+    pub nullifier_scope: [u8; 32],
+    /// Scoped nullifier of the credential (zero if none)
+    pub nullifier: [u8; 32],
+    /// Verifier challenge the proof answers (zero if none)
+    pub verifier_nonce: [u8; 32],
+    /// Salted commitment to the hidden subject (zero unless hidden)
+    pub subject_commitment: [u8; 32],
+    /// Root of the trusted-issuer set an anonymous issuer belongs to (zero
+    /// unless anonymous)
+    pub trusted_issuers_root: [u8; 32],
+    /// Hash of the evaluated predicates (zero if none)
+    pub predicates_hash: [u8; 32],
+    /// Bit i set if predicate i holds
+    pub predicate_results: u32,
+    /// Merkle root of the salted hashes of the claims not revealed (zero
+    /// unless claims are revealed)
+    pub withheld_claims_root: [u8; 32],
+    /// Root of the sanctions list the subject was screened against (zero
+    /// if not screened)
+    pub sanctions_root: [u8; 32],
+    /// Version of that sanctions list
+    pub sanctions_list_version: u32,
+    /// Holder pseudonym for the verifier (zero unless pseudonymous)
+    pub pseudonym: [u8; 32],
+    /// `TimestampGranularity` discriminant the times were rounded to
+    pub timestamp_granularity: u8,
+    /// Index of the `time::EPOCH_SECONDS` epoch containing the expiry
+    /// (zero for no expiry)
+    pub expiry_epoch: u64,
+    /// Verifier blind the credential hash was hashed with (zero if none)
+    pub credential_hash_blind: [u8; 32],
+    /// Root of the ring of eligible holders (zero unless a ring proof)
+    pub ring_root: [u8; 32],
+    /// Event the linkability tag is scoped to (zero unless a ring proof)
+    pub ring_event: [u8; 32],
+    /// Linkability tag, equal for repeat actions by one holder in an event
+    /// (zero unless a ring proof)
+    pub ring_tag: [u8; 32],
+    /// Whether the subject signed the verifier nonce
+    pub holder_bound: bool,
+    /// Relying party ID hash of the passkey that answered the nonce (zero
+    /// unless device-bound)
+    pub device_rp_id_hash: [u8; 32],
+    /// Whether a fresh biometric capture matched the enrolled template
+    pub biometric_bound: bool,
+    /// Hash of a single-use credential's serial number, for spent-serial
+    /// sets (zero unless single-use)
+    pub serial_hash: [u8; 32],
+    /// `RevocationMethod` discriminant of the registry the credential was
+    /// checked against
+    pub revocation_method: u8,
+    /// Root of that registry: the revocation list's Merkle root, the status
+    /// list credential's hash or the accumulator's hash (zero if not
+    /// checked)
+    pub revocation_root: [u8; 32],
+    /// Version of that registry
+    pub revocation_version: u32,
+    /// Oldest the registry root was allowed to be, in seconds (zero unless
+    /// freshness was checked)
+    pub revocation_max_age: u64,
+    /// `StatusPurpose` discriminant of the status the registry rules out:
+    /// revocation, or suspension for StatusList2021 suspension lists
+    pub status_purpose: u8,
+    /// Version of the key registry listing the issuer key (zero if none)
+    pub key_registry_version: u32,
+    /// Root of the on-chain issuer registry listing the accountable issuer
+    /// (zero if not checked)
+    pub issuer_registry_root: [u8; 32],
+    /// Chain ID of the verifier the proof is for (zero if unbound)
+    pub chain_id: u64,
+    /// Verifier contract the proof is for (zero if unbound)
+    pub verifying_contract: [u8; 20],
+    /// Time the credential was checked as of: its expiry, predicates, key
+    /// retirement and revocation freshness, for contracts to compare with
+    /// the block time
+    pub as_of: u64,
+    /// Salted hashes of the revealed claims, in credential order
+    pub revealed_claims: Vec<[u8; 32]>,
+}
+
+impl From<&PublicOutput> for CredentialOutput {
+    /// Converts an output into its ABI layout for on-chain verification
+    fn from(output: &PublicOutput) -> Self {
+        CredentialOutput {
+            version: OUTPUT_VERSION,
+            subject: output.subject.into(),
+            credentialType: output.credential_type,
+            credentialHash: output.credential_hash.into(),
+            issuedAt: output.issued_at,
+            expiresAt: output.expires_at,
+            chainId: output.chain_id,
+            verifyingContract: output.verifying_contract.into(),
+            asOf: output.as_of,
+            hashSuite: output.hash_suite,
+            timestampGranularity: output.timestamp_granularity,
+            expiryEpoch: output.expiry_epoch,
+            credentialHashBlind: output.credential_hash_blind.into(),
+            disclosureHash: output.disclosure_hash.into(),
+            schemaHash: output.schema_hash.into(),
+            predicatesHash: output.predicates_hash.into(),
+            predicateResults: output.predicate_results,
+            withheldClaimsRoot: output.withheld_claims_root.into(),
+            issuer: IssuerOutput {
+                issuerAddress: output.issuer_address.into(),
+                quorumHash: output.quorum_hash.into(),
+                hmacKeyCommitment: output.hmac_key_commitment.into(),
+                rootCaHash: output.root_ca_hash.into(),
+                vrfOutputHash: output.vrf_output_hash.into(),
+                rootIssuerHash: output.root_issuer_hash.into(),
+                keyRegistryVersion: output.key_registry_version,
+                trustedIssuersRoot: output.trusted_issuers_root.into(),
+                issuerRegistryRoot: output.issuer_registry_root.into(),
+            },
+            holder: HolderOutput {
+                nullifierScope: output.nullifier_scope.into(),
+                nullifier: output.nullifier.into(),
+                verifierNonce: output.verifier_nonce.into(),
+                subjectCommitment: output.subject_commitment.into(),
+                pseudonym: output.pseudonym.into(),
+                ringRoot: output.ring_root.into(),
+                ringEvent: output.ring_event.into(),
+                ringTag: output.ring_tag.into(),
+                holderBound: output.holder_bound,
+                deviceRpIdHash: output.device_rp_id_hash.into(),
+                biometricBound: output.biometric_bound,
+            },
+            status: StatusOutput {
+                sanctionsRoot: output.sanctions_root.into(),
+                sanctionsListVersion: output.sanctions_list_version,
+                serialHash: output.serial_hash.into(),
+                revocationMethod: output.revocation_method,
+                revocationRoot: output.revocation_root.into(),
+                revocationVersion: output.revocation_version,
+                revocationMaxAge: output.revocation_max_age,
+                statusPurpose: output.status_purpose,
+            },
+            revealedClaims: output
+                .revealed_claims
+                .iter()
+                .map(|&claim| claim.into())
+                .collect(),
+        }
+    }
+}
+
+/// Public values of a proof for chains that don't decode ABI: borsh-encoded
+/// for the SP1 Solana verifier, so Solana programs can deserialize them
+/// like their instruction data, or BCS-encoded for Move
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct NativeOutputs {
+    /// Layout version, [`OUTPUT_VERSION`] when committed
+    pub version: u32,
+    /// Policy hash and whether the credentials satisfied it (absent without
+    /// a policy)
+    pub policy: Option<([u8; 32], bool)>,
+    /// One output per credential, in input order
+    pub outputs: Vec<PublicOutput>,
+}
+
+impl NativeOutputs {
+    /// Borsh encoding the program commits for Solana
+    pub fn to_bytes(&self) -> Vec<u8> {
+        borsh::to_vec(self).expect("Vec writes are infallible")
+    }
+
+    /// BCS encoding the program commits for Move
+    ///
+    /// The layout is the Move struct with these fields, and the outputs'
+    /// fields in `PublicOutput` order: integers little-endian, byte arrays
+    /// as `vector<u8>` (Move has no fixed-size arrays and 32-byte
+    /// addresses) and the policy as an `Option` of its hash and result.
+    pub fn to_bcs(&self) -> Vec<u8> {
+        let mut bcs = self.version.to_le_bytes().to_vec();
+        match &self.policy {
+            Some((hash, satisfied)) => {
+                bcs.push(1);
+                bcs_bytes(&mut bcs, hash);
+                bcs.push(*satisfied as u8);
+            }
+            None => bcs.push(0),
+        }
+        bcs_length(&mut bcs, self.outputs.len());
+        for output in &self.outputs {
+            output.write_bcs(&mut bcs);
+        }
+        bcs
+    }
+}
+
+impl PublicOutput {
+    /// Appends the output's BCS encoding
+    fn write_bcs(&self, bcs: &mut Vec<u8>) {
+        bcs_bytes(bcs, &self.subject);
+        bcs.extend_from_slice(&self.credential_type.to_le_bytes());
+        bcs_bytes(bcs, &self.credential_hash);
+        bcs.extend_from_slice(&self.issued_at.to_le_bytes());
+        bcs.extend_from_slice(&self.expires_at.to_le_bytes());
+        bcs_bytes(bcs, &self.disclosure_hash);
+        bcs_bytes(bcs, &self.quorum_hash);
+        bcs_bytes(bcs, &self.issuer_address);
+        bcs_bytes(bcs, &self.hmac_key_commitment);
+        bcs_bytes(bcs, &self.root_ca_hash);
+        bcs_bytes(bcs, &self.vrf_output_hash);
+        bcs.push(self.hash_suite);
+        bcs_bytes(bcs, &self.schema_hash);
+        bcs_bytes(bcs, &self.root_issuer_hash);
+        bcs_bytes(bcs, &self.nullifier_scope);
+        bcs_bytes(bcs, &self.nullifier);
+        bcs_bytes(bcs, &self.verifier_nonce);
+        bcs_bytes(bcs, &self.subject_commitment);
+        bcs_bytes(bcs, &self.trusted_issuers_root);
+        bcs_bytes(bcs, &self.predicates_hash);
+        bcs.extend_from_slice(&self.predicate_results.to_le_bytes());
+        bcs_bytes(bcs, &self.withheld_claims_root);
+        bcs_bytes(bcs, &self.sanctions_root);
+        bcs.extend_from_slice(&self.sanctions_list_version.to_le_bytes());
+        bcs_bytes(bcs, &self.pseudonym);
+        bcs.push(self.timestamp_granularity);
+        bcs.extend_from_slice(&self.expiry_epoch.to_le_bytes());
+        bcs_bytes(bcs, &self.credential_hash_blind);
+        bcs_bytes(bcs, &self.ring_root);
+        bcs_bytes(bcs, &self.ring_event);
+        bcs_bytes(bcs, &self.ring_tag);
+        bcs.push(self.holder_bound as u8);
+        bcs_bytes(bcs, &self.device_rp_id_hash);
+        bcs.push(self.biometric_bound as u8);
+        bcs_bytes(bcs, &self.serial_hash);
+        bcs.push(self.revocation_method);
+        bcs_bytes(bcs, &self.revocation_root);
+        bcs.extend_from_slice(&self.revocation_version.to_le_bytes());
+        bcs.extend_from_slice(&self.revocation_max_age.to_le_bytes());
+        bcs.push(self.status_purpose);
+        bcs.extend_from_slice(&self.key_registry_version.to_le_bytes());
+        bcs_bytes(bcs, &self.issuer_registry_root);
+        bcs.extend_from_slice(&self.chain_id.to_le_bytes());
+        bcs_bytes(bcs, &self.verifying_contract);
+        bcs.extend_from_slice(&self.as_of.to_le_bytes());
+        bcs_length(bcs, self.revealed_claims.len());
+        for claim in &self.revealed_claims {
+            bcs_bytes(bcs, claim);
+        }
+    }
+}
+
+/// Appends a BCS sequence length, ULEB128-encoded
+fn bcs_length(bcs: &mut Vec<u8>, mut length: usize) {
+    while length >= 0x80 {
+        bcs.push(length as u8 | 0x80);
+        length >>= 7;
+    }
+    bcs.push(length as u8);
+}
+
+/// Appends bytes as a BCS `vector<u8>`
+fn bcs_bytes(bcs: &mut Vec<u8>, bytes: &[u8]) {
+    bcs_length(bcs, bytes.len());
+    bcs.extend_from_slice(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(crate) fn sample() -> CredentialInput {
+        CredentialInput {
+            subject: [0x12; 20],
+            credential_type: 2,
+            credential_data: vec![0, 0, 0, 2, 0, 0, 0, 0],
+            credential_format: CredentialFormat::Binary,
+            signature: vec![0xab; 64],
+            issuer_pubkey: vec![0x02; 33],
+            signature_scheme: SignatureScheme::Secp256k1,
+            eip712_domain: None,
+            hash_algorithm: HashAlgorithm::Keccak256,
+            bbs: None,
+            quorum: Some(IssuerQuorum {
+                threshold: 1,
+                pubkeys: vec![vec![0x03; 33]],
+                signatures: vec![QuorumSignature {
+                    key_index: 0,
+                    signature: vec![0xcd; 64],
+                }],
+            }),
+            hmac_key: None,
+            link_secret: None,
+            issuer_cert_chain: Vec::new(),
+            vrf_proof: None,
+            claim_schema: None,
+            delegation: None,
+            claim_proofs: Vec::new(),
+            sparse_claim_proofs: Vec::new(),
+            nullifier: None,
+            verifier_nonce: [0u8; 32],
+            subject_salt: None,
+            issuer_membership: None,
+            predicates: Vec::new(),
+            revealed_claims: Vec::new(),
+            disclosure_seed: None,
+            sanctions_exclusion: None,
+            verifier_id: None,
+            timestamp_granularity: TimestampGranularity::Exact,
+            credential_hash_blind: None,
+            ring: None,
+            holder_signature: None,
+            device_pubkey: None,
+            device_assertion: None,
+            biometric_template_hash: None,
+            serial_number: None,
+            revocation_exclusion: None,
+            status_list: None,
+            accumulator_witness: None,
+            key_registry: None,
+            revocation_freshness: None,
+            issuer_registry: None,
+            chain_id: None,
+            verifying_contract: None,
+            issued_at: 1_700_000_000,
+            expires_at: 0,
+            current_time: 1_700_000_100,
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let input = ProgramInput::Batch(vec![sample(), sample()]);
+        let encoded = encode(&input);
+        let ProgramInput::Batch(decoded) = decode(&encoded).unwrap() else {
+            panic!("Expected a batch");
+        };
+
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(encode(&ProgramInput::Batch(decoded.clone())), encoded);
+        assert_eq!(decoded[1].hash_algorithm, HashAlgorithm::Keccak256);
+        assert_eq!(
+            decoded[1].quorum.as_ref().unwrap().signatures[0].signature,
+            vec![0xcd; 64]
+        );
+    }
+
+    #[test]
+    fn test_base_credential_types() {
+        assert_eq!(base_credential_types(2), vec![2]);
+        assert_eq!(
+            base_credential_types(CREDENTIAL_TYPE_BITMASK | 0b10001),
+            vec![1, 5]
+        );
+        assert!(base_credential_types(CREDENTIAL_TYPE_BITMASK).is_empty());
+    }
+
+    #[test]
+    fn test_declared_type_must_match() {
+        assert!(declares_credential_type("urn:credence:type:kyc", 1));
+        assert!(declares_credential_type(
+            "urn:credence:type:2147483665",
+            CREDENTIAL_TYPE_BITMASK | 0b10001
+        ));
+
+        // A KYC credential can't be committed as an accreditation, nor a
+        // well-known type under its number
+        assert!(!declares_credential_type("urn:credence:type:kyc", 2));
+        assert!(!declares_credential_type("urn:credence:type:1", 1));
+        assert!(!declares_credential_type("kyc", 1));
+    }
+
+    #[test]
+    fn test_revocation_method_from_u8() {
+        assert_eq!(RevocationMethod::from_u8(0), Some(RevocationMethod::None));
+        assert_eq!(
+            RevocationMethod::from_u8(3),
+            Some(RevocationMethod::Accumulator)
+        );
+        assert_eq!(RevocationMethod::from_u8(4), None);
+    }
+
+    #[test]
+    fn test_status_purpose_from_u8() {
+        assert_eq!(StatusPurpose::from_u8(2), Some(StatusPurpose::Suspension));
+        assert_eq!(StatusPurpose::from_u8(3), None);
+    }
+
+    #[test]
+    fn test_output_leads_with_flat_fields() {
+        use alloy_sol_types::SolValue;
+
+        let output = CredentialOutput {
+            version: OUTPUT_VERSION,
+            subject: [0x11; 20].into(),
+            credentialType: 2,
+            issuedAt: 7,
+            asOf: 9,
+            revealedClaims: vec![[0x22; 32].into()],
+            ..Default::default()
+        };
+        let encoded = output.abi_encode_params();
+        assert_eq!(encoded[31] as u32, OUTPUT_VERSION);
+        assert_eq!(encoded[44..64], [0x11; 20]);
+        assert_eq!(encoded[95], 2);
+        assert_eq!(encoded[159], 7);
+        assert_eq!(encoded[287], 9);
+        assert_eq!(
+            CredentialOutput::abi_decode_params(&encoded, true).unwrap(),
+            output
+        );
+    }
+
+    #[test]
+    fn test_quorum_rejects_repeated_keys() {
+        let signature = |key_index| QuorumSignature {
+            key_index,
+            signature: vec![0xcd; 64],
+        };
+        let mut quorum = IssuerQuorum {
+            threshold: 2,
+            pubkeys: vec![vec![0x02; 33], vec![0x03; 33]],
+            signatures: vec![signature(0), signature(1)],
+        };
+        assert!(quorum.has_distinct_keys());
+
+        // One key listed twice would sign for two of the n
+        quorum.pubkeys[1] = quorum.pubkeys[0].clone();
+        assert!(!quorum.has_distinct_keys());
+
+        // As would the same key in another encoding
+        quorum.pubkeys[1] = vec![0x04; 65];
+        assert!(!quorum.has_distinct_keys());
+    }
+
+    #[test]
+    fn test_rejects_malformed_input() {
+        let mut encoded = encode(&ProgramInput::Single(Box::new(sample())));
+        encoded.push(0);
+        assert!(decode(&encoded).is_err());
+        assert!(decode(&encoded[..encoded.len() - 2]).is_err());
+    }
+}
@@ -0,0 +1,197 @@
+//! Canonical signing message
+//!
+//! Issuers sign a domain-separated encoding of the credential rather than
+//! the raw `credential_data`, so a signature can't be replayed as a
+//! signature over another protocol's bytes or re-bound to a different
+//! subject, credential type, validity window or hash suite. The circuit
+//! rebuilds the message and the host tools sign it, both with these
+//! functions.
+//!
+//! The optional bindings are appended as domain-separated SHA-256
+//! commitments, so one binding's bytes can't stand in for another's.
+
+use sha2::{Digest, Sha256};
+
+use crate::{CredentialInput, StatusListEntry};
+
+/// Domain tag prefixed to every signed credential message
+pub const SIGNING_DOMAIN: &[u8] = b"CREDENCE_CREDENTIAL_V1";
+
+/// Domain tag for holder secret commitments
+pub const HOLDER_SECRET_DOMAIN: &[u8] = b"CREDENCE_HOLDER_SECRET_V1";
+
+/// Domain tag for device key commitments
+pub const DEVICE_KEY_DOMAIN: &[u8] = b"CREDENCE_DEVICE_KEY_V1";
+
+/// Domain tag for biometric template commitments
+pub const BIOMETRIC_DOMAIN: &[u8] = b"CREDENCE_BIOMETRIC_V1";
+
+/// Domain tag for serial number hashes
+pub const SERIAL_DOMAIN: &[u8] = b"CREDENCE_SERIAL_V1";
+
+/// Domain tag for status entry commitments
+pub const STATUS_ENTRY_DOMAIN: &[u8] = b"CREDENCE_STATUS_ENTRY_V1";
+
+/// Domain tag for disclosure seed commitments
+pub const DISCLOSURE_SEED_DOMAIN: &[u8] = b"CREDENCE_DISCLOSURE_SEED_V1";
+
+/// Domain tag for per-claim disclosure salts
+pub const CLAIM_SALT_DOMAIN: &[u8] = b"CREDENCE_CLAIM_SALT_V1";
+
+/// Computes `SHA-256(domain || parts...)`
+fn tagged_hash(domain: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(domain);
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+/// Commitment to the holder secret behind nullifiers and pseudonyms
+pub fn holder_commitment(secret: &[u8; 32]) -> [u8; 32] {
+    tagged_hash(HOLDER_SECRET_DOMAIN, &[secret])
+}
+
+/// Commitment to the passkey a credential is bound to
+pub fn device_commitment(device_pubkey: &[u8]) -> [u8; 32] {
+    tagged_hash(DEVICE_KEY_DOMAIN, &[device_pubkey])
+}
+
+/// Commitment to the hash of an enrolled biometric template
+///
+/// The holder supplies the hash of a fresh capture and the message is
+/// rebuilt from it, so the issuer's signature only verifies if it matches
+/// the enrolled one. Nothing biometric is committed.
+pub fn template_commitment(template_hash: &[u8; 32]) -> [u8; 32] {
+    tagged_hash(BIOMETRIC_DOMAIN, &[template_hash])
+}
+
+/// Hash of a single-use credential's serial number
+///
+/// The issuer signs it and every proof of the credential commits it, so a
+/// contract keeping a set of spent serial hashes accepts each voucher or
+/// ticket once.
+pub fn serial_hash(serial_number: &[u8; 32]) -> [u8; 32] {
+    tagged_hash(SERIAL_DOMAIN, &[serial_number])
+}
+
+/// Commitment to a StatusList2021 entry
+///
+/// `SHA-256(STATUS_ENTRY_DOMAIN || index (BE u64) || purpose (u8) || list_url)`
+pub fn entry_commitment(entry: &StatusListEntry) -> [u8; 32] {
+    tagged_hash(
+        STATUS_ENTRY_DOMAIN,
+        &[
+            &entry.index.to_be_bytes(),
+            &[entry.purpose as u8],
+            entry.list_url.as_bytes(),
+        ],
+    )
+}
+
+/// Commitment to the seed of a credential's claim salts
+pub fn disclosure_seed_commitment(seed: &[u8; 32]) -> [u8; 32] {
+    tagged_hash(DISCLOSURE_SEED_DOMAIN, &[seed])
+}
+
+/// Salt of the claim at `position`
+///
+/// `SHA-256(CLAIM_SALT_DOMAIN || seed || position (BE u32))`. Each claim
+/// gets its own, so the holder can hand a verifier the salts of the claims
+/// they reveal without exposing the others'.
+pub fn claim_salt(seed: &[u8; 32], position: u32) -> [u8; 32] {
+    tagged_hash(CLAIM_SALT_DOMAIN, &[seed, &position.to_be_bytes()])
+}
+
+/// Builds the message issuers sign
+///
+/// `SIGNING_DOMAIN || hash_suite (u8) || subject (20) ||
+/// credential_type (BE u32) || issued_at (BE u64) || expires_at (BE u64) ||
+/// len (BE u32) || credential_data`, followed by the holder secret
+/// commitment (32) when the credential derives a nullifier, the device key
+/// commitment (32) when it is bound to a passkey, the template commitment
+/// (32) when it is bound to a biometric, the serial hash (32) when it is
+/// single-use, the status entry commitment (32) when it has a
+/// StatusList2021 entry and the disclosure seed commitment (32) when its
+/// claims can be revealed
+pub fn signing_message(input: &CredentialInput) -> Vec<u8> {
+    let mut message =
+        Vec::with_capacity(SIGNING_DOMAIN.len() + 45 + input.credential_data.len() + 6 * 32);
+    message.extend_from_slice(SIGNING_DOMAIN);
+    message.push(input.hash_algorithm as u8);
+    message.extend_from_slice(&input.subject);
+    message.extend_from_slice(&input.credential_type.to_be_bytes());
+    message.extend_from_slice(&input.issued_at.to_be_bytes());
+    message.extend_from_slice(&input.expires_at.to_be_bytes());
+    message.extend_from_slice(&(input.credential_data.len() as u32).to_be_bytes());
+    message.extend_from_slice(&input.credential_data);
+    if let Some(nullifier) = &input.nullifier {
+        message.extend_from_slice(&holder_commitment(&nullifier.secret));
+    }
+    if let Some(device_pubkey) = &input.device_pubkey {
+        message.extend_from_slice(&device_commitment(device_pubkey));
+    }
+    if let Some(template_hash) = &input.biometric_template_hash {
+        message.extend_from_slice(&template_commitment(template_hash));
+    }
+    if let Some(serial_number) = &input.serial_number {
+        message.extend_from_slice(&serial_hash(serial_number));
+    }
+    if let Some(entry) = &input.status_list {
+        message.extend_from_slice(&entry_commitment(entry));
+    }
+    if let Some(seed) = &input.disclosure_seed {
+        message.extend_from_slice(&disclosure_seed_commitment(seed));
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HashAlgorithm;
+
+    #[test]
+    fn test_message_names_hash_suite() {
+        let mut sha256 = crate::tests::sample();
+        sha256.hash_algorithm = HashAlgorithm::Sha256;
+        let mut poseidon = sha256.clone();
+        poseidon.hash_algorithm = HashAlgorithm::Poseidon;
+
+        // Poseidon suites sign a SHA-256 digest, so only the message tells
+        // the two apart
+        assert_ne!(signing_message(&sha256), signing_message(&poseidon));
+    }
+
+    #[test]
+    fn test_serial_cannot_stand_in_for_device_commitment() {
+        let device_pubkey = vec![0x02; 33];
+        let mut device_bound = crate::tests::sample();
+        device_bound.device_pubkey = Some(device_pubkey.clone());
+
+        // A holder presenting the device commitment as a serial number gets
+        // a message the issuer never signed
+        let mut single_use = crate::tests::sample();
+        single_use.serial_number = Some(device_commitment(&device_pubkey));
+
+        assert_ne!(signing_message(&device_bound), signing_message(&single_use));
+        assert_eq!(
+            signing_message(&single_use).len(),
+            signing_message(&device_bound).len()
+        );
+    }
+
+    #[test]
+    fn test_disclosure_seed_is_signed_and_salts_each_claim() {
+        let mut salted = crate::tests::sample();
+        salted.disclosure_seed = Some([0x5a; 32]);
+
+        // A holder can't swap in a seed of their own
+        assert_ne!(
+            signing_message(&salted),
+            signing_message(&crate::tests::sample())
+        );
+        assert_ne!(claim_salt(&[0x5a; 32], 0), claim_salt(&[0x5a; 32], 1));
+    }
+}
@@ -0,0 +1,123 @@
+//! Revocation by RSA accumulator non-membership
+//!
+//! Issuers with millions of credentials can publish a single accumulator
+//! instead of a revocation tree. Each credential's SHA-256 hash, whatever
+//! its hash suite, maps to a prime `x`, and the accumulator over the
+//! revoked primes `u = Π x_i` is
+//!
+//! ```text
+//! A = g^u mod N
+//! ```
+//!
+//! for a modulus `N` of unknown factorization. A holder whose prime isn't
+//! among them gets a constant-size witness `(a, d)` with `a·u + b·x = 1`
+//! and `d = g^-b`, which verifies when
+//!
+//! ```text
+//! A^a ≡ d^x · g  (mod N)
+//! ```
+//!
+//! Only the hash of `len(N) (BE u32) || N || A` is committed, for
+//! contracts to check against the issuer's latest accumulator.
+
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use crate::{hash, AccumulatorWitness, HashAlgorithm};
+
+/// Domain tag for mapping credential hashes to primes
+pub const PRIME_DOMAIN: &[u8] = b"CREDENCE_ACCUMULATOR_PRIME_V1";
+
+/// Base `g` of every accumulator
+pub const ACCUMULATOR_BASE: u32 = 3;
+
+/// Small primes candidates are trial-divided by before Miller-Rabin
+const SMALL_PRIMES: [u32; 24] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89,
+];
+
+/// Miller-Rabin bases; candidates are hash outputs, so no adversary picks
+/// them to fool a fixed set
+const WITNESS_BASES: [u32; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// Tests `n` for primality by trial division and Miller-Rabin
+fn is_probable_prime(n: &BigUint) -> bool {
+    for prime in SMALL_PRIMES {
+        if n % prime == BigUint::from(0u8) {
+            return *n == BigUint::from(prime);
+        }
+    }
+
+    let one = BigUint::from(1u8);
+    let n_minus_one = n - &one;
+    let s = n_minus_one.trailing_zeros().expect("n is odd and above 1");
+    let d = &n_minus_one >> s;
+    'bases: for base in WITNESS_BASES {
+        let mut x = BigUint::from(base).modpow(&d, n);
+        if x == one || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = &x * &x % n;
+            if x == n_minus_one {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// Maps a credential hash to its accumulator prime, the first probable
+/// prime among the 256-bit odd candidates
+/// `SHA-256(PRIME_DOMAIN || credential_hash || counter (BE u32))`
+///
+/// Taking the first prime keeps the mapping unique, so a holder can't
+/// pick one the issuer didn't revoke.
+pub fn hash_to_prime(credential_hash: &[u8; 32]) -> BigUint {
+    (0u32..)
+        .map(|counter| {
+            let mut candidate: [u8; 32] = Sha256::new()
+                .chain_update(PRIME_DOMAIN)
+                .chain_update(credential_hash)
+                .chain_update(counter.to_be_bytes())
+                .finalize()
+                .into();
+            candidate[0] |= 0x80;
+            candidate[31] |= 0x01;
+            BigUint::from_bytes_be(&candidate)
+        })
+        .find(is_probable_prime)
+        .expect("a prime within 2^32 candidates")
+}
+
+/// Returns the hash of the accumulator `witness` shows doesn't contain
+/// `credential_hash`, or `None` if the witness doesn't verify
+pub fn non_revoked_hash(
+    algorithm: HashAlgorithm,
+    credential_hash: &[u8; 32],
+    witness: &AccumulatorWitness,
+) -> Option<[u8; 32]> {
+    let modulus = BigUint::from_bytes_be(&witness.modulus);
+    let accumulator = BigUint::from_bytes_be(&witness.accumulator);
+    let d = BigUint::from_bytes_be(&witness.d);
+    if modulus <= BigUint::from(1u8) || accumulator >= modulus || d >= modulus {
+        return None;
+    }
+
+    let prime = hash_to_prime(credential_hash);
+    let a = BigUint::from_bytes_be(&witness.a);
+    if a >= prime {
+        return None;
+    }
+
+    let lhs = accumulator.modpow(&a, &modulus);
+    let rhs = d.modpow(&prime, &modulus) * ACCUMULATOR_BASE % &modulus;
+    let modulus_len = (witness.modulus.len() as u32).to_be_bytes();
+    (lhs == rhs).then(|| {
+        hash::hash_parts(
+            algorithm,
+            &[&modulus_len, &witness.modulus, &witness.accumulator],
+        )
+    })
+}
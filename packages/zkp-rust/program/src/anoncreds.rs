@@ -0,0 +1,173 @@
+//! Hyperledger AnonCreds credential verification
+//!
+//! Accepts an Indy/AnonCreds credential JSON (as stored in the holder's
+//! wallet) as `credential_data` and the primary key of its credential
+//! definition as `issuer_pubkey`, so credentials from existing Indy issuers
+//! can be proven without re-issuance. The CL signature `(A, e, v)` verifies
+//! when
+//!
+//! ```text
+//! Z ≡ A^e · S^v · Rctxt^m2 · R_ms^ms · Π R_i^m_i  (mod n)
+//! ```
+//!
+//! where `ms` is the holder's link secret, supplied privately.
+//!
+//! The CL signature covers nothing but the attributes, so the subject,
+//! credential type and validity period are read from the `subject`,
+//! `credential_type`, `issued_at` and `expires_at` attributes every
+//! credential must carry (a DID or address, a type name, and Unix seconds
+//! with 0 for no expiration).
+
+use std::collections::BTreeMap;
+
+use num_bigint::BigUint;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+/// Key of the link secret in the credential definition's `r` map
+const LINK_SECRET: &str = "master_secret";
+
+/// Bit length of the lower bound of the signature exponent `e`
+const E_START_BITS: usize = 596;
+
+/// Bit length of the range above that bound `e` is drawn from
+const E_RANGE_BITS: usize = 119;
+
+/// An attribute value with its CL message encoding
+#[derive(Deserialize)]
+struct AttributeValue {
+    raw: String,
+    encoded: String,
+}
+
+/// Primary CL signature, all values as decimal strings
+#[derive(Deserialize)]
+struct PrimarySignature {
+    m_2: String,
+    a: String,
+    e: String,
+    v: String,
+}
+
+#[derive(Deserialize)]
+struct CredentialSignature {
+    p_credential: PrimarySignature,
+}
+
+/// A stored AnonCreds credential
+#[derive(Deserialize)]
+pub struct Credential {
+    values: BTreeMap<String, AttributeValue>,
+    signature: CredentialSignature,
+}
+
+/// Credential fields bound from the signed attributes
+pub struct Bound {
+    /// Subject address as `0x`-prefixed hex, or a DID ending in one
+    pub subject: String,
+    /// Credential type name (see `declares_credential_type`)
+    pub credential_type: String,
+    /// Issuance time (seconds since the epoch)
+    pub issued_at: u64,
+    /// Expiration time (0 for no expiration)
+    pub expires_at: u64,
+}
+
+impl Credential {
+    /// Reads the bound fields, or `None` if an attribute is missing or a
+    /// time is not an integer
+    pub fn bound(&self) -> Option<Bound> {
+        let raw = |name: &str| self.values.get(name).map(|value| value.raw.as_str());
+        Some(Bound {
+            subject: raw("subject")?.to_string(),
+            credential_type: raw("credential_type")?.to_string(),
+            issued_at: raw("issued_at")?.parse().ok()?,
+            expires_at: raw("expires_at")?.parse().ok()?,
+        })
+    }
+}
+
+/// Primary public key of a credential definition
+#[derive(Deserialize)]
+struct PrimaryPublicKey {
+    n: String,
+    s: String,
+    r: BTreeMap<String, String>,
+    rctxt: String,
+    z: String,
+}
+
+/// Parses a stored credential
+pub fn parse(credential: &[u8]) -> Option<Credential> {
+    serde_json::from_slice(credential).ok()
+}
+
+/// Parses a decimal big integer
+fn number(decimal: &str) -> Option<BigUint> {
+    BigUint::parse_bytes(decimal.as_bytes(), 10)
+}
+
+/// Encodes a raw attribute value the way AnonCreds issuers do
+///
+/// 32-bit integers encode as themselves; anything else as the SHA-256 of
+/// the raw string read as a big-endian integer.
+fn encode_attribute(raw: &str) -> String {
+    match raw.parse::<i32>() {
+        Ok(value) => value.to_string(),
+        Err(_) => BigUint::from_bytes_be(&Sha256::digest(raw.as_bytes())).to_string(),
+    }
+}
+
+/// Canonical attribute name used as the `r` map key
+fn canonical_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Verifies the primary CL signature under the credential definition key
+///
+/// Every attribute of the credential definition must be present with an
+/// `encoded` value matching its `raw` value. Returns false for malformed
+/// keys or signatures.
+pub fn verify(credential: &Credential, pubkey: &[u8], link_secret: &[u8]) -> bool {
+    check_signature(credential, pubkey, link_secret).unwrap_or(false)
+}
+
+fn check_signature(credential: &Credential, pubkey: &[u8], link_secret: &[u8]) -> Option<bool> {
+    let key: PrimaryPublicKey = serde_json::from_slice(pubkey).ok()?;
+    let n = number(&key.n)?;
+    let signature = &credential.signature.p_credential;
+    let (a, e, v, m2) = (
+        number(&signature.a)?,
+        number(&signature.e)?,
+        number(&signature.v)?,
+        number(&signature.m_2)?,
+    );
+
+    let e_start = BigUint::from(1u8) << E_START_BITS;
+    let e_end = &e_start + (BigUint::from(1u8) << E_RANGE_BITS);
+    if e < e_start || e > e_end {
+        return Some(false);
+    }
+
+    // The link secret plus one key per attribute
+    if key.r.len() != credential.values.len() + 1 {
+        return Some(false);
+    }
+
+    let mut rx = number(&key.s)?.modpow(&v, &n);
+    rx = rx * number(&key.rctxt)?.modpow(&m2, &n) % &n;
+    rx =
+        rx * number(key.r.get(LINK_SECRET)?)?.modpow(&BigUint::from_bytes_be(link_secret), &n) % &n;
+    for (name, value) in &credential.values {
+        if encode_attribute(&value.raw) != value.encoded {
+            return Some(false);
+        }
+        let r = number(key.r.get(&canonical_name(name))?)?;
+        rx = rx * r.modpow(&number(&value.encoded)?, &n) % &n;
+    }
+
+    Some(a.modpow(&e, &n) * rx % &n == number(&key.z)?)
+}
@@ -0,0 +1,117 @@
+//! BBS+ signature verification for selective-disclosure credentials
+//!
+//! Issuers sign a vector of messages with a BLS12-381 key in G2. Inside the
+//! zkVM the holder supplies every message and the signature privately, and
+//! only the messages they choose to disclose are bound into the public
+//! output, so no proof-of-knowledge transcript is needed.
+//!
+//! A signature is `A || e || s` (48-byte compressed G1 point and two
+//! 32-byte big-endian scalars) and verifies when
+//! `e(A, w + g2·e) == e(g1 + h0·s + Σ hᵢ·mᵢ, g2)`.
+
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_381::{
+    multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, Scalar,
+};
+use sha2::{Digest, Sha512};
+
+use crate::hash;
+use crate::HashAlgorithm;
+
+/// Domain tag for deriving the message generators `h0, h1, ...`
+pub const GENERATOR_DST: &[u8] = b"CREDENCE_BBS_PLUS_BLS12381G1_XMD:SHA-256_SSWU_RO_";
+
+/// Length of an encoded BBS+ signature
+pub const SIGNATURE_BYTES: usize = 48 + 32 + 32;
+
+/// Derives generator `h_index` by hashing the index to G1
+///
+/// `h0` blinds the signature; message `i` (zero-based) uses `h_{i+1}`.
+pub fn generator(index: u32) -> G1Projective {
+    <G1Projective as HashToCurve<ExpandMsgXmd<sha2_v0_9::Sha256>>>::hash_to_curve(
+        index.to_be_bytes(),
+        GENERATOR_DST,
+    )
+}
+
+/// Maps a message to a scalar via a wide SHA-512 reduction
+pub fn message_scalar(message: &[u8]) -> Scalar {
+    let wide: [u8; 64] = Sha512::digest(message).into();
+    Scalar::from_bytes_wide(&wide)
+}
+
+/// Parses a canonical big-endian scalar
+fn scalar_from_be(bytes: &[u8]) -> Option<Scalar> {
+    let mut le: [u8; 32] = bytes.try_into().ok()?;
+    le.reverse();
+    Option::from(Scalar::from_bytes(&le))
+}
+
+/// Verifies a BBS+ signature over `messages` against the G2 public key `w`
+pub fn verify(messages: &[Vec<u8>], signature: &[u8], pubkey: &[u8]) -> bool {
+    if messages.is_empty() || signature.len() != SIGNATURE_BYTES {
+        return false;
+    }
+
+    let Ok(pubkey) = <&[u8; 96]>::try_from(pubkey) else {
+        return false;
+    };
+    let Some(w) = Option::<G2Affine>::from(G2Affine::from_compressed(pubkey)) else {
+        return false;
+    };
+    let Some(a) = Option::<G1Affine>::from(G1Affine::from_compressed(
+        signature[..48].try_into().expect("length checked"),
+    )) else {
+        return false;
+    };
+    if bool::from(w.is_identity()) || bool::from(a.is_identity()) {
+        return false;
+    }
+
+    let (Some(e), Some(s)) = (
+        scalar_from_be(&signature[48..80]),
+        scalar_from_be(&signature[80..]),
+    ) else {
+        return false;
+    };
+
+    let mut b = G1Projective::generator() + generator(0) * s;
+    for (i, message) in messages.iter().enumerate() {
+        b += generator(i as u32 + 1) * message_scalar(message);
+    }
+
+    let w_e = G2Affine::from(G2Projective::generator() * e + G2Projective::from(w));
+
+    multi_miller_loop(&[
+        (&a, &G2Prepared::from(w_e)),
+        (&-G1Affine::from(b), &G2Prepared::from(G2Affine::generator())),
+    ])
+    .final_exponentiation()
+        == Gt::identity()
+}
+
+/// Hashes the disclosed messages for the public output
+///
+/// `disclosed` must be strictly increasing indices into `messages`. Each
+/// entry contributes `index (BE u32) || length (BE u32) || message`.
+pub fn disclosed_messages_hash(
+    algorithm: HashAlgorithm,
+    messages: &[Vec<u8>],
+    disclosed: &[u32],
+) -> [u8; 32] {
+    assert!(
+        disclosed.windows(2).all(|pair| pair[0] < pair[1]),
+        "Disclosed indices must be strictly increasing"
+    );
+
+    let mut encoded = Vec::new();
+    for &index in disclosed {
+        let message = messages
+            .get(index as usize)
+            .expect("Disclosed index out of range");
+        encoded.extend_from_slice(&index.to_be_bytes());
+        encoded.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        encoded.extend_from_slice(message);
+    }
+    hash::hash_parts(algorithm, &[&encoded])
+}
@@ -0,0 +1,165 @@
+//! Credential data format v2: typed TLV claims
+//!
+//! Version 1 data is `version || claim_count || opaque bytes`, so the
+//! circuit can only count claims. Version 2 makes each claim a
+//! `type (BE u16) || length (BE u32) || value` record with the type drawn
+//! from a registry, and each credential type names the claim types it
+//! requires:
+//!
+//! ```text
+//! version = 2 (BE u32) || claim_count (BE u32) || claim*
+//! ```
+
+pub use credential_verifier_lib::claims::FORMAT_V2;
+
+/// Registered claim types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClaimType {
+    /// Legal name (UTF-8)
+    FullName = 1,
+    /// Date of birth (BE i64 Unix seconds)
+    DateOfBirth = 2,
+    /// Nationality (ISO 3166-1 alpha-2)
+    Nationality = 3,
+    /// Country of residence (ISO 3166-1 alpha-2)
+    ResidenceCountry = 4,
+    /// KYC assurance level
+    KycLevel = 5,
+    /// Accredited investor status
+    AccreditationStatus = 6,
+    /// Net worth in whole USD (BE u64)
+    NetWorth = 7,
+    /// Investor classification (e.g., qualified purchaser)
+    InvestorClass = 8,
+    /// Legal entity registration number (UTF-8)
+    EntityRegistration = 9,
+    /// AML screening result
+    AmlScreening = 10,
+}
+
+impl ClaimType {
+    /// Looks up a registered claim type
+    pub fn from_u16(value: u16) -> Option<Self> {
+        Some(match value {
+            1 => Self::FullName,
+            2 => Self::DateOfBirth,
+            3 => Self::Nationality,
+            4 => Self::ResidenceCountry,
+            5 => Self::KycLevel,
+            6 => Self::AccreditationStatus,
+            7 => Self::NetWorth,
+            8 => Self::InvestorClass,
+            9 => Self::EntityRegistration,
+            10 => Self::AmlScreening,
+            _ => return None,
+        })
+    }
+
+    /// Looks up a registered claim type by its key-value claim name
+    pub fn from_key(key: &str) -> Option<Self> {
+        Some(match key {
+            "fullName" => Self::FullName,
+            "dateOfBirth" => Self::DateOfBirth,
+            "nationality" => Self::Nationality,
+            "residenceCountry" => Self::ResidenceCountry,
+            "kycLevel" => Self::KycLevel,
+            "accreditationStatus" => Self::AccreditationStatus,
+            "netWorth" => Self::NetWorth,
+            "investorClass" => Self::InvestorClass,
+            "entityRegistration" => Self::EntityRegistration,
+            "amlScreening" => Self::AmlScreening,
+            _ => return None,
+        })
+    }
+
+    /// Returns true if `value` is well-formed for this claim type
+    pub fn accepts(self, value: &[u8]) -> bool {
+        match self {
+            Self::FullName | Self::EntityRegistration => {
+                !value.is_empty() && core::str::from_utf8(value).is_ok()
+            }
+            Self::Nationality | Self::ResidenceCountry => {
+                value.len() == 2 && value.iter().all(u8::is_ascii_uppercase)
+            }
+            Self::DateOfBirth | Self::NetWorth => value.len() == 8,
+            Self::KycLevel
+            | Self::AccreditationStatus
+            | Self::InvestorClass
+            | Self::AmlScreening => value.len() == 1,
+        }
+    }
+}
+
+/// A decoded v2 claim
+#[derive(Debug, Clone, Copy)]
+pub struct Claim<'a> {
+    /// Registered type of the claim
+    pub claim_type: ClaimType,
+    /// Raw claim value
+    pub value: &'a [u8],
+}
+
+/// Claim types each credential type must carry
+pub fn required_claims(credential_type: u32) -> &'static [ClaimType] {
+    use ClaimType::*;
+
+    match credential_type {
+        1 => &[KycLevel],                                          // KYC
+        2 => &[KycLevel, AccreditationStatus],                     // Accredited
+        3 => &[KycLevel, InvestorClass],                           // Qualified
+        4 => &[KycLevel, EntityRegistration, AccreditationStatus], // Institutional
+        5 => &[AmlScreening],                                      // AML
+        _ => &[],
+    }
+}
+
+/// Reads a big-endian integer field, advancing `data`
+fn take<const N: usize>(data: &mut &[u8]) -> Option<[u8; N]> {
+    let field = data.get(..N)?.try_into().ok()?;
+    *data = &data[N..];
+    Some(field)
+}
+
+/// Decodes v2 credential data
+///
+/// Returns `None` if the header is not v2, a claim type is unregistered or
+/// repeated, or the record count or lengths don't match the data exactly.
+pub fn parse(mut data: &[u8]) -> Option<Vec<Claim<'_>>> {
+    if u32::from_be_bytes(take(&mut data)?) != FORMAT_V2 {
+        return None;
+    }
+    let claim_count = u32::from_be_bytes(take(&mut data)?);
+
+    let mut claims: Vec<Claim> = Vec::new();
+    for _ in 0..claim_count {
+        let claim_type = ClaimType::from_u16(u16::from_be_bytes(take(&mut data)?))?;
+        let len = u32::from_be_bytes(take(&mut data)?) as usize;
+        let value = data.get(..len)?;
+        data = &data[len..];
+
+        if claims.iter().any(|claim| claim.claim_type == claim_type) {
+            return None;
+        }
+        claims.push(Claim { claim_type, value });
+    }
+
+    data.is_empty().then_some(claims)
+}
+
+/// Validates v2 credential data against the credential type's requirements
+///
+/// Every claim value must be well-formed for its type and every claim type
+/// the credential type requires must be present.
+pub fn validate(data: &[u8], credential_type: u32) -> bool {
+    let Some(claims) = parse(data) else {
+        return false;
+    };
+
+    !claims.is_empty()
+        && claims
+            .iter()
+            .all(|claim| claim.claim_type.accepts(claim.value))
+        && required_claims(credential_type)
+            .iter()
+            .all(|required| claims.iter().any(|claim| claim.claim_type == *required))
+}
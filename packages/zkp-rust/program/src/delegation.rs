@@ -0,0 +1,109 @@
+//! Issuer delegation chains
+//!
+//! A root issuer (e.g., a regulator) authorizes a delegate key to issue
+//! certain credential types, and that delegate may in turn authorize
+//! others up to a depth limit. The circuit walks the chain from the root,
+//! checks every signature, scope and depth, and commits the root issuer so
+//! contracts can trust one root instead of every delegate.
+
+use crate::{
+    base_credential_types, hash, signature, Delegation, DelegationChain, HashAlgorithm,
+    SignatureScheme,
+};
+
+/// Domain tag prefixed to every signed delegation
+pub const DELEGATION_DOMAIN: &[u8] = b"CREDENCE_DELEGATION_V1";
+
+/// Builds the message a delegating key signs
+///
+/// `DELEGATION_DOMAIN || len (BE u32) || delegate_pubkey || n (BE u32) ||
+/// credential_type (BE u32)* || max_depth (BE u32) || expires_at (BE u64)`
+pub fn delegation_message(delegation: &Delegation) -> Vec<u8> {
+    let mut message = DELEGATION_DOMAIN.to_vec();
+    message.extend_from_slice(&(delegation.delegate_pubkey.len() as u32).to_be_bytes());
+    message.extend_from_slice(&delegation.delegate_pubkey);
+    message.extend_from_slice(&(delegation.credential_types.len() as u32).to_be_bytes());
+    for credential_type in &delegation.credential_types {
+        message.extend_from_slice(&credential_type.to_be_bytes());
+    }
+    message.extend_from_slice(&delegation.max_depth.to_be_bytes());
+    message.extend_from_slice(&delegation.expires_at.to_be_bytes());
+    message
+}
+
+/// Verifies that `chain` authorizes `issuer_pubkey` to issue `credential_type`
+///
+/// Each link must be signed by the previous key (the root for the first),
+/// be unexpired, stay within its parent's credential types and depth, and
+/// the last delegate must be the credential's issuer. Only schemes that
+/// sign the message directly are supported.
+pub fn verify(
+    scheme: SignatureScheme,
+    chain: &DelegationChain,
+    issuer_pubkey: &[u8],
+    credential_type: u32,
+    current_time: u64,
+) -> bool {
+    let supported = matches!(
+        scheme,
+        SignatureScheme::Secp256k1
+            | SignatureScheme::Secp256r1
+            | SignatureScheme::Bls12381
+            | SignatureScheme::Rsa2048Pkcs1v15
+            | SignatureScheme::Rsa2048Pss
+    );
+    let Some(last) = chain.links.last() else {
+        return false;
+    };
+    if !supported || last.delegate_pubkey != issuer_pubkey {
+        return false;
+    }
+
+    let mut delegator: &[u8] = &chain.root_pubkey;
+    let mut parent: Option<&Delegation> = None;
+    for link in &chain.links {
+        let within_parent = match parent {
+            Some(parent) => {
+                parent.max_depth > link.max_depth
+                    && link
+                        .credential_types
+                        .iter()
+                        .all(|credential_type| parent.credential_types.contains(credential_type))
+            }
+            None => true,
+        };
+        let unexpired = link.expires_at == 0 || current_time <= link.expires_at;
+        let signed = signature::verify_signature(
+            scheme,
+            HashAlgorithm::Sha256,
+            &delegation_message(link),
+            &link.signature,
+            delegator,
+        );
+        if !within_parent || !unexpired || !signed {
+            return false;
+        }
+
+        delegator = &link.delegate_pubkey;
+        parent = Some(link);
+    }
+
+    // A bitmask credential needs every type it combines delegated
+    let base_types = base_credential_types(credential_type);
+    last.credential_types.contains(&credential_type)
+        || (!base_types.is_empty()
+            && base_types
+                .iter()
+                .all(|base| last.credential_types.contains(base)))
+}
+
+/// Hashes the root issuer for the public output
+///
+/// Encoded as `scheme (u8) || root_pubkey`.
+pub fn root_issuer_hash(
+    algorithm: HashAlgorithm,
+    scheme: SignatureScheme,
+    chain: &DelegationChain,
+) -> [u8; 32] {
+    hash::hash_parts(algorithm, &[&[scheme as u8], &chain.root_pubkey])
+}
@@ -0,0 +1,16 @@
+//! Identifier parsing for JSON credential formats
+//!
+//! JWT and W3C credentials name subjects and issuers by string ids rather
+//! than raw addresses. Ethereum-based ids (`0x...`, `did:pkh`, `did:ethr`)
+//! end in the address they refer to.
+
+/// Extracts the Ethereum address an id refers to
+///
+/// Takes the text after the last `:` so DIDs such as
+/// `did:pkh:eip155:1:0x...` and `did:ethr:0x...` resolve to the address
+/// they embed. Returns `None` for ids that don't end in an address.
+pub fn address(id: &str) -> Option<[u8; 20]> {
+    let address = id.rsplit(':').next()?;
+    let address = address.strip_prefix("0x").unwrap_or(address);
+    hex::decode(address).ok()?.try_into().ok()
+}
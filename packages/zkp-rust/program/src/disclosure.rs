@@ -0,0 +1,92 @@
+//! Selective disclosure of binary claims
+//!
+//! The holder names the positions of the v2 or v3 claims to reveal. Each
+//! revealed claim is committed as its own salted hash, and the withheld
+//! claims only as the Merkle root of theirs, so a verifier handed the
+//! revealed claims and their salts off-chain can check exactly what was
+//! shown:
+//!
+//! ```text
+//! v2 claim = SHA-256(0x00 || claim_type (BE u16) || value)
+//! v3 claim = SHA-256(0x00 || SHA-256(key) || tag || value)
+//! salted   = SHA-256(claim_salt(seed, position) || claim)
+//! ```
+//!
+//! The claims are hashed as the leaves they would have in a v4 or v5
+//! tree. Like SD-JWT disclosures, each is salted with its own salt from
+//! the issuer-signed disclosure seed, so a low-entropy withheld claim (a
+//! country, a flag) can't be recovered by hashing its few possible values.
+//! The withheld root is built like a v4 tree over the withheld salted
+//! hashes in credential order (zero if every claim is revealed).
+
+use credential_verifier_lib::message;
+use sha2::{Digest, Sha256};
+
+use crate::keyed::{self, KeyedClaims};
+use crate::{claims, merkle, smt};
+
+/// Revealed claim hashes and the root of the withheld ones
+#[derive(Debug, Clone)]
+pub struct Disclosure {
+    /// Salted hashes of the revealed claims, in credential order
+    pub revealed: Vec<[u8; 32]>,
+    /// Merkle root over the salted hashes of the other claims
+    pub withheld_root: [u8; 32],
+}
+
+/// Hashes each claim of v2 or v3 credential data, in credential order
+fn claim_hashes(data: &[u8]) -> Option<Vec<[u8; 32]>> {
+    let version = u32::from_be_bytes(data.get(..4)?.try_into().ok()?);
+    match version {
+        claims::FORMAT_V2 => Some(
+            claims::parse(data)?
+                .iter()
+                .map(|claim| merkle::leaf_hash(claim.claim_type as u16, claim.value))
+                .collect(),
+        ),
+        keyed::FORMAT_V3 => Some(
+            KeyedClaims::parse(data)?
+                .claims()
+                .iter()
+                .map(|(key, value)| smt::leaf_hash(&smt::key_path(key), &value.to_bytes()))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// Salts the hash of the claim at `position`
+fn salted(seed: &[u8; 32], position: usize, claim_hash: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(message::claim_salt(seed, position as u32));
+    hasher.update(claim_hash);
+    hasher.finalize().into()
+}
+
+/// Splits the credential's claims into revealed and withheld, salted from
+/// `seed`
+///
+/// Returns `None` if the data isn't v2 or v3, or the positions aren't
+/// strictly increasing and within the claims.
+pub fn disclose(data: &[u8], revealed: &[u32], seed: &[u8; 32]) -> Option<Disclosure> {
+    let hashes: Vec<[u8; 32]> = claim_hashes(data)?
+        .iter()
+        .enumerate()
+        .map(|(position, hash)| salted(seed, position, hash))
+        .collect();
+    let increasing = revealed.windows(2).all(|pair| pair[0] < pair[1]);
+    let in_bounds = revealed.iter().all(|&i| (i as usize) < hashes.len());
+    if !increasing || !in_bounds {
+        return None;
+    }
+
+    let (shown, withheld): (Vec<_>, Vec<_>) = hashes
+        .into_iter()
+        .enumerate()
+        .partition(|(i, _)| revealed.contains(&(*i as u32)));
+    let withheld: Vec<[u8; 32]> = withheld.into_iter().map(|(_, hash)| hash).collect();
+    Some(Disclosure {
+        revealed: shown.into_iter().map(|(_, hash)| hash).collect(),
+        withheld_root: merkle::root(&withheld),
+    })
+}
@@ -0,0 +1,63 @@
+//! Revocation data freshness
+//!
+//! A non-revocation proof is only as current as the registry snapshot it
+//! was built against. The issuer signs the time it published each root,
+//!
+//! ```text
+//! FRESHNESS_DOMAIN || method (u8) || root (32) || version (BE u32) ||
+//! published_at (BE u64)
+//! ```
+//!
+//! and the verifier bounds how old that root may be, so proofs can't be
+//! built against a snapshot taken before the credential was revoked.
+
+use crate::{signature, HashAlgorithm, RevocationFreshness, RevocationMethod, SignatureScheme};
+
+/// Domain tag prefixed to every signed publication time
+pub const FRESHNESS_DOMAIN: &[u8] = b"CREDENCE_REVOCATION_FRESHNESS_V1";
+
+/// Builds the message the issuer signs when publishing a registry root
+pub fn freshness_message(
+    method: RevocationMethod,
+    root: &[u8; 32],
+    version: u32,
+    published_at: u64,
+) -> Vec<u8> {
+    let mut message = FRESHNESS_DOMAIN.to_vec();
+    message.push(method as u8);
+    message.extend_from_slice(root);
+    message.extend_from_slice(&version.to_be_bytes());
+    message.extend_from_slice(&published_at.to_be_bytes());
+    message
+}
+
+/// Verifies the issuer's signature over a freshness message
+///
+/// EIP-712 issuers sign from a wallet, which signs anything but typed data
+/// with EIP-191 `personal_sign`; every other scheme signs the message as it
+/// signs credentials.
+pub fn verify_signature(
+    scheme: SignatureScheme,
+    message_hash: HashAlgorithm,
+    message: &[u8],
+    signature: &[u8],
+    issuer_pubkey: &[u8],
+) -> bool {
+    match scheme {
+        SignatureScheme::Eip712 => signature::issuer_address(issuer_pubkey)
+            .is_some_and(|address| signature::verify_personal_sign(message, signature, &address)),
+        _ => signature::verify_signature(scheme, message_hash, message, signature, issuer_pubkey),
+    }
+}
+
+/// Whether the root was published no later than `as_of` and at most the
+/// verifier's max age before it
+///
+/// `as_of` is the committed as-of time, so a proof backdated to when a
+/// stale root was still fresh shows up against the block time.
+pub fn is_fresh(freshness: &RevocationFreshness, as_of: u64) -> bool {
+    match as_of.checked_sub(freshness.published_at) {
+        Some(age) => age <= freshness.max_age,
+        None => false,
+    }
+}
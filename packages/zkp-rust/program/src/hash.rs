@@ -0,0 +1,31 @@
+//! Hash functions used to bind credentials
+//!
+//! The committed `credential_hash` can be produced with different hash
+//! functions so it matches whatever the consuming contract or circuit
+//! computes natively. The same suite digests the message ECDSA issuers
+//! sign, and its identifier is committed alongside the hash.
+
+pub use credential_verifier_lib::hash::{credential_hash, hash_parts, message_digest};
+
+use crate::HashAlgorithm;
+
+/// Computes keccak256 of `data`
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    hash_parts(HashAlgorithm::Keccak256, &[data])
+}
+
+/// Hashes a set of disclosed claim names for the public output
+///
+/// Names are sorted so the hash doesn't depend on presentation order, and
+/// each contributes `length (BE u32) || name`.
+pub fn claim_names_hash(algorithm: HashAlgorithm, names: &[String]) -> [u8; 32] {
+    let mut sorted: Vec<&String> = names.iter().collect();
+    sorted.sort();
+
+    let mut encoded = Vec::new();
+    for name in sorted {
+        encoded.extend_from_slice(&(name.len() as u32).to_be_bytes());
+        encoded.extend_from_slice(name.as_bytes());
+    }
+    hash_parts(algorithm, &[&encoded])
+}
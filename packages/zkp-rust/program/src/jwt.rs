@@ -0,0 +1,82 @@
+//! ES256K JWT credential parsing
+//!
+//! JWT credentials arrive as a compact JWS (`header.payload.signature`,
+//! each part base64url without padding) in `credential_data`. The issuer
+//! signs the ASCII `header.payload` with secp256k1 ECDSA over SHA-256, and
+//! the subject and timestamps come from the `sub`, `iat` and `exp` claims
+//! instead of the binary credential fields. The `vct` claim must name the
+//! committed `credential_type` (see `declares_credential_type`).
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::Deserialize;
+
+/// The only JWS algorithm accepted for JWT credentials
+pub const ALGORITHM: &str = "ES256K";
+
+/// JOSE header fields the circuit checks
+#[derive(Deserialize)]
+struct Header {
+    alg: String,
+}
+
+/// Registered claims bound into the public output
+#[derive(Deserialize)]
+pub struct Claims {
+    /// Subject address as `0x`-prefixed hex, or a DID ending in one
+    /// (see `did::address`)
+    pub sub: String,
+    /// Issuance time (seconds since the epoch)
+    pub iat: u64,
+    /// Expiration time (absent for no expiration)
+    #[serde(default)]
+    pub exp: u64,
+    /// Credential type name, e.g. `urn:credence:type:kyc`
+    pub vct: String,
+}
+
+/// A parsed compact JWS
+pub struct Jwt {
+    /// The `header.payload` bytes the issuer signed
+    pub signing_input: Vec<u8>,
+    /// The 64-byte `r || s` signature
+    pub signature: Vec<u8>,
+    /// Decoded payload claims
+    pub claims: Claims,
+    /// The full payload, for formats that interpret further claims
+    pub payload: serde_json::Value,
+}
+
+/// Parses a compact JWS, checking the header names ES256K
+///
+/// Returns `None` if the token is not three base64url parts, the header or
+/// payload is not valid JSON, or a required claim is missing. The signature
+/// itself is not checked here.
+pub fn parse(token: &[u8]) -> Option<Jwt> {
+    let token = core::str::from_utf8(token).ok()?;
+    let (signing_input, signature) = token.rsplit_once('.')?;
+    let (header, payload) = signing_input.split_once('.')?;
+    if payload.contains('.') {
+        return None;
+    }
+
+    let header: Header = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(header).ok()?).ok()?;
+    if header.alg != ALGORITHM {
+        return None;
+    }
+
+    let payload: serde_json::Value =
+        serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()?;
+    let claims = Claims::deserialize(&payload).ok()?;
+    let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+    if signature.len() != 64 {
+        return None;
+    }
+
+    Some(Jwt {
+        signing_input: signing_input.as_bytes().to_vec(),
+        signature,
+        claims,
+        payload,
+    })
+}
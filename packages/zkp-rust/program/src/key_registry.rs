@@ -0,0 +1,75 @@
+//! Issuer key rotation through a signed key registry
+//!
+//! An issuer keeps a long-term root key offline and signs a registry of
+//! the keys that sign its credentials. Rotating to a new key only needs a
+//! new registry version, so credentials signed by earlier keys stay valid
+//! until the issuer retires those keys. The root is committed like the
+//! root of a delegation chain, along with the registry version so
+//! contracts can refuse registries older than the latest.
+
+use crate::{hash, signature, HashAlgorithm, KeyRegistry, SignatureScheme};
+
+/// Domain tag prefixed to every signed key registry
+pub const KEY_REGISTRY_DOMAIN: &[u8] = b"CREDENCE_KEY_REGISTRY_V1";
+
+/// Builds the message the root key signs
+///
+/// `KEY_REGISTRY_DOMAIN || version (BE u32) || n (BE u32) ||
+/// (len (BE u32) || pubkey || retired_at (BE u64))*`
+pub fn registry_message(registry: &KeyRegistry) -> Vec<u8> {
+    let mut message = KEY_REGISTRY_DOMAIN.to_vec();
+    message.extend_from_slice(&registry.version.to_be_bytes());
+    message.extend_from_slice(&(registry.keys.len() as u32).to_be_bytes());
+    for key in &registry.keys {
+        message.extend_from_slice(&(key.pubkey.len() as u32).to_be_bytes());
+        message.extend_from_slice(&key.pubkey);
+        message.extend_from_slice(&key.retired_at.to_be_bytes());
+    }
+    message
+}
+
+/// Verifies that the root key signed `registry` and that it lists
+/// `issuer_pubkey` as a key not retired at `as_of`
+///
+/// `as_of` is the committed as-of time, so a contract comparing it with
+/// the block time catches a proof backdated to before a key's retirement.
+///
+/// Only schemes that sign the message directly are supported.
+pub fn verify(
+    scheme: SignatureScheme,
+    registry: &KeyRegistry,
+    issuer_pubkey: &[u8],
+    as_of: u64,
+) -> bool {
+    let supported = matches!(
+        scheme,
+        SignatureScheme::Secp256k1
+            | SignatureScheme::Secp256r1
+            | SignatureScheme::Bls12381
+            | SignatureScheme::Rsa2048Pkcs1v15
+            | SignatureScheme::Rsa2048Pss
+    );
+    let listed = registry
+        .keys
+        .iter()
+        .any(|key| key.pubkey == issuer_pubkey && (key.retired_at == 0 || as_of < key.retired_at));
+    supported
+        && listed
+        && signature::verify_signature(
+            scheme,
+            HashAlgorithm::Sha256,
+            &registry_message(registry),
+            &registry.signature,
+            &registry.root_pubkey,
+        )
+}
+
+/// Hashes the root key for the public output, encoded like a delegation
+/// root as `scheme (u8) || root_pubkey`
+pub fn root_issuer_hash(
+    algorithm: HashAlgorithm,
+    scheme: SignatureScheme,
+    registry: &KeyRegistry,
+) -> [u8; 32] {
+    hash::hash_parts(algorithm, &[&[scheme as u8], &registry.root_pubkey])
+}
@@ -0,0 +1,300 @@
+//! Credential data format v3: key-value claims with typed values
+//!
+//! Each claim is a UTF-8 key and a tagged value, so circuit logic can
+//! assert on named claims (`kycLevel >= 2`, `accredited == true`) instead
+//! of positional bytes:
+//!
+//! ```text
+//! version = 3 (BE u32) || claim_count (BE u32) || claim*
+//! claim = key_len (BE u16) || key || tag (u8) || value
+//! ```
+//!
+//! | tag | type        | value                                   |
+//! |-----|-------------|-----------------------------------------|
+//! | 1   | u64         | 8 bytes, big-endian                     |
+//! | 2   | bool        | 1 byte, 0 or 1                          |
+//! | 3   | string hash | 32-byte SHA-256 of the UTF-8 string     |
+//! | 4   | date        | 8 bytes, big-endian i64 Unix seconds    |
+//! | 5   | amount      | unit (3) || precision (1) || value (8)  |
+//!
+//! An amount is a big-endian u64 in units of `10^-precision` of an
+//! ISO 4217-style uppercase unit code, so `USD`, precision 2 and 25000000
+//! is $250,000.00. Amounts only compare within the same unit, at any
+//! precision up to `MAX_PRECISION`.
+
+/// Version tag of the key-value format
+pub const FORMAT_V3: u32 = 3;
+
+/// Most decimal places an amount may carry
+pub const MAX_PRECISION: u8 = 18;
+
+/// A decimal quantity of a unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Amount {
+    /// Uppercase ASCII unit code (e.g., `USD`)
+    pub unit: [u8; 3],
+    /// Decimal places of `value`
+    pub precision: u8,
+    /// Quantity in units of `10^-precision`
+    pub value: u64,
+}
+
+impl Amount {
+    /// Returns the value scaled to `MAX_PRECISION` decimal places
+    fn scaled(&self) -> u128 {
+        u128::from(self.value) * 10u128.pow(u32::from(MAX_PRECISION - self.precision))
+    }
+
+    /// True if `self` is in the same unit as `min` and not less than it
+    pub fn at_least(&self, min: &Amount) -> bool {
+        self.unit == min.unit && self.scaled() >= min.scaled()
+    }
+}
+
+/// A typed claim value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClaimValue {
+    /// Unsigned integer
+    U64(u64),
+    /// Boolean flag
+    Bool(bool),
+    /// SHA-256 of a string the holder keeps private
+    StringHash([u8; 32]),
+    /// Unix timestamp in seconds
+    Date(i64),
+    /// Decimal quantity with a unit
+    Amount(Amount),
+}
+
+impl ClaimValue {
+    /// Encodes the value as `tag || value`, the inverse of `decode_value`
+    pub fn to_bytes(self) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        match self {
+            Self::U64(value) => {
+                encoded.push(1);
+                encoded.extend_from_slice(&value.to_be_bytes());
+            }
+            Self::Bool(value) => encoded.extend_from_slice(&[2, value as u8]),
+            Self::StringHash(hash) => {
+                encoded.push(3);
+                encoded.extend_from_slice(&hash);
+            }
+            Self::Date(value) => {
+                encoded.push(4);
+                encoded.extend_from_slice(&value.to_be_bytes());
+            }
+            Self::Amount(amount) => {
+                encoded.push(5);
+                encoded.extend_from_slice(&amount.unit);
+                encoded.push(amount.precision);
+                encoded.extend_from_slice(&amount.value.to_be_bytes());
+            }
+        }
+        encoded
+    }
+}
+
+/// Reads a fixed-size field, advancing `data`
+fn take<const N: usize>(data: &mut &[u8]) -> Option<[u8; N]> {
+    let field = data.get(..N)?.try_into().ok()?;
+    *data = &data[N..];
+    Some(field)
+}
+
+/// Decodes a tagged value, advancing `data`
+fn take_value(data: &mut &[u8]) -> Option<ClaimValue> {
+    let [tag] = take::<1>(data)?;
+    Some(match tag {
+        1 => ClaimValue::U64(u64::from_be_bytes(take(data)?)),
+        2 => match take::<1>(data)? {
+            [0] => ClaimValue::Bool(false),
+            [1] => ClaimValue::Bool(true),
+            _ => return None,
+        },
+        3 => ClaimValue::StringHash(take(data)?),
+        4 => ClaimValue::Date(i64::from_be_bytes(take(data)?)),
+        5 => {
+            let unit: [u8; 3] = take(data)?;
+            let [precision] = take::<1>(data)?;
+            if !unit.iter().all(u8::is_ascii_uppercase) || precision > MAX_PRECISION {
+                return None;
+            }
+            ClaimValue::Amount(Amount {
+                unit,
+                precision,
+                value: u64::from_be_bytes(take(data)?),
+            })
+        }
+        _ => return None,
+    })
+}
+
+/// Decodes a value given on its own, as `tag || value`
+pub fn decode_value(mut data: &[u8]) -> Option<ClaimValue> {
+    let value = take_value(&mut data)?;
+    data.is_empty().then_some(value)
+}
+
+/// Annual income that qualifies an accredited investor ($200,000)
+const ACCREDITED_INCOME: Amount = Amount {
+    unit: *b"USD",
+    precision: 0,
+    value: 200_000,
+};
+
+/// Net worth that qualifies an accredited investor ($1,000,000)
+const ACCREDITED_NET_WORTH: Amount = Amount {
+    unit: *b"USD",
+    precision: 0,
+    value: 1_000_000,
+};
+
+/// Decoded v3 claims, looked up by key
+#[derive(Debug, Clone)]
+pub struct KeyedClaims<'a> {
+    claims: Vec<(&'a str, ClaimValue)>,
+}
+
+impl<'a> KeyedClaims<'a> {
+    /// Decodes v3 credential data
+    ///
+    /// Returns `None` if the header is not v3, a key is empty, not UTF-8 or
+    /// repeated, a tag is unknown, or the data has trailing bytes.
+    pub fn parse(mut data: &'a [u8]) -> Option<Self> {
+        if u32::from_be_bytes(take(&mut data)?) != FORMAT_V3 {
+            return None;
+        }
+        let claim_count = u32::from_be_bytes(take(&mut data)?);
+
+        let mut claims: Vec<(&str, ClaimValue)> = Vec::new();
+        for _ in 0..claim_count {
+            let key_len = u16::from_be_bytes(take(&mut data)?) as usize;
+            let key = core::str::from_utf8(data.get(..key_len)?).ok()?;
+            data = &data[key_len..];
+            claims.push((key, take_value(&mut data)?));
+        }
+
+        if !data.is_empty() {
+            return None;
+        }
+        Self::from_claims(claims)
+    }
+
+    /// Collects claims decoded elsewhere
+    ///
+    /// Returns `None` if a key is empty or repeated.
+    pub fn from_claims(claims: Vec<(&'a str, ClaimValue)>) -> Option<Self> {
+        let unique = claims.iter().enumerate().all(|(i, (key, _))| {
+            !key.is_empty() && claims[..i].iter().all(|(other, _)| other != key)
+        });
+        unique.then_some(Self { claims })
+    }
+
+    /// Returns the claims in credential order
+    pub fn claims(&self) -> &[(&'a str, ClaimValue)] {
+        &self.claims
+    }
+
+    /// Returns the value of `key`, if present
+    pub fn get(&self, key: &str) -> Option<ClaimValue> {
+        self.claims
+            .iter()
+            .find(|(existing, _)| *existing == key)
+            .map(|(_, value)| *value)
+    }
+
+    /// Returns `key` if it is a u64 claim
+    pub fn u64(&self, key: &str) -> Option<u64> {
+        match self.get(key)? {
+            ClaimValue::U64(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns `key` if it is a bool claim
+    pub fn bool(&self, key: &str) -> Option<bool> {
+        match self.get(key)? {
+            ClaimValue::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns `key` if it is a string-hash claim
+    pub fn string_hash(&self, key: &str) -> Option<[u8; 32]> {
+        match self.get(key)? {
+            ClaimValue::StringHash(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns `key` if it is a date claim
+    pub fn date(&self, key: &str) -> Option<i64> {
+        match self.get(key)? {
+            ClaimValue::Date(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns `key` if it is an amount claim
+    pub fn amount(&self, key: &str) -> Option<Amount> {
+        match self.get(key)? {
+            ClaimValue::Amount(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// True if `key` is an amount claim of at least `min`, in its unit
+    pub fn amount_at_least(&self, key: &str, min: &Amount) -> bool {
+        self.amount(key).is_some_and(|amount| amount.at_least(min))
+    }
+
+    /// True if `key` is a u64 claim of at least `min`
+    pub fn u64_at_least(&self, key: &str, min: u64) -> bool {
+        self.u64(key).is_some_and(|value| value >= min)
+    }
+
+    /// True if `key` is a bool claim set to true
+    pub fn is_true(&self, key: &str) -> bool {
+        self.bool(key) == Some(true)
+    }
+
+    /// True if `key` is a date claim no later than `time`
+    pub fn date_not_after(&self, key: &str, time: u64) -> bool {
+        self.date(key)
+            .is_some_and(|date| i128::from(date) <= i128::from(time))
+    }
+
+    /// True if the claims meet the credential type's requirements
+    ///
+    /// Verification dates must not postdate issuance.
+    pub fn satisfy(&self, credential_type: u32, issued_at: u64) -> bool {
+        match credential_type {
+            // KYC
+            1 => self.u64_at_least("kycLevel", 1) && self.date_not_after("verifiedAt", issued_at),
+            // Accredited, by status or by the SEC income or net worth test
+            2 => {
+                self.u64_at_least("kycLevel", 1)
+                    && (self.is_true("accredited")
+                        || self.amount_at_least("annualIncome", &ACCREDITED_INCOME)
+                        || self.amount_at_least("netWorth", &ACCREDITED_NET_WORTH))
+            }
+            // Qualified
+            3 => self.u64_at_least("kycLevel", 1) && self.is_true("qualifiedPurchaser"),
+            // Institutional
+            4 => {
+                self.u64_at_least("kycLevel", 1)
+                    && self.string_hash("entityRegistration").is_some()
+                    && self.is_true("accredited")
+            }
+            // AML
+            5 => self.is_true("amlCleared") && self.date_not_after("screenedAt", issued_at),
+            _ => !self.claims.is_empty(),
+        }
+    }
+}
+
+/// Validates v3 credential data against the credential type's requirements
+pub fn validate(data: &[u8], credential_type: u32, issued_at: u64) -> bool {
+    KeyedClaims::parse(data).is_some_and(|claims| claims.satisfy(credential_type, issued_at))
+}
@@ -0,0 +1,154 @@
+//! Linked-data proof (Data Integrity) credentials
+//!
+//! Accepts `{"document": <N-Quads>, "proof": <N-Quads>}` as
+//! `credential_data`: the host expands the VC and its proof configuration
+//! to N-Quads, and the circuit canonicalizes both with URDNA2015 and
+//! rebuilds the `ecdsa-rdfc-2019` verify data,
+//! `SHA-256(canonical proof) || SHA-256(canonical document)`, which the
+//! issuer signs with ES256. The subject, credential type and validity
+//! period are read from the canonical dataset rather than trusted from the
+//! host: the credential's one `rdf:type` under `CREDENTIAL_TYPE_URN` must
+//! name the committed type.
+
+use credential_verifier_lib::CREDENTIAL_TYPE_URN;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::rdf::{self, Quad, Term};
+use crate::time;
+
+/// The only cryptosuite accepted
+const CRYPTOSUITE: &str = "ecdsa-rdfc-2019";
+
+const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const CREDENTIALS: &str = "https://www.w3.org/2018/credentials#";
+const SECURITY: &str = "https://w3id.org/security#";
+
+/// The host-expanded credential and proof configuration
+#[derive(Deserialize)]
+struct Envelope {
+    document: String,
+    proof: String,
+}
+
+/// A parsed linked-data proof credential
+pub struct Credential {
+    /// `credentialSubject` IRI
+    pub subject: String,
+    /// The credential's type IRI under `CREDENTIAL_TYPE_URN`
+    pub credential_type: String,
+    /// Issuance time (seconds since the epoch)
+    pub issued_at: u64,
+    /// Expiration time (0 for no expiration)
+    pub expires_at: u64,
+    /// The `ecdsa-rdfc-2019` data the issuer signed
+    pub verify_data: Vec<u8>,
+}
+
+/// Returns the object of the unique `(subject, predicate, _)` quad in the
+/// default graph
+fn object<'a>(quads: &'a [Quad], subject: &Term, predicate: &str) -> Option<&'a Term> {
+    let mut objects = quads.iter().filter(|quad| {
+        quad.graph.is_none()
+            && &quad.subject == subject
+            && quad.predicate == Term::Iri(predicate.into())
+    });
+    let quad = objects.next()?;
+    objects.next().is_none().then_some(&quad.object)
+}
+
+/// Returns the unique default-graph node with `rdf:type` `kind`
+fn node_of_type(quads: &[Quad], kind: &str) -> Option<Term> {
+    let mut nodes = quads.iter().filter(|quad| {
+        quad.graph.is_none()
+            && quad.predicate == Term::Iri(RDF_TYPE.into())
+            && quad.object == Term::Iri(kind.into())
+    });
+    let quad = nodes.next()?;
+    nodes.next().is_none().then(|| quad.subject.clone())
+}
+
+/// Returns the unique `rdf:type` IRI of `node` under `CREDENTIAL_TYPE_URN`
+fn credential_type<'a>(quads: &'a [Quad], node: &Term) -> Option<&'a str> {
+    let mut types = quads
+        .iter()
+        .filter(|quad| {
+            quad.graph.is_none()
+                && &quad.subject == node
+                && quad.predicate == Term::Iri(RDF_TYPE.into())
+        })
+        .filter_map(|quad| iri(&quad.object))
+        .filter(|kind| kind.starts_with(CREDENTIAL_TYPE_URN));
+    let kind = types.next()?;
+    types.next().is_none().then_some(kind)
+}
+
+/// Returns an IRI object's value
+fn iri(term: &Term) -> Option<&str> {
+    match term {
+        Term::Iri(iri) => Some(iri),
+        _ => None,
+    }
+}
+
+/// Returns a literal object's value, ignoring its datatype
+fn literal(term: &Term) -> Option<&str> {
+    match term {
+        Term::Literal { value, .. } => Some(value),
+        _ => None,
+    }
+}
+
+/// Reads a date-time property, trying the VC 1.1 then the VC 2.0 name
+fn date(quads: &[Quad], node: &Term, names: [&str; 2]) -> Option<Option<u64>> {
+    let found = names
+        .iter()
+        .find_map(|name| object(quads, node, &format!("{CREDENTIALS}{name}")));
+    match found {
+        Some(term) => Some(Some(time::parse_rfc3339(literal(term)?)?)),
+        None => Some(None),
+    }
+}
+
+/// Parses and canonicalizes a linked-data proof credential
+///
+/// Returns `None` if either dataset is malformed, the proof is not an
+/// `ecdsa-rdfc-2019` assertion proof, or the document lacks exactly one
+/// `VerifiableCredential` with an IRI issuer and subject, one credential
+/// type and a valid issuance date.
+pub fn parse(credential_data: &[u8]) -> Option<Credential> {
+    let envelope: Envelope = serde_json::from_slice(credential_data).ok()?;
+    let document = rdf::parse(&envelope.document)?;
+    let proof = rdf::parse(&envelope.proof)?;
+
+    let proof_node = node_of_type(&proof, &format!("{SECURITY}DataIntegrityProof"))?;
+    let cryptosuite = object(&proof, &proof_node, &format!("{SECURITY}cryptosuite"))?;
+    let purpose = object(&proof, &proof_node, &format!("{SECURITY}proofPurpose"))?;
+    if literal(cryptosuite)? != CRYPTOSUITE || iri(purpose)? != format!("{SECURITY}assertionMethod")
+    {
+        return None;
+    }
+
+    let credential = node_of_type(&document, &format!("{CREDENTIALS}VerifiableCredential"))?;
+    let issuer = object(&document, &credential, &format!("{CREDENTIALS}issuer"))?;
+    let subject = object(
+        &document,
+        &credential,
+        &format!("{CREDENTIALS}credentialSubject"),
+    )?;
+    iri(issuer)?;
+    let credential_type = credential_type(&document, &credential)?.into();
+    let issued_at = date(&document, &credential, ["issuanceDate", "validFrom"])??;
+    let expires_at = date(&document, &credential, ["expirationDate", "validUntil"])?;
+
+    let mut verify_data = Sha256::digest(rdf::canonicalize(&proof)).to_vec();
+    verify_data.extend_from_slice(&Sha256::digest(rdf::canonicalize(&document)));
+
+    Some(Credential {
+        subject: iri(subject)?.into(),
+        credential_type,
+        issued_at,
+        expires_at: expires_at.unwrap_or(0),
+        verify_data,
+    })
+}
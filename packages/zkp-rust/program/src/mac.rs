@@ -0,0 +1,33 @@
+//! HMAC-SHA256 credentials for private/internal issuers
+//!
+//! Issuers operating fully off-chain can authenticate credentials with a
+//! symmetric key instead of a signature. The key stays private to the
+//! prover; only a commitment to it is public, and the circuit checks both
+//! the commitment and the MAC.
+
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+/// Domain tag for HMAC key commitments
+pub const KEY_COMMITMENT_DOMAIN: &[u8] = b"CREDENCE_HMAC_KEY_V1";
+
+/// Computes the public commitment to an HMAC key
+pub fn key_commitment(key: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(KEY_COMMITMENT_DOMAIN);
+    hasher.update(key);
+    hasher.finalize().into()
+}
+
+/// Verifies `tag` is HMAC-SHA256(key, message) and `key` opens `commitment`
+pub fn verify(key: &[u8], commitment: &[u8], message: &[u8], tag: &[u8]) -> bool {
+    if key_commitment(key) != commitment {
+        return false;
+    }
+
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(key) else {
+        return false;
+    };
+    mac.update(message);
+    mac.verify_slice(tag).is_ok()
+}
@@ -11,101 +11,82 @@
 #![no_main]
 sp1_zkvm::entrypoint!(main);
 
-use sha2::{Sha256, Digest};
-use serde::{Deserialize, Serialize};
-
-/// Credential input data (private to the prover)
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CredentialInput {
-    /// The subject's Ethereum address (20 bytes as hex string)
-    pub subject: [u8; 20],
-    /// The credential type (e.g., 1=KYC, 2=Accredited, etc.)
-    pub credential_type: u32,
-    /// Raw credential data (contains claims and metadata)
-    pub credential_data: Vec<u8>,
-    /// Issuer's signature over the credential
-    pub signature: Vec<u8>,
-    /// Issuer's public key
-    pub issuer_pubkey: Vec<u8>,
-    /// Issuance timestamp
-    pub issued_at: u64,
-    /// Expiration timestamp (0 for no expiration)
-    pub expires_at: u64,
-    /// Current timestamp for verification
-    pub current_time: u64,
-}
+mod accumulator;
+mod anoncreds;
+mod bbs;
+mod claims;
+mod delegation;
+mod did;
+mod disclosure;
+mod freshness;
+mod hash;
+mod jwt;
+mod key_registry;
+mod keyed;
+mod ld;
+mod mac;
+mod mdl;
+mod merkle;
+mod nullifier;
+mod policy;
+mod predicate;
+mod quorum;
+mod rdf;
+mod revocation;
+mod ring;
+mod rsa;
+mod sanctions;
+mod schema;
+mod sd_jwt;
+mod signature;
+mod smt;
+mod status_list;
+mod time;
+mod trusted;
+mod vc;
+mod vrf;
+mod webauthn;
+mod x509;
 
-/// Public output values that will be verified on-chain
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PublicOutput {
-    /// The subject's address
-    pub subject: [u8; 20],
-    /// The credential type
-    pub credential_type: u32,
-    /// Hash of the credential for uniqueness
-    pub credential_hash: [u8; 32],
-    /// When the credential was issued
-    pub issued_at: u64,
-    /// When the credential expires
-    pub expires_at: u64,
-}
+use alloy_sol_types::SolValue;
+use credential_verifier_lib::eip712;
+use credential_verifier_lib::message;
+pub use credential_verifier_lib::{
+    base_credential_types, declares_credential_type, AccumulatorWitness, BatchOutputs, ClaimProof,
+    ClaimSchema, CredentialFormat, CredentialInput, CredentialOutput, Delegation, DelegationChain,
+    DeviceAssertion, Eip712Domain, FieldType, HashAlgorithm, IssuerMembership, IssuerQuorum,
+    KeyRegistry, NativeOutputs, NullifierInput, Policy, PolicyInput, PolicyOutputs, Predicate,
+    ProgramInput, PublicOutput, RevocationExclusion, RevocationFreshness, RevocationMethod,
+    RingMembership, SanctionsExclusion, SchemaField, SignatureScheme, SparseClaimProof,
+    StatusListEntry, StatusPurpose, TimestampGranularity, OUTPUT_VERSION,
+};
 
-/// Verifies an ECDSA signature (simplified for demonstration)
-/// In production, this would use proper ECDSA verification
-fn verify_signature(message: &[u8], signature: &[u8], pubkey: &[u8]) -> bool {
-    // For demonstration purposes, we verify that:
-    // 1. Signature is not empty
-    // 2. Public key is valid length (33 or 65 bytes for compressed/uncompressed)
-    // 3. Signature length is valid (64 or 65 bytes)
+/// Validates credential data contains required claims
+///
+/// Claim proofs are only accepted for the tree formats whose claims they
+/// prove: `claim_proofs` for v4 (Merkle root) data and
+/// `sparse_claim_proofs` for v5 (sparse Merkle tree) data.
+fn validate_credential_claims(input: &CredentialInput, credential_type: u32) -> bool {
+    let credential_data = &input.credential_data;
 
-    if signature.is_empty() || signature.len() < 64 {
+    // A bitmask type must meet the requirements of every type it combines
+    let base_types = base_credential_types(credential_type);
+    if base_types.is_empty() {
         return false;
     }
-
-    if pubkey.is_empty() || (pubkey.len() != 33 && pubkey.len() != 65) {
-        return false;
+    if base_types != [credential_type] {
+        return base_types
+            .iter()
+            .all(|&base| validate_credential_claims(input, base));
     }
 
-    // In a real implementation, you would use:
-    // - secp256k1 ECDSA verification
-    // - Or Ed25519 signature verification
-    // - The SP1 zkVM supports these cryptographic operations
-
-    // For now, we do a simplified check
-    // Hash the message and verify the signature matches expected format
-    let mut hasher = Sha256::new();
-    hasher.update(message);
-    let _message_hash = hasher.finalize();
-
-    // Placeholder verification - replace with actual ECDSA in production
-    true
-}
-
-/// Computes the credential hash
-fn compute_credential_hash(
-    subject: &[u8; 20],
-    credential_type: u32,
-    credential_data: &[u8],
-    issuer_pubkey: &[u8],
-) -> [u8; 32] {
-    let mut hasher = Sha256::new();
-    hasher.update(subject);
-    hasher.update(credential_type.to_be_bytes());
-    hasher.update(credential_data);
-    hasher.update(issuer_pubkey);
-
-    let result = hasher.finalize();
-    let mut hash = [0u8; 32];
-    hash.copy_from_slice(&result);
-    hash
-}
-
-/// Validates credential data contains required claims
-fn validate_credential_claims(credential_data: &[u8], credential_type: u32) -> bool {
     // Credential data format (simplified):
     // - First 4 bytes: version
     // - Next 4 bytes: claim count
-    // - Remaining: claim data
+    // - Remaining: claim data (typed TLV records in version 2, key-value
+    //   records in version 3; version 4 has a tree depth and Merkle root
+    //   in place of the count and claims, and version 5 a sparse Merkle
+    //   root)
 
     if credential_data.len() < 8 {
         return false;
@@ -118,7 +99,32 @@ fn validate_credential_claims(credential_data: &[u8], credential_type: u32) -> b
         credential_data[3],
     ]);
 
-    // Only support version 1
+    // Version 2 carries typed claims that are checked against the
+    // registry, version 3 named claims that are checked by key and
+    // versions 4 and 5 roots the proven claims are checked against;
+    // otherwise only version 1 is supported
+    let claim_proofs = &input.claim_proofs;
+    let sparse_claim_proofs = &input.sparse_claim_proofs;
+    if version == merkle::FORMAT_V4 && sparse_claim_proofs.is_empty() {
+        return merkle::validate(credential_data, claim_proofs, credential_type);
+    }
+    if version == smt::FORMAT_V5 && claim_proofs.is_empty() {
+        return smt::validate(
+            credential_data,
+            sparse_claim_proofs,
+            credential_type,
+            input.issued_at,
+        );
+    }
+    if !claim_proofs.is_empty() || !sparse_claim_proofs.is_empty() {
+        return false;
+    }
+    if version == claims::FORMAT_V2 {
+        return claims::validate(credential_data, credential_type);
+    }
+    if version == keyed::FORMAT_V3 {
+        return keyed::validate(credential_data, credential_type, input.issued_at);
+    }
     if version != 1 {
         return false;
     }
@@ -142,64 +148,842 @@ fn validate_credential_claims(credential_data: &[u8], credential_type: u32) -> b
 }
 
 fn main() {
-    // Read the credential input from the prover
-    let input: CredentialInput = sp1_zkvm::io::read();
+    // Read the borsh-encoded input from the prover. The cycle-tracker
+    // lines are read by the executor, which reports each stage's cycles
+    // (summed over a batch's credentials) to the `bench` binary
+    println!("cycle-tracker-report-start: deserialize");
+    let input = credential_verifier_lib::decode(&sp1_zkvm::io::read_vec())
+        .expect("Malformed credential input");
+    println!("cycle-tracker-report-end: deserialize");
+
+    sp1_zkvm::io::commit_slice(&public_values(input));
+}
+
+/// Verifies the input and returns its public values
+///
+/// The values are ABI-encoded for contracts to `abi.decode` and start with
+/// the layout version. After it a single output continues with the subject
+/// address, a batch with the 0x40 offset of its outputs and a policy
+/// evaluation with the policy hash, while a digest is only 32 bytes long,
+/// so the layouts can't be mistaken for one another. Solana and Move
+/// inputs are borsh- and BCS-encoded instead, leading with the version as
+/// a little-endian `u32`.
+fn public_values(input: ProgramInput) -> Vec<u8> {
+    match input {
+        ProgramInput::Single(credential) => {
+            CredentialOutput::from(&verify_credential(*credential)).abi_encode_params()
+        }
+        ProgramInput::Batch(credentials) => BatchOutputs {
+            version: OUTPUT_VERSION,
+            outputs: verify_batch(credentials).iter().map(Into::into).collect(),
+        }
+        .abi_encode_params(),
+        ProgramInput::Policy(policy_input) => {
+            let (outputs, policy_hash, policy_satisfied) = verify_policy(policy_input);
+            PolicyOutputs {
+                version: OUTPUT_VERSION,
+                policyHash: policy_hash.into(),
+                policySatisfied: policy_satisfied,
+                outputs: outputs.iter().map(Into::into).collect(),
+            }
+            .abi_encode_params()
+        }
+        // Where calldata dominates gas, e.g. on L2s, the proof commits
+        // just a hash and the contract re-hashes the values it's passed
+        ProgramInput::Digest(inner) => {
+            assert!(
+                matches!(
+                    *inner,
+                    ProgramInput::Single(_) | ProgramInput::Batch(_) | ProgramInput::Policy(_)
+                ),
+                "Digests only wrap ABI-encoded inputs"
+            );
+            hash::keccak256(&public_values(*inner)).to_vec()
+        }
+        ProgramInput::Solana(inner) => native_outputs(*inner)
+            .expect("Solana outputs only wrap single, batch or policy inputs")
+            .to_bytes(),
+        ProgramInput::Move(inner) => native_outputs(*inner)
+            .expect("Move outputs only wrap single, batch or policy inputs")
+            .to_bcs(),
+    }
+}
+
+/// Verifies a single, batch or policy input into its outputs for Solana or
+/// Move, or returns `None` for another wrapper
+fn native_outputs(input: ProgramInput) -> Option<NativeOutputs> {
+    let (outputs, policy) = match input {
+        ProgramInput::Single(credential) => (vec![verify_credential(*credential)], None),
+        ProgramInput::Batch(credentials) => (verify_batch(credentials), None),
+        ProgramInput::Policy(policy_input) => {
+            let (outputs, policy_hash, policy_satisfied) = verify_policy(policy_input);
+            (outputs, Some((policy_hash, policy_satisfied)))
+        }
+        ProgramInput::Digest(_) | ProgramInput::Solana(_) | ProgramInput::Move(_) => return None,
+    };
+    Some(NativeOutputs {
+        version: OUTPUT_VERSION,
+        policy,
+        outputs,
+    })
+}
+
+/// Verifies a policy's credentials and returns their outputs, the policy
+/// hash and whether the credentials satisfy it
+fn verify_policy(policy_input: PolicyInput) -> (Vec<PublicOutput>, [u8; 32], bool) {
+    let outputs = verify_batch(policy_input.credentials);
+    let credential_types: Vec<u32> = outputs
+        .iter()
+        .flat_map(|output| base_credential_types(output.credential_type))
+        .collect();
+    let hash = policy::policy_hash(&policy_input.policy);
+    let satisfied = policy::evaluate(&policy_input.policy, &credential_types);
+    (outputs, hash, satisfied)
+}
+
+/// Verifies a batch of credentials that must share a subject
+fn verify_batch(credentials: Vec<CredentialInput>) -> Vec<PublicOutput> {
+    let subject = credentials.first().expect("Empty credential batch").subject;
+    assert!(
+        credentials.iter().all(|credential| credential.subject == subject),
+        "Batched credentials must share a subject"
+    );
+    credentials.into_iter().map(verify_credential).collect()
+}
+
+/// Verifies one credential and returns its public output
+///
+/// Panics if any check fails, which aborts proof generation.
+fn verify_credential(mut input: CredentialInput) -> PublicOutput {
+    // JSON, CBOR and AnonCreds credentials carry their own timestamps
+    // (and, except for mDL, subject; and except for W3C, signature), which
+    // replace the corresponding input fields
+    println!("cycle-tracker-report-start: parse");
+    let mut disclosed_claims = None;
+    let mut anoncreds_credential = None;
+    let format_signing_input = match input.credential_format {
+        CredentialFormat::Binary => None,
+        CredentialFormat::Jwt | CredentialFormat::SdJwt => {
+            assert!(
+                input.signature_scheme == SignatureScheme::Secp256k1,
+                "JWT credentials must be signed with ES256K"
+            );
+            let jwt = if input.credential_format == CredentialFormat::SdJwt {
+                let sd_jwt =
+                    sd_jwt::parse(&input.credential_data).expect("Malformed SD-JWT credential");
+                disclosed_claims = Some(sd_jwt.disclosed);
+                sd_jwt.jwt
+            } else {
+                jwt::parse(&input.credential_data).expect("Malformed JWT credential")
+            };
+            assert!(
+                declares_credential_type(&jwt.claims.vct, input.credential_type),
+                "Credential type does not match the JWT's vct"
+            );
+            input.subject = did::address(&jwt.claims.sub).expect("Invalid JWT subject");
+            input.issued_at = jwt.claims.iat;
+            input.expires_at = jwt.claims.exp;
+            input.signature = jwt.signature;
+            Some(jwt.signing_input)
+        }
+        CredentialFormat::Mdl => {
+            assert!(
+                input.signature_scheme == SignatureScheme::Secp256r1,
+                "mDL credentials must be signed with ES256"
+            );
+            // An mDL names no Ethereum account, so the prover supplies the
+            // subject and the MSO's device key must be its key
+            let mdl = mdl::parse(&input.credential_data).expect("Malformed mDL credential");
+            assert!(
+                declares_credential_type(&mdl.doc_type, input.credential_type),
+                "Credential type does not match the mDL's docType"
+            );
+            assert!(
+                signature::issuer_address(&mdl.device_key) == Some(input.subject),
+                "Subject does not match the mDL's device key"
+            );
+            input.issued_at = mdl.valid_from;
+            input.expires_at = mdl.valid_until;
+            input.signature = mdl.issuer_auth.signature.clone();
+            disclosed_claims = Some(mdl.disclosed);
+            Some(mdl.issuer_auth.tbs_data(&[]))
+        }
+        CredentialFormat::AnonCreds => {
+            assert!(
+                input.signature_scheme == SignatureScheme::AnonCredsCl,
+                "AnonCreds credentials must use CL signatures"
+            );
+            // The CL signature only covers the attributes, which must
+            // carry the subject, type and validity period
+            let credential =
+                anoncreds::parse(&input.credential_data).expect("Malformed AnonCreds credential");
+            let bound = credential
+                .bound()
+                .expect("AnonCreds credentials must carry the bound attributes");
+            assert!(
+                declares_credential_type(&bound.credential_type, input.credential_type),
+                "Credential type does not match the credential_type attribute"
+            );
+            input.subject = did::address(&bound.subject).expect("Invalid AnonCreds subject");
+            input.issued_at = bound.issued_at;
+            input.expires_at = bound.expires_at;
+            anoncreds_credential = Some(credential);
+            None
+        }
+        CredentialFormat::LdProof => {
+            assert!(
+                input.signature_scheme == SignatureScheme::Secp256r1,
+                "ecdsa-rdfc-2019 proofs must be signed with ES256"
+            );
+            let ld = ld::parse(&input.credential_data).expect("Malformed linked-data credential");
+            assert!(
+                declares_credential_type(&ld.credential_type, input.credential_type),
+                "Credential type does not match the credential's type"
+            );
+            input.subject = did::address(&ld.subject).expect("Invalid credential subject");
+            input.issued_at = ld.issued_at;
+            input.expires_at = ld.expires_at;
+            Some(ld.verify_data)
+        }
+        CredentialFormat::W3cVc => {
+            let vc = vc::parse(&input.credential_data).expect("Malformed W3C credential");
+            input.subject = did::address(&vc.subject).expect("Invalid credential subject");
+            input.issued_at = vc.issued_at;
+            input.expires_at = vc.expires_at;
+
+            // An issuer id that embeds an Ethereum address must belong to
+            // the signing key
+            if let (Some(issuer), SignatureScheme::Secp256k1 | SignatureScheme::Eip712) =
+                (did::address(&vc.issuer), input.signature_scheme)
+            {
+                assert!(
+                    signature::issuer_address(&input.issuer_pubkey) == Some(issuer),
+                    "Credential issuer does not match the signing key"
+                );
+            }
+            None
+        }
+    };
+    println!("cycle-tracker-report-end: parse");
 
     // Validate credential type
     assert!(input.credential_type > 0, "Invalid credential type");
 
+    // Every time-dependent check runs as of the prover's current time,
+    // which is committed as `as_of` for contracts to hold against the
+    // block time
+    let as_of = input.current_time;
+
     // Validate timestamps
     assert!(input.issued_at > 0, "Invalid issuance time");
-    assert!(
-        input.current_time >= input.issued_at,
-        "Current time before issuance"
-    );
+    assert!(as_of >= input.issued_at, "Current time before issuance");
 
     // Check expiration if set
     if input.expires_at > 0 {
-        assert!(
-            input.current_time <= input.expires_at,
-            "Credential has expired"
-        );
+        assert!(as_of <= input.expires_at, "Credential has expired");
     }
 
-    // Verify the signature
-    let signature_valid = verify_signature(
-        &input.credential_data,
-        &input.signature,
-        &input.issuer_pubkey,
-    );
+    // Issuers sign the domain-separated canonical message, except EIP-712
+    // issuers which sign a typed-data digest, and JWT, mDL and linked-data
+    // issuers which sign the JWS signing input, COSE `Sig_structure` or
+    // canonical-dataset verify data
+    let format_signs_message = format_signing_input.is_none();
+    let message = match (format_signing_input, input.signature_scheme) {
+        (Some(signing_input), _) => signing_input,
+        (None, SignatureScheme::Eip712) => {
+            let domain = input
+                .eip712_domain
+                .as_ref()
+                .expect("Missing EIP-712 domain");
+            eip712::credential_digest(&input, domain).to_vec()
+        }
+        (None, _) => message::signing_message(&input),
+    };
+
+    // ECDSA issuers sign a digest under the credential's hash suite, except
+    // JWS (and so SD-JWT), COSE and ecdsa-rdfc-2019 which fix SHA-256
+    let message_hash = match input.credential_format {
+        CredentialFormat::Binary | CredentialFormat::W3cVc | CredentialFormat::AnonCreds => {
+            input.hash_algorithm
+        }
+        CredentialFormat::Jwt
+        | CredentialFormat::SdJwt
+        | CredentialFormat::Mdl
+        | CredentialFormat::LdProof => HashAlgorithm::Sha256,
+    };
+
+    // Verify the signature. Quorum credentials need m-of-n co-signatures,
+    // BBS+ credentials sign a message vector whose first entry is the
+    // signing message, and AnonCreds sign their attribute values.
+    println!("cycle-tracker-report-start: signature");
+    let signature_valid = if let Some(quorum) = &input.quorum {
+        quorum::verify_quorum(input.signature_scheme, message_hash, &message, quorum)
+    } else if let (SignatureScheme::BbsPlus, Some(bbs)) = (input.signature_scheme, &input.bbs) {
+        assert!(
+            bbs.messages.first() == Some(&message),
+            "First BBS+ message must be the signing message"
+        );
+        bbs::verify(&bbs.messages, &input.signature, &input.issuer_pubkey)
+    } else if let Some(credential) = &anoncreds_credential {
+        let link_secret = input.link_secret.as_ref().expect("Missing link secret");
+        anoncreds::verify(credential, &input.issuer_pubkey, link_secret)
+    } else if input.signature_scheme == SignatureScheme::HmacSha256 {
+        let key = input.hmac_key.as_ref().expect("Missing HMAC key");
+        mac::verify(key, &input.issuer_pubkey, &message, &input.signature)
+    } else {
+        signature::verify_signature(
+            input.signature_scheme,
+            message_hash,
+            &message,
+            &input.signature,
+            &input.issuer_pubkey,
+        )
+    };
     assert!(signature_valid, "Invalid signature");
+    println!("cycle-tracker-report-end: signature");
 
-    // Validate credential claims
-    let claims_valid = validate_credential_claims(
-        &input.credential_data,
-        input.credential_type,
+    // Delegated issuers prove their authority back to a root issuer
+    let root_issuer_hash = match &input.delegation {
+        Some(chain) => {
+            assert!(
+                input.quorum.is_none(),
+                "Delegation chains require a single issuer key"
+            );
+            assert!(
+                delegation::verify(
+                    input.signature_scheme,
+                    chain,
+                    &input.issuer_pubkey,
+                    input.credential_type,
+                    as_of,
+                ),
+                "Invalid delegation chain"
+            );
+            delegation::root_issuer_hash(input.hash_algorithm, input.signature_scheme, chain)
+        }
+        None => [0u8; 32],
+    };
+
+    // Rotating issuers show their root key lists the signing key in a
+    // registry, committed like a delegation root
+    let (root_issuer_hash, key_registry_version) = match &input.key_registry {
+        Some(registry) => {
+            assert!(
+                input.quorum.is_none() && input.delegation.is_none(),
+                "Key registries require a single, undelegated issuer key"
+            );
+            assert!(
+                key_registry::verify(
+                    input.signature_scheme,
+                    registry,
+                    &input.issuer_pubkey,
+                    as_of,
+                ),
+                "Issuer key is not a current key of the registry"
+            );
+            let root = key_registry::root_issuer_hash(
+                input.hash_algorithm,
+                input.signature_scheme,
+                registry,
+            );
+            (root, registry.version)
+        }
+        None => (root_issuer_hash, 0),
+    };
+
+    // Enterprise issuers prove their key chains up to a pinned root CA
+    let root_ca_hash = if input.issuer_cert_chain.is_empty() {
+        [0u8; 32]
+    } else {
+        x509::verify_chain(
+            &input.issuer_cert_chain,
+            input.signature_scheme,
+            &input.issuer_pubkey,
+            as_of,
+        )
+        .expect("Invalid issuer certificate chain")
+    };
+
+    // Issuer-assigned randomness, e.g. for lottery-style eligibility
+    let vrf_output_hash = match &input.vrf_proof {
+        Some(proof) => {
+            assert!(
+                input.signature_scheme == SignatureScheme::Secp256r1,
+                "VRF proofs require a P-256 issuer key"
+            );
+            vrf::verify(&input.issuer_pubkey, &message, proof).expect("Invalid VRF proof")
+        }
+        None => [0u8; 32],
+    };
+
+    // Anonymous issuers prove membership of a trusted set, and nothing that
+    // identifies them is committed
+    let trusted_issuers_root = match &input.issuer_membership {
+        Some(membership) => {
+            assert!(
+                input.quorum.is_none()
+                    && input.delegation.is_none()
+                    && input.key_registry.is_none()
+                    && input.hmac_key.is_none(),
+                "Anonymous issuers must sign alone with a public-key scheme"
+            );
+            trusted::trusted_set_root(input.signature_scheme, &input.issuer_pubkey, membership)
+                .expect("Malformed trusted-issuer path")
+        }
+        None => [0u8; 32],
+    };
+
+    // Listed issuers commit the root of the on-chain registry, so contracts
+    // compare one root instead of keeping pubkey mappings. Delegates and
+    // rotating keys are accountable through their root key
+    let issuer_registry_root = match &input.issuer_registry {
+        Some(path) => {
+            assert!(
+                input.quorum.is_none()
+                    && input.issuer_membership.is_none()
+                    && input.hmac_key.is_none(),
+                "Issuer registries list a single, identified public-key issuer"
+            );
+            let issuer_key = match (&input.delegation, &input.key_registry) {
+                (Some(chain), _) => &chain.root_pubkey,
+                (None, Some(registry)) => &registry.root_pubkey,
+                (None, None) => &input.issuer_pubkey,
+            };
+            trusted::trusted_set_root(input.signature_scheme, issuer_key, path)
+                .expect("Malformed issuer registry path")
+        }
+        None => [0u8; 32],
+    };
+
+    // Derive the issuer's Ethereum address so contracts can check it
+    // against an address-based allow list instead of raw pubkeys
+    let anonymous_issuer = input.issuer_membership.is_some();
+    let issuer_address = match input.signature_scheme {
+        SignatureScheme::Secp256k1 | SignatureScheme::Eip712
+            if input.quorum.is_none() && !anonymous_issuer =>
+        {
+            signature::issuer_address(&input.issuer_pubkey).expect("Invalid issuer pubkey")
+        }
+        _ => [0u8; 20],
+    };
+
+    // Only the key commitment of an HMAC issuer is made public
+    let hmac_key_commitment = match &input.hmac_key {
+        Some(key) => mac::key_commitment(key),
+        None => [0u8; 32],
+    };
+
+    let quorum_hash = match &input.quorum {
+        Some(quorum) => quorum::policy_hash(input.hash_algorithm, input.signature_scheme, quorum),
+        None => [0u8; 32],
+    };
+
+    // Bind the holder-selected BBS+ messages, SD-JWT claims, mDL elements
+    // or Merkle-proven claims into the output
+    let disclosure_hash = match (&input.bbs, &disclosed_claims) {
+        (Some(bbs), _) => {
+            bbs::disclosed_messages_hash(input.hash_algorithm, &bbs.messages, &bbs.disclosed)
+        }
+        (None, Some(names)) => hash::claim_names_hash(input.hash_algorithm, names),
+        (None, None) if !input.claim_proofs.is_empty() => {
+            merkle::proven_claims_hash(input.hash_algorithm, &input.claim_proofs)
+        }
+        (None, None) if !input.sparse_claim_proofs.is_empty() => {
+            smt::proven_keys_hash(input.hash_algorithm, &input.sparse_claim_proofs)
+        }
+        (None, None) => [0u8; 32],
+    };
+
+    assert!(
+        !base_credential_types(input.credential_type).is_empty(),
+        "Credential type bitmask attests nothing"
     );
+
+    // Validate credential claims (JSON formats were checked when parsing)
+    println!("cycle-tracker-report-start: claims");
+    let claims_valid = match input.credential_format {
+        CredentialFormat::Binary => validate_credential_claims(&input, input.credential_type),
+        CredentialFormat::Jwt
+        | CredentialFormat::W3cVc
+        | CredentialFormat::SdJwt
+        | CredentialFormat::Mdl
+        | CredentialFormat::AnonCreds
+        | CredentialFormat::LdProof => {
+            input.claim_proofs.is_empty() && input.sparse_claim_proofs.is_empty()
+        }
+    };
     assert!(claims_valid, "Invalid credential claims");
+    println!("cycle-tracker-report-end: claims");
+
+    // Check the claims against the issuer's registered schema
+    let schema_hash = match &input.claim_schema {
+        Some(claim_schema) => {
+            assert!(
+                input.credential_format == CredentialFormat::Binary,
+                "Claim schemas apply to binary credentials"
+            );
+            assert!(
+                schema::validate(claim_schema, &input.credential_data),
+                "Credential data does not match the claim schema"
+            );
+            schema::schema_hash(input.hash_algorithm, claim_schema)
+        }
+        None => [0u8; 32],
+    };
+
+    // Answer the verifier's predicates over the now-validated claims
+    let predicate_results = predicate::results(&input, as_of);
+
+    // Screen the subject against a sanctions list snapshot
+    let (sanctions_root, sanctions_list_version) = match &input.sanctions_exclusion {
+        Some(exclusion) => {
+            let root = sanctions::excluding_root(&input.subject, exclusion)
+                .expect("Sanctions entries must be neighbours bracketing the subject");
+            (root, exclusion.list_version)
+        }
+        None => ([0u8; 32], 0),
+    };
 
     // Compute the credential hash
-    let credential_hash = compute_credential_hash(
+    println!("cycle-tracker-report-start: hash");
+    let credential_hash = hash::credential_hash(
+        input.hash_algorithm,
         &input.subject,
         input.credential_type,
         &input.credential_data,
         &input.issuer_pubkey,
     );
+    println!("cycle-tracker-report-end: hash");
+
+    // A verifier-chosen blind keeps proofs of one credential to different
+    // verifiers from sharing a public hash
+    let committed_hash = match &input.credential_hash_blind {
+        Some(blind) => hash::hash_parts(input.hash_algorithm, &[&credential_hash, blind]),
+        None => credential_hash,
+    };
+
+    // Nullifiers, revocation lists and accumulators key on the SHA-256
+    // credential hash whatever the suite, so a prover can't derive a fresh
+    // nullifier or dodge a revocation by picking another
+    let sha256_credential_hash = match input.hash_algorithm {
+        HashAlgorithm::Sha256 => credential_hash,
+        _ => hash::credential_hash(
+            HashAlgorithm::Sha256,
+            &input.subject,
+            input.credential_type,
+            &input.credential_data,
+            &input.issuer_pubkey,
+        ),
+    };
 
-    // Create the public output
-    let output = PublicOutput {
-        subject: input.subject,
+    // Holder secrets, device keys and disclosure seeds are only bound where
+    // the issuer signed the canonical message, which covers their
+    // commitments
+    let signs_canonical_message = format_signs_message
+        && !matches!(
+            input.signature_scheme,
+            SignatureScheme::Eip712 | SignatureScheme::AnonCredsCl
+        );
+
+    // Reveal the holder-chosen binary claims one salted hash at a time, and
+    // the rest only through their root
+    let (revealed_claims, withheld_claims_root) = if input.revealed_claims.is_empty() {
+        (Vec::new(), [0u8; 32])
+    } else {
+        assert!(
+            input.credential_format == CredentialFormat::Binary,
+            "Only binary claims can be revealed"
+        );
+        assert!(
+            signs_canonical_message,
+            "Revealed claims need a credential signed over the canonical message"
+        );
+        let seed = input
+            .disclosure_seed
+            .as_ref()
+            .expect("Revealed claims need the credential's disclosure seed");
+        let disclosure = disclosure::disclose(&input.credential_data, &input.revealed_claims, seed)
+            .expect("Revealed claims must be increasing positions in v2 or v3 claims");
+        (disclosure.revealed, disclosure.withheld_root)
+    };
+
+    let (nullifier_scope, nullifier) = match &input.nullifier {
+        Some(holder) => {
+            assert!(
+                signs_canonical_message,
+                "Nullifiers need a credential signed over the canonical message"
+            );
+            let nullifier = nullifier::nullifier(&sha256_credential_hash, holder);
+            (holder.scope, nullifier)
+        }
+        None => ([0u8; 32], [0u8; 32]),
+    };
+
+    // Relayed proofs can hide the subject behind a salted commitment, which
+    // the subject opens to the contract when binding it to their account
+    let (subject, subject_commitment) = match &input.subject_salt {
+        Some(salt) => (
+            [0u8; 20],
+            hash::hash_parts(input.hash_algorithm, &[&input.subject, salt]),
+        ),
+        None => (input.subject, [0u8; 32]),
+    };
+
+    // Pseudonymous proofs replace the subject with an identity that stays
+    // stable for one verifier but is unlinkable across verifiers
+    let (subject, pseudonym) = match &input.verifier_id {
+        Some(verifier_id) => {
+            let holder = input
+                .nullifier
+                .as_ref()
+                .expect("Pseudonyms need the holder secret of a nullifier");
+            assert!(
+                input.subject_salt.is_none(),
+                "Pseudonyms can't be combined with a subject commitment"
+            );
+            let pseudonym = nullifier::pseudonym(&holder.secret, verifier_id);
+            ([0u8; 20], pseudonym)
+        }
+        None => (subject, [0u8; 32]),
+    };
+
+    // Holder binding shows the prover controls the subject address, not
+    // just a copy of the credential, by signing this request's nonce
+    let holder_bound = match &input.holder_signature {
+        Some(holder_signature) => {
+            assert!(
+                input.verifier_nonce != [0u8; 32],
+                "Holder binding needs a verifier nonce"
+            );
+            assert!(
+                signature::verify_personal_sign(
+                    &input.verifier_nonce,
+                    holder_signature,
+                    &input.subject
+                ),
+                "Invalid holder signature"
+            );
+            true
+        }
+        None => false,
+    };
+
+    // Device-bound credentials can only be proven by their passkey, which
+    // must answer this request's nonce
+    let device_rp_id_hash = match &input.device_pubkey {
+        Some(device_pubkey) => {
+            let assertion = input
+                .device_assertion
+                .as_ref()
+                .expect("Device-bound credentials need a WebAuthn assertion");
+            assert!(
+                signs_canonical_message,
+                "Device binding needs a credential signed over the canonical message"
+            );
+            assert!(
+                input.verifier_nonce != [0u8; 32],
+                "Device binding needs a verifier nonce"
+            );
+            webauthn::verify(device_pubkey, assertion, &input.verifier_nonce)
+                .expect("Invalid WebAuthn assertion")
+        }
+        None => {
+            assert!(
+                input.device_assertion.is_none(),
+                "WebAuthn assertion without a device key"
+            );
+            [0u8; 32]
+        }
+    };
+
+    // A biometric binding was checked by the signature over the canonical
+    // message, which covers the fresh template hash's commitment
+    let biometric_bound = input.biometric_template_hash.is_some();
+    assert!(
+        !biometric_bound || signs_canonical_message,
+        "Biometric binding needs a credential signed over the canonical message"
+    );
+
+    // Single-use credentials commit their signed serial number's hash,
+    // which is the same in every proof of the credential
+    let serial_hash = match &input.serial_number {
+        Some(serial_number) => {
+            assert!(
+                signs_canonical_message,
+                "Serial numbers need a credential signed over the canonical message"
+            );
+            message::serial_hash(serial_number)
+        }
+        None => [0u8; 32],
+    };
+
+    // Check the credential against at most one issuer revocation registry,
+    // committing its root and version for contracts to match against the
+    // issuer's current ones
+    let registries = [
+        input.revocation_exclusion.is_some(),
+        input.status_list.is_some(),
+        input.accumulator_witness.is_some(),
+    ];
+    assert!(
+        registries.iter().filter(|&&checked| checked).count() <= 1,
+        "Credentials are checked against one revocation registry at a time"
+    );
+    let (revocation_method, revocation_root, revocation_version) = match (
+        &input.revocation_exclusion,
+        &input.status_list,
+        &input.accumulator_witness,
+    ) {
+        (Some(exclusion), _, _) => {
+            let root = revocation::excluding_root(&sha256_credential_hash, exclusion)
+                .expect("Revocation entries must be neighbours bracketing the credential hash");
+            (RevocationMethod::MerkleList, root, exclusion.list_version)
+        }
+        // StatusList2021 entries must themselves be signed to pin the list,
+        // bit and purpose
+        (_, Some(entry), _) => {
+            assert!(
+                signs_canonical_message,
+                "Status list entries need a credential signed over the canonical message"
+            );
+            let root = status_list::unset_list_hash(
+                input.hash_algorithm,
+                input.signature_scheme,
+                message_hash,
+                &input.issuer_pubkey,
+                entry,
+            )
+            .expect("Status list must be the issuer's and show the credential's bit unset");
+            (RevocationMethod::StatusList2021, root, entry.list_version)
+        }
+        // Accumulators need only a constant-size witness
+        (_, _, Some(witness)) => {
+            let root = accumulator::non_revoked_hash(
+                input.hash_algorithm,
+                &sha256_credential_hash,
+                witness,
+            )
+            .expect("Invalid accumulator non-membership witness");
+            (RevocationMethod::Accumulator, root, witness.version)
+        }
+        (None, None, None) => (RevocationMethod::None, [0u8; 32], 0),
+    };
+
+    // Revocation lists and accumulators only record permanent revocation,
+    // while status lists may be suspension lists
+    let status_purpose = match (revocation_method, &input.status_list) {
+        (RevocationMethod::None, _) => StatusPurpose::None,
+        (RevocationMethod::StatusList2021, Some(entry)) => entry.purpose,
+        _ => StatusPurpose::Revocation,
+    };
+
+    // Verifiers can bound how old that root may be, going by the issuer's
+    // signed publication time
+    let revocation_max_age = match &input.revocation_freshness {
+        Some(freshness) => {
+            assert!(
+                revocation_method != RevocationMethod::None,
+                "Revocation freshness needs a revocation check"
+            );
+            let message = freshness::freshness_message(
+                revocation_method,
+                &revocation_root,
+                revocation_version,
+                freshness.published_at,
+            );
+            assert!(
+                freshness::verify_signature(
+                    input.signature_scheme,
+                    message_hash,
+                    &message,
+                    &freshness.signature,
+                    &input.issuer_pubkey,
+                ),
+                "Invalid revocation publication signature"
+            );
+            assert!(
+                freshness::is_fresh(freshness, as_of),
+                "Revocation registry root is older than the verifier's max age"
+            );
+            freshness.max_age
+        }
+        None => 0,
+    };
+
+    // A lifted suspension clears its bit, so an old suspension list can't
+    // show the credential isn't suspended now
+    assert!(
+        status_purpose != StatusPurpose::Suspension || input.revocation_freshness.is_some(),
+        "Suspension lists need a recent publication time"
+    );
+
+    // Ring proofs hide the subject among eligible holders, leaving a tag
+    // that links repeat actions in one event
+    let (subject, ring_root, ring_event, ring_tag) = match &input.ring {
+        Some(ring) => {
+            assert!(
+                input.subject_salt.is_none() && input.verifier_id.is_none(),
+                "Ring proofs can't be combined with other subject hiding"
+            );
+            assert!(
+                input.credential_hash_blind.is_some(),
+                "Ring proofs need a blinded credential hash"
+            );
+            let (root, tag) = ring::ring_root_and_tag(&input.subject, ring)
+                .expect("Subject key must control a member of the ring");
+            ([0u8; 20], root, ring.event, tag)
+        }
+        None => (subject, [0u8; 32], [0u8; 32], [0u8; 32]),
+    };
+
+    PublicOutput {
+        subject,
         credential_type: input.credential_type,
-        credential_hash,
-        issued_at: input.issued_at,
-        expires_at: input.expires_at,
-    };
-
-    // Commit the public values for on-chain verification
-    // The output is ABI-encoded for compatibility with Solidity
-    sp1_zkvm::io::commit(&output.subject);
-    sp1_zkvm::io::commit(&output.credential_type);
-    sp1_zkvm::io::commit(&output.credential_hash);
-    sp1_zkvm::io::commit(&output.issued_at);
-    sp1_zkvm::io::commit(&output.expires_at);
+        credential_hash: committed_hash,
+        // Expiry was checked exactly above, so only the committed times
+        // are coarsened
+        issued_at: time::round_down(input.issued_at, input.timestamp_granularity),
+        expires_at: time::round_down(input.expires_at, input.timestamp_granularity),
+        disclosure_hash,
+        quorum_hash,
+        issuer_address,
+        hmac_key_commitment,
+        root_ca_hash,
+        vrf_output_hash,
+        hash_suite: input.hash_algorithm as u8,
+        schema_hash,
+        root_issuer_hash,
+        nullifier_scope,
+        nullifier,
+        verifier_nonce: input.verifier_nonce,
+        subject_commitment,
+        trusted_issuers_root,
+        predicates_hash: predicate::predicates_hash(&input.predicates),
+        predicate_results,
+        withheld_claims_root,
+        sanctions_root,
+        sanctions_list_version,
+        pseudonym,
+        timestamp_granularity: input.timestamp_granularity as u8,
+        expiry_epoch: time::expiry_epoch(input.expires_at),
+        credential_hash_blind: input.credential_hash_blind.unwrap_or_default(),
+        ring_root,
+        ring_event,
+        ring_tag,
+        holder_bound,
+        device_rp_id_hash,
+        biometric_bound,
+        serial_hash,
+        revocation_method: revocation_method as u8,
+        revocation_root,
+        revocation_version,
+        revocation_max_age,
+        status_purpose: status_purpose as u8,
+        key_registry_version,
+        issuer_registry_root,
+        chain_id: input.chain_id.unwrap_or(0),
+        verifying_contract: input.verifying_contract.unwrap_or_default(),
+        as_of,
+        revealed_claims,
+    }
 }
@@ -0,0 +1,180 @@
+//! ISO 18013-5 mobile driving licence (mDL) parsing
+//!
+//! mDL credentials arrive as CBOR `IssuerSigned` structures:
+//!
+//! ```text
+//! IssuerSigned = { "nameSpaces": { namespace => [ #6.24(bstr .cbor IssuerSignedItem) ] },
+//!                  "issuerAuth": COSE_Sign1 }
+//! ```
+//!
+//! The COSE_Sign1 payload is the tagged Mobile Security Object (MSO), which
+//! carries the validity period and a SHA-256 digest of every data element.
+//! Each presented element must hash to its digest, and only the element
+//! identifiers reach the public output. The issuer signs with ES256, so
+//! `issuer_pubkey` is the document signer's P-256 key (which an issuer
+//! certificate chain can pin to an IACA root).
+//!
+//! The MSO also fixes who and what the credential is about: its `docType`
+//! must name the committed credential type (see `declares_credential_type`)
+//! and its device key must be the secp256k1 key of the subject's address.
+
+use coset::cbor::value::Value;
+use coset::{iana, AsCborValue, CoseKey, CoseSign1, KeyType, Label, RegisteredLabelWithPrivate};
+use sha2::{Digest, Sha256};
+
+use crate::time;
+
+/// The only MSO digest algorithm accepted
+const DIGEST_ALGORITHM: &str = "SHA-256";
+
+/// CBOR tag for embedded encoded CBOR data items
+const TAG_ENCODED_CBOR: u64 = 24;
+
+/// CBOR tag for RFC 3339 date-time strings
+const TAG_DATE_TIME: u64 = 0;
+
+/// A parsed mDL with verified data element digests
+pub struct Mdl {
+    /// The issuer's COSE_Sign1 over the MSO (its signature is not checked here)
+    pub issuer_auth: CoseSign1,
+    /// Document type the MSO declares
+    pub doc_type: String,
+    /// Holder's device key from the MSO, as an uncompressed SEC1 secp256k1
+    /// key
+    pub device_key: Vec<u8>,
+    /// Start of the validity period (seconds since the epoch)
+    pub valid_from: u64,
+    /// End of the validity period (seconds since the epoch)
+    pub valid_until: u64,
+    /// Presented elements as `namespace/elementIdentifier`
+    pub disclosed: Vec<String>,
+}
+
+/// Looks up a text key in a CBOR map
+fn map_get<'a>(map: &'a Value, key: &str) -> Option<&'a Value> {
+    map.as_map()?
+        .iter()
+        .find(|(entry, _)| entry.as_text() == Some(key))
+        .map(|(_, value)| value)
+}
+
+/// Unwraps `#6.24(bstr)` and decodes the embedded item
+fn decode_embedded(value: &Value) -> Option<Value> {
+    match value {
+        Value::Tag(TAG_ENCODED_CBOR, inner) => {
+            coset::cbor::from_reader(inner.as_bytes()?.as_slice()).ok()
+        }
+        _ => None,
+    }
+}
+
+/// Parses a `#6.0(tdate)` value
+fn decode_date(value: &Value) -> Option<u64> {
+    match value {
+        Value::Tag(TAG_DATE_TIME, inner) => time::parse_rfc3339(inner.as_text()?),
+        _ => None,
+    }
+}
+
+/// Reads the MSO's device key, which must be an EC2 secp256k1 COSE_Key, as
+/// an uncompressed SEC1 key
+fn device_key(mso: &Value) -> Option<Vec<u8>> {
+    let key = map_get(map_get(mso, "deviceKeyInfo")?, "deviceKey")?;
+    let key = CoseKey::from_cbor_value(key.clone()).ok()?;
+    let param = |label: iana::Ec2KeyParameter| {
+        key.params
+            .iter()
+            .find(|(entry, _)| *entry == Label::Int(label as i64))
+            .map(|(_, value)| value)
+    };
+    if key.kty != KeyType::Assigned(iana::KeyType::EC2)
+        || *param(iana::Ec2KeyParameter::Crv)? != Value::from(iana::EllipticCurve::Secp256k1 as u64)
+    {
+        return None;
+    }
+
+    let x = param(iana::Ec2KeyParameter::X)?.as_bytes()?;
+    let y = param(iana::Ec2KeyParameter::Y)?.as_bytes()?;
+    if x.len() != 32 || y.len() != 32 {
+        return None;
+    }
+    let mut sec1 = Vec::with_capacity(65);
+    sec1.push(0x04);
+    sec1.extend_from_slice(x);
+    sec1.extend_from_slice(y);
+    Some(sec1)
+}
+
+/// Returns the MSO digest for `digest_id` in `namespace`
+fn value_digest<'a>(value_digests: &'a Value, namespace: &str, digest_id: u64) -> Option<&'a [u8]> {
+    map_get(value_digests, namespace)?
+        .as_map()?
+        .iter()
+        .find(|(id, _)| id.as_integer().and_then(|id| u64::try_from(id).ok()) == Some(digest_id))
+        .and_then(|(_, digest)| digest.as_bytes())
+        .map(Vec::as_slice)
+}
+
+/// Parses an `IssuerSigned` structure and checks every presented element
+/// against the MSO digests
+///
+/// Returns `None` if the CBOR is malformed, the COSE algorithm is not ES256,
+/// the MSO uses another digest algorithm or lacks a secp256k1 device key,
+/// or a presented element does not match its digest.
+pub fn parse(issuer_signed: &[u8]) -> Option<Mdl> {
+    let issuer_signed: Value = coset::cbor::from_reader(issuer_signed).ok()?;
+
+    let issuer_auth =
+        CoseSign1::from_cbor_value(map_get(&issuer_signed, "issuerAuth")?.clone()).ok()?;
+    if issuer_auth.protected.header.alg
+        != Some(RegisteredLabelWithPrivate::Assigned(iana::Algorithm::ES256))
+    {
+        return None;
+    }
+
+    let payload: Value = coset::cbor::from_reader(issuer_auth.payload.as_ref()?.as_slice()).ok()?;
+    let mso = decode_embedded(&payload)?;
+    if map_get(&mso, "digestAlgorithm")?.as_text() != Some(DIGEST_ALGORITHM) {
+        return None;
+    }
+    let doc_type = map_get(&mso, "docType")?.as_text()?.to_string();
+    let device_key = device_key(&mso)?;
+
+    let validity = map_get(&mso, "validityInfo")?;
+    let valid_from = decode_date(map_get(validity, "validFrom")?)?;
+    let valid_until = decode_date(map_get(validity, "validUntil")?)?;
+
+    let value_digests = map_get(&mso, "valueDigests")?;
+    let mut disclosed = Vec::new();
+    let mut seen = Vec::new();
+    for (namespace, items) in map_get(&issuer_signed, "nameSpaces")?.as_map()? {
+        let namespace = namespace.as_text()?;
+        for item in items.as_array()? {
+            // Element digests cover the full tagged encoding
+            let mut encoded = Vec::new();
+            coset::cbor::into_writer(item, &mut encoded).ok()?;
+            let digest = Sha256::digest(&encoded);
+
+            let item = decode_embedded(item)?;
+            let digest_id = u64::try_from(map_get(&item, "digestID")?.as_integer()?).ok()?;
+            let identifier = map_get(&item, "elementIdentifier")?.as_text()?;
+
+            if value_digest(value_digests, namespace, digest_id)? != &digest[..]
+                || seen.contains(&(namespace, digest_id))
+            {
+                return None;
+            }
+            seen.push((namespace, digest_id));
+            disclosed.push(format!("{}/{}", namespace, identifier));
+        }
+    }
+
+    Some(Mdl {
+        issuer_auth,
+        doc_type,
+        device_key,
+        valid_from,
+        valid_until,
+        disclosed,
+    })
+}
@@ -0,0 +1,140 @@
+//! Credential data format v4: a Merkle root of claims
+//!
+//! The issuer signs only the root of a SHA-256 Merkle tree over v2-style
+//! claims, and the holder supplies inclusion proofs for just the claims a
+//! verifier checks. Unproven claims never enter the circuit:
+//!
+//! ```text
+//! version = 4 (BE u32) || depth (BE u32) || root (32 bytes)
+//! leaf = SHA-256(0x00 || claim_type (BE u16) || value)
+//! node = SHA-256(0x01 || left || right)
+//! ```
+//!
+//! Unused leaves are 32 zero bytes, which no claim hashes to.
+
+use sha2::{Digest, Sha256};
+
+use crate::claims::{self, ClaimType};
+use crate::{hash, ClaimProof, HashAlgorithm};
+
+/// Version tag of the Merkle root format
+pub const FORMAT_V4: u32 = 4;
+
+/// Deepest tree accepted, matching the width of `ClaimProof::index`
+const MAX_DEPTH: u32 = 32;
+
+/// Hashes a claim into a leaf
+pub fn leaf_hash(claim_type: u16, value: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(claim_type.to_be_bytes());
+    hasher.update(value);
+    hasher.finalize().into()
+}
+
+/// Hashes two children into their parent
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Computes the root of a tree over `leaves`, padded with zero leaves to
+/// a power of two (zero if there are none)
+pub fn root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return [0u8; 32];
+    }
+    let mut level = leaves.to_vec();
+    level.resize(leaves.len().next_power_of_two(), [0u8; 32]);
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| node_hash(&pair[0], &pair[1]))
+            .collect();
+    }
+    level[0]
+}
+
+/// Decodes v4 credential data into `(depth, root)`
+fn parse(data: &[u8]) -> Option<(u32, [u8; 32])> {
+    if data.len() != 40 || u32::from_be_bytes(data[..4].try_into().ok()?) != FORMAT_V4 {
+        return None;
+    }
+    let depth = u32::from_be_bytes(data[4..8].try_into().ok()?);
+    (depth <= MAX_DEPTH).then(|| (depth, data[8..].try_into().expect("32-byte root")))
+}
+
+/// Folds a leaf up its authentication path into the root
+///
+/// Bit i of `index` selects whether `siblings[i]` is on the left. Returns
+/// `None` if `index` doesn't fit the path's depth.
+pub fn path_root(leaf: [u8; 32], index: u32, siblings: &[[u8; 32]]) -> Option<[u8; 32]> {
+    if siblings.len() > MAX_DEPTH as usize || u64::from(index) >> siblings.len() != 0 {
+        return None;
+    }
+
+    let mut node = leaf;
+    for (level, sibling) in siblings.iter().enumerate() {
+        node = if index >> level & 1 == 0 {
+            node_hash(&node, sibling)
+        } else {
+            node_hash(sibling, &node)
+        };
+    }
+    Some(node)
+}
+
+/// Returns true if `proof` places its claim under `root` at `depth`
+fn includes(root: &[u8; 32], depth: u32, proof: &ClaimProof) -> bool {
+    let leaf = leaf_hash(proof.claim_type, &proof.value);
+    proof.siblings.len() == depth as usize
+        && path_root(leaf, proof.index, &proof.siblings) == Some(*root)
+}
+
+/// Validates proven claims against a v4 credential's signed root
+///
+/// Every proof must verify, each claim type may be proven once, values
+/// must be well-formed for their type, and every claim type the credential
+/// type requires must be among the proven claims.
+pub fn validate(data: &[u8], proofs: &[ClaimProof], credential_type: u32) -> bool {
+    let Some((depth, root)) = parse(data) else {
+        return false;
+    };
+
+    let mut proven: Vec<ClaimType> = Vec::new();
+    for proof in proofs {
+        let Some(claim_type) = ClaimType::from_u16(proof.claim_type) else {
+            return false;
+        };
+        if proven.contains(&claim_type)
+            || !claim_type.accepts(&proof.value)
+            || !includes(&root, depth, proof)
+        {
+            return false;
+        }
+        proven.push(claim_type);
+    }
+
+    !proven.is_empty()
+        && claims::required_claims(credential_type)
+            .iter()
+            .all(|required| proven.contains(required))
+}
+
+/// Hashes the proven claim types for the public output
+///
+/// Types are sorted so the hash doesn't depend on proof order, and each
+/// contributes its BE u16.
+pub fn proven_claims_hash(algorithm: HashAlgorithm, proofs: &[ClaimProof]) -> [u8; 32] {
+    let mut claim_types: Vec<u16> = proofs.iter().map(|proof| proof.claim_type).collect();
+    claim_types.sort_unstable();
+
+    let encoded: Vec<u8> = claim_types
+        .iter()
+        .flat_map(|claim_type| claim_type.to_be_bytes())
+        .collect();
+    hash::hash_parts(algorithm, &[&encoded])
+}
@@ -0,0 +1,28 @@
+//! Scoped nullifiers
+//!
+//! A nullifier lets a contract reject a second use of the same credential
+//! in one context (an airdrop claim, a vote) while uses in different
+//! contexts stay unlinkable. It is derived from the credential's SHA-256
+//! hash, the verifier's scope and a holder secret. The issuer signs a
+//! commitment to the secret, so the holder can't mint fresh nullifiers by
+//! varying it, and both hashes are SHA-256 whatever the credential's hash
+//! suite, so they can't by switching suites either.
+//!
+//! The same secret yields per-verifier pseudonyms, which identify a holder
+//! consistently to one relying party without linking them across parties.
+
+use crate::{hash, HashAlgorithm, NullifierInput};
+
+/// Derives `SHA-256(credential_hash || scope || secret)` from the
+/// credential's SHA-256 hash
+pub fn nullifier(credential_hash: &[u8; 32], input: &NullifierInput) -> [u8; 32] {
+    hash::hash_parts(
+        HashAlgorithm::Sha256,
+        &[credential_hash, &input.scope, &input.secret],
+    )
+}
+
+/// Derives the pseudonym `SHA-256(secret || verifier_id)`
+pub fn pseudonym(secret: &[u8; 32], verifier_id: &[u8; 32]) -> [u8; 32] {
+    hash::hash_parts(HashAlgorithm::Sha256, &[secret, verifier_id])
+}
@@ -0,0 +1,32 @@
+//! Verifier policies over credential types
+//!
+//! A verifier can ask for a combination of credentials (e.g., KYC and AML,
+//! or KYC or a passport) instead of a single type. The circuit verifies
+//! every supplied credential, evaluates the policy against their types and
+//! commits the policy hash with the result, so the contract only has to
+//! pin the policy it expects.
+
+use sha2::{Digest, Sha256};
+
+use crate::Policy;
+
+/// Returns true if the verified `credential_types` satisfy `policy`
+///
+/// Bitmask credential types must already be expanded into their base
+/// types, so a combined KYC and AML credential satisfies `kyc & aml`.
+pub fn evaluate(policy: &Policy, credential_types: &[u32]) -> bool {
+    match policy {
+        Policy::Credential(credential_type) => credential_types.contains(credential_type),
+        Policy::All(policies) => policies
+            .iter()
+            .all(|policy| evaluate(policy, credential_types)),
+        Policy::Any(policies) => policies
+            .iter()
+            .any(|policy| evaluate(policy, credential_types)),
+    }
+}
+
+/// SHA-256 of the policy's canonical encoding, for the public output
+pub fn policy_hash(policy: &Policy) -> [u8; 32] {
+    Sha256::digest(policy.to_bytes()).into()
+}
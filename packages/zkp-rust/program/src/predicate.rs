@@ -0,0 +1,173 @@
+//! Predicates over private claims
+//!
+//! A verifier asks whether a claim meets a condition (over 18, net worth
+//! within a bracket, residence in an allowed country) without learning the
+//! claim, or whether the credential is old enough. The circuit evaluates
+//! each predicate against the credential and commits the predicates' hash,
+//! which covers their thresholds, bounds and lists, with one result bit
+//! per predicate. Age and credential-age predicates are evaluated as of the
+//! committed `as_of` time, so a contract can tell a proof dated in the
+//! future.
+
+use sha2::{Digest, Sha256};
+
+use crate::claims::{self, ClaimType};
+use crate::keyed::{self, Amount, ClaimValue, KeyedClaims};
+use crate::{merkle, smt, time, CredentialFormat, CredentialInput, Predicate};
+
+/// Most predicates one credential can carry, one per result bit
+pub const MAX_PREDICATES: usize = 32;
+
+/// Key of the date of birth in key-value (v3 and v5) claims
+const DATE_OF_BIRTH_KEY: &str = "dateOfBirth";
+
+/// Reads a v2 claim as the key-value claim of the same name
+///
+/// Net worth becomes an amount in whole USD, single-byte claims integers
+/// and strings their SHA-256, as a v3 issuer would encode them.
+fn typed_value(claim_type: ClaimType, value: &[u8]) -> Option<ClaimValue> {
+    if !claim_type.accepts(value) {
+        return None;
+    }
+    Some(match claim_type {
+        ClaimType::DateOfBirth => ClaimValue::Date(i64::from_be_bytes(value.try_into().ok()?)),
+        ClaimType::NetWorth => ClaimValue::Amount(Amount {
+            unit: *b"USD",
+            precision: 0,
+            value: u64::from_be_bytes(value.try_into().ok()?),
+        }),
+        ClaimType::KycLevel
+        | ClaimType::AccreditationStatus
+        | ClaimType::InvestorClass
+        | ClaimType::AmlScreening => ClaimValue::U64(u64::from(value[0])),
+        ClaimType::FullName
+        | ClaimType::Nationality
+        | ClaimType::ResidenceCountry
+        | ClaimType::EntityRegistration => ClaimValue::StringHash(Sha256::digest(value).into()),
+    })
+}
+
+/// Returns the claim named `key` from any binary claim format
+///
+/// Typed formats (v2 and v4) look the name up in the claim registry. Tree
+/// formats (v4 and v5) read it from the supplied proofs, which have
+/// already been checked against the signed root.
+fn claim_value(input: &CredentialInput, key: &str) -> Option<ClaimValue> {
+    if input.credential_format != CredentialFormat::Binary {
+        return None;
+    }
+    let data = &input.credential_data;
+    let version = u32::from_be_bytes(data.get(..4)?.try_into().ok()?);
+
+    match version {
+        claims::FORMAT_V2 => {
+            let claim_type = ClaimType::from_key(key)?;
+            let claim = claims::parse(data)?
+                .into_iter()
+                .find(|claim| claim.claim_type == claim_type)?;
+            typed_value(claim_type, claim.value)
+        }
+        keyed::FORMAT_V3 => KeyedClaims::parse(data)?.get(key),
+        merkle::FORMAT_V4 => {
+            let claim_type = ClaimType::from_key(key)?;
+            let proof = input
+                .claim_proofs
+                .iter()
+                .find(|proof| proof.claim_type == claim_type as u16)?;
+            typed_value(claim_type, &proof.value)
+        }
+        smt::FORMAT_V5 => {
+            let proof = input
+                .sparse_claim_proofs
+                .iter()
+                .find(|proof| proof.key == key)?;
+            keyed::decode_value(proof.value.as_ref()?)
+        }
+        _ => None,
+    }
+}
+
+/// Returns true if `value` is a number within `[min, max]`
+///
+/// Amounts must be in `unit` and are compared at full precision; integers
+/// take no unit and precision 0.
+fn in_range(value: ClaimValue, unit: Option<[u8; 3]>, precision: u8, min: u64, max: u64) -> bool {
+    match (value, unit) {
+        (ClaimValue::U64(value), None) => precision == 0 && (min..=max).contains(&value),
+        (ClaimValue::Amount(amount), Some(unit)) if precision <= keyed::MAX_PRECISION => {
+            let bound = |value| Amount {
+                unit,
+                precision,
+                value,
+            };
+            amount.at_least(&bound(min)) && bound(max).at_least(&amount)
+        }
+        _ => false,
+    }
+}
+
+/// Evaluates one predicate as of `as_of`; a missing or malformed claim
+/// fails it
+pub fn evaluate(input: &CredentialInput, predicate: &Predicate, as_of: u64) -> bool {
+    match predicate {
+        Predicate::AgeAtLeast(years) => match claim_value(input, DATE_OF_BIRTH_KEY) {
+            Some(ClaimValue::Date(born)) => {
+                i128::from(time::anniversary(born, *years)) <= i128::from(as_of)
+            }
+            _ => false,
+        },
+        Predicate::InRange {
+            claim,
+            unit,
+            precision,
+            min,
+            max,
+        } => claim_value(input, claim)
+            .is_some_and(|value| in_range(value, *unit, *precision, *min, *max)),
+        Predicate::InSet { claim, members } => {
+            // Sorted members give the list one encoding, and so one hash
+            let sorted = members.windows(2).all(|pair| pair[0] < pair[1]);
+            match claim_value(input, claim) {
+                Some(ClaimValue::StringHash(hash)) => {
+                    sorted && members.binary_search(&hash).is_ok()
+                }
+                _ => false,
+            }
+        }
+        Predicate::IssuedAtLeastDaysAgo(days) => as_of
+            .checked_sub(input.issued_at)
+            .is_some_and(|age| age >= u64::from(*days) * 86400),
+    }
+}
+
+/// Evaluates the credential's predicates as of `as_of` into a bitmask,
+/// bit i set if predicate i holds
+pub fn results(input: &CredentialInput, as_of: u64) -> u32 {
+    assert!(
+        input.predicates.len() <= MAX_PREDICATES,
+        "Too many predicates"
+    );
+    input
+        .predicates
+        .iter()
+        .enumerate()
+        .filter(|(_, predicate)| evaluate(input, predicate, as_of))
+        .fold(0, |bits, (i, _)| bits | 1 << i)
+}
+
+/// Hashes the predicates for the public output (zero if none)
+///
+/// `SHA-256(count (BE u32) || predicate*)` over their canonical encodings,
+/// so a contract can compare it with the hash of the predicates it asked
+/// for.
+pub fn predicates_hash(predicates: &[Predicate]) -> [u8; 32] {
+    if predicates.is_empty() {
+        return [0u8; 32];
+    }
+    let mut hasher = Sha256::new();
+    hasher.update((predicates.len() as u32).to_be_bytes());
+    for predicate in predicates {
+        hasher.update(predicate.to_bytes());
+    }
+    hasher.finalize().into()
+}
@@ -0,0 +1,67 @@
+//! Threshold m-of-n issuer signatures
+//!
+//! High-assurance credentials are co-signed by several compliance officers.
+//! The circuit accepts the credential when at least `threshold` distinct
+//! keys from the declared set produced a valid signature over the message.
+
+use crate::{hash, signature, HashAlgorithm, IssuerQuorum, SignatureScheme};
+
+/// Returns true if at least `threshold` distinct keys signed `message`
+///
+/// The declared keys must be distinct and signatures must reference them
+/// in strictly increasing index order, which rules out counting the same
+/// key twice.
+pub fn verify_quorum(
+    scheme: SignatureScheme,
+    message_hash: HashAlgorithm,
+    message: &[u8],
+    quorum: &IssuerQuorum,
+) -> bool {
+    let key_count = quorum.pubkeys.len();
+    if quorum.threshold == 0 || quorum.threshold as usize > key_count {
+        return false;
+    }
+    if !quorum.has_distinct_keys() {
+        return false;
+    }
+    if !quorum
+        .signatures
+        .windows(2)
+        .all(|pair| pair[0].key_index < pair[1].key_index)
+    {
+        return false;
+    }
+
+    let mut valid: u32 = 0;
+    for entry in &quorum.signatures {
+        let Some(pubkey) = quorum.pubkeys.get(entry.key_index as usize) else {
+            return false;
+        };
+        if signature::verify_signature(scheme, message_hash, message, &entry.signature, pubkey) {
+            valid += 1;
+        }
+    }
+
+    valid >= quorum.threshold
+}
+
+/// Hashes the quorum policy (scheme, threshold and ordered key set)
+///
+/// Encoded as `scheme (u8) || threshold (BE u32) || n (BE u32)` followed by
+/// `len (BE u32) || pubkey` for each key, so a contract can pin the exact
+/// signer set and threshold it trusts.
+pub fn policy_hash(
+    algorithm: HashAlgorithm,
+    scheme: SignatureScheme,
+    quorum: &IssuerQuorum,
+) -> [u8; 32] {
+    let mut encoded = Vec::new();
+    encoded.push(scheme as u8);
+    encoded.extend_from_slice(&quorum.threshold.to_be_bytes());
+    encoded.extend_from_slice(&(quorum.pubkeys.len() as u32).to_be_bytes());
+    for pubkey in &quorum.pubkeys {
+        encoded.extend_from_slice(&(pubkey.len() as u32).to_be_bytes());
+        encoded.extend_from_slice(pubkey);
+    }
+    hash::hash_parts(algorithm, &[&encoded])
+}
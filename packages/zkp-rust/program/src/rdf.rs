@@ -0,0 +1,527 @@
+//! N-Quads parsing and URDNA2015 canonicalization
+//!
+//! Linked-data proofs sign the canonical form of a credential's RDF
+//! dataset. JSON-LD expansion needs remote contexts, so the host expands
+//! the document to N-Quads and the circuit canonicalizes the dataset
+//! itself: blank nodes are relabeled `_:c14n0, _:c14n1, ...` by URDNA2015
+//! and the serialized quads sorted, so any relabeling or reordering the
+//! host applies yields the same signed bytes.
+
+use std::collections::BTreeMap;
+
+use sha2::{Digest, Sha256};
+
+/// `xsd:string`, the implied datatype of plain literals
+const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+
+/// An RDF term
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Term {
+    /// IRI (without angle brackets)
+    Iri(String),
+    /// Blank node label (without the `_:` prefix)
+    Blank(String),
+    /// Literal with its unescaped value; plain strings have no datatype
+    Literal {
+        value: String,
+        datatype: Option<String>,
+        language: Option<String>,
+    },
+}
+
+/// An RDF quad (`graph` is `None` for the default graph)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Quad {
+    pub subject: Term,
+    pub predicate: Term,
+    pub object: Term,
+    pub graph: Option<Term>,
+}
+
+impl Quad {
+    /// Returns the blank node labels among subject, object and graph, with
+    /// their URDNA2015 position markers
+    fn blank_nodes(&self) -> impl Iterator<Item = (&str, &'static str)> {
+        [
+            (Some(&self.subject), "s"),
+            (Some(&self.object), "o"),
+            (self.graph.as_ref(), "g"),
+        ]
+        .into_iter()
+        .filter_map(|(term, position)| match term {
+            Some(Term::Blank(label)) => Some((label.as_str(), position)),
+            _ => None,
+        })
+    }
+}
+
+/// Cursor over one N-Quads line
+struct Cursor<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    fn skip_whitespace(&mut self) {
+        self.rest = self.rest.trim_start_matches([' ', '\t']);
+    }
+
+    /// Consumes `prefix` if the input starts with it
+    fn eat(&mut self, prefix: char) -> bool {
+        match self.rest.strip_prefix(prefix) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Reads an `<iri>`
+    fn iri(&mut self) -> Option<String> {
+        let rest = self.rest.strip_prefix('<')?;
+        let end = rest.find('>')?;
+        let iri = &rest[..end];
+        if iri.contains([' ', '<', '"', '\\']) {
+            return None;
+        }
+        self.rest = &rest[end + 1..];
+        Some(iri.to_string())
+    }
+
+    /// Reads a `_:label`
+    fn blank(&mut self) -> Option<String> {
+        let rest = self.rest.strip_prefix("_:")?;
+        let end = rest
+            .find(|c: char| !(c.is_alphanumeric() || "_-.".contains(c)))
+            .unwrap_or(rest.len());
+        let label = rest[..end].trim_end_matches('.');
+        if label.is_empty() {
+            return None;
+        }
+        self.rest = &rest[label.len()..];
+        Some(label.to_string())
+    }
+
+    /// Reads a quoted string, resolving escapes
+    fn string(&mut self) -> Option<String> {
+        let mut chars = self.rest.strip_prefix('"')?.char_indices();
+        let mut value = String::new();
+        loop {
+            let (index, c) = chars.next()?;
+            match c {
+                '"' => {
+                    self.rest = &self.rest[index + 2..];
+                    return Some(value);
+                }
+                '\\' => {
+                    let (_, escape) = chars.next()?;
+                    let digits = match escape {
+                        'u' => 4,
+                        'U' => 8,
+                        _ => {
+                            value.push(match escape {
+                                't' => '\t',
+                                'b' => '\u{8}',
+                                'n' => '\n',
+                                'r' => '\r',
+                                'f' => '\u{c}',
+                                '"' | '\'' | '\\' => escape,
+                                _ => return None,
+                            });
+                            continue;
+                        }
+                    };
+                    let hex: String = (0..digits)
+                        .map(|_| chars.next().map(|(_, c)| c))
+                        .collect::<Option<_>>()?;
+                    value.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+                }
+                '\n' | '\r' => return None,
+                c => value.push(c),
+            }
+        }
+    }
+
+    /// Reads a literal with its optional datatype or language tag
+    fn literal(&mut self) -> Option<Term> {
+        let value = self.string()?;
+        let (mut datatype, mut language) = (None, None);
+        if self.rest.starts_with("^^") {
+            self.rest = &self.rest[2..];
+            datatype = Some(self.iri()?).filter(|datatype| datatype != XSD_STRING);
+        } else if self.eat('@') {
+            let end = self
+                .rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+                .unwrap_or(self.rest.len());
+            if end == 0 {
+                return None;
+            }
+            language = Some(self.rest[..end].to_string());
+            self.rest = &self.rest[end..];
+        }
+        Some(Term::Literal {
+            value,
+            datatype,
+            language,
+        })
+    }
+
+    /// Reads any term
+    fn term(&mut self) -> Option<Term> {
+        self.skip_whitespace();
+        match self.rest.chars().next()? {
+            '<' => self.iri().map(Term::Iri),
+            '_' => self.blank().map(Term::Blank),
+            '"' => self.literal(),
+            _ => None,
+        }
+    }
+}
+
+/// Parses one non-empty N-Quads statement
+fn parse_quad(line: &str) -> Option<Quad> {
+    let mut cursor = Cursor { rest: line };
+    let subject = cursor.term()?;
+    let predicate = cursor.term()?;
+    let object = cursor.term()?;
+
+    cursor.skip_whitespace();
+    let graph = if cursor.rest.starts_with('.') {
+        None
+    } else {
+        Some(cursor.term()?)
+    };
+    cursor.skip_whitespace();
+    if !cursor.eat('.') {
+        return None;
+    }
+    cursor.skip_whitespace();
+
+    let valid = matches!(subject, Term::Iri(_) | Term::Blank(_))
+        && matches!(predicate, Term::Iri(_))
+        && matches!(graph, None | Some(Term::Iri(_)) | Some(Term::Blank(_)))
+        && (cursor.rest.is_empty() || cursor.rest.starts_with('#'));
+    valid.then_some(Quad {
+        subject,
+        predicate,
+        object,
+        graph,
+    })
+}
+
+/// Parses an N-Quads document into a dataset
+///
+/// Blank lines and comments are skipped and duplicate quads collapse, since
+/// a dataset is a set. Returns `None` on any malformed statement.
+pub fn parse(document: &str) -> Option<Vec<Quad>> {
+    let mut quads = Vec::new();
+    for line in document.lines() {
+        let line = line.trim_matches([' ', '\t']);
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        quads.push(parse_quad(line)?);
+    }
+    quads.sort();
+    quads.dedup();
+    Some(quads)
+}
+
+/// Escapes a literal value as canonical N-Quads does
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '"' => escaped.push_str("\\\""),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Serializes a term, naming blank nodes with `label`
+fn serialize_term(term: &Term, label: &dyn Fn(&str) -> String) -> String {
+    match term {
+        Term::Iri(iri) => format!("<{iri}>"),
+        Term::Blank(blank) => label(blank),
+        Term::Literal {
+            value,
+            datatype,
+            language,
+        } => {
+            let mut literal = format!("\"{}\"", escape(value));
+            if let Some(language) = language {
+                literal.push('@');
+                literal.push_str(language);
+            } else if let Some(datatype) = datatype {
+                literal.push_str(&format!("^^<{datatype}>"));
+            }
+            literal
+        }
+    }
+}
+
+/// Serializes a quad as an N-Quads line (with trailing newline)
+fn serialize_quad(quad: &Quad, label: &dyn Fn(&str) -> String) -> String {
+    let mut line = format!(
+        "{} {} {} ",
+        serialize_term(&quad.subject, label),
+        serialize_term(&quad.predicate, label),
+        serialize_term(&quad.object, label),
+    );
+    if let Some(graph) = &quad.graph {
+        line.push_str(&serialize_term(graph, label));
+        line.push(' ');
+    }
+    line.push_str(".\n");
+    line
+}
+
+/// Lowercase hex SHA-256, as URDNA2015 hashes are compared as strings
+fn sha256_hex(data: &str) -> String {
+    hex::encode(Sha256::digest(data.as_bytes()))
+}
+
+/// Issues sequential blank node identifiers (URDNA2015 "identifier issuer")
+#[derive(Clone)]
+struct IdentifierIssuer {
+    prefix: &'static str,
+    /// `(existing label, issued identifier)` in issue order
+    issued: Vec<(String, String)>,
+}
+
+impl IdentifierIssuer {
+    fn new(prefix: &'static str) -> Self {
+        Self {
+            prefix,
+            issued: Vec::new(),
+        }
+    }
+
+    fn get(&self, existing: &str) -> Option<&str> {
+        self.issued
+            .iter()
+            .find(|(label, _)| label == existing)
+            .map(|(_, issued)| issued.as_str())
+    }
+
+    fn issue(&mut self, existing: &str) -> String {
+        if let Some(issued) = self.get(existing) {
+            return issued.to_string();
+        }
+        let issued = format!("{}{}", self.prefix, self.issued.len());
+        self.issued.push((existing.to_string(), issued.clone()));
+        issued
+    }
+}
+
+/// All orderings of `items`
+fn permutations(items: &[String]) -> Vec<Vec<String>> {
+    if items.len() <= 1 {
+        return vec![items.to_vec()];
+    }
+    let mut all = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        let mut rest = items.to_vec();
+        rest.remove(i);
+        for mut permutation in permutations(&rest) {
+            permutation.insert(0, item.clone());
+            all.push(permutation);
+        }
+    }
+    all
+}
+
+/// URDNA2015 canonicalization state
+struct Canonicalizer<'a> {
+    /// Quads mentioning each blank node
+    quads_by_node: BTreeMap<&'a str, Vec<&'a Quad>>,
+    /// First-degree hash of each blank node
+    first_degree: BTreeMap<&'a str, String>,
+    canonical: IdentifierIssuer,
+}
+
+impl<'a> Canonicalizer<'a> {
+    /// Hashes the quads mentioning `node`, with it as `_:a` and other
+    /// blank nodes as `_:z`
+    fn hash_first_degree(&self, node: &str) -> String {
+        let label = |blank: &str| if blank == node { "_:a" } else { "_:z" }.to_string();
+        let mut lines: Vec<String> = self.quads_by_node[node]
+            .iter()
+            .map(|quad| serialize_quad(quad, &label))
+            .collect();
+        lines.sort();
+        sha256_hex(&lines.concat())
+    }
+
+    /// Hashes `related`'s relationship to a node through `quad`
+    fn hash_related(
+        &self,
+        related: &str,
+        quad: &Quad,
+        issuer: &IdentifierIssuer,
+        position: &str,
+    ) -> String {
+        let identifier = match self.canonical.get(related).or_else(|| issuer.get(related)) {
+            Some(issued) => issued.to_string(),
+            None => self.first_degree[related].clone(),
+        };
+        let mut input = position.to_string();
+        if position != "g" {
+            input.push_str(&serialize_term(&quad.predicate, &|_| String::new()));
+        }
+        input.push_str(&identifier);
+        sha256_hex(&input)
+    }
+
+    /// Hash N-Degree Quads: disambiguates `node` by the paths to the blank
+    /// nodes around it, returning the hash and the issuer that labeled them
+    fn hash_n_degree(
+        &self,
+        node: &str,
+        mut issuer: IdentifierIssuer,
+    ) -> (String, IdentifierIssuer) {
+        let mut related_by_hash: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for quad in &self.quads_by_node[node] {
+            for (related, position) in quad.blank_nodes() {
+                if related != node {
+                    let hash = self.hash_related(related, quad, &issuer, position);
+                    related_by_hash
+                        .entry(hash)
+                        .or_default()
+                        .push(related.to_string());
+                }
+            }
+        }
+
+        let mut data_to_hash = String::new();
+        for (related_hash, blank_nodes) in &related_by_hash {
+            data_to_hash.push_str(related_hash);
+            let mut chosen_path = String::new();
+            let mut chosen_issuer: Option<IdentifierIssuer> = None;
+
+            'permutations: for permutation in permutations(blank_nodes) {
+                let mut issuer_copy = issuer.clone();
+                let mut path = String::new();
+                let mut recursion_list = Vec::new();
+                let worse = |path: &str, chosen: &str| {
+                    !chosen.is_empty() && path.len() >= chosen.len() && path > chosen
+                };
+
+                for related in &permutation {
+                    if let Some(canonical) = self.canonical.get(related) {
+                        path.push_str(canonical);
+                    } else {
+                        if issuer_copy.get(related).is_none() {
+                            recursion_list.push(related.clone());
+                        }
+                        path.push_str(&issuer_copy.issue(related));
+                    }
+                    if worse(&path, &chosen_path) {
+                        continue 'permutations;
+                    }
+                }
+
+                for related in &recursion_list {
+                    let (hash, result_issuer) = self.hash_n_degree(related, issuer_copy.clone());
+                    path.push_str(&issuer_copy.issue(related));
+                    path.push('<');
+                    path.push_str(&hash);
+                    path.push('>');
+                    issuer_copy = result_issuer;
+                    if worse(&path, &chosen_path) {
+                        continue 'permutations;
+                    }
+                }
+
+                if chosen_path.is_empty() || path < chosen_path {
+                    chosen_path = path;
+                    chosen_issuer = Some(issuer_copy);
+                }
+            }
+
+            data_to_hash.push_str(&chosen_path);
+            issuer = chosen_issuer.expect("at least one permutation");
+        }
+
+        (sha256_hex(&data_to_hash), issuer)
+    }
+}
+
+/// Canonicalizes a dataset with URDNA2015
+///
+/// Returns the canonical N-Quads document: quads with canonical blank node
+/// labels, sorted in code point order, each ending in a newline.
+pub fn canonicalize(quads: &[Quad]) -> String {
+    let mut quads_by_node: BTreeMap<&str, Vec<&Quad>> = BTreeMap::new();
+    for quad in quads {
+        for (node, _) in quad.blank_nodes() {
+            let entry = quads_by_node.entry(node).or_default();
+            if !entry.iter().any(|existing| std::ptr::eq(*existing, quad)) {
+                entry.push(quad);
+            }
+        }
+    }
+
+    let mut state = Canonicalizer {
+        quads_by_node,
+        first_degree: BTreeMap::new(),
+        canonical: IdentifierIssuer::new("_:c14n"),
+    };
+    let nodes: Vec<&str> = state.quads_by_node.keys().copied().collect();
+    for node in &nodes {
+        let hash = state.hash_first_degree(node);
+        state.first_degree.insert(node, hash);
+    }
+
+    // Nodes with a unique first-degree hash are labeled in hash order
+    let mut nodes_by_hash: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for (node, hash) in &state.first_degree {
+        nodes_by_hash.entry(hash.clone()).or_default().push(node);
+    }
+    let (unique, shared): (Vec<_>, Vec<_>) = nodes_by_hash
+        .into_values()
+        .partition(|nodes| nodes.len() == 1);
+    for nodes in &unique {
+        state.canonical.issue(nodes[0]);
+    }
+
+    // The rest are ordered by their N-degree hashes
+    for nodes in shared {
+        let mut hash_paths = Vec::new();
+        for node in nodes {
+            if state.canonical.get(node).is_some() {
+                continue;
+            }
+            let mut issuer = IdentifierIssuer::new("_:b");
+            issuer.issue(node);
+            hash_paths.push(state.hash_n_degree(node, issuer));
+        }
+        hash_paths.sort_by(|a, b| a.0.cmp(&b.0));
+        for (_, issuer) in hash_paths {
+            for (existing, _) in &issuer.issued {
+                state.canonical.issue(existing);
+            }
+        }
+    }
+
+    let label = |blank: &str| {
+        state
+            .canonical
+            .get(blank)
+            .expect("every blank node is labeled")
+            .to_string()
+    };
+    let mut lines: Vec<String> = quads
+        .iter()
+        .map(|quad| serialize_quad(quad, &label))
+        .collect();
+    lines.sort();
+    lines.dedup();
+    lines.concat()
+}
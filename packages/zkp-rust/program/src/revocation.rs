@@ -0,0 +1,53 @@
+//! Revocation checks by non-membership
+//!
+//! An issuer publishes the SHA-256 hashes of the credentials it revoked,
+//! whatever their hash suite, as a Merkle tree, hashed like v4 claim trees,
+//! over the hashes in ascending order between the sentinels `0x00..00` and
+//! `0xff..ff`:
+//!
+//! ```text
+//! leaf = SHA-256(0x00 || credential_hash)
+//! ```
+//!
+//! The prover shows two adjacent leaves whose hashes bracket the
+//! credential's strictly, so it can't have been revoked in that snapshot.
+//! Only the tree root is committed, for contracts to check against the
+//! issuer's latest root.
+
+use sha2::{Digest, Sha256};
+
+use crate::{merkle, RevocationExclusion};
+
+/// Hashes a revoked credential hash into a leaf
+fn revoked_leaf(credential_hash: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(credential_hash);
+    hasher.finalize().into()
+}
+
+/// Returns the root of the revocation list `exclusion` shows doesn't hold
+/// `credential_hash`, or `None` if its entries don't bracket the hash as
+/// neighbours
+pub fn excluding_root(
+    credential_hash: &[u8; 32],
+    exclusion: &RevocationExclusion,
+) -> Option<[u8; 32]> {
+    if !(exclusion.lower < *credential_hash && *credential_hash < exclusion.upper)
+        || exclusion.lower_siblings.len() != exclusion.upper_siblings.len()
+    {
+        return None;
+    }
+
+    let lower = merkle::path_root(
+        revoked_leaf(&exclusion.lower),
+        exclusion.index,
+        &exclusion.lower_siblings,
+    )?;
+    let upper = merkle::path_root(
+        revoked_leaf(&exclusion.upper),
+        exclusion.index.checked_add(1)?,
+        &exclusion.upper_siblings,
+    )?;
+    (lower == upper).then_some(lower)
+}
@@ -0,0 +1,49 @@
+//! Linkable ring membership for accountable anonymity
+//!
+//! For votes and other one-per-person actions, the subject proves they are
+//! one of a published ring of eligible holders, a Merkle tree hashed like
+//! v4 claim trees over leaves `SHA-256(0x00 || address)`, by showing the
+//! secp256k1 key that controls their address. Only the ring root and a
+//! linkability tag are committed:
+//!
+//! ```text
+//! tag = SHA-256(RING_TAG_DOMAIN || subject_key || event)
+//! ```
+//!
+//! The tag is the same whenever the subject acts in one event, whatever the
+//! credential's hash suite, so a second vote is detectable, but reveals
+//! nothing about who they are.
+
+use sha2::{Digest, Sha256};
+
+use crate::{hash, merkle, signature, HashAlgorithm, RingMembership};
+
+/// Domain tag for linkability tags
+pub const RING_TAG_DOMAIN: &[u8] = b"CREDENCE_RING_TAG_V1";
+
+/// Hashes an eligible address into a ring leaf
+fn address_leaf(address: &[u8; 20]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(address);
+    hasher.finalize().into()
+}
+
+/// Returns the ring root and linkability tag for `subject`, or `None` if
+/// the key doesn't control the subject address or the path is malformed
+pub fn ring_root_and_tag(
+    subject: &[u8; 20],
+    ring: &RingMembership,
+) -> Option<([u8; 32], [u8; 32])> {
+    let signing_key = k256::ecdsa::SigningKey::from_slice(&ring.subject_key).ok()?;
+    if signature::ethereum_address(signing_key.verifying_key()) != *subject {
+        return None;
+    }
+
+    let root = merkle::path_root(address_leaf(subject), ring.index, &ring.siblings)?;
+    let tag = hash::hash_parts(
+        HashAlgorithm::Sha256,
+        &[RING_TAG_DOMAIN, &ring.subject_key, &ring.event],
+    );
+    Some((root, tag))
+}
@@ -0,0 +1,141 @@
+//! RSA-2048 signature verification for legacy PKI issuers
+//!
+//! Uses fixed-width 2048-bit Montgomery arithmetic from `crypto-bigint`
+//! instead of a heap-allocating bignum, and fixes the public exponent to
+//! 65537 so the modular exponentiation is 16 squarings and one multiply.
+//! Both paddings use SHA-256 as the message digest.
+
+use crypto_bigint::modular::runtime_mod::{DynResidue, DynResidueParams};
+use crypto_bigint::{Encoding, U2048};
+use sha2::{Digest, Sha256};
+
+/// Size of the modulus, signature, and encoded message in bytes
+pub const MODULUS_BYTES: usize = 256;
+
+/// Length of a SHA-256 digest (also the PSS salt length we accept)
+const HASH_LEN: usize = 32;
+
+/// Length of the message randomizer carried with blind signatures
+pub const BLIND_RANDOMIZER_BYTES: usize = 32;
+
+/// DER prefix of the PKCS#1 v1.5 `DigestInfo` for SHA-256
+const SHA256_DIGEST_INFO: [u8; 19] = [
+    0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01,
+    0x05, 0x00, 0x04, 0x20,
+];
+
+/// Computes `signature^65537 mod n`, returning the encoded message
+///
+/// The modulus is the issuer public key as 256 big-endian bytes. Rejects
+/// moduli that are even or shorter than 2048 bits, and signatures that are
+/// not reduced modulo `n`.
+fn rsa_public_op(modulus: &[u8], signature: &[u8]) -> Option<[u8; MODULUS_BYTES]> {
+    if modulus.len() != MODULUS_BYTES || signature.len() != MODULUS_BYTES {
+        return None;
+    }
+    if modulus[0] & 0x80 == 0 || modulus[MODULUS_BYTES - 1] & 1 == 0 {
+        return None;
+    }
+
+    let n = U2048::from_be_slice(modulus);
+    let s = U2048::from_be_slice(signature);
+    if s >= n {
+        return None;
+    }
+
+    let base = DynResidue::new(&s, DynResidueParams::new(&n));
+    let mut acc = base;
+    for _ in 0..16 {
+        acc = acc.square();
+    }
+    Some((acc * base).retrieve().to_be_bytes())
+}
+
+/// Verifies an RSASSA-PKCS1-v1_5 signature with SHA-256 (RFC 8017 §8.2.2)
+pub fn verify_pkcs1v15(message: &[u8], signature: &[u8], modulus: &[u8]) -> bool {
+    let Some(encoded) = rsa_public_op(modulus, signature) else {
+        return false;
+    };
+
+    // 0x00 || 0x01 || PS (0xff..) || 0x00 || DigestInfo || H(m)
+    let mut expected = [0xffu8; MODULUS_BYTES];
+    let t_start = MODULUS_BYTES - SHA256_DIGEST_INFO.len() - HASH_LEN;
+    expected[0] = 0x00;
+    expected[1] = 0x01;
+    expected[t_start - 1] = 0x00;
+    expected[t_start..MODULUS_BYTES - HASH_LEN].copy_from_slice(&SHA256_DIGEST_INFO);
+    expected[MODULUS_BYTES - HASH_LEN..].copy_from_slice(&Sha256::digest(message));
+
+    encoded == expected
+}
+
+/// Verifies an RSASSA-PSS signature with SHA-256, MGF1-SHA-256 and a
+/// 32-byte salt (RFC 8017 §9.1.2)
+pub fn verify_pss(message: &[u8], signature: &[u8], modulus: &[u8]) -> bool {
+    let Some(encoded) = rsa_public_op(modulus, signature) else {
+        return false;
+    };
+
+    // A 2048-bit modulus gives emBits = 2047, so emLen = 256 and the top
+    // bit of the encoded message must be clear.
+    if encoded[MODULUS_BYTES - 1] != 0xbc || encoded[0] & 0x80 != 0 {
+        return false;
+    }
+
+    let db_len = MODULUS_BYTES - HASH_LEN - 1;
+    let (masked_db, rest) = encoded.split_at(db_len);
+    let h = &rest[..HASH_LEN];
+
+    let mut db = mgf1_sha256(h, db_len);
+    for (byte, mask) in db.iter_mut().zip(masked_db) {
+        *byte ^= mask;
+    }
+    db[0] &= 0x7f;
+
+    // DB = PS (zeros) || 0x01 || salt
+    let separator = db_len - HASH_LEN - 1;
+    if db[..separator].iter().any(|&b| b != 0) || db[separator] != 0x01 {
+        return false;
+    }
+    let salt = &db[separator + 1..];
+
+    let mut hasher = Sha256::new();
+    hasher.update([0u8; 8]);
+    hasher.update(Sha256::digest(message));
+    hasher.update(salt);
+
+    hasher.finalize()[..] == *h
+}
+
+/// Verifies an unblinded RSA blind signature (RFC 9474, randomized PSS
+/// variant with SHA-256)
+///
+/// `signature` is `randomizer || s`. The holder prefixed the randomizer to
+/// the message before blinding, so the underlying PSS signature covers
+/// `randomizer || message`.
+pub fn verify_blind(message: &[u8], signature: &[u8], modulus: &[u8]) -> bool {
+    if signature.len() != BLIND_RANDOMIZER_BYTES + MODULUS_BYTES {
+        return false;
+    }
+    let (randomizer, signature) = signature.split_at(BLIND_RANDOMIZER_BYTES);
+
+    let mut prepared = Vec::with_capacity(randomizer.len() + message.len());
+    prepared.extend_from_slice(randomizer);
+    prepared.extend_from_slice(message);
+    verify_pss(&prepared, signature, modulus)
+}
+
+/// MGF1 mask generation with SHA-256
+fn mgf1_sha256(seed: &[u8], len: usize) -> Vec<u8> {
+    let mut mask = Vec::with_capacity(len + HASH_LEN);
+    let mut counter: u32 = 0;
+    while mask.len() < len {
+        let mut hasher = Sha256::new();
+        hasher.update(seed);
+        hasher.update(counter.to_be_bytes());
+        mask.extend_from_slice(&hasher.finalize());
+        counter += 1;
+    }
+    mask.truncate(len);
+    mask
+}
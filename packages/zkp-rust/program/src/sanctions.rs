@@ -0,0 +1,47 @@
+//! Sanctions-list screening by non-membership
+//!
+//! A sanctions list is published as a Merkle tree, hashed like v4 claim
+//! trees, over its addresses in ascending order between the sentinels
+//! `0x00..00` and `0xff..ff`:
+//!
+//! ```text
+//! leaf = SHA-256(0x00 || address)
+//! ```
+//!
+//! The prover shows two adjacent leaves whose addresses bracket the
+//! subject strictly. Being sorted, the list then can't hold the subject,
+//! and only the tree root is committed, with its version.
+
+use sha2::{Digest, Sha256};
+
+use crate::{merkle, SanctionsExclusion};
+
+/// Hashes a listed address into a leaf
+fn address_leaf(address: &[u8; 20]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(address);
+    hasher.finalize().into()
+}
+
+/// Returns the root of the list `exclusion` shows doesn't hold `subject`,
+/// or `None` if its entries don't bracket the subject as neighbours
+pub fn excluding_root(subject: &[u8; 20], exclusion: &SanctionsExclusion) -> Option<[u8; 32]> {
+    if !(exclusion.lower < *subject && *subject < exclusion.upper)
+        || exclusion.lower_siblings.len() != exclusion.upper_siblings.len()
+    {
+        return None;
+    }
+
+    let lower = merkle::path_root(
+        address_leaf(&exclusion.lower),
+        exclusion.index,
+        &exclusion.lower_siblings,
+    )?;
+    let upper = merkle::path_root(
+        address_leaf(&exclusion.upper),
+        exclusion.index.checked_add(1)?,
+        &exclusion.upper_siblings,
+    )?;
+    (lower == upper).then_some(lower)
+}
@@ -0,0 +1,71 @@
+//! Claim schema validation
+//!
+//! An issuer registers a `ClaimSchema` describing its v2 claims (field
+//! names, value types and bounds). The holder supplies the schema as input,
+//! the circuit checks `credential_data` against it, and the schema hash is
+//! committed so verifiers can look up exactly which structure was proven.
+
+use crate::claims::{self, Claim};
+use crate::{hash, ClaimSchema, FieldType, HashAlgorithm, SchemaField};
+
+/// Hashes the schema's canonical encoding for the public output
+pub fn schema_hash(algorithm: HashAlgorithm, schema: &ClaimSchema) -> [u8; 32] {
+    hash::hash_parts(algorithm, &[&schema.to_bytes()])
+}
+
+/// Decodes a big-endian unsigned integer of 1 to 8 bytes
+fn uint(value: &[u8]) -> Option<u64> {
+    if value.is_empty() || value.len() > 8 {
+        return None;
+    }
+    Some(value.iter().fold(0, |acc, &byte| (acc << 8) | byte as u64))
+}
+
+/// Returns true if a claim value satisfies the field's type and bounds
+fn field_accepts(field: &SchemaField, value: &[u8]) -> bool {
+    let measured = match field.field_type {
+        FieldType::Utf8 if core::str::from_utf8(value).is_err() => return false,
+        FieldType::Utf8 | FieldType::Bytes => value.len() as u64,
+        FieldType::Uint => match uint(value) {
+            Some(number) => number,
+            None => return false,
+        },
+    };
+    (field.min..=field.max).contains(&measured)
+}
+
+/// Validates v2 credential data against a schema
+///
+/// Every claim must be declared by exactly one field and satisfy it, and
+/// every required field must be present. Schemas that declare a claim type
+/// twice are rejected.
+pub fn validate(schema: &ClaimSchema, data: &[u8]) -> bool {
+    let Some(claims) = claims::parse(data) else {
+        return false;
+    };
+
+    let fields = &schema.fields;
+    let unique = fields.iter().enumerate().all(|(i, field)| {
+        fields[..i]
+            .iter()
+            .all(|other| other.claim_type != field.claim_type)
+    });
+    if !unique {
+        return false;
+    }
+
+    let declared = |claim: &Claim| {
+        fields
+            .iter()
+            .find(|field| field.claim_type == claim.claim_type as u16)
+            .is_some_and(|field| field_accepts(field, claim.value))
+    };
+    let present = |field: &SchemaField| {
+        !field.required
+            || claims
+                .iter()
+                .any(|claim| claim.claim_type as u16 == field.claim_type)
+    };
+
+    claims.iter().all(declared) && fields.iter().all(present)
+}
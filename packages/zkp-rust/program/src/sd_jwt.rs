@@ -0,0 +1,110 @@
+//! SD-JWT credential parsing with selective disclosure
+//!
+//! An SD-JWT is `<issuer-jwt>~<disclosure>~...~<kb-jwt>`. The issuer JWT
+//! is an ES256K JWS whose payload lists salted digests of the hidden claims
+//! in `_sd` arrays; each disclosure is base64url JSON `[salt, name, value]`
+//! and reveals one claim when its SHA-256 digest appears in an `_sd` array.
+//! Only the names of the revealed claims reach the public output.
+//!
+//! The subject, timestamps and credential type are bound from the issuer
+//! JWT's own `sub`, `iat`, `exp` and `vct`, which as in SD-JWT VC can't be
+//! selectively disclosed, so a disclosure can't pass itself off as one.
+//!
+//! Holder key binding is not checked: the trailing key-binding JWT, if any,
+//! is ignored since the proof itself is what gets presented.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::jwt::{self, Jwt};
+
+/// The only digest algorithm accepted in `_sd_alg`
+const DIGEST_ALGORITHM: &str = "sha-256";
+
+/// Claims the circuit binds from the issuer JWT, which no disclosure may
+/// name
+const BOUND_CLAIMS: [&str; 4] = ["sub", "iat", "exp", "vct"];
+
+/// A parsed SD-JWT with verified disclosures
+pub struct SdJwt {
+    /// The issuer-signed JWT (its signature is not checked here)
+    pub jwt: Jwt,
+    /// Names of the disclosed claims, in presentation order
+    pub disclosed: Vec<String>,
+}
+
+/// Collects every `_sd` digest in `value`, including nested objects
+fn collect_digests(value: &Value, digests: &mut Vec<String>) {
+    match value {
+        Value::Object(object) => {
+            for (key, entry) in object {
+                match (key.as_str(), entry) {
+                    ("_sd", Value::Array(entries)) => digests.extend(
+                        entries
+                            .iter()
+                            .filter_map(|digest| digest.as_str().map(str::to_owned)),
+                    ),
+                    _ => collect_digests(entry, digests),
+                }
+            }
+        }
+        Value::Array(entries) => {
+            for entry in entries {
+                collect_digests(entry, digests);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Parses an SD-JWT and checks every disclosure against the `_sd` digests
+///
+/// Returns `None` if the issuer JWT is malformed, `_sd_alg` is not SHA-256,
+/// or a disclosure is malformed, matches no digest, is repeated or names a
+/// bound claim. Only object-property disclosures are supported.
+pub fn parse(token: &[u8]) -> Option<SdJwt> {
+    let token = core::str::from_utf8(token).ok()?;
+    let mut parts = token.split('~');
+    let jwt = jwt::parse(parts.next()?.as_bytes())?;
+
+    // The final part is the optional key-binding JWT
+    let mut disclosures: Vec<&str> = parts.collect();
+    disclosures.pop()?;
+
+    if jwt
+        .payload
+        .get("_sd_alg")
+        .is_some_and(|alg| alg != DIGEST_ALGORITHM)
+    {
+        return None;
+    }
+
+    let mut digests = Vec::new();
+    collect_digests(&jwt.payload, &mut digests);
+
+    let mut disclosed = Vec::with_capacity(disclosures.len());
+    for disclosure in disclosures {
+        // Each digest may be claimed by a single disclosure
+        let digest = URL_SAFE_NO_PAD.encode(Sha256::digest(disclosure.as_bytes()));
+        let position = digests.iter().position(|entry| *entry == digest)?;
+        digests.swap_remove(position);
+
+        let decoded: Value =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(disclosure).ok()?).ok()?;
+        let [Value::String(_salt), Value::String(name), value] = decoded.as_array()?.as_slice()
+        else {
+            return None;
+        };
+        if name == "_sd" || name == "..." || BOUND_CLAIMS.contains(&name.as_str()) {
+            return None;
+        }
+
+        // Disclosed objects may carry digests of further nested claims
+        collect_digests(value, &mut digests);
+        disclosed.push(name.clone());
+    }
+
+    Some(SdJwt { jwt, disclosed })
+}
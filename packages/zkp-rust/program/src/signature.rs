@@ -0,0 +1,196 @@
+//! Issuer signature verification
+//!
+//! Dispatches on the credential's `SignatureScheme` so the circuit can accept
+//! credentials from issuers on different curves.
+
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_381::{multi_miller_loop, G1Affine, G2Affine, G2Prepared, G2Projective, Gt};
+use p256::ecdsa::signature::hazmat::PrehashVerifier;
+
+use credential_verifier_lib::eip712;
+
+use crate::{bbs, hash, rsa, HashAlgorithm, SignatureScheme};
+
+/// Ciphersuite tag for BLS signatures (basic scheme, signatures in G2)
+pub const BLS_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// Verifies the issuer's signature over `message` using the given scheme
+///
+/// `message_hash` selects the digest for the ECDSA schemes; the others fix
+/// their hash as part of the scheme.
+pub fn verify_signature(
+    scheme: SignatureScheme,
+    message_hash: HashAlgorithm,
+    message: &[u8],
+    signature: &[u8],
+    pubkey: &[u8],
+) -> bool {
+    match scheme {
+        SignatureScheme::Secp256k1 => {
+            verify_secp256k1(&hash::message_digest(message_hash, message), signature, pubkey)
+        }
+        SignatureScheme::Secp256r1 => {
+            verify_secp256r1(&hash::message_digest(message_hash, message), signature, pubkey)
+        }
+        SignatureScheme::Bls12381 => verify_bls12381(message, signature, pubkey),
+        SignatureScheme::Rsa2048Pkcs1v15 => rsa::verify_pkcs1v15(message, signature, pubkey),
+        SignatureScheme::Rsa2048Pss => rsa::verify_pss(message, signature, pubkey),
+        SignatureScheme::Rsa2048Blind => rsa::verify_blind(message, signature, pubkey),
+        SignatureScheme::Eip712 => verify_eip712(message, signature, pubkey),
+        SignatureScheme::BbsPlus => bbs::verify(&[message.to_vec()], signature, pubkey),
+        // HMAC needs the private key, which only `mac::verify` receives
+        SignatureScheme::HmacSha256 => false,
+        // CL signatures cover the attribute vector, which only
+        // `anoncreds::verify` receives
+        SignatureScheme::AnonCredsCl => false,
+    }
+}
+
+/// Verifies a secp256k1 ECDSA signature over a message digest
+///
+/// The public key is SEC1-encoded (33 or 65 bytes). The signature is the
+/// 64-byte `r || s` form; a trailing recovery byte is tolerated and
+/// ignored. High-s signatures are rejected.
+fn verify_secp256k1(digest: &[u8], signature: &[u8], pubkey: &[u8]) -> bool {
+    let Ok(verifying_key) = k256::ecdsa::VerifyingKey::from_sec1_bytes(pubkey) else {
+        return false;
+    };
+
+    if signature.len() != 64 && signature.len() != 65 {
+        return false;
+    }
+    let Ok(signature) = k256::ecdsa::Signature::from_slice(&signature[..64]) else {
+        return false;
+    };
+
+    verifying_key.verify_prehash(digest, &signature).is_ok()
+}
+
+/// Verifies a NIST P-256 ECDSA signature over a message digest
+///
+/// The public key is SEC1-encoded (33 or 65 bytes). The signature may be
+/// either the fixed 64-byte `r || s` form or ASN.1 DER, since eID and
+/// passport signers commonly emit DER.
+pub fn verify_secp256r1(digest: &[u8], signature: &[u8], pubkey: &[u8]) -> bool {
+    let Ok(verifying_key) = p256::ecdsa::VerifyingKey::from_sec1_bytes(pubkey) else {
+        return false;
+    };
+
+    let parsed = if signature.len() == 64 {
+        p256::ecdsa::Signature::from_slice(signature)
+    } else {
+        p256::ecdsa::Signature::from_der(signature)
+    };
+    let Ok(signature) = parsed else {
+        return false;
+    };
+
+    verifying_key.verify_prehash(digest, &signature).is_ok()
+}
+
+/// Verifies a BLS12-381 signature in the min-pk setting
+///
+/// Checks `e(pk, H(m)) == e(g1, sig)` with `H` the RFC 9380 hash-to-G2
+/// under [`BLS_DST`]. Both points must be compressed and in the prime-order
+/// subgroup, and the public key must not be the identity.
+fn verify_bls12381(message: &[u8], signature: &[u8], pubkey: &[u8]) -> bool {
+    let (Ok(pubkey), Ok(signature)) = (
+        <&[u8; 48]>::try_from(pubkey),
+        <&[u8; 96]>::try_from(signature),
+    ) else {
+        return false;
+    };
+
+    let pubkey = G1Affine::from_compressed(pubkey);
+    let signature = G2Affine::from_compressed(signature);
+    if pubkey.is_none().into() || signature.is_none().into() {
+        return false;
+    }
+    let (pubkey, signature) = (pubkey.unwrap(), signature.unwrap());
+    if pubkey.is_identity().into() {
+        return false;
+    }
+
+    let hashed = G2Affine::from(
+        <G2Projective as HashToCurve<ExpandMsgXmd<sha2_v0_9::Sha256>>>::hash_to_curve(
+            message, BLS_DST,
+        ),
+    );
+
+    multi_miller_loop(&[
+        (&pubkey, &G2Prepared::from(hashed)),
+        (&-G1Affine::generator(), &G2Prepared::from(signature)),
+    ])
+    .final_exponentiation()
+        == Gt::identity()
+}
+
+/// Verifies a recoverable secp256k1 signature over an EIP-712 digest
+///
+/// `digest` is the 32-byte typed-data hash. The signature is the 65-byte
+/// `r || s || v` form wallets return (`v` as 0/1 or 27/28); high-s values
+/// are rejected as on Ethereum. The recovered key is compared against the
+/// SEC1 pubkey, or against its address when a 20-byte address is supplied.
+fn verify_eip712(digest: &[u8], signature: &[u8], pubkey: &[u8]) -> bool {
+    use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+    if digest.len() != 32 || signature.len() != 65 {
+        return false;
+    }
+
+    let Ok(parsed) = Signature::from_slice(&signature[..64]) else {
+        return false;
+    };
+    if parsed.normalize_s().is_some() {
+        return false;
+    }
+
+    let v = signature[64];
+    let Some(recovery_id) = RecoveryId::from_byte(if v >= 27 { v - 27 } else { v }) else {
+        return false;
+    };
+
+    let Ok(recovered) = VerifyingKey::recover_from_prehash(digest, &parsed, recovery_id) else {
+        return false;
+    };
+
+    if pubkey.len() == 20 {
+        ethereum_address(&recovered) == *pubkey
+    } else {
+        VerifyingKey::from_sec1_bytes(pubkey).is_ok_and(|expected| expected == recovered)
+    }
+}
+
+/// Verifies an EIP-191 `personal_sign` signature over `message` by the key
+/// controlling `address`
+///
+/// The digest is `eip712::personal_sign_digest`, as wallets sign it; the
+/// signature is checked like an EIP-712 one.
+pub fn verify_personal_sign(message: &[u8], signature: &[u8], address: &[u8; 20]) -> bool {
+    verify_eip712(&eip712::personal_sign_digest(message), signature, address)
+}
+
+/// Derives the Ethereum address of a secp256k1 key
+///
+/// The address is the last 20 bytes of keccak256 over the 64-byte
+/// uncompressed point (without the 0x04 prefix).
+pub fn ethereum_address(key: &k256::ecdsa::VerifyingKey) -> [u8; 20] {
+    let uncompressed = key.to_encoded_point(false);
+    let hash = hash::keccak256(&uncompressed.as_bytes()[1..]);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
+
+/// Returns the Ethereum address of a secp256k1 issuer key
+///
+/// Accepts a SEC1 pubkey (compressed keys are decompressed first) or an
+/// address that was supplied directly. Returns `None` if the key is not a
+/// valid secp256k1 point.
+pub fn issuer_address(pubkey: &[u8]) -> Option<[u8; 20]> {
+    if let Ok(address) = <[u8; 20]>::try_from(pubkey) {
+        return Some(address);
+    }
+    let key = k256::ecdsa::VerifyingKey::from_sec1_bytes(pubkey).ok()?;
+    Some(ethereum_address(&key))
+}
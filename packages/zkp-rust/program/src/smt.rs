@@ -0,0 +1,142 @@
+//! Credential data format v5: a sparse Merkle tree of key-value claims
+//!
+//! For credentials with many optional fields, the issuer signs the root of
+//! a 256-level SHA-256 tree in which each v3 claim sits at the path given
+//! by the hash of its key. Holders prove just the keys a verifier asks
+//! about, either present with their value or provably absent:
+//!
+//! ```text
+//! version = 5 (BE u32) || root (32 bytes)
+//! path = SHA-256(key), most significant bit first from the root
+//! leaf = SHA-256(0x00 || path || tag || value)
+//! node = SHA-256(0x01 || left || right), or 32 zero bytes when both
+//!        children are
+//! ```
+//!
+//! Empty leaves and subtrees are 32 zero bytes.
+
+use sha2::{Digest, Sha256};
+
+use crate::keyed::{self, KeyedClaims};
+use crate::{hash, HashAlgorithm, SparseClaimProof};
+
+/// Version tag of the sparse Merkle tree format
+pub const FORMAT_V5: u32 = 5;
+
+/// Levels below the root
+const DEPTH: usize = 256;
+
+/// The hash of an empty leaf or subtree
+const EMPTY: [u8; 32] = [0u8; 32];
+
+/// Returns the path of `key`
+pub fn key_path(key: &str) -> [u8; 32] {
+    Sha256::digest(key.as_bytes()).into()
+}
+
+/// Hashes a claim into a leaf
+pub fn leaf_hash(path: &[u8; 32], value: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(path);
+    hasher.update(value);
+    hasher.finalize().into()
+}
+
+/// Hashes two children into their parent
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    if *left == EMPTY && *right == EMPTY {
+        return EMPTY;
+    }
+    let mut hasher = Sha256::new();
+    hasher.update([0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Recomputes the root from a proof, or `None` if its siblings don't
+/// match its occupancy bitmap
+fn proof_root(proof: &SparseClaimProof) -> Option<[u8; 32]> {
+    let path = key_path(&proof.key);
+    let mut node = match &proof.value {
+        Some(value) => leaf_hash(&path, value),
+        None => EMPTY,
+    };
+
+    let mut siblings = proof.siblings.iter();
+    for height in 0..DEPTH {
+        let sibling = if proof.occupied[height / 8] >> (height % 8) & 1 == 1 {
+            *siblings.next()?
+        } else {
+            EMPTY
+        };
+        // The bit choosing this node's side, counted from the root
+        let level = DEPTH - 1 - height;
+        node = if path[level / 8] >> (7 - level % 8) & 1 == 0 {
+            node_hash(&node, &sibling)
+        } else {
+            node_hash(&sibling, &node)
+        };
+    }
+    siblings.next().is_none().then_some(node)
+}
+
+/// Validates proven keys against a v5 credential's signed root
+///
+/// Every proof must verify and each key may be proven once. The values of
+/// the keys proven present must be well-formed and meet the credential
+/// type's requirements, exactly as v3 claims would.
+pub fn validate(
+    data: &[u8],
+    proofs: &[SparseClaimProof],
+    credential_type: u32,
+    issued_at: u64,
+) -> bool {
+    if data.len() != 36 || data[..4] != FORMAT_V5.to_be_bytes() {
+        return false;
+    }
+    let root = &data[4..];
+
+    let mut present = Vec::new();
+    for proof in proofs {
+        if proof_root(proof).as_ref().map(|node| &node[..]) != Some(root) {
+            return false;
+        }
+        if let Some(value) = &proof.value {
+            let Some(value) = keyed::decode_value(value) else {
+                return false;
+            };
+            present.push((proof.key.as_str(), value));
+        }
+    }
+    let unique = proofs
+        .iter()
+        .enumerate()
+        .all(|(i, proof)| proofs[..i].iter().all(|other| other.key != proof.key));
+
+    unique
+        && KeyedClaims::from_claims(present)
+            .is_some_and(|claims| claims.satisfy(credential_type, issued_at))
+}
+
+/// Hashes the proven keys for the public output
+///
+/// Keys are sorted so the hash doesn't depend on proof order, and each
+/// contributes `length (BE u32) || key || present (u8)`, so verifiers learn
+/// which keys were shown present or absent but not their values.
+pub fn proven_keys_hash(algorithm: HashAlgorithm, proofs: &[SparseClaimProof]) -> [u8; 32] {
+    let mut keys: Vec<(&str, bool)> = proofs
+        .iter()
+        .map(|proof| (proof.key.as_str(), proof.value.is_some()))
+        .collect();
+    keys.sort_unstable();
+
+    let mut encoded = Vec::new();
+    for (key, present) in keys {
+        encoded.extend_from_slice(&(key.len() as u32).to_be_bytes());
+        encoded.extend_from_slice(key.as_bytes());
+        encoded.push(present as u8);
+    }
+    hash::hash_parts(algorithm, &[&encoded])
+}
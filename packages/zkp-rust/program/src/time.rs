@@ -0,0 +1,158 @@
+//! RFC 3339 timestamp parsing and calendar arithmetic
+//!
+//! W3C credentials carry their validity period as date-time strings, which
+//! are converted to the Unix seconds used everywhere else in the circuit.
+
+use crate::TimestampGranularity;
+
+/// Length of an expiry epoch (30 days)
+pub const EPOCH_SECONDS: u64 = 30 * 86400;
+
+/// Parses an RFC 3339 date-time into seconds since the Unix epoch
+///
+/// Accepts `YYYY-MM-DDTHH:MM:SS` with optional fractional seconds (which
+/// are truncated) and a `Z` or `±HH:MM` offset. Times before the epoch are
+/// rejected.
+pub fn parse_rfc3339(value: &str) -> Option<u64> {
+    let bytes = value.as_bytes();
+    if bytes.len() < 20
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || !matches!(bytes[10], b'T' | b't')
+        || bytes[13] != b':'
+        || bytes[16] != b':'
+    {
+        return None;
+    }
+
+    let number = |start: usize, end: usize| -> Option<i64> {
+        bytes[start..end].iter().try_fold(0i64, |acc, &b| {
+            b.is_ascii_digit().then(|| acc * 10 + i64::from(b - b'0'))
+        })
+    };
+    let (year, month, day) = (number(0, 4)?, number(5, 7)?, number(8, 10)?);
+    let (hour, minute, second) = (number(11, 13)?, number(14, 16)?, number(17, 19)?);
+
+    if !(1..=12).contains(&month)
+        || day < 1
+        || day > days_in_month(year, month)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+
+    // Skip fractional seconds
+    let mut rest = &value[19..];
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return None;
+        }
+        rest = &fraction[digits..];
+    }
+
+    let offset = match rest.as_bytes() {
+        [b'Z' | b'z'] => 0,
+        [sign @ (b'+' | b'-'), _, _, b':', _, _] => {
+            let start = value.len() - 5;
+            let (hours, minutes) = (number(start, start + 2)?, number(start + 3, start + 5)?);
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            let offset = hours * 3600 + minutes * 60;
+            if *sign == b'+' {
+                offset
+            } else {
+                -offset
+            }
+        }
+        _ => return None,
+    };
+
+    let local = days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second;
+    u64::try_from(local - offset).ok()
+}
+
+/// Returns true for Gregorian leap years
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Number of days in `month` of `year`
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Proleptic Gregorian date of a day count since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Returns the start (00:00 UTC) of the `years`-th anniversary of the day
+/// containing `timestamp`
+///
+/// A February 29 anniversary falls on March 1 in common years, so someone
+/// born on a leap day comes of age the day after February 28.
+pub fn anniversary(timestamp: i64, years: u32) -> i64 {
+    let (year, month, day) = civil_from_days(timestamp.div_euclid(86400));
+    let year = year + i64::from(years);
+    let days = if day > days_in_month(year, month) {
+        days_from_civil(year, 3, 1)
+    } else {
+        days_from_civil(year, month, day)
+    };
+    days * 86400
+}
+
+/// Rounds `timestamp` down to a multiple of `granularity`
+///
+/// Weeks are counted from the Unix epoch, so they start on Thursdays.
+pub fn round_down(timestamp: u64, granularity: TimestampGranularity) -> u64 {
+    let seconds = match granularity {
+        TimestampGranularity::Exact => 1,
+        TimestampGranularity::Day => 86400,
+        TimestampGranularity::Week => 7 * 86400,
+        TimestampGranularity::Epoch => EPOCH_SECONDS,
+    };
+    timestamp - timestamp % seconds
+}
+
+/// Returns the index of the expiry epoch containing `expires_at`, or 0 for
+/// a credential that doesn't expire
+///
+/// The credential is valid through every earlier epoch, so a contract
+/// accepts it while `block.timestamp / EPOCH_SECONDS` is below the index.
+pub fn expiry_epoch(expires_at: u64) -> u64 {
+    expires_at / EPOCH_SECONDS
+}
@@ -0,0 +1,38 @@
+//! Anonymous issuers via trusted-set membership
+//!
+//! Instead of revealing which issuer signed, the prover shows the issuer's
+//! key is a leaf of a published Merkle tree of trusted issuers and only
+//! the tree root is committed. Verifiers learn that some trusted issuer
+//! signed the credential, not which one. The tree uses the v4 claim
+//! tree's node hashing, with leaves
+//! `SHA-256(0x00 || scheme (u8) || issuer_pubkey)`.
+//!
+//! On-chain issuer registries publish the same kind of tree. There the
+//! issuer stays identified and the circuit commits the registry root, so a
+//! contract checks one root instead of keeping a mapping of issuer keys.
+
+use sha2::{Digest, Sha256};
+
+use crate::{merkle, IssuerMembership, SignatureScheme};
+
+/// Hashes an issuer key into a trusted-set leaf
+pub fn issuer_leaf(scheme: SignatureScheme, issuer_pubkey: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00, scheme as u8]);
+    hasher.update(issuer_pubkey);
+    hasher.finalize().into()
+}
+
+/// Returns the root of the trusted set `membership` places the issuer in,
+/// or `None` if the path is malformed
+pub fn trusted_set_root(
+    scheme: SignatureScheme,
+    issuer_pubkey: &[u8],
+    membership: &IssuerMembership,
+) -> Option<[u8; 32]> {
+    merkle::path_root(
+        issuer_leaf(scheme, issuer_pubkey),
+        membership.index,
+        &membership.siblings,
+    )
+}
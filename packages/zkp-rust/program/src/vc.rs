@@ -0,0 +1,93 @@
+//! W3C Verifiable Credential parsing
+//!
+//! Accepts a VC Data Model JSON document as `credential_data`. The subject
+//! comes from `credentialSubject.id` and the validity period from
+//! `issuanceDate`/`expirationDate` (or the VC 2.0 `validFrom`/`validUntil`),
+//! replacing the binary credential fields. The issuer still signs the
+//! canonical signing message, which covers the document bytes.
+
+use serde::Deserialize;
+
+use crate::time;
+
+/// The type every W3C credential must declare
+const CREDENTIAL_TYPE: &str = "VerifiableCredential";
+
+/// A JSON value that may be a single entry or an array of entries
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+/// An `issuer` given as a bare id or as an object with an `id`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Issuer {
+    Id(String),
+    Object { id: String },
+}
+
+/// The credential subject; claims other than `id` are not interpreted
+#[derive(Deserialize)]
+struct Subject {
+    id: String,
+}
+
+/// The fields of a VC document the circuit interprets
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Document {
+    #[serde(rename = "type")]
+    types: OneOrMany<String>,
+    issuer: Issuer,
+    credential_subject: Subject,
+    #[serde(alias = "validFrom")]
+    issuance_date: String,
+    #[serde(default, alias = "validUntil")]
+    expiration_date: Option<String>,
+}
+
+/// A parsed W3C credential
+pub struct Credential {
+    /// Issuer id (a DID or URL)
+    pub issuer: String,
+    /// `credentialSubject.id`
+    pub subject: String,
+    /// Issuance time (seconds since the epoch)
+    pub issued_at: u64,
+    /// Expiration time (0 for no expiration)
+    pub expires_at: u64,
+}
+
+/// Parses a VC JSON document
+///
+/// Returns `None` if the document is not valid JSON, lacks the
+/// `VerifiableCredential` type or a required field, or has a malformed
+/// date.
+pub fn parse(document: &[u8]) -> Option<Credential> {
+    let document: Document = serde_json::from_slice(document).ok()?;
+
+    let is_credential = match &document.types {
+        OneOrMany::One(kind) => kind == CREDENTIAL_TYPE,
+        OneOrMany::Many(kinds) => kinds.iter().any(|kind| kind == CREDENTIAL_TYPE),
+    };
+    if !is_credential {
+        return None;
+    }
+
+    let expires_at = match &document.expiration_date {
+        Some(date) => time::parse_rfc3339(date)?,
+        None => 0,
+    };
+
+    Some(Credential {
+        issuer: match document.issuer {
+            Issuer::Id(id) | Issuer::Object { id } => id,
+        },
+        subject: document.credential_subject.id,
+        issued_at: time::parse_rfc3339(&document.issuance_date)?,
+        expires_at,
+    })
+}
@@ -0,0 +1,100 @@
+//! ECVRF verification for issuer-assigned randomness
+//!
+//! Implements ECVRF-P256-SHA256-TAI (RFC 9381, suite 0x01). The issuer
+//! evaluates its VRF over the credential's signing message, so the output
+//! is unpredictable to the holder yet fixed per credential, which is what
+//! lottery-style eligibility schemes need.
+//!
+//! A proof is `Gamma || c || s`: a 33-byte compressed point, a 16-byte
+//! challenge and a 32-byte big-endian scalar.
+
+use p256::elliptic_curve::ff::PrimeField;
+use p256::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+use p256::{AffinePoint, EncodedPoint, FieldBytes, ProjectivePoint, Scalar};
+use sha2::{Digest, Sha256};
+
+/// RFC 9381 suite string for ECVRF-P256-SHA256-TAI
+const SUITE: u8 = 0x01;
+
+/// Challenge length in bytes
+const CHALLENGE_BYTES: usize = 16;
+
+/// Length of an encoded proof
+pub const PROOF_BYTES: usize = 33 + CHALLENGE_BYTES + 32;
+
+/// Decodes a SEC1 point, rejecting the identity
+fn decode_point(bytes: &[u8]) -> Option<ProjectivePoint> {
+    let encoded = EncodedPoint::from_bytes(bytes).ok()?;
+    Option::<AffinePoint>::from(AffinePoint::from_encoded_point(&encoded))
+        .map(ProjectivePoint::from)
+}
+
+/// Encodes a point in compressed SEC1 form (RFC 9381 `point_to_string`)
+fn encode_point(point: &ProjectivePoint) -> Vec<u8> {
+    point.to_affine().to_encoded_point(true).as_bytes().to_vec()
+}
+
+/// Parses a big-endian scalar, left-padding shorter inputs
+fn decode_scalar(bytes: &[u8]) -> Option<Scalar> {
+    let mut repr = FieldBytes::default();
+    repr[32 - bytes.len()..].copy_from_slice(bytes);
+    Option::from(Scalar::from_repr(repr))
+}
+
+/// Hashes `alpha` to a curve point by try-and-increment (RFC 9381 §5.4.1.1)
+fn encode_to_curve(pubkey: &[u8], alpha: &[u8]) -> Option<ProjectivePoint> {
+    (0u8..=255).find_map(|counter| {
+        let mut hasher = Sha256::new();
+        hasher.update([SUITE, 0x01]);
+        hasher.update(pubkey);
+        hasher.update(alpha);
+        hasher.update([counter, 0x00]);
+
+        let mut candidate = [0x02u8; 33];
+        candidate[1..].copy_from_slice(&hasher.finalize());
+        decode_point(&candidate)
+    })
+}
+
+/// Computes the truncated challenge over five points (RFC 9381 §5.4.3)
+fn challenge(points: [&ProjectivePoint; 5]) -> [u8; CHALLENGE_BYTES] {
+    let mut hasher = Sha256::new();
+    hasher.update([SUITE, 0x02]);
+    for point in points {
+        hasher.update(encode_point(point));
+    }
+    hasher.update([0x00]);
+
+    let mut c = [0u8; CHALLENGE_BYTES];
+    c.copy_from_slice(&hasher.finalize()[..CHALLENGE_BYTES]);
+    c
+}
+
+/// Verifies a VRF proof and returns the output `beta`
+///
+/// Returns `None` if the key or proof is malformed or the proof does not
+/// verify for `alpha` under `pubkey` (a SEC1 P-256 key).
+pub fn verify(pubkey: &[u8], alpha: &[u8], proof: &[u8]) -> Option<[u8; 32]> {
+    if proof.len() != PROOF_BYTES {
+        return None;
+    }
+    let y = decode_point(pubkey)?;
+    let gamma = decode_point(&proof[..33])?;
+    let c = decode_scalar(&proof[33..33 + CHALLENGE_BYTES])?;
+    let s = decode_scalar(&proof[33 + CHALLENGE_BYTES..])?;
+
+    let h = encode_to_curve(&encode_point(&y), alpha)?;
+    let u = ProjectivePoint::GENERATOR * s - y * c;
+    let v = h * s - gamma * c;
+
+    if challenge([&y, &h, &gamma, &u, &v])[..] != proof[33..33 + CHALLENGE_BYTES] {
+        return None;
+    }
+
+    // P-256 has cofactor 1, so beta hashes Gamma directly
+    let mut hasher = Sha256::new();
+    hasher.update([SUITE, 0x03]);
+    hasher.update(encode_point(&gamma));
+    hasher.update([0x00]);
+    Some(hasher.finalize().into())
+}
@@ -0,0 +1,60 @@
+//! WebAuthn device binding
+//!
+//! An issuer can bind a credential to the holder's passkey by signing a
+//! commitment to its P-256 public key. Proving the credential then takes a
+//! fresh WebAuthn assertion from that passkey over the verifier nonce, so
+//! a copied credential file can't be proven from another device:
+//!
+//! ```text
+//! signed = authenticatorData || SHA-256(clientDataJSON)
+//! clientDataJSON.type = "webauthn.get"
+//! clientDataJSON.challenge = base64url(verifier_nonce)
+//! ```
+//!
+//! Only the relying party ID hash from the authenticator data is
+//! committed, so verifiers can check which site the passkey belongs to.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::{signature, DeviceAssertion};
+
+/// Authenticator data flag set when the user was present
+const USER_PRESENT: u8 = 0x01;
+
+/// Fields of the client data checked by the circuit
+#[derive(Deserialize)]
+struct ClientData {
+    #[serde(rename = "type")]
+    kind: String,
+    challenge: String,
+}
+
+/// Verifies a passkey assertion over `nonce` and returns the relying party
+/// ID hash, or `None` if the assertion is malformed, wasn't made with the
+/// user present, answers another challenge or isn't signed by the key
+pub fn verify(
+    device_pubkey: &[u8],
+    assertion: &DeviceAssertion,
+    nonce: &[u8; 32],
+) -> Option<[u8; 32]> {
+    let authenticator_data = &assertion.authenticator_data;
+    // rpIdHash (32) || flags (1) || signCount (4) at the least
+    if authenticator_data.len() < 37 || authenticator_data[32] & USER_PRESENT == 0 {
+        return None;
+    }
+
+    let client_data: ClientData = serde_json::from_slice(&assertion.client_data_json).ok()?;
+    if client_data.kind != "webauthn.get" || client_data.challenge != URL_SAFE_NO_PAD.encode(nonce)
+    {
+        return None;
+    }
+
+    let mut signed = authenticator_data.clone();
+    signed.extend_from_slice(&Sha256::digest(&assertion.client_data_json));
+    let digest: [u8; 32] = Sha256::digest(&signed).into();
+    signature::verify_secp256r1(&digest, &assertion.signature, device_pubkey)
+        .then(|| authenticator_data[..32].try_into().expect("32-byte hash"))
+}
@@ -0,0 +1,165 @@
+//! X.509 issuer certificate chain verification
+//!
+//! Enterprise issuers sign credentials with keys certified by a sub-CA. The
+//! prover supplies the chain (leaf first, root last) and the circuit checks
+//! every link up to the root, whose hash is committed so verifiers can pin
+//! the CA they trust instead of trusting the prover's claim about the key.
+//!
+//! Supported links are ECDSA P-256 with SHA-256 and RSA-2048 PKCS#1 v1.5
+//! with SHA-256, matching the schemes the issuer itself can sign with.
+
+use sha2::{Digest, Sha256};
+use x509_cert::der::oid::ObjectIdentifier;
+use x509_cert::der::{Decode, Encode};
+use x509_cert::ext::pkix::BasicConstraints;
+use x509_cert::Certificate;
+
+use crate::{rsa, signature, HashAlgorithm, SignatureScheme};
+
+const ECDSA_WITH_SHA256: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.2");
+const SHA256_WITH_RSA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.11");
+const EC_PUBLIC_KEY: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.2.1");
+const SECP256R1: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.3.1.7");
+const RSA_ENCRYPTION: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1");
+const BASIC_CONSTRAINTS: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.29.19");
+
+/// Public key certified by a certificate
+enum CertifiedKey {
+    /// SEC1-encoded P-256 point
+    P256(Vec<u8>),
+    /// 256-byte RSA modulus (exponent 65537)
+    Rsa2048(Vec<u8>),
+}
+
+/// Extracts the subject public key, if it is of a supported type
+fn subject_key(cert: &Certificate) -> Option<CertifiedKey> {
+    let spki = &cert.tbs_certificate.subject_public_key_info;
+    let key_bytes = spki.subject_public_key.as_bytes()?;
+
+    if spki.algorithm.oid == EC_PUBLIC_KEY {
+        let curve: ObjectIdentifier = spki.algorithm.parameters.as_ref()?.decode_as().ok()?;
+        (curve == SECP256R1).then(|| CertifiedKey::P256(key_bytes.to_vec()))
+    } else if spki.algorithm.oid == RSA_ENCRYPTION {
+        let key = pkcs1::RsaPublicKey::from_der(key_bytes).ok()?;
+        if key.public_exponent.as_bytes() != [0x01, 0x00, 0x01] {
+            return None;
+        }
+        let modulus = key.modulus.as_bytes();
+        (modulus.len() == rsa::MODULUS_BYTES).then(|| CertifiedKey::Rsa2048(modulus.to_vec()))
+    } else {
+        None
+    }
+}
+
+/// Checks `cert` carries a valid signature by `issuer_key`
+fn verify_issued_by(cert: &Certificate, issuer_key: &CertifiedKey) -> bool {
+    let Ok(tbs) = cert.tbs_certificate.to_der() else {
+        return false;
+    };
+    let Some(signature) = cert.signature.as_bytes() else {
+        return false;
+    };
+
+    let algorithm = cert.signature_algorithm.oid;
+    match issuer_key {
+        CertifiedKey::P256(key) if algorithm == ECDSA_WITH_SHA256 => signature::verify_signature(
+            SignatureScheme::Secp256r1,
+            HashAlgorithm::Sha256,
+            &tbs,
+            signature,
+            key,
+        ),
+        CertifiedKey::Rsa2048(modulus) if algorithm == SHA256_WITH_RSA => {
+            rsa::verify_pkcs1v15(&tbs, signature, modulus)
+        }
+        _ => false,
+    }
+}
+
+/// Returns true if the certificate is marked as a CA by basicConstraints
+fn is_ca(cert: &Certificate) -> bool {
+    cert.tbs_certificate
+        .extensions
+        .iter()
+        .flatten()
+        .filter(|extension| extension.extn_id == BASIC_CONSTRAINTS)
+        .any(|extension| {
+            BasicConstraints::from_der(extension.extn_value.as_bytes())
+                .is_ok_and(|constraints| constraints.ca)
+        })
+}
+
+/// Returns true if `now` falls inside the certificate's validity period
+fn within_validity(cert: &Certificate, now: u64) -> bool {
+    let validity = &cert.tbs_certificate.validity;
+    validity.not_before.to_unix_duration().as_secs() <= now
+        && now <= validity.not_after.to_unix_duration().as_secs()
+}
+
+/// Returns true if the leaf certifies the key the credential was signed with
+fn certifies_issuer(
+    leaf_key: &CertifiedKey,
+    scheme: SignatureScheme,
+    issuer_pubkey: &[u8],
+) -> bool {
+    match (leaf_key, scheme) {
+        (CertifiedKey::P256(key), SignatureScheme::Secp256r1) => {
+            // Compare points rather than bytes so compressed and
+            // uncompressed encodings of the same key match
+            match (
+                p256::PublicKey::from_sec1_bytes(key),
+                p256::PublicKey::from_sec1_bytes(issuer_pubkey),
+            ) {
+                (Ok(certified), Ok(issuer)) => certified == issuer,
+                _ => false,
+            }
+        }
+        (
+            CertifiedKey::Rsa2048(modulus),
+            SignatureScheme::Rsa2048Pkcs1v15
+            | SignatureScheme::Rsa2048Pss
+            | SignatureScheme::Rsa2048Blind,
+        ) => modulus.as_slice() == issuer_pubkey,
+        _ => false,
+    }
+}
+
+/// Verifies the issuer certificate chain and returns the root CA hash
+///
+/// `chain` holds DER certificates ordered leaf first. Every certificate
+/// must be valid at `current_time`, each link must name and be signed by
+/// the next certificate, which must be a CA, and the leaf must certify
+/// `issuer_pubkey`. The root is trusted only through its SHA-256 hash,
+/// which the caller commits.
+pub fn verify_chain(
+    chain: &[Vec<u8>],
+    scheme: SignatureScheme,
+    issuer_pubkey: &[u8],
+    current_time: u64,
+) -> Option<[u8; 32]> {
+    let certs = chain
+        .iter()
+        .map(|der| Certificate::from_der(der).ok())
+        .collect::<Option<Vec<_>>>()?;
+    let (leaf, root) = (certs.first()?, chain.last()?);
+
+    if !certs.iter().all(|cert| within_validity(cert, current_time)) {
+        return None;
+    }
+
+    for link in certs.windows(2) {
+        let (child, parent) = (&link[0], &link[1]);
+        if child.tbs_certificate.issuer != parent.tbs_certificate.subject || !is_ca(parent) {
+            return None;
+        }
+        if !verify_issued_by(child, &subject_key(parent)?) {
+            return None;
+        }
+    }
+
+    if !certifies_issuer(&subject_key(leaf)?, scheme, issuer_pubkey) {
+        return None;
+    }
+
+    Some(Sha256::digest(root).into())
+}
@@ -0,0 +1,114 @@
+//! Sample RSA revocation accumulators
+//!
+//! Mirrors the program's `accumulator` module. The modulus comes from a
+//! throwaway RSA key whose factors are dropped, as an issuer's trusted
+//! setup would, and witnesses are computed from the revoked primes alone.
+
+use anyhow::{anyhow, Result};
+use credential_verifier_lib::hash::message_digest;
+use credential_verifier_lib::{AccumulatorWitness, HashAlgorithm};
+use num_bigint_dig::prime::probably_prime;
+use num_bigint_dig::ModInverse;
+use rsa::traits::PublicKeyParts;
+use rsa::{BigUint, RsaPrivateKey};
+use sha2::{Digest, Sha256};
+
+/// Domain tag for mapping credential hashes to primes
+const PRIME_DOMAIN: &[u8] = b"CREDENCE_ACCUMULATOR_PRIME_V1";
+
+/// Base `g` of every accumulator
+const ACCUMULATOR_BASE: u32 = 3;
+
+/// Version of the sample accumulator
+const SAMPLE_VERSION: u32 = 1;
+
+/// An issuer accumulator over a few revoked sample credentials
+pub struct SampleAccumulator {
+    modulus: BigUint,
+    /// Product of the revoked credentials' primes
+    revoked: BigUint,
+}
+
+/// Maps a credential hash to its accumulator prime (mirrors
+/// `accumulator::hash_to_prime`)
+fn hash_to_prime(credential_hash: &[u8; 32]) -> BigUint {
+    (0u32..)
+        .map(|counter| {
+            let mut candidate: [u8; 32] = Sha256::new()
+                .chain_update(PRIME_DOMAIN)
+                .chain_update(credential_hash)
+                .chain_update(counter.to_be_bytes())
+                .finalize()
+                .into();
+            candidate[0] |= 0x80;
+            candidate[31] |= 0x01;
+            BigUint::from_bytes_be(&candidate)
+        })
+        .find(|candidate| probably_prime(candidate, 20))
+        .expect("a prime within 2^32 candidates")
+}
+
+/// Hashes a witness's accumulator for the public output (mirrors
+/// `accumulator::non_revoked_hash`) with any suite but Poseidon
+pub fn accumulator_hash(witness: &AccumulatorWitness, hash: HashAlgorithm) -> [u8; 32] {
+    let mut preimage = (witness.modulus.len() as u32).to_be_bytes().to_vec();
+    preimage.extend_from_slice(&witness.modulus);
+    preimage.extend_from_slice(&witness.accumulator);
+    message_digest(hash, &preimage)
+}
+
+impl SampleAccumulator {
+    /// Sets up a 2048-bit accumulator and revokes two sample credentials
+    pub fn new() -> Result<Self> {
+        let mut rng = rand::thread_rng();
+        let modulus = RsaPrivateKey::new(&mut rng, 2048)?.n().clone();
+        let revoked = [[0x2a; 32], [0x95; 32]]
+            .iter()
+            .map(hash_to_prime)
+            .fold(BigUint::from(1u8), |product, prime| product * prime);
+        Ok(Self { modulus, revoked })
+    }
+
+    /// Computes the non-membership witness for `credential_hash`
+    ///
+    /// With `a = u^-1 mod x`, `a·u - 1` is a multiple of `x`, so
+    /// `d = g^((a·u - 1) / x)` needs no inverse modulo `N`.
+    pub fn witness(&self, credential_hash: &[u8; 32]) -> Result<AccumulatorWitness> {
+        let prime = hash_to_prime(credential_hash);
+        let a = (&self.revoked % &prime)
+            .mod_inverse(&prime)
+            .and_then(|a| a.to_biguint())
+            .ok_or_else(|| anyhow!("Credential is revoked in the sample accumulator"))?;
+        let exponent = (&a * &self.revoked - 1u8) / &prime;
+
+        let base = BigUint::from(ACCUMULATOR_BASE);
+        Ok(AccumulatorWitness {
+            version: SAMPLE_VERSION,
+            modulus: self.modulus.to_bytes_be(),
+            accumulator: base.modpow(&self.revoked, &self.modulus).to_bytes_be(),
+            a: a.to_bytes_be(),
+            d: base.modpow(&exponent, &self.modulus).to_bytes_be(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_witness_verifies() {
+        let accumulator = SampleAccumulator::new().unwrap();
+        let witness = accumulator.witness(&[0x11; 32]).unwrap();
+
+        let n = BigUint::from_bytes_be(&witness.modulus);
+        let lhs = BigUint::from_bytes_be(&witness.accumulator)
+            .modpow(&BigUint::from_bytes_be(&witness.a), &n);
+        let rhs = BigUint::from_bytes_be(&witness.d).modpow(&hash_to_prime(&[0x11; 32]), &n)
+            * ACCUMULATOR_BASE
+            % &n;
+        assert_eq!(lhs, rhs);
+
+        assert!(accumulator.witness(&[0x2a; 32]).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+//! AnonCreds CL signing helpers for building test credentials
+//!
+//! Generates a throwaway credential definition and issues a credential the
+//! way an Indy issuer does, producing the wallet credential JSON and primary
+//! public key JSON the program verifies. The modulus comes from an ordinary
+//! RSA key rather than safe primes, which is enough for a test fixture.
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+use num_bigint_dig::prime::probably_prime;
+use num_bigint_dig::ModInverse;
+use rand::{CryptoRng, RngCore};
+use rsa::traits::{PrivateKeyParts, PublicKeyParts};
+use rsa::{BigUint, RsaPrivateKey};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+/// Key of the link secret in the `r` map, must match the program
+const LINK_SECRET: &str = "master_secret";
+
+/// Lower bound and range of the signature exponent `e`, in bits
+const E_START_BITS: usize = 596;
+const E_RANGE_BITS: usize = 119;
+
+/// Bit lengths of the blinding value `v` and the context message `m2`
+const V_BITS: usize = 2724;
+const M2_BITS: usize = 256;
+
+/// A credential definition with its private factorization
+pub struct IssuerKey {
+    n: BigUint,
+    s: BigUint,
+    z: BigUint,
+    rctxt: BigUint,
+    r: BTreeMap<String, BigUint>,
+    /// Order of the group, for computing `e^-1`
+    phi: BigUint,
+}
+
+/// Draws a uniformly random integer of at most `bits` bits
+fn random_bits(rng: &mut impl RngCore, bits: usize) -> BigUint {
+    let mut bytes = vec![0u8; bits.div_ceil(8)];
+    rng.fill_bytes(&mut bytes);
+    BigUint::from_bytes_be(&bytes) >> (bytes.len() * 8 - bits)
+}
+
+/// Encodes a raw attribute value (mirrors the program)
+fn encode_attribute(raw: &str) -> String {
+    match raw.parse::<i32>() {
+        Ok(value) => value.to_string(),
+        Err(_) => BigUint::from_bytes_be(&Sha256::digest(raw.as_bytes())).to_string(),
+    }
+}
+
+/// Generates a credential definition over `attributes`
+pub fn generate_key(
+    attributes: &[&str],
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Result<IssuerKey> {
+    let private_key = RsaPrivateKey::new(rng, 2048)?;
+    let n = private_key.n().clone();
+    let one = BigUint::from(1u8);
+    let phi = private_key
+        .primes()
+        .iter()
+        .fold(one.clone(), |acc, prime| acc * (prime - &one));
+
+    // S generates the quadratic residues; every other base is a power of S
+    let s = random_bits(rng, 2048).modpow(&BigUint::from(2u8), &n);
+    let mut power_of_s = || s.modpow(&random_bits(rng, 2048), &n);
+    let z = power_of_s();
+    let rctxt = power_of_s();
+    let r = std::iter::once(LINK_SECRET)
+        .chain(attributes.iter().copied())
+        .map(|name| (name.to_string(), power_of_s()))
+        .collect();
+
+    Ok(IssuerKey {
+        n,
+        s,
+        z,
+        rctxt,
+        r,
+        phi,
+    })
+}
+
+/// Returns the primary public key JSON of the credential definition
+pub fn public_key_json(key: &IssuerKey) -> Value {
+    let r: BTreeMap<&String, String> = key
+        .r
+        .iter()
+        .map(|(name, r)| (name, r.to_string()))
+        .collect();
+    json!({
+        "n": key.n.to_string(),
+        "s": key.s.to_string(),
+        "r": r,
+        "rctxt": key.rctxt.to_string(),
+        "z": key.z.to_string(),
+    })
+}
+
+/// Issues a credential over `values` bound to `link_secret`
+///
+/// Returns the credential JSON as stored in the holder's wallet.
+pub fn issue(
+    key: &IssuerKey,
+    values: &[(&str, &str)],
+    link_secret: &BigUint,
+    rng: &mut impl RngCore,
+) -> Result<Value> {
+    let n = &key.n;
+    let v = random_bits(rng, V_BITS) | (BigUint::from(1u8) << (V_BITS - 1));
+    let m2 = random_bits(rng, M2_BITS);
+
+    // e is a prime in [2^596, 2^596 + 2^119]
+    let e_start = BigUint::from(1u8) << E_START_BITS;
+    let e = loop {
+        let candidate = &e_start + random_bits(rng, E_RANGE_BITS);
+        if probably_prime(&candidate, 20) {
+            break candidate;
+        }
+    };
+
+    let mut rx = key.s.modpow(&v, n);
+    rx = rx * key.rctxt.modpow(&m2, n) % n;
+    rx = rx * key.r[LINK_SECRET].modpow(link_secret, n) % n;
+    let mut encoded_values = serde_json::Map::new();
+    for (name, raw) in values {
+        let encoded = encode_attribute(raw);
+        let message: BigUint = encoded.parse()?;
+        let r = key
+            .r
+            .get(*name)
+            .ok_or_else(|| anyhow!("unknown attribute: {}", name))?;
+        rx = rx * r.modpow(&message, n) % n;
+        encoded_values.insert(name.to_string(), json!({ "raw": raw, "encoded": encoded }));
+    }
+
+    // A = (Z / rx)^(1/e) mod n
+    let inverse = |value: &BigUint, modulus: &BigUint| {
+        value
+            .clone()
+            .mod_inverse(modulus)
+            .and_then(|inverse| inverse.to_biguint())
+            .ok_or_else(|| anyhow!("value is not invertible"))
+    };
+    let q = &key.z * inverse(&rx, n)? % n;
+    let a = q.modpow(&inverse(&e, &key.phi)?, n);
+
+    Ok(json!({
+        "schema_id": "sample:2:credence:1.0",
+        "cred_def_id": "sample:3:CL:1:default",
+        "values": encoded_values,
+        "signature": {
+            "p_credential": {
+                "m_2": m2.to_string(),
+                "a": a.to_string(),
+                "e": e.to_string(),
+                "v": v.to_string(),
+            },
+            "r_credential": null,
+        },
+    }))
+}
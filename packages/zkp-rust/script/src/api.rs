@@ -0,0 +1,614 @@
+//! REST proving API
+//!
+//! `prove --serve <port>` lets backends prove credentials over HTTP rather
+//! than running the binaries:
+//!
+//! - `POST /prove` takes a credential in the file format (JSON, or TOML with
+//!   `Content-Type: application/toml`) and queues a job, answering `202`
+//!   with its id
+//! - `GET /jobs/{id}` reports the job's status
+//! - `DELETE /jobs/{id}` cancels the job
+//! - `GET /jobs/{id}/proof` downloads the proof envelope once done
+//! - `GET /jobs/{id}/events` upgrades to a WebSocket streaming the job's
+//!   events as JSON text messages until it's final, for progress bars
+//! - `POST /verify` checks a proof envelope against the program's key
+//! - `GET /metrics` serves Prometheus metrics (see [`crate::metrics`])
+//!
+//! Proofs are saved in `--output-dir` as `0x<subject>.json` like
+//! `--batch-dir` saves them, so the `gateway` binary can serve them too; a
+//! subject's newer proof replaces the older one. `--jobs` proofs are
+//! generated at once. Jobs and their proofs are persisted in `--output-dir`'s
+//! `jobs/` directory or `--database` (see [`crate::storage`]), and the gRPC
+//! service of `prove --grpc` shares them.
+//!
+//! With `--api-keys`, requests need a client's key (see [`crate::clients`]):
+//! those without one are refused with `401`, and those over the client's
+//! rate limit with `429` and a `Retry-After` header. `/metrics` doesn't need
+//! one, so Prometheus can scrape it. A client only reaches the jobs it
+//! submitted: other clients' jobs answer `404`.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use axum::body::Bytes;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path as UrlPath, Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Extension, Json, Router};
+use credential_verifier_lib::file::{self, FileFormat};
+use serde_json::{json, Value};
+use sp1_sdk::{SP1ProvingKey, SP1VerifyingKey};
+use tokio::sync::{broadcast, Semaphore};
+
+use crate::batch::{self, BatchOptions};
+use crate::cache::ProofCache;
+use crate::clients::{Client, Clients, Refusal};
+use crate::jobs::{self, Job, JobEvent, JobStatus, JobStore};
+use crate::metrics::{FailureReason, Metrics};
+use crate::prover::Prover;
+use crate::trusted;
+use crate::{CredentialInput, ProofMode, ProofOutput, ELF, ENVELOPE_VERSION};
+
+/// How often watchers hear how proving is going
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Settings the API proves with, and its jobs
+pub struct ApiState {
+    pub prover: Prover,
+    pub pk: SP1ProvingKey,
+    pub vk: SP1VerifyingKey,
+    pub vkey: String,
+    pub mode: ProofMode,
+    pub output_dir: PathBuf,
+    /// Verifier's challenge every proof is bound to
+    pub nonce: Option<[u8; 32]>,
+    /// Chain every proof is bound to
+    pub chain_id: Option<u64>,
+    /// Contract every proof is bound to
+    pub verifying_contract: Option<[u8; 20]>,
+    /// Cache of earlier proofs to reuse
+    pub cache: Option<ProofCache>,
+    /// Issuer keys credentials must be accountable under (any if empty)
+    pub trusted_issuers: Vec<Vec<u8>>,
+    /// Clients of `--api-keys` (anyone can call the API without)
+    pub clients: Option<Clients>,
+    /// One permit per proof generated at once
+    pub provers: Semaphore,
+    pub jobs: JobStore,
+    /// Seconds spent proving and shards proven so far, for estimating
+    /// how long proofs take
+    pub proven: Mutex<(f64, u64)>,
+    pub metrics: Metrics,
+}
+
+impl ApiState {
+    fn options(&self) -> BatchOptions<'_> {
+        BatchOptions {
+            prover: &self.prover,
+            pk: &self.pk,
+            vkey: self.vkey.clone(),
+            mode: self.mode,
+            jobs: 1,
+            nonce: self.nonce,
+            chain_id: self.chain_id,
+            verifying_contract: self.verifying_contract,
+            cache: self.cache.as_ref(),
+            trusted_issuers: &self.trusted_issuers,
+        }
+    }
+
+    /// Records that proving `shards` shards took `seconds`
+    fn record_proof(&self, shards: u64, seconds: f64) {
+        self.metrics.observe_proof(seconds);
+        let mut proven = self.proven.lock().expect("Timings aren't poisoned");
+        proven.0 += seconds;
+        proven.1 += shards;
+    }
+
+    /// How long proving `shards` shards should take, from the proofs so far
+    pub fn estimate(&self, shards: u64) -> Option<f64> {
+        let (seconds, proven) = *self.proven.lock().expect("Timings aren't poisoned");
+        (proven > 0).then(|| seconds / proven as f64 * shards as f64)
+    }
+
+    /// Queues a credential `client` submitted for proving
+    pub fn submit(
+        self: &Arc<Self>,
+        credential: CredentialInput,
+        client: Option<&Client>,
+    ) -> Result<Job> {
+        let client = client.map(|client| client.name.clone());
+        let job = self.jobs.insert(credential, client)?;
+        tracing::info!(job = %job.id, client = job.client, "Queued job");
+        tokio::spawn(run_job(self.clone(), job.id.clone()));
+        Ok(job)
+    }
+
+    /// Runs the jobs a restart interrupted again
+    pub fn resume(self: &Arc<Self>, ids: Vec<String>) {
+        for id in ids {
+            tracing::info!(job = %id, "Resuming job");
+            tokio::spawn(run_job(self.clone(), id));
+        }
+    }
+
+    /// Checks an envelope against the program's verifying key
+    pub async fn verify(self: &Arc<Self>, envelope: ProofOutput) -> Result<()> {
+        let state = self.clone();
+        tokio::task::spawn_blocking(move || verify_envelope(&state, &envelope))
+            .await
+            .unwrap_or_else(|panic| Err(anyhow::anyhow!("Verification panicked: {}", panic)))
+    }
+}
+
+/// Why a job has no proof to download
+pub fn not_done(job: &Job) -> String {
+    match job.status {
+        JobStatus::Failed => format!("Job {} failed", job.id),
+        JobStatus::Cancelled => format!("Job {} was cancelled", job.id),
+        _ => format!("Job {} isn't done yet", job.id),
+    }
+}
+
+/// Why a job can't be cancelled
+pub fn not_running(job: &Job) -> String {
+    match job.status {
+        JobStatus::Failed => format!("Job {} already failed", job.id),
+        _ => format!("Job {} is already done", job.id),
+    }
+}
+
+/// Status and JSON body of a failed request
+type Failure = (StatusCode, Json<Value>);
+
+/// JSON error body with `status`
+fn error(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(json!({ "message": message.into() }))).into_response()
+}
+
+/// Parses a submitted credential in the format its content type names
+fn submitted_credential(headers: &HeaderMap, body: &[u8]) -> Result<CredentialInput, Failure> {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("application/json");
+    let format = if content_type.starts_with("application/toml") {
+        FileFormat::Toml
+    } else {
+        FileFormat::Json
+    };
+    let text = std::str::from_utf8(body).map_err(|_| {
+        let body = json!({ "message": "The credential isn't UTF-8" });
+        (StatusCode::BAD_REQUEST, Json(body))
+    })?;
+    file::parse_credential(text, format).map_err(|file_error| {
+        let body = json!({ "message": file_error.to_string(), "field": file_error.field });
+        (StatusCode::BAD_REQUEST, Json(body))
+    })
+}
+
+/// Runs the program on a job's credential and proves it, returning the
+/// proof and where it was saved (or nothing if the job was cancelled first)
+fn execute_and_prove(
+    state: &ApiState,
+    id: &str,
+    credential: CredentialInput,
+) -> Result<Option<(ProofOutput, PathBuf)>> {
+    let options = state.options();
+    trusted::check(options.trusted_issuers, &credential)?;
+    let credential = batch::bind(&options, credential);
+    let (_, stdin) = batch::stdin(&credential);
+    let (_, report) = tracing::info_span!("execute")
+        .in_scope(|| state.prover.client().execute(ELF, stdin).run())
+        .context("The program rejected the credential")?;
+    let cycles = report.total_instruction_count();
+    let shards = jobs::estimated_shards(cycles);
+    tracing::info!(cycles, shards, "Executed the credential");
+    state.metrics.observe_cycles(cycles);
+
+    if !state.jobs.advance(id, |record| {
+        record.job.status = JobStatus::Proving;
+        record.job.cycles = Some(cycles);
+        record.job.shards = Some(shards);
+    }) {
+        return Ok(None);
+    }
+    let proving = Instant::now();
+    let output = batch::prove_credential(&options, credential)?;
+    state.record_proof(shards, proving.elapsed().as_secs_f64());
+    // Proofs of jobs cancelled while proving are discarded
+    if state.jobs.get(id).is_some_and(|job| job.status.is_final()) {
+        return Ok(None);
+    }
+    let path = batch::save_in(&output, &state.output_dir)?;
+    Ok(Some((output, path)))
+}
+
+/// Reports how proving job `id` is going every [`PROGRESS_INTERVAL`] until
+/// it's final
+async fn report_progress(state: Arc<ApiState>, id: String) {
+    let mut proving_since = None;
+    loop {
+        tokio::time::sleep(PROGRESS_INTERVAL).await;
+        let Some(job) = state.jobs.get(&id) else {
+            return;
+        };
+        if job.status.is_final() {
+            return;
+        }
+        if job.status != JobStatus::Proving {
+            continue;
+        }
+        let since = *proving_since.get_or_insert_with(Instant::now);
+        state.jobs.report(JobEvent::Proving {
+            id: id.clone(),
+            elapsed_seconds: since.elapsed().as_secs_f64(),
+            estimated_seconds: job.shards.and_then(|shards| state.estimate(shards)),
+        });
+    }
+}
+
+/// Executes and proves a queued job once a prover is free, and its client
+/// is under its quota
+#[tracing::instrument(name = "job", skip_all, fields(id = %id))]
+async fn run_job(state: Arc<ApiState>, id: String) {
+    let client = state
+        .jobs
+        .get(&id)
+        .and_then(|job| job.client)
+        .and_then(|name| state.clients.as_ref()?.get(&name));
+    let _client_permit = match &client {
+        Some(client) => client.acquire().await,
+        None => None,
+    };
+    let _permit = state
+        .provers
+        .acquire()
+        .await
+        .expect("The semaphore isn't closed");
+    let Some(credential) = state.jobs.credential(&id) else {
+        return;
+    };
+    // Jobs cancelled while queued don't run
+    if !state
+        .jobs
+        .advance(&id, |record| record.job.status = JobStatus::Executing)
+    {
+        return;
+    }
+    tokio::spawn(report_progress(state.clone(), id.clone()));
+    let started = Instant::now();
+    let worker = state.clone();
+    let job_id = id.clone();
+    let span = tracing::Span::current();
+    let result = tokio::task::spawn_blocking(move || {
+        span.in_scope(|| execute_and_prove(&worker, &job_id, credential))
+    })
+    .await
+    .unwrap_or_else(|panic| Err(anyhow::anyhow!("Proving panicked: {}", panic)));
+    let seconds = Some(started.elapsed().as_secs_f64());
+    match result {
+        Ok(Some((output, path))) => {
+            tracing::info!(seconds, "Proved the job");
+            if let Err(store_error) = state.jobs.save_proof(&id, &output) {
+                tracing::error!(
+                    error = format!("{:#}", store_error),
+                    "Failed to store the job's proof"
+                );
+            }
+            state.jobs.advance(&id, |record| {
+                record.job.status = JobStatus::Done;
+                record.job.seconds = seconds;
+                record.proof = Some(path);
+            });
+        }
+        Ok(None) => tracing::info!("Cancelled the job"),
+        Err(prove_error) => {
+            tracing::warn!(
+                error = format!("{:#}", prove_error),
+                "Failed to prove the job"
+            );
+            let executing = state
+                .jobs
+                .get(&id)
+                .is_some_and(|job| job.status == JobStatus::Executing);
+            state.metrics.observe_failure(if executing {
+                FailureReason::Rejected
+            } else {
+                FailureReason::Proving
+            });
+            state.jobs.advance(&id, |record| {
+                record.job.status = JobStatus::Failed;
+                record.job.seconds = seconds;
+                record.job.error = Some(format!("{:#}", prove_error));
+            });
+        }
+    }
+}
+
+/// Response refusing a request
+fn refused(refusal: Refusal) -> Response {
+    let message = refusal.to_string();
+    match refusal {
+        Refusal::NoKey | Refusal::UnknownKey => (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer".to_string())],
+            Json(json!({ "message": message })),
+        )
+            .into_response(),
+        Refusal::RateLimited { retry_after } => (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(
+                header::RETRY_AFTER,
+                retry_after.as_secs_f64().ceil().to_string(),
+            )],
+            Json(json!({ "message": message })),
+        )
+            .into_response(),
+    }
+}
+
+/// Checks a request's API key, passing its client on to the handler
+async fn authenticate(
+    State(state): State<Arc<ApiState>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let Some(clients) = &state.clients else {
+        return next.run(request).await;
+    };
+    let authorization = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+    match clients.authorize(authorization) {
+        Ok(client) => {
+            request.extensions_mut().insert(client);
+            next.run(request).await
+        }
+        Err(refusal) => refused(refusal),
+    }
+}
+
+/// `POST /prove` with a credential
+async fn prove(
+    State(state): State<Arc<ApiState>>,
+    client: Option<Extension<Arc<Client>>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let credential = match submitted_credential(&headers, &body) {
+        Ok(credential) => credential,
+        Err(failure) => return failure.into_response(),
+    };
+    let client = client.as_ref().map(|Extension(client)| client.as_ref());
+    match state.submit(credential, client) {
+        Ok(job) => (StatusCode::ACCEPTED, Json(job)).into_response(),
+        Err(submit_error) => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Can't queue the job: {:#}", submit_error),
+        ),
+    }
+}
+
+/// Name of the request's client, with `--api-keys`
+fn client_name(client: &Option<Extension<Arc<Client>>>) -> Option<&str> {
+    client
+        .as_ref()
+        .map(|Extension(client)| client.name.as_str())
+}
+
+/// `GET /jobs/{id}`
+async fn job_status(
+    State(state): State<Arc<ApiState>>,
+    client: Option<Extension<Arc<Client>>>,
+    UrlPath(id): UrlPath<String>,
+) -> Response {
+    match state.jobs.get_for(&id, client_name(&client)) {
+        Some(job) => Json(job).into_response(),
+        None => error(StatusCode::NOT_FOUND, format!("No job {id}")),
+    }
+}
+
+/// `DELETE /jobs/{id}`
+async fn cancel_job(
+    State(state): State<Arc<ApiState>>,
+    client: Option<Extension<Arc<Client>>>,
+    UrlPath(id): UrlPath<String>,
+) -> Response {
+    if state.jobs.get_for(&id, client_name(&client)).is_none() {
+        return error(StatusCode::NOT_FOUND, format!("No job {id}"));
+    }
+    match state.jobs.cancel(&id) {
+        Some(job) if job.status == JobStatus::Cancelled => Json(job).into_response(),
+        Some(job) => error(StatusCode::CONFLICT, not_running(&job)),
+        None => error(StatusCode::NOT_FOUND, format!("No job {id}")),
+    }
+}
+
+/// `GET /jobs/{id}/events`, upgraded to a WebSocket
+async fn job_events(
+    State(state): State<Arc<ApiState>>,
+    client: Option<Extension<Arc<Client>>>,
+    UrlPath(id): UrlPath<String>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    // Subscribe before reading the job, so no change is missed
+    let updates = state.jobs.subscribe();
+    let Some(job) = state.jobs.get_for(&id, client_name(&client)) else {
+        return error(StatusCode::NOT_FOUND, format!("No job {id}"));
+    };
+    upgrade.on_upgrade(move |socket| stream_events(state, socket, job, updates))
+}
+
+/// Sends a job's events over `socket`, starting from its current state,
+/// until it's final or the client leaves
+async fn stream_events(
+    state: Arc<ApiState>,
+    mut socket: WebSocket,
+    job: Job,
+    mut updates: broadcast::Receiver<JobEvent>,
+) {
+    let id = job.id.clone();
+    let mut event = JobEvent::Update { job };
+    loop {
+        let done = matches!(&event, JobEvent::Update { job } if job.status.is_final());
+        let Ok(text) = serde_json::to_string(&event) else {
+            return;
+        };
+        if socket.send(Message::Text(text)).await.is_err() {
+            return;
+        }
+        if done {
+            break;
+        }
+        match state.jobs.next_event(&mut updates, &id).await {
+            Some(next) => event = next,
+            None => break,
+        }
+    }
+    let _ = socket.send(Message::Close(None)).await;
+}
+
+/// `GET /jobs/{id}/proof`
+async fn job_proof(
+    State(state): State<Arc<ApiState>>,
+    client: Option<Extension<Arc<Client>>>,
+    UrlPath(id): UrlPath<String>,
+) -> Response {
+    let Some(job) = state.jobs.get_for(&id, client_name(&client)) else {
+        return error(StatusCode::NOT_FOUND, format!("No job {id}"));
+    };
+    match state.jobs.proof(&id) {
+        Ok(Some(envelope)) => {
+            ([(header::CONTENT_TYPE, "application/json")], envelope).into_response()
+        }
+        Ok(None) => error(StatusCode::CONFLICT, not_done(&job)),
+        Err(read_error) => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Can't read the proof: {:#}", read_error),
+        ),
+    }
+}
+
+/// Checks an envelope against the program's verifying key
+fn verify_envelope(state: &ApiState, envelope: &ProofOutput) -> Result<()> {
+    let _span = tracing::info_span!("verify", subject = %envelope.subject).entered();
+    anyhow::ensure!(
+        envelope.envelope_version == ENVELOPE_VERSION,
+        "Unsupported envelope version {}",
+        envelope.envelope_version
+    );
+    anyhow::ensure!(
+        envelope.vkey == state.vkey,
+        "The proof is for program {}, not {}",
+        envelope.vkey,
+        state.vkey
+    );
+    state
+        .prover
+        .client()
+        .verify(&envelope.sp1_proof, &state.vk)
+        .context("Proof verification failed")
+}
+
+/// `POST /verify` with a proof envelope
+async fn verify(State(state): State<Arc<ApiState>>, body: Bytes) -> Response {
+    let envelope: ProofOutput = match serde_json::from_slice(&body) {
+        Ok(envelope) => envelope,
+        Err(parse_error) => {
+            return error(
+                StatusCode::BAD_REQUEST,
+                format!("Not a proof envelope: {parse_error}"),
+            )
+        }
+    };
+    let subject = envelope.subject.clone();
+    let public_values = envelope.public_values.clone();
+    match state.verify(envelope).await {
+        Ok(()) => Json(json!({
+            "verified": true,
+            "vkey": state.vkey,
+            "subject": subject,
+            "public_values": public_values,
+        }))
+        .into_response(),
+        Err(verify_error) => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({ "verified": false, "message": format!("{:#}", verify_error) })),
+        )
+            .into_response(),
+    }
+}
+
+/// `GET /metrics`
+async fn metrics(State(state): State<Arc<ApiState>>) -> Response {
+    let metrics = state.metrics.render(&state.jobs);
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics,
+    )
+        .into_response()
+}
+
+/// Routes of the API
+pub fn router(state: Arc<ApiState>) -> Router {
+    Router::new()
+        .route("/prove", post(prove))
+        .route("/jobs/:id", get(job_status).delete(cancel_job))
+        .route("/jobs/:id/proof", get(job_proof))
+        .route("/jobs/:id/events", get(job_events))
+        .route("/verify", post(verify))
+        .layer(middleware::from_fn_with_state(state.clone(), authenticate))
+        .route("/metrics", get(metrics))
+        .with_state(state)
+}
+
+/// Serves the API on `port` until SIGINT, letting running requests finish
+pub async fn serve(state: Arc<ApiState>, port: u16) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", port))
+        .await
+        .with_context(|| format!("Can't listen on port {}", port))?;
+    tracing::info!(port, "Serving the proving API");
+    axum::serve(listener, router(state))
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_submitted_credentials() {
+        let credential = crate::create_sample_credential(
+            "0x1234567890123456789012345678901234567890",
+            1,
+            crate::SignatureScheme::Secp256k1,
+            crate::HashAlgorithm::Sha256,
+            crate::CredentialFormat::Binary,
+            crate::ClaimsVersion::V2,
+            crate::HolderBindings::default(),
+        )
+        .unwrap();
+        let body = serde_json::to_vec(&credential).unwrap();
+        let mut headers = HeaderMap::new();
+        let parsed = submitted_credential(&headers, &body).unwrap();
+        assert_eq!(parsed.subject, credential.subject);
+
+        let (status, Json(failure)) = submitted_credential(&headers, b"{}").unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(failure["message"].is_string());
+        headers.insert(header::CONTENT_TYPE, "application/toml".parse().unwrap());
+        let (status, _) = submitted_credential(&headers, &body).unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,319 @@
+//! Proving a directory of credentials
+//!
+//! Issuers onboarding many holders prove their credentials in one run:
+//! every JSON or TOML credential file in the directory gets a proof of its
+//! own, saved as `0x<subject>.json` like `--output-dir` saves single proofs,
+//! with several proofs generated at once. A `manifest.json` beside the
+//! proofs records how each credential fared, so failed ones can be fixed
+//! and proven again.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use credential_verifier_lib::file;
+use serde::{Deserialize, Serialize};
+use sp1_sdk::{SP1ProvingKey, SP1Stdin};
+use tracing::{info, warn};
+
+use crate::cache::ProofCache;
+use crate::prover::Prover;
+use crate::trusted;
+use crate::{
+    proof_output, save_proof, unix_time, CredentialInput, ProgramInput, ProofMode, ProofOutput,
+};
+
+/// Name of the manifest written beside the proofs
+pub const MANIFEST: &str = "manifest.json";
+
+/// How proving a credential file went
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Proven,
+    Failed,
+}
+
+/// Manifest entry of one credential file
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Credential file
+    pub credential: PathBuf,
+    pub status: JobStatus,
+    /// Subject address (absent if the file couldn't be read)
+    pub subject: Option<String>,
+    /// Saved proof (absent unless proven)
+    pub proof: Option<PathBuf>,
+    /// Why proving failed (absent unless failed)
+    pub error: Option<String>,
+    /// Seconds spent on the credential
+    pub seconds: f64,
+}
+
+/// Results of proving a directory
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Hash of the program verification key the proofs verify against
+    pub vkey: String,
+    pub proven: usize,
+    pub failed: usize,
+    /// Seconds the whole batch took
+    pub seconds: f64,
+    /// One entry per credential file, in file name order
+    pub entries: Vec<ManifestEntry>,
+}
+
+/// Settings every proof in the batch shares
+pub struct BatchOptions<'a> {
+    pub prover: &'a Prover,
+    pub pk: &'a SP1ProvingKey,
+    pub vkey: String,
+    pub mode: ProofMode,
+    /// Proofs generated at once
+    pub jobs: usize,
+    /// Verifier's challenge every proof is bound to
+    pub nonce: Option<[u8; 32]>,
+    /// Chain every proof is bound to
+    pub chain_id: Option<u64>,
+    /// Contract every proof is bound to
+    pub verifying_contract: Option<[u8; 20]>,
+    /// Cache of earlier proofs to reuse
+    pub cache: Option<&'a ProofCache>,
+    /// Issuer keys credentials must be accountable under (any if empty)
+    pub trusted_issuers: &'a [Vec<u8>],
+}
+
+/// Lists the JSON and TOML files in `dir`, sorted by name
+pub fn credential_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).with_context(|| format!("Can't read {}", dir.display()))? {
+        let path = entry?.path();
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        if path.is_file() && matches!(extension, Some("json" | "toml")) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Reads every credential file, failing those that can't be read or that
+/// repeat an earlier file's subject (their proofs would share a path)
+fn load_all(files: &[PathBuf]) -> Vec<Result<CredentialInput>> {
+    let mut subjects = HashSet::new();
+    files
+        .iter()
+        .map(|path| {
+            let credential = file::load_credential(path)?;
+            anyhow::ensure!(
+                subjects.insert(credential.subject),
+                "Another credential in the batch has subject 0x{}",
+                hex::encode(credential.subject)
+            );
+            Ok(credential)
+        })
+        .collect()
+}
+
+/// Binds a credential to the batch's verifier nonce, chain and contract
+pub fn bind(options: &BatchOptions, mut credential: CredentialInput) -> CredentialInput {
+    if let Some(nonce) = options.nonce {
+        credential.verifier_nonce = nonce;
+    }
+    if options.chain_id.is_some() {
+        credential.chain_id = options.chain_id;
+    }
+    if options.verifying_contract.is_some() {
+        credential.verifying_contract = options.verifying_contract;
+    }
+    credential
+}
+
+/// Program input proving one credential
+pub fn stdin(credential: &CredentialInput) -> (ProgramInput, SP1Stdin) {
+    let input = ProgramInput::Single(Box::new(credential.clone()));
+    let mut stdin = SP1Stdin::new();
+    stdin.write_vec(credential_verifier_lib::encode(&input));
+    (input, stdin)
+}
+
+/// Proves one bound credential, returning its envelope (or the cached one)
+pub fn prove_credential(
+    options: &BatchOptions,
+    credential: CredentialInput,
+) -> Result<ProofOutput> {
+    let cached = options.cache.map(|cache| {
+        (
+            cache,
+            ProofCache::key(&options.vkey, options.mode, &credential),
+        )
+    });
+    if let Some((cache, key)) = &cached {
+        if let Some(output) = cache.get(key, unix_time()?) {
+            info!(subject = %output.subject, "Reusing the cached proof");
+            return Ok(output);
+        }
+    }
+
+    let (input, stdin) = stdin(&credential);
+    let started_at = unix_time()?;
+    let proof = options.prover.prove(options.pk, stdin, options.mode)?;
+    let (output, _) = proof_output(
+        &proof,
+        options.vkey.clone(),
+        options.mode,
+        started_at,
+        &input,
+        std::slice::from_ref(&credential),
+        None,
+    )?;
+    if let Some((cache, key)) = &cached {
+        if let Err(error) = cache.put(key, &credential, &output) {
+            warn!(error = format!("{:#}", error), "Failed to cache the proof");
+        }
+    }
+    Ok(output)
+}
+
+/// Saves a proof in `output_dir` as `0x<subject>.json`
+pub fn save_in(output: &ProofOutput, output_dir: &Path) -> Result<PathBuf> {
+    let path = output_dir.join(format!("{}.json", output.subject));
+    save_proof(output, &path)?;
+    Ok(path)
+}
+
+/// Proves one credential, saving its proof in `output_dir`
+fn prove_one(
+    options: &BatchOptions,
+    credential: CredentialInput,
+    output_dir: &Path,
+) -> Result<PathBuf> {
+    trusted::check(options.trusted_issuers, &credential)?;
+    let output = prove_credential(options, bind(options, credential))?;
+    save_in(&output, output_dir)
+}
+
+/// Proves a loaded credential file and records how it went
+pub fn prove_entry(
+    options: &BatchOptions,
+    path: &Path,
+    credential: &Result<CredentialInput>,
+    output_dir: &Path,
+) -> ManifestEntry {
+    let started = Instant::now();
+    let result = match credential {
+        Ok(credential) => prove_one(options, credential.clone(), output_dir),
+        Err(error) => Err(anyhow::anyhow!("{:#}", error)),
+    };
+    let (status, proof, error) = match result {
+        Ok(proof) => {
+            info!(credential = %path.display(), "Proved");
+            (JobStatus::Proven, Some(proof), None)
+        }
+        Err(error) => {
+            warn!(
+                credential = %path.display(),
+                error = format!("{:#}", error),
+                "Failed to prove"
+            );
+            (JobStatus::Failed, None, Some(format!("{:#}", error)))
+        }
+    };
+    ManifestEntry {
+        credential: path.to_path_buf(),
+        status,
+        subject: credential
+            .as_ref()
+            .ok()
+            .map(|credential| format!("0x{}", hex::encode(credential.subject))),
+        proof,
+        error,
+        seconds: started.elapsed().as_secs_f64(),
+    }
+}
+
+/// Proves every credential file in `dir` with `options.jobs` workers and
+/// writes the manifest to `output_dir`
+pub fn prove_dir(dir: &Path, output_dir: &Path, options: &BatchOptions) -> Result<Manifest> {
+    let started = Instant::now();
+    let files = credential_files(dir)?;
+    anyhow::ensure!(
+        !files.is_empty(),
+        "No credential files in {}",
+        dir.display()
+    );
+    // Proofs and the manifest are JSON too, and would be read back as
+    // credentials
+    anyhow::ensure!(
+        dir != output_dir,
+        "Save the proofs outside the credentials' directory"
+    );
+    std::fs::create_dir_all(output_dir)
+        .with_context(|| format!("Can't create {}", output_dir.display()))?;
+
+    let credentials = load_all(&files);
+
+    // Workers take the next unproven file until none are left
+    let next = AtomicUsize::new(0);
+    let mut entries: Vec<(usize, ManifestEntry)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..options.jobs.clamp(1, files.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut entries = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(path) = files.get(index) else {
+                            break entries;
+                        };
+                        let entry = prove_entry(options, path, &credentials[index], output_dir);
+                        entries.push((index, entry));
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("Batch workers don't panic"))
+            .collect()
+    });
+    entries.sort_by_key(|(index, _)| *index);
+    let entries: Vec<ManifestEntry> = entries.into_iter().map(|(_, entry)| entry).collect();
+    let proven = entries
+        .iter()
+        .filter(|entry| entry.status == JobStatus::Proven)
+        .count();
+    let manifest = Manifest {
+        vkey: options.vkey.clone(),
+        proven,
+        failed: entries.len() - proven,
+        seconds: started.elapsed().as_secs_f64(),
+        entries,
+    };
+    std::fs::write(
+        output_dir.join(MANIFEST),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lists_credential_files() {
+        let dir = std::env::temp_dir().join(format!("credence-batch-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("nested.json")).unwrap();
+        for name in ["b.toml", "a.json", "notes.txt"] {
+            std::fs::write(dir.join(name), "").unwrap();
+        }
+        let files = credential_files(&dir).unwrap();
+        assert_eq!(files, [dir.join("a.json"), dir.join("b.toml")]);
+
+        let loaded = load_all(&files);
+        assert!(loaded.iter().all(Result::is_err));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,53 @@
+//! BBS+ signing helpers for building selective-disclosure test credentials
+//!
+//! Mirrors the program's `bbs` module: generators are hashed to G1 from
+//! their index, messages are mapped to scalars with SHA-512, and the
+//! signature is `A || e || s` with big-endian scalars.
+
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_381::{G1Affine, G1Projective, G2Affine, Scalar};
+use sha2::{Digest, Sha512};
+
+/// Domain tag for the message generators, must match the program
+pub const GENERATOR_DST: &[u8] = b"CREDENCE_BBS_PLUS_BLS12381G1_XMD:SHA-256_SSWU_RO_";
+
+fn generator(index: u32) -> G1Projective {
+    <G1Projective as HashToCurve<ExpandMsgXmd<sha2_v0_9::Sha256>>>::hash_to_curve(
+        index.to_be_bytes(),
+        GENERATOR_DST,
+    )
+}
+
+fn message_scalar(message: &[u8]) -> Scalar {
+    let wide: [u8; 64] = Sha512::digest(message).into();
+    Scalar::from_bytes_wide(&wide)
+}
+
+fn scalar_to_be(scalar: &Scalar) -> [u8; 32] {
+    let mut bytes = scalar.to_bytes();
+    bytes.reverse();
+    bytes
+}
+
+/// Returns the compressed G2 public key for `secret_key`
+pub fn public_key(secret_key: &Scalar) -> [u8; 96] {
+    G2Affine::from(G2Affine::generator() * secret_key).to_compressed()
+}
+
+/// Signs `messages`, deriving `e` and `s` deterministically from `nonce`
+pub fn sign(secret_key: &Scalar, messages: &[Vec<u8>], nonce: &[u8]) -> [u8; 112] {
+    let e = message_scalar(&[nonce, b"e"].concat());
+    let s = message_scalar(&[nonce, b"s"].concat());
+
+    let mut b = G1Projective::generator() + generator(0) * s;
+    for (i, message) in messages.iter().enumerate() {
+        b += generator(i as u32 + 1) * message_scalar(message);
+    }
+    let a = G1Affine::from(b * (secret_key + e).invert().unwrap());
+
+    let mut signature = [0u8; 112];
+    signature[..48].copy_from_slice(&a.to_compressed());
+    signature[48..80].copy_from_slice(&scalar_to_be(&e));
+    signature[80..].copy_from_slice(&scalar_to_be(&s));
+    signature
+}
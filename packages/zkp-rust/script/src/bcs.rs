@@ -0,0 +1,210 @@
+//! Decoder for BCS-encoded public values
+//!
+//! Mirrors `NativeOutputs::to_bcs`, which Move programs on Aptos and Sui
+//! decode with `bcs::peel_*` in the same field order: integers
+//! little-endian, sequences behind a ULEB128 length and every byte array
+//! as a `vector<u8>`.
+
+use anyhow::{bail, Context, Result};
+use credential_verifier_lib::{NativeOutputs, PublicOutput};
+
+/// Cursor over BCS bytes
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl Reader<'_> {
+    fn take(&mut self, length: usize) -> Result<&[u8]> {
+        if self.bytes.len() < length {
+            bail!("BCS values end unexpectedly");
+        }
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn bool(&mut self) -> Result<bool> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            byte => bail!("Invalid BCS bool {byte}"),
+        }
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into()?))
+    }
+
+    fn length(&mut self) -> Result<usize> {
+        let mut length = 0usize;
+        for shift in (0..32).step_by(7) {
+            let byte = self.u8()?;
+            length |= usize::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(length);
+            }
+        }
+        bail!("BCS length overflows")
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let length = self.length()?;
+        self.take(length)?
+            .try_into()
+            .with_context(|| format!("Expected a {N}-byte vector, got {length} bytes"))
+    }
+}
+
+/// Decodes values committed for a Move program
+pub fn decode(values: &[u8]) -> Result<NativeOutputs> {
+    let mut reader = Reader { bytes: values };
+    let version = reader.u32()?;
+    let policy = match reader.u8()? {
+        0 => None,
+        1 => Some((reader.array()?, reader.bool()?)),
+        tag => bail!("Invalid BCS option tag {tag}"),
+    };
+    let count = reader.length()?;
+    let outputs = (0..count)
+        .map(|_| read_output(&mut reader))
+        .collect::<Result<_>>()?;
+    if !reader.bytes.is_empty() {
+        bail!("Trailing bytes after BCS values");
+    }
+    Ok(NativeOutputs {
+        version,
+        policy,
+        outputs,
+    })
+}
+
+/// Reads one output's fields in `PublicOutput` order
+fn read_output(reader: &mut Reader) -> Result<PublicOutput> {
+    Ok(PublicOutput {
+        subject: reader.array()?,
+        credential_type: reader.u32()?,
+        credential_hash: reader.array()?,
+        issued_at: reader.u64()?,
+        expires_at: reader.u64()?,
+        disclosure_hash: reader.array()?,
+        quorum_hash: reader.array()?,
+        issuer_address: reader.array()?,
+        hmac_key_commitment: reader.array()?,
+        root_ca_hash: reader.array()?,
+        vrf_output_hash: reader.array()?,
+        hash_suite: reader.u8()?,
+        schema_hash: reader.array()?,
+        root_issuer_hash: reader.array()?,
+        nullifier_scope: reader.array()?,
+        nullifier: reader.array()?,
+        verifier_nonce: reader.array()?,
+        subject_commitment: reader.array()?,
+        trusted_issuers_root: reader.array()?,
+        predicates_hash: reader.array()?,
+        predicate_results: reader.u32()?,
+        withheld_claims_root: reader.array()?,
+        sanctions_root: reader.array()?,
+        sanctions_list_version: reader.u32()?,
+        pseudonym: reader.array()?,
+        timestamp_granularity: reader.u8()?,
+        expiry_epoch: reader.u64()?,
+        credential_hash_blind: reader.array()?,
+        ring_root: reader.array()?,
+        ring_event: reader.array()?,
+        ring_tag: reader.array()?,
+        holder_bound: reader.bool()?,
+        device_rp_id_hash: reader.array()?,
+        biometric_bound: reader.bool()?,
+        serial_hash: reader.array()?,
+        revocation_method: reader.u8()?,
+        revocation_root: reader.array()?,
+        revocation_version: reader.u32()?,
+        revocation_max_age: reader.u64()?,
+        status_purpose: reader.u8()?,
+        key_registry_version: reader.u32()?,
+        issuer_registry_root: reader.array()?,
+        chain_id: reader.u64()?,
+        verifying_contract: reader.array()?,
+        as_of: reader.u64()?,
+        revealed_claims: {
+            let count = reader.length()?;
+            (0..count).map(|_| reader.array()).collect::<Result<_>>()?
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use credential_verifier_lib::OUTPUT_VERSION;
+
+    #[test]
+    fn test_decode_inverts_to_bcs() {
+        let output = PublicOutput {
+            subject: [0x12; 20],
+            credential_type: 2,
+            credential_hash: [0xab; 32],
+            issued_at: 1_700_000_000,
+            expires_at: u64::MAX,
+            disclosure_hash: [0; 32],
+            quorum_hash: [0; 32],
+            issuer_address: [0x34; 20],
+            hmac_key_commitment: [0; 32],
+            root_ca_hash: [0; 32],
+            vrf_output_hash: [0; 32],
+            hash_suite: 1,
+            schema_hash: [0; 32],
+            root_issuer_hash: [0; 32],
+            nullifier_scope: [0; 32],
+            nullifier: [0x56; 32],
+            verifier_nonce: [0; 32],
+            subject_commitment: [0; 32],
+            trusted_issuers_root: [0; 32],
+            predicates_hash: [0; 32],
+            predicate_results: 0b101,
+            withheld_claims_root: [0; 32],
+            sanctions_root: [0; 32],
+            sanctions_list_version: 0,
+            pseudonym: [0; 32],
+            timestamp_granularity: 0,
+            expiry_epoch: 0,
+            credential_hash_blind: [0; 32],
+            ring_root: [0; 32],
+            ring_event: [0; 32],
+            ring_tag: [0; 32],
+            holder_bound: true,
+            device_rp_id_hash: [0; 32],
+            biometric_bound: false,
+            serial_hash: [0; 32],
+            revocation_method: 0,
+            revocation_root: [0; 32],
+            revocation_version: 0,
+            revocation_max_age: 0,
+            status_purpose: 0,
+            key_registry_version: 0,
+            issuer_registry_root: [0; 32],
+            chain_id: 11_155_111,
+            verifying_contract: [0xcd; 20],
+            as_of: 1_700_000_100,
+            revealed_claims: vec![[0x78; 32]; 130],
+        };
+        let outputs = NativeOutputs {
+            version: OUTPUT_VERSION,
+            policy: Some(([0x9a; 32], true)),
+            outputs: vec![output],
+        };
+
+        let bcs = outputs.to_bcs();
+        assert_eq!(&bcs[..6], &[4, 0, 0, 0, 1, 32]);
+        assert_eq!(decode(&bcs).unwrap(), outputs);
+        assert!(decode(&bcs[..bcs.len() - 1]).is_err());
+    }
+}
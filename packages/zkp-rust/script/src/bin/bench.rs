@@ -0,0 +1,320 @@
+//! Cycle benchmark for the credential verifier program
+//! Executes the program on representative inputs and breaks each run's
+//! cycles down by the stages the program marks for the cycle tracker:
+//! deserializing the input, parsing the credential format, verifying the
+//! signature, validating the claims and hashing the credential. Cycles
+//! outside those stages (timestamps, optional checks, encoding the public
+//! values) are reported as `other`
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+use credential_verifier_lib::hash::message_digest;
+use credential_verifier_lib::message::signing_message;
+use credential_verifier_lib::{
+    CredentialFormat, CredentialInput, HashAlgorithm, ProgramInput, SignatureScheme,
+    TimestampGranularity,
+};
+use serde::Serialize;
+use sp1_sdk::{ProverClient, SP1Stdin};
+use tracing::{info, info_span};
+
+#[path = "../logging.rs"]
+mod logging;
+
+const ELF: &[u8] = include_bytes!("../../../program/elf/riscv32im-succinct-zkvm-elf");
+
+/// Stages the program reports cycles for, in the order they run
+const STAGES: [&str; 5] = ["deserialize", "parse", "signature", "claims", "hash"];
+
+/// CLI arguments
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Credentials in the batch scenario
+    #[arg(long, default_value = "4")]
+    batch_size: usize,
+
+    /// Only run the scenarios whose names contain this
+    #[arg(long)]
+    filter: Option<String>,
+
+    /// Also save the results as JSON, e.g. to compare across changes
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    #[command(flatten)]
+    log: logging::LogArgs,
+}
+
+/// Representative input the program is executed on
+struct Scenario {
+    name: &'static str,
+    scheme: SignatureScheme,
+    hash: HashAlgorithm,
+    /// Claims in each credential
+    claims: u32,
+    /// Whether to verify a batch of `--batch-size` credentials
+    batch: bool,
+}
+
+const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "secp256k1-sha256",
+        scheme: SignatureScheme::Secp256k1,
+        hash: HashAlgorithm::Sha256,
+        claims: 2,
+        batch: false,
+    },
+    Scenario {
+        name: "secp256k1-keccak256",
+        scheme: SignatureScheme::Secp256k1,
+        hash: HashAlgorithm::Keccak256,
+        claims: 2,
+        batch: false,
+    },
+    Scenario {
+        name: "secp256r1-sha256",
+        scheme: SignatureScheme::Secp256r1,
+        hash: HashAlgorithm::Sha256,
+        claims: 2,
+        batch: false,
+    },
+    Scenario {
+        name: "secp256k1-sha256-64-claims",
+        scheme: SignatureScheme::Secp256k1,
+        hash: HashAlgorithm::Sha256,
+        claims: 64,
+        batch: false,
+    },
+    Scenario {
+        name: "secp256k1-sha256-batch",
+        scheme: SignatureScheme::Secp256k1,
+        hash: HashAlgorithm::Sha256,
+        claims: 2,
+        batch: true,
+    },
+];
+
+/// Cycles of one scenario
+#[derive(Debug, Serialize)]
+struct BenchResult {
+    scenario: String,
+    total: u64,
+    /// Cycles of each stage, summed over a batch's credentials
+    stages: BTreeMap<String, u64>,
+    other: u64,
+}
+
+/// Builds the scenario's credential, signed with a fixed key
+///
+/// Times are fixed so every run executes the same input.
+fn credential(scenario: &Scenario) -> Result<CredentialInput> {
+    let mut credential_data = Vec::new();
+    credential_data.extend_from_slice(&1u32.to_be_bytes()); // version
+    credential_data.extend_from_slice(&scenario.claims.to_be_bytes()); // claim_count
+    for claim in 0..scenario.claims {
+        credential_data.extend_from_slice(&[claim as u8; 32]);
+    }
+
+    let mut credential = CredentialInput {
+        subject: [0x12; 20],
+        credential_type: 2, // Accredited investor
+        credential_data,
+        credential_format: CredentialFormat::Binary,
+        signature: Vec::new(),
+        issuer_pubkey: Vec::new(),
+        signature_scheme: scenario.scheme,
+        eip712_domain: None,
+        hash_algorithm: scenario.hash,
+        bbs: None,
+        quorum: None,
+        hmac_key: None,
+        link_secret: None,
+        issuer_cert_chain: Vec::new(),
+        vrf_proof: None,
+        claim_schema: None,
+        delegation: None,
+        claim_proofs: Vec::new(),
+        sparse_claim_proofs: Vec::new(),
+        nullifier: None,
+        verifier_nonce: [0u8; 32],
+        subject_salt: None,
+        issuer_membership: None,
+        predicates: Vec::new(),
+        revealed_claims: Vec::new(),
+        disclosure_seed: None,
+        sanctions_exclusion: None,
+        verifier_id: None,
+        timestamp_granularity: TimestampGranularity::Exact,
+        credential_hash_blind: None,
+        ring: None,
+        holder_signature: None,
+        device_pubkey: None,
+        device_assertion: None,
+        biometric_template_hash: None,
+        serial_number: None,
+        revocation_exclusion: None,
+        status_list: None,
+        accumulator_witness: None,
+        key_registry: None,
+        revocation_freshness: None,
+        issuer_registry: None,
+        chain_id: None,
+        verifying_contract: None,
+        issued_at: 1_700_000_000,
+        expires_at: 0,
+        current_time: 1_700_000_100,
+    };
+
+    let digest = message_digest(scenario.hash, &signing_message(&credential));
+    let (signature, issuer_pubkey) = match scenario.scheme {
+        SignatureScheme::Secp256k1 => {
+            use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+
+            let signing_key = SigningKey::from_slice(&[0x11; 32])?;
+            let signature: Signature = signing_key.sign_prehash(&digest)?;
+            let pubkey = signing_key.verifying_key().to_encoded_point(true);
+            (signature.to_vec(), pubkey.as_bytes().to_vec())
+        }
+        SignatureScheme::Secp256r1 => {
+            use p256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+
+            let signing_key = SigningKey::from_slice(&[0x11; 32])?;
+            let signature: Signature = signing_key.sign_prehash(&digest)?;
+            let pubkey = signing_key.verifying_key().to_encoded_point(true);
+            (signature.to_vec(), pubkey.as_bytes().to_vec())
+        }
+        scheme => anyhow::bail!("Benchmarks don't sign with {:?} keys", scheme),
+    };
+    credential.signature = signature;
+    credential.issuer_pubkey = issuer_pubkey;
+    Ok(credential)
+}
+
+/// Builds the program input of a scenario
+fn input(scenario: &Scenario, batch_size: usize) -> Result<ProgramInput> {
+    let credential = credential(scenario)?;
+    if scenario.batch {
+        // Batched credentials share a subject
+        Ok(ProgramInput::Batch(vec![credential; batch_size]))
+    } else {
+        Ok(ProgramInput::Single(Box::new(credential)))
+    }
+}
+
+/// Splits a run's cycles into the stages and the rest
+///
+/// Takes the report's cycle tracker as entries so it doesn't depend on
+/// which map type the SDK keeps them in.
+fn breakdown<'a>(
+    scenario: &str,
+    total: u64,
+    cycle_tracker: impl IntoIterator<Item = (&'a String, &'a u64)>,
+) -> BenchResult {
+    let mut stages: BTreeMap<String, u64> =
+        STAGES.iter().map(|stage| (stage.to_string(), 0)).collect();
+    for (name, cycles) in cycle_tracker {
+        if let Some(stage) = stages.get_mut(name) {
+            *stage += cycles;
+        }
+    }
+    let other = total.saturating_sub(stages.values().sum());
+    BenchResult {
+        scenario: scenario.to_string(),
+        total,
+        stages,
+        other,
+    }
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    args.log.init()?;
+
+    let client = ProverClient::new();
+    let mut results = Vec::new();
+    for scenario in SCENARIOS {
+        if let Some(filter) = &args.filter {
+            if !scenario.name.contains(filter.as_str()) {
+                continue;
+            }
+        }
+        let mut stdin = SP1Stdin::new();
+        let input = input(scenario, args.batch_size)?;
+        stdin.write_vec(credential_verifier_lib::encode(&input));
+        let (_, report) = info_span!("execute", scenario = scenario.name)
+            .in_scope(|| client.execute(ELF, stdin).run())?;
+        results.push(breakdown(
+            scenario.name,
+            report.total_instruction_count(),
+            &report.cycle_tracker,
+        ));
+    }
+    anyhow::ensure!(!results.is_empty(), "No scenario matches the filter");
+
+    print!("{:<28} {:>12}", "Scenario", "Total");
+    for stage in STAGES {
+        print!(" {:>12}", stage);
+    }
+    println!(" {:>12}", "other");
+    for result in &results {
+        print!("{:<28} {:>12}", result.scenario, result.total);
+        for stage in STAGES {
+            print!(" {:>12}", result.stages[stage]);
+        }
+        println!(" {:>12}", result.other);
+    }
+
+    if let Some(path) = &args.output {
+        std::fs::write(path, serde_json::to_string_pretty(&results)?)?;
+        info!(path = %path.display(), "Saved the results");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breakdown_and_inputs() {
+        let cycle_tracker: std::collections::HashMap<String, u64> =
+            [("signature".to_string(), 600), ("hash".to_string(), 100)]
+                .into_iter()
+                .collect();
+        let result = breakdown("scenario", 1000, &cycle_tracker);
+        assert_eq!(result.stages["signature"], 600);
+        assert_eq!(result.stages["claims"], 0);
+        assert_eq!(result.other, 300);
+
+        for scenario in SCENARIOS {
+            match input(scenario, 3).unwrap() {
+                ProgramInput::Batch(credentials) => assert_eq!(credentials.len(), 3),
+                ProgramInput::Single(credential) => {
+                    assert_eq!(
+                        credential.credential_data.len(),
+                        8 + 32 * scenario.claims as usize
+                    )
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        // Every hash suite signs its own digest
+        for hash in [
+            HashAlgorithm::Sha3_256,
+            HashAlgorithm::Blake3,
+            HashAlgorithm::Poseidon,
+        ] {
+            let scenario = Scenario {
+                name: "suite",
+                hash,
+                ..SCENARIOS[0]
+            };
+            assert!(credential(&scenario).is_ok());
+        }
+    }
+}
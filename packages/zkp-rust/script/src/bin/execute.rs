@@ -1,115 +1,407 @@
 //! Fast execution test for the credential verifier circuit
-//! Runs the program without generating a proof to verify logic
+//! Runs the program without generating a proof to verify logic, on a
+//! credential file or a configurable sample; `--invalid` produces a sample
+//! the program must reject, for negative testing. The exit status tells CI
+//! pipelines how it went: 0 if the program accepted the credential (or
+//! rejected an invalid sample), 1 if it rejected it and 2 if the input
+//! couldn't be loaded. The decoded public values can be saved as JSON.
 
-use anyhow::Result;
-use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use alloy_sol_types::SolValue;
+use anyhow::{Context, Result};
+use clap::{Parser, ValueEnum};
+use credential_verifier_lib::message::signing_message;
+use credential_verifier_lib::{
+    file, CredentialFormat, CredentialInput, CredentialOutput, HashAlgorithm, ProgramInput,
+    SignatureScheme, TimestampGranularity, CREDENTIAL_TYPES,
+};
+use k256::ecdsa::signature::Signer;
+use serde_json::{json, Value};
 use sp1_sdk::{ProverClient, SP1Stdin};
+use tracing::{error, info, info_span};
+
+#[path = "../logging.rs"]
+mod logging;
 
 const ELF: &[u8] = include_bytes!("../../../program/elf/riscv32im-succinct-zkvm-elf");
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CredentialInput {
-    pub subject: [u8; 20],
-    pub credential_type: u32,
-    pub credential_data: Vec<u8>,
-    pub signature: Vec<u8>,
-    pub issuer_pubkey: Vec<u8>,
-    pub issued_at: u64,
-    pub expires_at: u64,
-    pub current_time: u64,
+/// Exit status when the program rejects the credential
+const EXIT_REJECTED: u8 = 1;
+
+/// Exit status when the input can't be loaded
+const EXIT_BAD_INPUT: u8 = 2;
+
+/// CLI arguments
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// JSON or TOML credential file to execute with, instead of a signed
+    /// sample
+    #[arg(short, long, visible_alias = "credential")]
+    input: Option<PathBuf>,
+
+    /// Save the decoded public values as JSON
+    #[arg(long)]
+    json: Option<PathBuf>,
+
+    /// Credential type of the sample
+    #[arg(long = "type", default_value = "2")]
+    credential_type: u32,
+
+    /// Number of claims in the sample
+    #[arg(long, default_value = "2")]
+    claims: u32,
+
+    /// Days the sample is valid for after issuance, a day ago (0 for no
+    /// expiration)
+    #[arg(long, default_value = "365")]
+    valid_for_days: u64,
+
+    /// Signature scheme of the sample's issuer key
+    #[arg(long, value_enum, default_value = "secp256k1")]
+    scheme: SignatureScheme,
+
+    /// Make the sample invalid, expecting the program to reject it
+    #[arg(long, value_enum, conflicts_with = "input")]
+    invalid: Option<Defect>,
+
+    #[command(flatten)]
+    log: logging::LogArgs,
 }
 
-fn main() -> Result<()> {
-    println!("SP1 Credential Verifier - Execute Test");
-    println!("======================================");
+/// Ways to break a sample for negative testing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Defect {
+    /// Expired a second ago
+    Expired,
+    /// Signature that doesn't verify
+    BadSignature,
+    /// Claims changed after signing
+    Tampered,
+}
 
-    // Create sample credential
+/// Builds a sample credential signed with a fixed key, broken by `defect`
+fn sample_credential(
+    args: &Args,
+    defect: Option<Defect>,
+    current_time: u64,
+) -> Result<CredentialInput> {
     let subject_bytes = hex::decode("1234567890123456789012345678901234567890")?;
     let mut subject = [0u8; 20];
     subject.copy_from_slice(&subject_bytes);
 
-    let current_time = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)?
-        .as_secs();
+    let issued_at = current_time - 86400;
+    let expires_at = match (defect, args.valid_for_days) {
+        (Some(Defect::Expired), _) => current_time - 1,
+        (_, 0) => 0,
+        (_, days) => issued_at + days * 86400,
+    };
 
     // Build credential data
     let mut credential_data = Vec::new();
     credential_data.extend_from_slice(&1u32.to_be_bytes()); // version
-    credential_data.extend_from_slice(&2u32.to_be_bytes()); // claim_count
-    credential_data.extend_from_slice(&[0u8; 32]); // claim 1
-    credential_data.extend_from_slice(&[1u8; 32]); // claim 2
+    credential_data.extend_from_slice(&args.claims.to_be_bytes()); // claim_count
+    for claim in 0..args.claims {
+        credential_data.extend_from_slice(&[claim as u8; 32]);
+    }
 
-    let credential = CredentialInput {
+    let mut credential = CredentialInput {
         subject,
-        credential_type: 2, // Accredited investor
+        credential_type: args.credential_type,
         credential_data,
-        signature: vec![0u8; 64],
-        issuer_pubkey: vec![0x02; 33],
-        issued_at: current_time - 86400,
-        expires_at: current_time + 365 * 86400,
+        credential_format: CredentialFormat::Binary,
+        signature: Vec::new(),
+        issuer_pubkey: Vec::new(),
+        signature_scheme: args.scheme,
+        eip712_domain: None,
+        hash_algorithm: HashAlgorithm::Sha256,
+        bbs: None,
+        quorum: None,
+        hmac_key: None,
+        link_secret: None,
+        issuer_cert_chain: Vec::new(),
+        vrf_proof: None,
+        claim_schema: None,
+        delegation: None,
+        claim_proofs: Vec::new(),
+        sparse_claim_proofs: Vec::new(),
+        nullifier: None,
+        verifier_nonce: [0u8; 32],
+        subject_salt: None,
+        issuer_membership: None,
+        predicates: Vec::new(),
+        revealed_claims: Vec::new(),
+        disclosure_seed: None,
+        sanctions_exclusion: None,
+        verifier_id: None,
+        timestamp_granularity: TimestampGranularity::Exact,
+        credential_hash_blind: None,
+        ring: None,
+        holder_signature: None,
+        device_pubkey: None,
+        device_assertion: None,
+        biometric_template_hash: None,
+        serial_number: None,
+        revocation_exclusion: None,
+        status_list: None,
+        accumulator_witness: None,
+        key_registry: None,
+        revocation_freshness: None,
+        issuer_registry: None,
+        chain_id: None,
+        verifying_contract: None,
+        issued_at,
+        expires_at,
         current_time,
     };
 
-    println!("Subject: 0x{}", hex::encode(credential.subject));
-    println!("Credential Type: {}", credential.credential_type);
-    println!("Current Time: {}", credential.current_time);
-    println!("Expires At: {}", credential.expires_at);
+    // Sign with a fixed test key
+    let message = signing_message(&credential);
+    let (signature, issuer_pubkey) = match args.scheme {
+        SignatureScheme::Secp256k1 => {
+            let signing_key = k256::ecdsa::SigningKey::from_slice(&[0x11; 32])?;
+            let signature: k256::ecdsa::Signature = signing_key.sign(&message);
+            let pubkey = signing_key.verifying_key().to_encoded_point(true);
+            (signature.to_vec(), pubkey.as_bytes().to_vec())
+        }
+        SignatureScheme::Secp256r1 => {
+            let signing_key = p256::ecdsa::SigningKey::from_slice(&[0x11; 32])?;
+            let signature: p256::ecdsa::Signature = signing_key.sign(&message);
+            let pubkey = signing_key.verifying_key().to_encoded_point(true);
+            (signature.to_vec(), pubkey.as_bytes().to_vec())
+        }
+        scheme => anyhow::bail!("Samples can't be signed with {:?} keys", scheme),
+    };
+    credential.signature = signature;
+    credential.issuer_pubkey = issuer_pubkey;
+
+    match defect {
+        Some(Defect::BadSignature) => *credential.signature.last_mut().unwrap() ^= 0x01,
+        Some(Defect::Tampered) => *credential.credential_data.last_mut().unwrap() ^= 0x01,
+        _ => {}
+    }
+    Ok(credential)
+}
+
+/// Decoded public values as JSON, with the Solidity field names
+fn output_json(output: &CredentialOutput) -> Value {
+    let issuer = &output.issuer;
+    let holder = &output.holder;
+    let status = &output.status;
+    json!({
+        "version": output.version,
+        "subject": output.subject.to_string(),
+        "credentialType": output.credentialType,
+        "credentialHash": output.credentialHash.to_string(),
+        "issuedAt": output.issuedAt,
+        "expiresAt": output.expiresAt,
+        "chainId": output.chainId,
+        "verifyingContract": output.verifyingContract.to_string(),
+        "asOf": output.asOf,
+        "hashSuite": output.hashSuite,
+        "timestampGranularity": output.timestampGranularity,
+        "expiryEpoch": output.expiryEpoch,
+        "credentialHashBlind": output.credentialHashBlind.to_string(),
+        "disclosureHash": output.disclosureHash.to_string(),
+        "schemaHash": output.schemaHash.to_string(),
+        "predicatesHash": output.predicatesHash.to_string(),
+        "predicateResults": output.predicateResults,
+        "withheldClaimsRoot": output.withheldClaimsRoot.to_string(),
+        "issuer": {
+            "issuerAddress": issuer.issuerAddress.to_string(),
+            "quorumHash": issuer.quorumHash.to_string(),
+            "hmacKeyCommitment": issuer.hmacKeyCommitment.to_string(),
+            "rootCaHash": issuer.rootCaHash.to_string(),
+            "vrfOutputHash": issuer.vrfOutputHash.to_string(),
+            "rootIssuerHash": issuer.rootIssuerHash.to_string(),
+            "keyRegistryVersion": issuer.keyRegistryVersion,
+            "trustedIssuersRoot": issuer.trustedIssuersRoot.to_string(),
+            "issuerRegistryRoot": issuer.issuerRegistryRoot.to_string(),
+        },
+        "holder": {
+            "nullifierScope": holder.nullifierScope.to_string(),
+            "nullifier": holder.nullifier.to_string(),
+            "verifierNonce": holder.verifierNonce.to_string(),
+            "subjectCommitment": holder.subjectCommitment.to_string(),
+            "pseudonym": holder.pseudonym.to_string(),
+            "ringRoot": holder.ringRoot.to_string(),
+            "ringEvent": holder.ringEvent.to_string(),
+            "ringTag": holder.ringTag.to_string(),
+            "holderBound": holder.holderBound,
+            "deviceRpIdHash": holder.deviceRpIdHash.to_string(),
+            "biometricBound": holder.biometricBound,
+        },
+        "status": {
+            "sanctionsRoot": status.sanctionsRoot.to_string(),
+            "sanctionsListVersion": status.sanctionsListVersion,
+            "serialHash": status.serialHash.to_string(),
+            "revocationMethod": status.revocationMethod,
+            "revocationRoot": status.revocationRoot.to_string(),
+            "revocationVersion": status.revocationVersion,
+            "revocationMaxAge": status.revocationMaxAge,
+            "statusPurpose": status.statusPurpose,
+        },
+        "revealedClaims": output
+            .revealedClaims
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    if let Err(error) = args.log.init() {
+        eprintln!("Error: {:#}", error);
+        return ExitCode::from(EXIT_BAD_INPUT);
+    }
+
+    let credential = match load_credential(&args) {
+        Ok(credential) => credential,
+        Err(error) => {
+            error!(error = format!("{:#}", error), "Can't load the credential");
+            return ExitCode::from(EXIT_BAD_INPUT);
+        }
+    };
+    match execute(&args, credential) {
+        Ok(status) => status,
+        Err(error) => {
+            error!(error = format!("{:#}", error), "Execution failed");
+            ExitCode::from(EXIT_REJECTED)
+        }
+    }
+}
+
+/// Loads the input file, or builds the sample
+fn load_credential(args: &Args) -> Result<CredentialInput> {
+    match &args.input {
+        Some(path) => {
+            info!(path = %path.display(), "Loading the credential");
+            file::load_credential(path)
+                .with_context(|| format!("Invalid credential file {}", path.display()))
+        }
+        None => {
+            let current_time = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_secs();
+            sample_credential(args, args.invalid, current_time)
+        }
+    }
+}
+
+/// Executes the program on the credential, failing with the reason it was
+/// rejected
+fn execute(args: &Args, credential: CredentialInput) -> Result<ExitCode> {
+    info!(
+        subject = hex::encode(credential.subject),
+        credential_type = credential.credential_type,
+        current_time = credential.current_time,
+        expires_at = credential.expires_at,
+        "Credential"
+    );
 
     // Initialize prover client
-    println!("\nInitializing SP1...");
     let client = ProverClient::new();
 
     // Prepare inputs
     let mut stdin = SP1Stdin::new();
-    stdin.write(&credential);
+    let input = ProgramInput::Single(Box::new(credential));
+    stdin.write_vec(credential_verifier_lib::encode(&input));
 
     // Execute only (no proof generation) - much faster
-    println!("\nExecuting program (no proof generation)...");
-    let (public_values, report) = client.execute(ELF, stdin).run()?;
-
-    println!("\n✓ Execution successful!");
-    println!("Cycles used: {}", report.total_instruction_count());
-    println!("Public values length: {} bytes", public_values.to_vec().len());
+    // A failed assertion halts the program, which prints its message
+    // before the executor reports the halt
+    let result = info_span!("execute").in_scope(|| client.execute(ELF, stdin).run());
+    if let Some(defect) = args.invalid {
+        match result {
+            Ok(_) => anyhow::bail!("The program accepted a sample with {:?}", defect),
+            Err(error) => {
+                info!(
+                    defect = ?defect,
+                    error = %error,
+                    "The program rejected the invalid sample"
+                );
+                return Ok(ExitCode::SUCCESS);
+            }
+        }
+    }
+    let (public_values, report) = result.context("The program rejected the credential")?;
 
-    // Decode the public values to verify output
-    // SP1 outputs in little-endian format
-    // Format: subject (20) + topic (4) + hash (32) + issued_at (8) + expires_at (8) = 72 bytes
+    // Decode the ABI-encoded public values to verify output
     let pv_bytes = public_values.to_vec();
-    if pv_bytes.len() >= 72 {
-        let mut subject_out = [0u8; 20];
-        subject_out.copy_from_slice(&pv_bytes[0..20]);
-
-        // Little-endian u32
-        let topic = u32::from_le_bytes([pv_bytes[20], pv_bytes[21], pv_bytes[22], pv_bytes[23]]);
-
-        let mut hash = [0u8; 32];
-        hash.copy_from_slice(&pv_bytes[24..56]);
-
-        // Little-endian u64
-        let issued_at = u64::from_le_bytes([
-            pv_bytes[56], pv_bytes[57], pv_bytes[58], pv_bytes[59],
-            pv_bytes[60], pv_bytes[61], pv_bytes[62], pv_bytes[63],
-        ]);
-
-        let expires_at = u64::from_le_bytes([
-            pv_bytes[64], pv_bytes[65], pv_bytes[66], pv_bytes[67],
-            pv_bytes[68], pv_bytes[69], pv_bytes[70], pv_bytes[71],
-        ]);
-
-        println!("\n--- Public Values (Decoded) ---");
-        println!("Subject: 0x{}", hex::encode(subject_out));
-        println!("Credential Topic: {} (Accredited Investor)", topic);
-        println!("Credential Hash: 0x{}", hex::encode(hash));
-        println!("Issued At: {} (UNIX timestamp)", issued_at);
-        println!("Expires At: {} (UNIX timestamp)", expires_at);
-        println!("\nRaw public values (hex): 0x{}", hex::encode(&pv_bytes));
+    let output = CredentialOutput::abi_decode_params(&pv_bytes, true)?;
+    let type_name = CREDENTIAL_TYPES
+        .iter()
+        .find(|(_, credential_type)| *credential_type == output.credentialType)
+        .map_or("combined", |(name, _)| name);
+    info!(
+        cycles = report.total_instruction_count(),
+        version = output.version,
+        subject = hex::encode(output.subject),
+        credential_type = output.credentialType,
+        type_name,
+        credential_hash = hex::encode(output.credentialHash),
+        issued_at = output.issuedAt,
+        expires_at = output.expiresAt,
+        chain_id = output.chainId,
+        verifying_contract = %output.verifyingContract,
+        as_of = output.asOf,
+        public_values = hex::encode(&pv_bytes),
+        "The program accepted the credential"
+    );
+
+    if let Some(path) = &args.json {
+        std::fs::write(path, serde_json::to_string_pretty(&output_json(&output))?)?;
+        info!(path = %path.display(), "Saved the public values");
     }
 
-    println!("\n======================================");
-    println!("Circuit execution test PASSED!");
-    println!("The ZK program logic is working correctly.");
-    println!("\nTo generate a full proof for on-chain verification,");
-    println!("run: cargo run --release --bin prove -- --credential sample");
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok(())
+    #[test]
+    fn test_sample_defects() {
+        let args = Args::parse_from(["execute", "--claims", "3", "--scheme", "secp256r1"]);
+        let valid = sample_credential(&args, None, 1_700_000_000).unwrap();
+        assert_eq!(valid.credential_data.len(), 8 + 3 * 32);
+        assert_eq!(valid.expires_at, 1_700_000_000 - 86400 + 365 * 86400);
+
+        let expired = sample_credential(&args, Some(Defect::Expired), 1_700_000_000).unwrap();
+        assert!(expired.expires_at < expired.current_time);
+        assert!(expired.expires_at > expired.issued_at);
+
+        use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+        let pubkey = VerifyingKey::from_sec1_bytes(&valid.issuer_pubkey).unwrap();
+        for (defect, verifies) in [
+            (None, true),
+            (Some(Defect::BadSignature), false),
+            (Some(Defect::Tampered), false),
+        ] {
+            let credential = sample_credential(&args, defect, 1_700_000_000).unwrap();
+            let signature = Signature::from_slice(&credential.signature).unwrap();
+            let message = signing_message(&credential);
+            assert_eq!(pubkey.verify(&message, &signature).is_ok(), verifies);
+        }
+    }
+
+    #[test]
+    fn test_output_json_uses_solidity_names() {
+        let json = output_json(&CredentialOutput {
+            credentialType: 2,
+            chainId: 11_155_111,
+            ..Default::default()
+        });
+        assert_eq!(json["credentialType"], 2);
+        assert_eq!(json["chainId"], 11_155_111);
+        assert_eq!(json["holder"]["holderBound"], false);
+        assert_eq!(
+            json["subject"],
+            "0x0000000000000000000000000000000000000000"
+        );
+    }
 }
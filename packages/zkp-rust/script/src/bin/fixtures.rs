@@ -0,0 +1,193 @@
+//! Solidity fixture generator for the credential verifier contract
+//! Proves a sample credential with Groth16 or PLONK and writes the proof,
+//! public values and verification key as JSON for Foundry tests
+
+use std::path::PathBuf;
+
+use alloy_sol_types::SolValue;
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+use credential_verifier_lib::message::signing_message;
+use credential_verifier_lib::{
+    CredentialFormat, CredentialInput, CredentialOutput, HashAlgorithm, ProgramInput,
+    SignatureScheme, TimestampGranularity,
+};
+use k256::ecdsa::{signature::Signer, Signature, SigningKey};
+use serde::{Deserialize, Serialize};
+use sp1_sdk::{HashableKey, ProverClient, SP1Stdin};
+use tracing::{info, info_span};
+
+#[path = "../logging.rs"]
+mod logging;
+
+const ELF: &[u8] = include_bytes!("../../../program/elf/riscv32im-succinct-zkvm-elf");
+
+/// On-chain proof system to wrap the proof in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ProofSystem {
+    Groth16,
+    Plonk,
+}
+
+/// CLI arguments
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Proof system of the fixture
+    #[arg(long, value_enum, default_value = "groth16")]
+    system: ProofSystem,
+
+    /// Directory the fixture is written to (defaults to the contracts'
+    /// test fixtures)
+    #[arg(long)]
+    out: Option<PathBuf>,
+
+    #[command(flatten)]
+    log: logging::LogArgs,
+}
+
+/// Proof fixture read by the contract tests, with the decoded head of the
+/// public values alongside for assertions
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CredentialProofFixture {
+    subject: String,
+    credential_type: u32,
+    credential_hash: String,
+    issued_at: u64,
+    expires_at: u64,
+    vkey: String,
+    public_values: String,
+    proof: String,
+}
+
+/// Signs a binary accredited investor credential with a fixed secp256k1
+/// test key
+fn sample_credential() -> Result<CredentialInput> {
+    let subject_bytes = hex::decode("1234567890123456789012345678901234567890")?;
+    let mut subject = [0u8; 20];
+    subject.copy_from_slice(&subject_bytes);
+
+    let current_time = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+
+    let mut credential_data = Vec::new();
+    credential_data.extend_from_slice(&1u32.to_be_bytes()); // version
+    credential_data.extend_from_slice(&2u32.to_be_bytes()); // claim_count
+    credential_data.extend_from_slice(&[0u8; 32]); // claim 1
+    credential_data.extend_from_slice(&[1u8; 32]); // claim 2
+
+    let mut credential = CredentialInput {
+        subject,
+        credential_type: 2, // Accredited investor
+        credential_data,
+        credential_format: CredentialFormat::Binary,
+        signature: Vec::new(),
+        issuer_pubkey: Vec::new(),
+        signature_scheme: SignatureScheme::Secp256k1,
+        eip712_domain: None,
+        hash_algorithm: HashAlgorithm::Sha256,
+        bbs: None,
+        quorum: None,
+        hmac_key: None,
+        link_secret: None,
+        issuer_cert_chain: Vec::new(),
+        vrf_proof: None,
+        claim_schema: None,
+        delegation: None,
+        claim_proofs: Vec::new(),
+        sparse_claim_proofs: Vec::new(),
+        nullifier: None,
+        verifier_nonce: [0u8; 32],
+        subject_salt: None,
+        issuer_membership: None,
+        predicates: Vec::new(),
+        revealed_claims: Vec::new(),
+        disclosure_seed: None,
+        sanctions_exclusion: None,
+        verifier_id: None,
+        timestamp_granularity: TimestampGranularity::Exact,
+        credential_hash_blind: None,
+        ring: None,
+        holder_signature: None,
+        device_pubkey: None,
+        device_assertion: None,
+        biometric_template_hash: None,
+        serial_number: None,
+        revocation_exclusion: None,
+        status_list: None,
+        accumulator_witness: None,
+        key_registry: None,
+        revocation_freshness: None,
+        issuer_registry: None,
+        chain_id: None,
+        verifying_contract: None,
+        issued_at: current_time - 86400,
+        expires_at: current_time + 365 * 86400,
+        current_time,
+    };
+
+    let signing_key = SigningKey::from_slice(&[0x11; 32])?;
+    let signature: Signature = signing_key.sign(&signing_message(&credential));
+    credential.signature = signature.to_vec();
+    credential.issuer_pubkey = signing_key
+        .verifying_key()
+        .to_encoded_point(true)
+        .as_bytes()
+        .to_vec();
+    Ok(credential)
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    args.log.init()?;
+
+    let credential = sample_credential()?;
+    let mut stdin = SP1Stdin::new();
+    let input = ProgramInput::Single(Box::new(credential));
+    stdin.write_vec(credential_verifier_lib::encode(&input));
+
+    let client = ProverClient::new();
+    let (pk, vk) = client.setup(ELF);
+
+    let proof = info_span!("prove", system = ?args.system).in_scope(|| {
+        info!("Generating the proof");
+        match args.system {
+            ProofSystem::Groth16 => client.prove(&pk, stdin).groth16().run(),
+            ProofSystem::Plonk => client.prove(&pk, stdin).plonk().run(),
+        }
+    })?;
+    info_span!("verify").in_scope(|| client.verify(&proof, &vk))?;
+
+    let public_values = proof.public_values.to_vec();
+    let output = CredentialOutput::abi_decode_params(&public_values, true)?;
+    let fixture = CredentialProofFixture {
+        subject: format!("0x{}", hex::encode(output.subject)),
+        credential_type: output.credentialType,
+        credential_hash: format!("0x{}", hex::encode(output.credentialHash)),
+        issued_at: output.issuedAt,
+        expires_at: output.expiresAt,
+        vkey: vk.bytes32(),
+        public_values: format!("0x{}", hex::encode(&public_values)),
+        proof: format!("0x{}", hex::encode(proof.bytes())),
+    };
+
+    let dir = args.out.unwrap_or_else(|| {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../contracts/test/fixtures")
+    });
+    std::fs::create_dir_all(&dir)?;
+    let system = format!("{:?}", args.system).to_lowercase();
+    let path = dir.join(format!("{system}-fixture.json"));
+    std::fs::write(&path, serde_json::to_string_pretty(&fixture)?)?;
+
+    info!(
+        vkey = fixture.vkey,
+        subject = fixture.subject,
+        credential_hash = fixture.credential_hash,
+        path = %path.display(),
+        "Saved the fixture"
+    );
+
+    Ok(())
+}
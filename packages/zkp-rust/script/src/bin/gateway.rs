@@ -0,0 +1,169 @@
+//! CCIP-Read (EIP-3668) gateway serving credential proofs
+//! `SP1CredentialVerifier.verifyCredentialOffchain` reverts with an
+//! `OffchainLookup` for `credentialProof(address subject)`; this gateway
+//! answers it with `abi.encode(publicValues, proofBytes)` from the subject's
+//! saved proof, which the client passes to the contract's callback
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use alloy_sol_types::{SolCall, SolValue};
+use anyhow::{Context, Result};
+use axum::extract::{Path as UrlPath, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use clap::Parser;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+#[path = "../logging.rs"]
+mod logging;
+
+alloy_sol_types::sol! {
+    /// Lookup the verifier contract asks the gateway for
+    function credentialProof(address subject);
+
+    /// Response passed to `verifyCredentialCallback`
+    struct ProofResponse {
+        bytes publicValues;
+        bytes proofBytes;
+    }
+}
+
+/// CLI arguments
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Directory of proofs from the `prove` binary, saved as
+    /// `0x<subject>.json` with the subject lowercase
+    #[arg(long, default_value = "proofs")]
+    proofs: PathBuf,
+
+    /// Port to listen on
+    #[arg(long, default_value = "8080")]
+    port: u16,
+
+    #[command(flatten)]
+    log: logging::LogArgs,
+}
+
+/// Body of a CCIP-Read POST request
+#[derive(Debug, Deserialize)]
+struct LookupRequest {
+    data: String,
+}
+
+/// Fields of a saved proof the gateway serves
+#[derive(Debug, Deserialize)]
+struct SavedProof {
+    proof: String,
+    public_values: String,
+}
+
+type Response = (StatusCode, Json<Value>);
+
+/// Answers the lookup in hex `data`, or fails with the status and message
+/// EIP-3668 clients expect
+fn lookup(proofs: &Path, data: &str) -> Result<String, Response> {
+    let error = |status, message: String| (status, Json(json!({ "message": message })));
+    let call_data = hex::decode(data.trim_start_matches("0x"))
+        .map_err(|_| error(StatusCode::BAD_REQUEST, "Malformed call data".into()))?;
+    let call = credentialProofCall::abi_decode(&call_data, true)
+        .map_err(|_| error(StatusCode::BAD_REQUEST, "Unsupported lookup".into()))?;
+
+    let subject = format!("0x{}", hex::encode(call.subject));
+    let path = proofs.join(format!("{subject}.json"));
+    let saved: SavedProof = std::fs::read(&path)
+        .ok()
+        .and_then(|json| serde_json::from_slice(&json).ok())
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, format!("No proof for {subject}")))?;
+
+    let decode = |field: &str| {
+        hex::decode(field.trim_start_matches("0x"))
+            .map_err(|_| error(StatusCode::INTERNAL_SERVER_ERROR, "Malformed proof".into()))
+    };
+    let response = ProofResponse {
+        publicValues: decode(&saved.public_values)?.into(),
+        proofBytes: decode(&saved.proof)?.into(),
+    };
+    Ok(format!("0x{}", hex::encode(response.abi_encode_params())))
+}
+
+/// Wraps the answer to a lookup in the JSON body clients expect
+fn respond(proofs: &Path, data: &str) -> Response {
+    match lookup(proofs, data) {
+        Ok(data) => (StatusCode::OK, Json(json!({ "data": data }))),
+        Err(response) => response,
+    }
+}
+
+/// `GET /{sender}/{data}.json`
+async fn lookup_get(
+    State(proofs): State<Arc<PathBuf>>,
+    UrlPath((_sender, data)): UrlPath<(String, String)>,
+) -> Response {
+    respond(&proofs, data.trim_end_matches(".json"))
+}
+
+/// `POST /` with `{"sender": ..., "data": ...}`
+async fn lookup_post(
+    State(proofs): State<Arc<PathBuf>>,
+    Json(request): Json<LookupRequest>,
+) -> Response {
+    respond(&proofs, &request.data)
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    args.log.init()?;
+
+    let app = Router::new()
+        .route("/:sender/:data", get(lookup_get))
+        .route("/", post(lookup_post))
+        .with_state(Arc::new(args.proofs.clone()));
+
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", args.port))
+        .await
+        .with_context(|| format!("Can't listen on port {}", args.port))?;
+    tracing::info!(
+        proofs = %args.proofs.display(),
+        port = args.port,
+        "Serving proofs"
+    );
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_serves_saved_proof() {
+        let proofs = std::env::temp_dir().join("credence-gateway-test");
+        std::fs::create_dir_all(&proofs).unwrap();
+        let subject = [0x12; 20];
+        std::fs::write(
+            proofs.join(format!("0x{}.json", hex::encode(subject))),
+            json!({ "proof": "abcd", "public_values": "0x01" }).to_string(),
+        )
+        .unwrap();
+
+        let call = credentialProofCall {
+            subject: subject.into(),
+        };
+        let data = lookup(&proofs, &hex::encode(call.abi_encode())).unwrap();
+        let response =
+            ProofResponse::abi_decode_params(&hex::decode(&data[2..]).unwrap(), true).unwrap();
+        assert_eq!(response.proofBytes.to_vec(), vec![0xab, 0xcd]);
+        assert_eq!(response.publicValues.to_vec(), vec![0x01]);
+
+        let other = credentialProofCall {
+            subject: [0x34; 20].into(),
+        };
+        let (status, _) = lookup(&proofs, &hex::encode(other.abi_encode())).unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
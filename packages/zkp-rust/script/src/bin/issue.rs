@@ -0,0 +1,480 @@
+//! Issues a signed credential from the command line
+//! Encodes the claims as v2 typed TLV `credential_data`, signs the
+//! canonical message (or the EIP-712 digest) with the ECDSA issuer key of a
+//! signing backend and writes a credential file the `prove` and `execute`
+//! binaries load with `--credential`
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use credential_verifier_lib::claims;
+use credential_verifier_lib::eip712;
+use credential_verifier_lib::hash::message_digest;
+use credential_verifier_lib::message::signing_message;
+use credential_verifier_lib::{
+    CredentialFormat, CredentialInput, Eip712Domain, HashAlgorithm, SignatureScheme,
+    TimestampGranularity,
+};
+
+#[path = "../config.rs"]
+mod config;
+#[path = "issue/kms.rs"]
+mod kms;
+#[path = "issue/ledger.rs"]
+mod ledger;
+#[path = "../logging.rs"]
+mod logging;
+#[path = "issue/signer.rs"]
+mod signer;
+
+use signer::{Curve, LocalKey, SigningBackend};
+
+/// `credence.toml` keys of the arguments
+const SETTINGS: &[config::Setting] = &[
+    config::setting("issuer", "key", "key"),
+    config::setting("issuer", "keystore", "keystore"),
+    config::setting("issuer", "ledger", "ledger"),
+    config::setting("issuer", "aws_kms_key", "aws_kms_key"),
+    config::setting("issuer", "gcp_kms_key", "gcp_kms_key"),
+    config::setting("issuer", "derivation_path", "derivation_path"),
+    config::setting("issuer", "scheme", "scheme"),
+    config::setting("issuer", "domain_name", "domain_name"),
+    config::setting("issuer", "domain_version", "domain_version"),
+    config::setting("issuer", "chain_id", "chain_id"),
+    config::setting("issuer", "verifying_contract", "verifying_contract"),
+    config::setting("issuer", "hash", "hash"),
+    config::setting("issuer", "output", "output"),
+    config::setting("log", "format", "log_format"),
+    config::setting("log", "level", "log_level"),
+];
+
+/// CLI arguments
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Subject address (hex encoded)
+    #[arg(long, value_parser = parse_address)]
+    subject: [u8; 20],
+
+    /// Credential type (1 = KYC, 2 = Accredited, 3 = Qualified,
+    /// 4 = Institutional, 5 = AML)
+    #[arg(long = "type")]
+    credential_type: u32,
+
+    /// Claim as `type:value`, repeatable; the value is `0x` hex or text
+    /// (e.g., `5:0x02` for KYC level 2, `4:CH` for residence)
+    #[arg(long = "claim", value_parser = parse_claim)]
+    claims: Vec<(u16, Vec<u8>)>,
+
+    /// Issuance timestamp (defaults to now)
+    #[arg(long)]
+    issued_at: Option<u64>,
+
+    /// Expiration timestamp (0 for no expiration)
+    #[arg(long, default_value = "0", conflicts_with = "valid_for_days")]
+    expires_at: u64,
+
+    /// Expire this many days after issuance instead
+    #[arg(long)]
+    valid_for_days: Option<u64>,
+
+    /// File holding the issuer's 32-byte private key (hex encoded)
+    #[arg(long, required_unless_present_any = ["keystore", "ledger", "aws_kms_key", "gcp_kms_key"])]
+    key: Option<PathBuf>,
+
+    /// Keystore from the `keys` binary holding the issuer key, decrypted
+    /// with `ISSUER_KEY_PASSWORD`
+    #[arg(long, conflicts_with = "key")]
+    keystore: Option<PathBuf>,
+
+    /// Sign on a Ledger device with its Ethereum app instead, as an
+    /// `eip712` credential (the key never leaves the device)
+    #[arg(long, conflicts_with_all = ["key", "keystore", "scheme", "aws_kms_key", "gcp_kms_key"])]
+    ledger: bool,
+
+    /// Sign with this AWS KMS key instead (id, alias or ARN), with the
+    /// credentials of the AWS environment variables
+    #[arg(long, conflicts_with_all = ["key", "keystore", "gcp_kms_key"])]
+    aws_kms_key: Option<String>,
+
+    /// Sign with this GCP Cloud KMS key version instead
+    /// (`projects/.../cryptoKeyVersions/N`), with the token of
+    /// `GOOGLE_OAUTH_ACCESS_TOKEN`
+    #[arg(long, conflicts_with_all = ["key", "keystore"])]
+    gcp_kms_key: Option<String>,
+
+    /// Derivation path of the Ledger issuer key
+    #[arg(long, default_value = "m/44'/60'/0'/0/0")]
+    derivation_path: String,
+
+    /// Signature scheme of the issuer key
+    #[arg(long, value_enum, default_value = "secp256k1")]
+    scheme: SignatureScheme,
+
+    /// EIP-712 domain name of `eip712` credentials
+    #[arg(long, default_value = "Credence")]
+    domain_name: String,
+
+    /// EIP-712 domain version of `eip712` credentials
+    #[arg(long, default_value = "1")]
+    domain_version: String,
+
+    /// Chain `eip712` credentials are signed for (defaults to Mantle
+    /// Sepolia)
+    #[arg(long, default_value = "5003")]
+    chain_id: u64,
+
+    /// Contract `eip712` credentials are scoped to (hex encoded)
+    #[arg(long, default_value = "0x0000000000000000000000000000000000000000", value_parser = parse_address)]
+    verifying_contract: [u8; 20],
+
+    /// Hash suite binding the credential
+    #[arg(long, value_enum, default_value = "sha256")]
+    hash: HashAlgorithm,
+
+    /// Output path for the credential file
+    #[arg(short, long, default_value = "credential.json")]
+    output: PathBuf,
+
+    /// Read settings from this TOML file rather than `credence.toml` (flags
+    /// override its values)
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    #[command(flatten)]
+    log: logging::LogArgs,
+}
+
+/// Parses a hex-encoded 20-byte address
+fn parse_address(value: &str) -> Result<[u8; 20], String> {
+    let bytes = hex::decode(value.trim_start_matches("0x")).map_err(|err| err.to_string())?;
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| format!("expected 20 bytes, got {}", bytes.len()))
+}
+
+/// Parses a claim from `type:value`
+fn parse_claim(value: &str) -> Result<(u16, Vec<u8>), String> {
+    let (claim_type, value) = value
+        .split_once(':')
+        .ok_or_else(|| format!("expected type:value, got {value}"))?;
+    let claim_type = claim_type.parse::<u16>().map_err(|err| err.to_string())?;
+    let value = match value.strip_prefix("0x") {
+        Some(digits) => hex::decode(digits).map_err(|err| err.to_string())?,
+        None => value.as_bytes().to_vec(),
+    };
+    Ok((claim_type, value))
+}
+
+/// The EIP-712 digest of an `eip712` credential, as its domain separator
+/// and struct hash
+fn eip712_hashes(credential: &CredentialInput) -> Result<([u8; 32], [u8; 32])> {
+    let domain = credential
+        .eip712_domain
+        .as_ref()
+        .context("EIP-712 credentials need a domain")?;
+    Ok((
+        eip712::domain_separator(domain),
+        eip712::credential_struct_hash(credential),
+    ))
+}
+
+/// Signs the credential through `backend`, filling in its signature and
+/// the issuer's compressed public key
+fn sign(credential: &mut CredentialInput, backend: &mut dyn SigningBackend) -> Result<()> {
+    let curve = Curve::of(credential.signature_scheme)?;
+    anyhow::ensure!(
+        backend.curve() == curve,
+        "The issuer key is a {:?} key, but {:?} credentials need a {:?} one",
+        backend.curve(),
+        credential.signature_scheme,
+        curve
+    );
+    let pubkey = backend.public_key()?;
+    let (digest, signature) = if credential.signature_scheme == SignatureScheme::Eip712 {
+        let (separator, hash) = eip712_hashes(credential)?;
+        let signature = backend.sign_typed_data(&separator, &hash)?;
+        (
+            eip712::typed_data_digest(&separator, &hash),
+            signature.to_vec(),
+        )
+    } else {
+        let digest = message_digest(credential.hash_algorithm, &signing_message(credential));
+        (digest, backend.sign_prehash(&digest)?.to_vec())
+    };
+    // Devices and KMS sign out of sight; check they signed the credential
+    let valid = if signature.len() == 65 {
+        let rs: [u8; 64] = signature[..64].try_into()?;
+        signer::recoverable(&pubkey, &digest, &rs)
+            .is_ok_and(|recoverable| recoverable[..] == signature[..])
+    } else {
+        curve.verify(&pubkey, &digest, &signature)
+    };
+    anyhow::ensure!(valid, "The signature doesn't match the issuer key");
+    credential.signature = signature;
+    credential.issuer_pubkey = pubkey;
+    Ok(())
+}
+
+/// Loads the issuer key of `--key` or `--keystore`
+fn issuer_key(args: &Args) -> Result<[u8; 32]> {
+    let key = match (&args.key, &args.keystore) {
+        (Some(path), _) => {
+            let key_hex = std::fs::read_to_string(path)
+                .with_context(|| format!("Can't read issuer key {}", path.display()))?;
+            hex::decode(key_hex.trim().trim_start_matches("0x"))?
+        }
+        (None, Some(path)) => {
+            let password =
+                std::env::var("ISSUER_KEY_PASSWORD").context("ISSUER_KEY_PASSWORD is not set")?;
+            eth_keystore::decrypt_key(path, password)
+                .with_context(|| format!("Can't decrypt keystore {}", path.display()))?
+        }
+        (None, None) => unreachable!("clap requires --key or --keystore without --ledger"),
+    };
+    key.try_into()
+        .map_err(|_| anyhow::anyhow!("Issuer keys are 32 bytes"))
+}
+
+/// Builds the credential the arguments describe, still unsigned
+fn unsigned_credential(
+    args: &Args,
+    issued_at: u64,
+    expires_at: u64,
+    current_time: u64,
+) -> CredentialInput {
+    let signature_scheme = if args.ledger {
+        SignatureScheme::Eip712
+    } else {
+        args.scheme
+    };
+    let eip712_domain = (signature_scheme == SignatureScheme::Eip712).then(|| Eip712Domain {
+        name: args.domain_name.clone(),
+        version: args.domain_version.clone(),
+        chain_id: args.chain_id,
+        verifying_contract: args.verifying_contract,
+    });
+    CredentialInput {
+        subject: args.subject,
+        credential_type: args.credential_type,
+        credential_data: claims::encode(&args.claims),
+        credential_format: CredentialFormat::Binary,
+        signature: Vec::new(),
+        issuer_pubkey: Vec::new(),
+        signature_scheme,
+        eip712_domain,
+        hash_algorithm: args.hash,
+        bbs: None,
+        quorum: None,
+        hmac_key: None,
+        link_secret: None,
+        issuer_cert_chain: Vec::new(),
+        vrf_proof: None,
+        claim_schema: None,
+        delegation: None,
+        claim_proofs: Vec::new(),
+        sparse_claim_proofs: Vec::new(),
+        nullifier: None,
+        verifier_nonce: [0u8; 32],
+        subject_salt: None,
+        issuer_membership: None,
+        predicates: Vec::new(),
+        revealed_claims: Vec::new(),
+        disclosure_seed: None,
+        sanctions_exclusion: None,
+        verifier_id: None,
+        timestamp_granularity: TimestampGranularity::Exact,
+        credential_hash_blind: None,
+        ring: None,
+        holder_signature: None,
+        device_pubkey: None,
+        device_assertion: None,
+        biometric_template_hash: None,
+        serial_number: None,
+        revocation_exclusion: None,
+        status_list: None,
+        accumulator_witness: None,
+        key_registry: None,
+        revocation_freshness: None,
+        issuer_registry: None,
+        chain_id: None,
+        verifying_contract: None,
+        issued_at,
+        expires_at,
+        current_time,
+    }
+}
+
+fn main() -> Result<()> {
+    let args: Args = config::parse(SETTINGS)?;
+    args.log.init()?;
+
+    let current_time = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+    let issued_at = args.issued_at.unwrap_or(current_time);
+    let expires_at = match args.valid_for_days {
+        Some(days) => issued_at + days * 86400,
+        None => args.expires_at,
+    };
+    anyhow::ensure!(
+        expires_at == 0 || expires_at > issued_at,
+        "The credential would expire before it's issued"
+    );
+    anyhow::ensure!(!args.claims.is_empty(), "Issue at least one --claim");
+
+    let mut credential = unsigned_credential(&args, issued_at, expires_at, current_time);
+    let mut backend: Box<dyn SigningBackend> = if args.ledger {
+        let path = ledger::parse_path(&args.derivation_path)?;
+        Box::new(ledger::Ledger::new(ledger::Hidraw::open()?, path))
+    } else if let Some(key_id) = &args.aws_kms_key {
+        Box::new(kms::AwsKms::connect(key_id)?)
+    } else if let Some(name) = &args.gcp_kms_key {
+        Box::new(kms::GcpKms::connect(name)?)
+    } else {
+        let curve = Curve::of(credential.signature_scheme)?;
+        Box::new(LocalKey::new(issuer_key(&args)?, curve))
+    };
+    sign(&mut credential, backend.as_mut())?;
+
+    std::fs::write(&args.output, serde_json::to_string_pretty(&credential)?)?;
+    tracing::info!(
+        path = %args.output.display(),
+        issuer = hex::encode(&credential.issuer_pubkey),
+        "Issued the credential"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issues_verifiable_credential() {
+        let claim = parse_claim("5:0x02").unwrap();
+        assert_eq!(claim, (5, vec![2]));
+        assert_eq!(parse_claim("4:CH").unwrap(), (4, b"CH".to_vec()));
+        let data = claims::encode(&[claim]);
+        assert_eq!(data, [0, 0, 0, 2, 0, 0, 0, 1, 0, 5, 0, 0, 0, 1, 2]);
+
+        let args = Args::parse_from([
+            "issue",
+            "--subject",
+            "0x1234567890123456789012345678901234567890",
+            "--type",
+            "1",
+            "--claim",
+            "5:0x02",
+            "--key",
+            "issuer.key",
+            "--hash",
+            "keccak256",
+        ]);
+        let mut credential = unsigned_credential(&args, 1, 0, 2);
+        assert_eq!(credential.credential_data, data);
+        sign(
+            &mut credential,
+            &mut LocalKey::new([0x11; 32], Curve::Secp256k1),
+        )
+        .unwrap();
+
+        use k256::ecdsa::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey};
+        let pubkey = VerifyingKey::from_sec1_bytes(&credential.issuer_pubkey).unwrap();
+        let signature = Signature::from_slice(&credential.signature).unwrap();
+        let digest = message_digest(HashAlgorithm::Keccak256, &signing_message(&credential));
+        assert!(pubkey.verify_prehash(&digest, &signature).is_ok());
+    }
+
+    /// Ledger Ethereum app answering over the HID framing
+    struct Device(k256::ecdsa::SigningKey);
+
+    impl ledger::Transport for Device {
+        fn exchange(&mut self, apdu: &[u8]) -> Result<Vec<u8>> {
+            let framed = ledger::frame(apdu).concat();
+            let apdu = ledger::unframe(&mut framed.as_slice())?;
+            let data = &apdu[5 + 1 + 4 * apdu[5] as usize..];
+            let mut response = match apdu[1] {
+                0x02 => {
+                    let pubkey = self.0.verifying_key().to_encoded_point(false);
+                    [&[65], pubkey.as_bytes()].concat()
+                }
+                0x0c => {
+                    let digest = eip712::typed_data_digest(
+                        data[..32].try_into().unwrap(),
+                        data[32..].try_into().unwrap(),
+                    );
+                    let (signature, recovery_id) = self.0.sign_prehash_recoverable(&digest)?;
+                    [&[27 + recovery_id.to_byte()], signature.to_vec().as_slice()].concat()
+                }
+                _ => Vec::new(),
+            };
+            response.extend_from_slice(&[0x90, 0x00]);
+            Ok(response)
+        }
+    }
+
+    #[test]
+    fn test_signs_on_a_ledger() {
+        let args = Args::parse_from([
+            "issue",
+            "--subject",
+            "0x1234567890123456789012345678901234567890",
+            "--type",
+            "1",
+            "--claim",
+            "5:0x02",
+            "--ledger",
+            "--chain-id",
+            "1",
+        ]);
+        let path = ledger::parse_path(&args.derivation_path).unwrap();
+        assert_eq!(path, [44 | 1 << 31, 60 | 1 << 31, 1 << 31, 0, 0]);
+        let mut credential = unsigned_credential(&args, 1, 0, 2);
+        assert_eq!(credential.signature_scheme, SignatureScheme::Eip712);
+        let signing_key = k256::ecdsa::SigningKey::from_slice(&[0x11; 32]).unwrap();
+        let mut device = ledger::Ledger::new(Device(signing_key), path);
+        sign(&mut credential, &mut device).unwrap();
+
+        // A local key signs the same way
+        let mut local = unsigned_credential(&args, 1, 0, 2);
+        sign(&mut local, &mut LocalKey::new([0x11; 32], Curve::Secp256k1)).unwrap();
+        assert_eq!(local.issuer_pubkey, credential.issuer_pubkey);
+        assert_eq!(credential.signature.len(), 65);
+        assert!(ledger::parse_path("44'/60'").is_err());
+    }
+
+    #[test]
+    fn test_signs_aws_requests() {
+        // The example request of AWS's Signature Version 4 documentation
+        let credentials = kms::AwsCredentials {
+            access_key_id: "AKIDEXAMPLE".into(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".into(),
+            session_token: None,
+        };
+        let request = kms::AwsRequest {
+            method: "GET",
+            host: "iam.amazonaws.com",
+            query: "Action=ListUsers&Version=2010-05-08",
+            headers: [
+                (
+                    "content-type".to_string(),
+                    "application/x-www-form-urlencoded; charset=utf-8".to_string(),
+                ),
+                ("x-amz-date".to_string(), "20150830T123600Z".to_string()),
+            ]
+            .into(),
+            body: b"",
+            service: "iam",
+            region: "us-east-1",
+            amz_date: "20150830T123600Z",
+        };
+        assert_eq!(
+            kms::aws_authorization(&credentials, &request),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/iam/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date, \
+             Signature=5d672d79c15b13162d9279b0855cfba6789a8edb4c82c400e06b5924a6f2b5d7"
+        );
+    }
+}
@@ -0,0 +1,304 @@
+//! Issuer keys in AWS KMS and GCP Cloud KMS
+//!
+//! Both sign digests with asymmetric EC keys (`ECC_SECG_P256K1` or
+//! `ECC_NIST_P256` on AWS, `EC_SIGN_SECP256K1_SHA256` or
+//! `EC_SIGN_P256_SHA256` on GCP) without the key leaving their HSMs. Their
+//! REST APIs are called directly: AWS requests are signed with Signature
+//! Version 4 from `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and
+//! `AWS_SESSION_TOKEN`, and GCP requests carry `GOOGLE_OAUTH_ACCESS_TOKEN`
+//! (e.g., from `gcloud auth print-access-token`).
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::signer::{Curve, SigningBackend};
+
+/// Sends a JSON request, returning the JSON response or the service's
+/// error
+fn send(request: reqwest::blocking::RequestBuilder, service: &str) -> Result<Value> {
+    let response = request
+        .send()
+        .with_context(|| format!("Can't reach {service}"))?;
+    let status = response.status();
+    let body: Value = response.json().unwrap_or(Value::Null);
+    if !status.is_success() {
+        let message = body
+            .get("message")
+            .or_else(|| body.pointer("/error/message"))
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        anyhow::bail!("{service} failed with {status}: {message}");
+    }
+    Ok(body)
+}
+
+/// Base64 field `field` of a response
+fn decoded(response: &Value, field: &str) -> Result<Vec<u8>> {
+    let encoded = response
+        .get(field)
+        .and_then(Value::as_str)
+        .with_context(|| format!("The response has no {field}"))?;
+    Ok(STANDARD.decode(encoded)?)
+}
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes any key");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// AWS credentials from the environment
+pub struct AwsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+impl AwsCredentials {
+    pub fn from_env() -> Result<Self> {
+        Ok(AwsCredentials {
+            access_key_id: std::env::var("AWS_ACCESS_KEY_ID")
+                .context("AWS_ACCESS_KEY_ID is not set")?,
+            secret_access_key: std::env::var("AWS_SECRET_ACCESS_KEY")
+                .context("AWS_SECRET_ACCESS_KEY is not set")?,
+            session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+        })
+    }
+}
+
+/// Request to sign with Signature Version 4
+pub struct AwsRequest<'a> {
+    pub method: &'a str,
+    pub host: &'a str,
+    /// Canonical query string (parameters sorted and encoded)
+    pub query: &'a str,
+    /// Headers to sign besides `host`, lowercase
+    pub headers: BTreeMap<String, String>,
+    pub body: &'a [u8],
+    pub service: &'a str,
+    pub region: &'a str,
+    /// Request time as `YYYYMMDDTHHMMSSZ`, also sent as `x-amz-date`
+    pub amz_date: &'a str,
+}
+
+/// `Authorization` header of a request to the root path, signed with
+/// Signature Version 4
+pub fn aws_authorization(credentials: &AwsCredentials, request: &AwsRequest) -> String {
+    let mut headers = request.headers.clone();
+    headers.insert("host".into(), request.host.into());
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{name}:{}\n", value.trim()))
+        .collect();
+    let signed_headers = headers.keys().cloned().collect::<Vec<_>>().join(";");
+    let canonical_request = format!(
+        "{}\n/\n{}\n{canonical_headers}\n{signed_headers}\n{}",
+        request.method,
+        request.query,
+        hex::encode(Sha256::digest(request.body))
+    );
+    let date = &request.amz_date[..8];
+    let scope = format!("{date}/{}/{}/aws4_request", request.region, request.service);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{scope}\n{}",
+        request.amz_date,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let key = hmac(
+        format!("AWS4{}", credentials.secret_access_key).as_bytes(),
+        date,
+    );
+    let key = hmac(&key, request.region);
+    let key = hmac(&key, request.service);
+    let key = hmac(&key, "aws4_request");
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={}",
+        credentials.access_key_id,
+        hex::encode(hmac(&key, &string_to_sign))
+    )
+}
+
+/// Key in AWS KMS, by id, alias or ARN
+pub struct AwsKms {
+    key_id: String,
+    region: String,
+    credentials: AwsCredentials,
+    client: reqwest::blocking::Client,
+    curve: Curve,
+    pubkey: Vec<u8>,
+}
+
+impl AwsKms {
+    /// Connects to `key_id`, in the region of its ARN or `AWS_REGION`
+    pub fn connect(key_id: &str) -> Result<Self> {
+        let region = match key_id.strip_prefix("arn:aws:kms:") {
+            Some(arn) => arn.split(':').next().unwrap_or_default().to_string(),
+            None => std::env::var("AWS_REGION")
+                .or_else(|_| std::env::var("AWS_DEFAULT_REGION"))
+                .context("Set AWS_REGION or pass the key's ARN")?,
+        };
+        let mut kms = AwsKms {
+            key_id: key_id.to_string(),
+            region,
+            credentials: AwsCredentials::from_env()?,
+            client: reqwest::blocking::Client::new(),
+            curve: Curve::Secp256k1,
+            pubkey: Vec::new(),
+        };
+        let response = kms.call("GetPublicKey", json!({ "KeyId": key_id }))?;
+        kms.curve = match response.get("KeySpec").and_then(Value::as_str) {
+            Some("ECC_SECG_P256K1") => Curve::Secp256k1,
+            Some("ECC_NIST_P256") => Curve::P256,
+            spec => anyhow::bail!("KMS key {key_id} isn't a secp256k1 or P-256 key ({spec:?})"),
+        };
+        kms.pubkey = kms
+            .curve
+            .public_key_from_der(&decoded(&response, "PublicKey")?)?;
+        Ok(kms)
+    }
+
+    /// Calls KMS action `action`
+    fn call(&self, action: &str, body: Value) -> Result<Value> {
+        let host = format!("kms.{}.amazonaws.com", self.region);
+        let body = serde_json::to_vec(&body)?;
+        let amz_date = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let mut headers = BTreeMap::from([
+            (
+                "content-type".to_string(),
+                "application/x-amz-json-1.1".to_string(),
+            ),
+            ("x-amz-date".to_string(), amz_date.clone()),
+            ("x-amz-target".to_string(), format!("TrentService.{action}")),
+        ]);
+        if let Some(token) = &self.credentials.session_token {
+            headers.insert("x-amz-security-token".into(), token.clone());
+        }
+        let authorization = aws_authorization(
+            &self.credentials,
+            &AwsRequest {
+                method: "POST",
+                host: &host,
+                query: "",
+                headers: headers.clone(),
+                body: &body,
+                service: "kms",
+                region: &self.region,
+                amz_date: &amz_date,
+            },
+        );
+        let mut request = self
+            .client
+            .post(format!("https://{host}/"))
+            .header("authorization", authorization)
+            .body(body);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        send(request, "AWS KMS")
+    }
+}
+
+impl SigningBackend for AwsKms {
+    fn curve(&self) -> Curve {
+        self.curve
+    }
+
+    fn public_key(&mut self) -> Result<Vec<u8>> {
+        Ok(self.pubkey.clone())
+    }
+
+    fn sign_prehash(&mut self, digest: &[u8; 32]) -> Result<[u8; 64]> {
+        let response = self.call(
+            "Sign",
+            json!({
+                "KeyId": self.key_id,
+                "Message": STANDARD.encode(digest),
+                "MessageType": "DIGEST",
+                "SigningAlgorithm": "ECDSA_SHA_256",
+            }),
+        )?;
+        self.curve
+            .signature_from_der(&decoded(&response, "Signature")?)
+    }
+}
+
+/// Key version in GCP Cloud KMS, as
+/// `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*`
+pub struct GcpKms {
+    name: String,
+    token: String,
+    client: reqwest::blocking::Client,
+    curve: Curve,
+    pubkey: Vec<u8>,
+}
+
+impl GcpKms {
+    pub fn connect(name: &str) -> Result<Self> {
+        let token = std::env::var("GOOGLE_OAUTH_ACCESS_TOKEN")
+            .context("GOOGLE_OAUTH_ACCESS_TOKEN is not set")?;
+        let client = reqwest::blocking::Client::new();
+        let response = send(
+            client
+                .get(format!(
+                    "https://cloudkms.googleapis.com/v1/{name}/publicKey"
+                ))
+                .bearer_auth(&token),
+            "GCP KMS",
+        )?;
+        let curve = match response.get("algorithm").and_then(Value::as_str) {
+            Some("EC_SIGN_SECP256K1_SHA256") => Curve::Secp256k1,
+            Some("EC_SIGN_P256_SHA256") => Curve::P256,
+            algorithm => {
+                anyhow::bail!("KMS key {name} isn't a secp256k1 or P-256 key ({algorithm:?})")
+            }
+        };
+        let pem = response
+            .get("pem")
+            .and_then(Value::as_str)
+            .context("The response has no pem")?;
+        let der: String = pem
+            .lines()
+            .filter(|line| !line.starts_with("-----"))
+            .collect();
+        let pubkey = curve.public_key_from_der(&STANDARD.decode(der)?)?;
+        Ok(GcpKms {
+            name: name.to_string(),
+            token,
+            client,
+            curve,
+            pubkey,
+        })
+    }
+}
+
+impl SigningBackend for GcpKms {
+    fn curve(&self) -> Curve {
+        self.curve
+    }
+
+    fn public_key(&mut self) -> Result<Vec<u8>> {
+        Ok(self.pubkey.clone())
+    }
+
+    fn sign_prehash(&mut self, digest: &[u8; 32]) -> Result<[u8; 64]> {
+        // KMS signs the 32 bytes as given, whichever hash produced them
+        let response = send(
+            self.client
+                .post(format!(
+                    "https://cloudkms.googleapis.com/v1/{}:asymmetricSign",
+                    self.name
+                ))
+                .bearer_auth(&self.token)
+                .json(&json!({ "digest": { "sha256": STANDARD.encode(digest) } })),
+            "GCP KMS",
+        )?;
+        self.curve
+            .signature_from_der(&decoded(&response, "signature")?)
+    }
+}
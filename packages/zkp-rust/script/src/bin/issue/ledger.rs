@@ -0,0 +1,253 @@
+//! Signing on a Ledger device with its Ethereum app
+//!
+//! The Ethereum app doesn't sign arbitrary digests, so Ledger-issued
+//! credentials use the `eip712` scheme: the app signs the typed-data hash
+//! of the credential (its `SIGN_EIP712` instruction with the domain
+//! separator and struct hash), showing both hashes on the device for the
+//! issuer to confirm. The key never leaves the device.
+//!
+//! APDUs are exchanged over USB HID in Ledger's framing: 64-byte packets on
+//! channel `0x0101`, numbered from 0, the first carrying the APDU's length.
+//! Devices are found through Linux's hidraw interface.
+
+use std::io::{Read, Write};
+
+use anyhow::{Context, Result};
+
+use crate::signer::{Curve, SigningBackend};
+
+/// Class of the Ethereum app's instructions
+const CLA: u8 = 0xe0;
+const INS_GET_PUBLIC_KEY: u8 = 0x02;
+const INS_SIGN_EIP712: u8 = 0x0c;
+
+/// USB vendor id of Ledger devices
+const LEDGER_VENDOR: &str = "00002C97";
+/// HID framing of APDUs
+const CHANNEL: u16 = 0x0101;
+const TAG_APDU: u8 = 0x05;
+const PACKET_SIZE: usize = 64;
+
+/// Something APDUs can be exchanged with
+pub trait Transport {
+    /// Sends a command APDU, returning the response with its status word
+    fn exchange(&mut self, apdu: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Splits an APDU into HID packets
+pub fn frame(apdu: &[u8]) -> Vec<[u8; PACKET_SIZE]> {
+    let mut payload = (apdu.len() as u16).to_be_bytes().to_vec();
+    payload.extend_from_slice(apdu);
+    payload
+        .chunks(PACKET_SIZE - 5)
+        .enumerate()
+        .map(|(sequence, chunk)| {
+            let mut packet = [0u8; PACKET_SIZE];
+            packet[..2].copy_from_slice(&CHANNEL.to_be_bytes());
+            packet[2] = TAG_APDU;
+            packet[3..5].copy_from_slice(&(sequence as u16).to_be_bytes());
+            packet[5..5 + chunk.len()].copy_from_slice(chunk);
+            packet
+        })
+        .collect()
+}
+
+/// Reads a framed response APDU from `device`
+pub fn unframe(device: &mut impl Read) -> Result<Vec<u8>> {
+    let mut response = Vec::new();
+    let mut length = None;
+    let mut sequence = 0u16;
+    while length.is_none_or(|length| response.len() < length) {
+        let mut packet = [0u8; PACKET_SIZE];
+        device
+            .read_exact(&mut packet)
+            .context("Can't read from the Ledger device")?;
+        anyhow::ensure!(
+            packet[..2] == CHANNEL.to_be_bytes()
+                && packet[2] == TAG_APDU
+                && packet[3..5] == sequence.to_be_bytes(),
+            "Unexpected packet from the Ledger device"
+        );
+        let data = if sequence == 0 {
+            length = Some(u16::from_be_bytes([packet[5], packet[6]]) as usize);
+            &packet[7..]
+        } else {
+            &packet[5..]
+        };
+        response.extend_from_slice(data);
+        sequence += 1;
+    }
+    response.truncate(length.unwrap_or_default());
+    Ok(response)
+}
+
+/// A Ledger device's hidraw node
+pub struct Hidraw {
+    device: std::fs::File,
+}
+
+impl Hidraw {
+    /// Opens the first Ledger device plugged in
+    #[cfg(target_os = "linux")]
+    pub fn open() -> Result<Self> {
+        for entry in std::fs::read_dir("/sys/class/hidraw").context("Can't list HID devices")? {
+            let entry = entry?;
+            let device = entry.path().join("device");
+            let uevent = std::fs::read_to_string(device.join("uevent")).unwrap_or_default();
+            if !uevent
+                .lines()
+                .any(|line| line.contains(&format!(":{LEDGER_VENDOR}:")))
+            {
+                continue;
+            }
+            // Ledger devices also expose a FIDO interface; APDUs go to the
+            // one with the vendor-defined usage page 0xffa0
+            let descriptor = std::fs::read(device.join("report_descriptor")).unwrap_or_default();
+            if !descriptor.windows(3).any(|item| item == [0x06, 0xa0, 0xff]) {
+                continue;
+            }
+            let path = std::path::Path::new("/dev").join(entry.file_name());
+            let device = std::fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(&path)
+                .with_context(|| {
+                    format!("Can't open {} (check its permissions)", path.display())
+                })?;
+            return Ok(Hidraw { device });
+        }
+        anyhow::bail!("No Ledger device found; plug it in and unlock it")
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn open() -> Result<Self> {
+        anyhow::bail!("Ledger devices are only supported on Linux")
+    }
+}
+
+impl Transport for Hidraw {
+    fn exchange(&mut self, apdu: &[u8]) -> Result<Vec<u8>> {
+        for packet in frame(apdu) {
+            // hidraw reports start with their report id, 0 for Ledger devices
+            let mut report = vec![0u8];
+            report.extend_from_slice(&packet);
+            self.device
+                .write_all(&report)
+                .context("Can't write to the Ledger device")?;
+        }
+        unframe(&mut self.device)
+    }
+}
+
+/// Parses a BIP-32 derivation path like `m/44'/60'/0'/0/0`
+pub fn parse_path(path: &str) -> Result<Vec<u32>> {
+    let components = path
+        .strip_prefix("m/")
+        .with_context(|| format!("Derivation paths start with m/, not {path}"))?;
+    components
+        .split('/')
+        .map(|component| {
+            let (index, hardened) = match component.strip_suffix('\'') {
+                Some(index) => (index, true),
+                None => (component, false),
+            };
+            let index = index
+                .parse::<u32>()
+                .ok()
+                .filter(|index| *index < 1 << 31)
+                .with_context(|| format!("Invalid derivation path component {component}"))?;
+            Ok(if hardened { index | 1 << 31 } else { index })
+        })
+        .collect()
+}
+
+/// The Ethereum app of a Ledger device, using one derivation path
+pub struct Ledger<T> {
+    transport: T,
+    path: Vec<u32>,
+}
+
+impl<T: Transport> Ledger<T> {
+    pub fn new(transport: T, path: Vec<u32>) -> Self {
+        Ledger { transport, path }
+    }
+
+    /// Sends an instruction with the path and `data`, returning the
+    /// response data
+    fn command(&mut self, instruction: u8, data: &[u8]) -> Result<Vec<u8>> {
+        let mut payload = vec![self.path.len() as u8];
+        for index in &self.path {
+            payload.extend_from_slice(&index.to_be_bytes());
+        }
+        payload.extend_from_slice(data);
+        let mut apdu = vec![CLA, instruction, 0, 0, payload.len() as u8];
+        apdu.extend_from_slice(&payload);
+        let mut response = self.transport.exchange(&apdu)?;
+        anyhow::ensure!(response.len() >= 2, "The Ledger device didn't answer");
+        let status = response.split_off(response.len() - 2);
+        match u16::from_be_bytes([status[0], status[1]]) {
+            0x9000 => Ok(response),
+            0x6985 => anyhow::bail!("Signing was rejected on the Ledger device"),
+            0x6d00 | 0x6e00 | 0x6e01 | 0x6511 => {
+                anyhow::bail!("Open the Ethereum app on the Ledger device")
+            }
+            0x5515 => anyhow::bail!("Unlock the Ledger device"),
+            status => anyhow::bail!("The Ledger device failed with status {status:#06x}"),
+        }
+    }
+
+    /// Uncompressed SEC1 public key of the path's key
+    pub fn uncompressed_key(&mut self) -> Result<[u8; 65]> {
+        let response = self.command(INS_GET_PUBLIC_KEY, &[])?;
+        let length = *response.first().context("Empty public key response")? as usize;
+        anyhow::ensure!(length == 65, "Unexpected public key length {length}");
+        response
+            .get(1..66)
+            .and_then(|pubkey| pubkey.try_into().ok())
+            .context("Truncated public key response")
+    }
+
+    /// Signs an EIP-712 digest from its domain separator and struct hash,
+    /// returning `r || s || v` with v = 27/28
+    pub fn sign_eip712(
+        &mut self,
+        domain_separator: &[u8; 32],
+        struct_hash: &[u8; 32],
+    ) -> Result<[u8; 65]> {
+        let mut data = domain_separator.to_vec();
+        data.extend_from_slice(struct_hash);
+        let response = self.command(INS_SIGN_EIP712, &data)?;
+        anyhow::ensure!(response.len() == 65, "Unexpected signature response");
+        let mut signature = [0u8; 65];
+        signature[..64].copy_from_slice(&response[1..]);
+        signature[64] = response[0];
+        Ok(signature)
+    }
+}
+
+impl<T: Transport> SigningBackend for Ledger<T> {
+    fn curve(&self) -> Curve {
+        Curve::Secp256k1
+    }
+
+    fn public_key(&mut self) -> Result<Vec<u8>> {
+        Curve::Secp256k1.compress(&self.uncompressed_key()?)
+    }
+
+    fn sign_prehash(&mut self, _digest: &[u8; 32]) -> Result<[u8; 64]> {
+        anyhow::bail!("Ledger devices only sign eip712 credentials")
+    }
+
+    fn sign_typed_data(
+        &mut self,
+        domain_separator: &[u8; 32],
+        struct_hash: &[u8; 32],
+    ) -> Result<[u8; 65]> {
+        tracing::info!(
+            domain_hash = hex::encode(domain_separator),
+            message_hash = hex::encode(struct_hash),
+            "Confirm the credential on the Ledger device"
+        );
+        self.sign_eip712(domain_separator, struct_hash)
+    }
+}
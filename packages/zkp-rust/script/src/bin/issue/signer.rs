@@ -0,0 +1,205 @@
+//! Signing backends of the issuer CLI
+//!
+//! Credentials are signed through [`SigningBackend`], whichever holds the
+//! issuer key: a local key file or keystore, a Ledger device, or an AWS or
+//! GCP KMS key (whose HSMs never release it). Backends sign 32-byte ECDSA
+//! prehashes, and the EIP-712 digests of `eip712` credentials.
+
+use anyhow::{Context, Result};
+use credential_verifier_lib::{eip712, SignatureScheme};
+
+/// Curve of an issuer key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Curve {
+    Secp256k1,
+    P256,
+}
+
+impl Curve {
+    /// Curve of the keys issuing with `scheme`
+    pub fn of(scheme: SignatureScheme) -> Result<Self> {
+        match scheme {
+            SignatureScheme::Secp256k1 | SignatureScheme::Eip712 => Ok(Curve::Secp256k1),
+            SignatureScheme::Secp256r1 => Ok(Curve::P256),
+            scheme => anyhow::bail!("Issuing with {:?} keys isn't supported", scheme),
+        }
+    }
+
+    /// Normalizes a DER signature to `r || s` with a low s, as the program
+    /// expects
+    pub fn signature_from_der(self, der: &[u8]) -> Result<[u8; 64]> {
+        let signature = match self {
+            Curve::Secp256k1 => {
+                let signature = k256::ecdsa::Signature::from_der(der)?;
+                signature
+                    .normalize_s()
+                    .unwrap_or(signature)
+                    .to_bytes()
+                    .to_vec()
+            }
+            Curve::P256 => {
+                let signature = p256::ecdsa::Signature::from_der(der)?;
+                signature
+                    .normalize_s()
+                    .unwrap_or(signature)
+                    .to_bytes()
+                    .to_vec()
+            }
+        };
+        Ok(signature.try_into().expect("Signatures are 64 bytes"))
+    }
+
+    /// Compressed SEC1 key of a DER `SubjectPublicKeyInfo`
+    pub fn public_key_from_der(self, der: &[u8]) -> Result<Vec<u8>> {
+        use k256::pkcs8::DecodePublicKey;
+
+        let pubkey = match self {
+            Curve::Secp256k1 => k256::PublicKey::from_public_key_der(der)
+                .context("Not a secp256k1 public key")?
+                .to_sec1_bytes(),
+            Curve::P256 => p256::PublicKey::from_public_key_der(der)
+                .context("Not a P-256 public key")?
+                .to_sec1_bytes(),
+        };
+        self.compress(&pubkey)
+    }
+
+    /// Compresses a SEC1 public key
+    pub fn compress(self, pubkey: &[u8]) -> Result<Vec<u8>> {
+        Ok(match self {
+            Curve::Secp256k1 => k256::ecdsa::VerifyingKey::from_sec1_bytes(pubkey)?
+                .to_encoded_point(true)
+                .as_bytes()
+                .to_vec(),
+            Curve::P256 => p256::ecdsa::VerifyingKey::from_sec1_bytes(pubkey)?
+                .to_encoded_point(true)
+                .as_bytes()
+                .to_vec(),
+        })
+    }
+
+    /// Whether `signature` (`r || s`) over `digest` is by `pubkey`
+    pub fn verify(self, pubkey: &[u8], digest: &[u8; 32], signature: &[u8]) -> bool {
+        match self {
+            Curve::Secp256k1 => {
+                use k256::ecdsa::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey};
+
+                let (Ok(pubkey), Ok(signature)) = (
+                    VerifyingKey::from_sec1_bytes(pubkey),
+                    Signature::from_slice(signature),
+                ) else {
+                    return false;
+                };
+                pubkey.verify_prehash(digest, &signature).is_ok()
+            }
+            Curve::P256 => {
+                use p256::ecdsa::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey};
+
+                let (Ok(pubkey), Ok(signature)) = (
+                    VerifyingKey::from_sec1_bytes(pubkey),
+                    Signature::from_slice(signature),
+                ) else {
+                    return false;
+                };
+                pubkey.verify_prehash(digest, &signature).is_ok()
+            }
+        }
+    }
+}
+
+/// Appends the recovery byte (27/28) wallets add to a secp256k1 signature
+/// of `digest` by `pubkey`
+pub fn recoverable(pubkey: &[u8], digest: &[u8; 32], signature: &[u8; 64]) -> Result<[u8; 65]> {
+    use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+    let expected = VerifyingKey::from_sec1_bytes(pubkey)?;
+    let parsed = Signature::from_slice(signature)?;
+    let recovery_id = (0..2)
+        .filter_map(RecoveryId::from_byte)
+        .find(|recovery_id| {
+            VerifyingKey::recover_from_prehash(digest, &parsed, *recovery_id)
+                .is_ok_and(|recovered| recovered == expected)
+        })
+        .context("The signature isn't by the issuer key")?;
+    let mut recoverable = [0u8; 65];
+    recoverable[..64].copy_from_slice(signature);
+    recoverable[64] = 27 + recovery_id.to_byte();
+    Ok(recoverable)
+}
+
+/// Holder of an issuer key
+pub trait SigningBackend {
+    /// Curve of the key
+    fn curve(&self) -> Curve;
+
+    /// Compressed SEC1 public key
+    fn public_key(&mut self) -> Result<Vec<u8>>;
+
+    /// Signs a 32-byte prehash, returning `r || s` with a low s
+    fn sign_prehash(&mut self, digest: &[u8; 32]) -> Result<[u8; 64]>;
+
+    /// Signs an EIP-712 digest from its domain separator and struct hash,
+    /// returning `r || s || v` with v = 27/28
+    fn sign_typed_data(
+        &mut self,
+        domain_separator: &[u8; 32],
+        struct_hash: &[u8; 32],
+    ) -> Result<[u8; 65]> {
+        let digest = eip712::typed_data_digest(domain_separator, struct_hash);
+        let signature = self.sign_prehash(&digest)?;
+        recoverable(&self.public_key()?, &digest, &signature)
+    }
+}
+
+/// Issuer key in memory, from `--key` or `--keystore`
+pub struct LocalKey {
+    key: [u8; 32],
+    curve: Curve,
+}
+
+impl LocalKey {
+    pub fn new(key: [u8; 32], curve: Curve) -> Self {
+        LocalKey { key, curve }
+    }
+}
+
+impl SigningBackend for LocalKey {
+    fn curve(&self) -> Curve {
+        self.curve
+    }
+
+    fn public_key(&mut self) -> Result<Vec<u8>> {
+        Ok(match self.curve {
+            Curve::Secp256k1 => k256::ecdsa::SigningKey::from_slice(&self.key)?
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes()
+                .to_vec(),
+            Curve::P256 => p256::ecdsa::SigningKey::from_slice(&self.key)?
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes()
+                .to_vec(),
+        })
+    }
+
+    fn sign_prehash(&mut self, digest: &[u8; 32]) -> Result<[u8; 64]> {
+        let signature = match self.curve {
+            Curve::Secp256k1 => {
+                use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+
+                let signature: Signature =
+                    SigningKey::from_slice(&self.key)?.sign_prehash(digest)?;
+                signature.to_vec()
+            }
+            Curve::P256 => {
+                use p256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+
+                let signature: Signature =
+                    SigningKey::from_slice(&self.key)?.sign_prehash(digest)?;
+                signature.to_vec()
+            }
+        };
+        Ok(signature.try_into().expect("Signatures are 64 bytes"))
+    }
+}
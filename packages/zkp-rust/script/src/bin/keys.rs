@@ -0,0 +1,225 @@
+//! Issuer key management
+//!
+//! Generates issuer keypairs, stores the private keys encrypted as Web3
+//! Secret Storage keystores (scrypt and AES-128-CTR, password from
+//! `ISSUER_KEY_PASSWORD`) and prints the public forms issuers register
+//! on-chain: the SEC1 public key, the Ethereum address of secp256k1 keys
+//! and the issuer registry leaf
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use credential_verifier_lib::SignatureScheme;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tiny_keccak::{Hasher, Keccak};
+
+#[path = "../logging.rs"]
+mod logging;
+
+/// CLI arguments
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Directory the keys are stored in
+    #[arg(long, default_value = "keys")]
+    dir: PathBuf,
+
+    #[command(flatten)]
+    log: logging::LogArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Generate a keypair and store it encrypted
+    Generate {
+        /// Name to store the key under
+        name: String,
+
+        /// Signature scheme of the key (secp256k1 or secp256r1)
+        #[arg(long, value_enum, default_value = "secp256k1")]
+        scheme: SignatureScheme,
+    },
+    /// List the stored keys
+    List,
+    /// Print a key's public forms for on-chain registration
+    Show {
+        /// Name the key is stored under
+        name: String,
+    },
+}
+
+/// Public half of a stored key, saved beside its keystore as
+/// `<name>.pub.json` so keys can be listed without the password
+#[derive(Debug, Serialize, Deserialize)]
+struct KeyInfo {
+    name: String,
+    scheme: SignatureScheme,
+    /// Compressed SEC1 public key, the credential's `issuer_pubkey` (hex)
+    pubkey: String,
+    /// Uncompressed SEC1 public key (hex)
+    pubkey_uncompressed: String,
+    /// Ethereum address (secp256k1 keys only)
+    address: Option<String>,
+    /// Leaf of the key in an issuer registry or trusted set (hex)
+    registry_leaf: String,
+}
+
+/// Derives the public forms of `secret`
+fn key_info(name: &str, scheme: SignatureScheme, secret: &[u8]) -> Result<KeyInfo> {
+    let (compressed, uncompressed) = match scheme {
+        SignatureScheme::Secp256k1 => {
+            let key = k256::ecdsa::SigningKey::from_slice(secret)?;
+            let point = key.verifying_key();
+            (
+                point.to_encoded_point(true).to_bytes(),
+                point.to_encoded_point(false).to_bytes(),
+            )
+        }
+        SignatureScheme::Secp256r1 => {
+            let key = p256::ecdsa::SigningKey::from_slice(secret)?;
+            let point = key.verifying_key();
+            (
+                point.to_encoded_point(true).to_bytes(),
+                point.to_encoded_point(false).to_bytes(),
+            )
+        }
+        scheme => anyhow::bail!("{:?} issuer keys aren't supported", scheme),
+    };
+
+    // Ethereum addresses are the last 20 bytes of the keccak256 of the
+    // uncompressed point
+    let address = (scheme == SignatureScheme::Secp256k1).then(|| {
+        let mut hash = [0u8; 32];
+        let mut keccak = Keccak::v256();
+        keccak.update(&uncompressed[1..]);
+        keccak.finalize(&mut hash);
+        format!("0x{}", hex::encode(&hash[12..]))
+    });
+    Ok(KeyInfo {
+        name: name.to_string(),
+        scheme,
+        pubkey: format!("0x{}", hex::encode(&compressed)),
+        pubkey_uncompressed: format!("0x{}", hex::encode(&uncompressed)),
+        address,
+        registry_leaf: registry_leaf(scheme, &compressed),
+    })
+}
+
+/// Hashes a key into a registry leaf (mirrors `trusted::issuer_leaf`)
+fn registry_leaf(scheme: SignatureScheme, pubkey: &[u8]) -> String {
+    let leaf = Sha256::new()
+        .chain_update([0x00, scheme as u8])
+        .chain_update(pubkey)
+        .finalize();
+    format!("0x{}", hex::encode(leaf))
+}
+
+/// Reads the keystore password from `ISSUER_KEY_PASSWORD`
+fn password() -> Result<String> {
+    std::env::var("ISSUER_KEY_PASSWORD").context("ISSUER_KEY_PASSWORD is not set")
+}
+
+/// Generates a key, encrypts it into `dir` and saves its public forms
+fn generate(dir: &Path, name: &str, scheme: SignatureScheme, password: &str) -> Result<KeyInfo> {
+    anyhow::ensure!(
+        !dir.join(name).exists(),
+        "A key named {} already exists in {}",
+        name,
+        dir.display()
+    );
+    let secret = match scheme {
+        SignatureScheme::Secp256k1 => k256::ecdsa::SigningKey::random(&mut rand::rngs::OsRng)
+            .to_bytes()
+            .to_vec(),
+        SignatureScheme::Secp256r1 => p256::ecdsa::SigningKey::random(&mut rand::rngs::OsRng)
+            .to_bytes()
+            .to_vec(),
+        scheme => anyhow::bail!("{:?} issuer keys aren't supported", scheme),
+    };
+    let info = key_info(name, scheme, &secret)?;
+
+    std::fs::create_dir_all(dir)?;
+    eth_keystore::encrypt_key(dir, &mut rand::rngs::OsRng, &secret, password, Some(name))?;
+    std::fs::write(
+        dir.join(format!("{name}.pub.json")),
+        serde_json::to_string_pretty(&info)?,
+    )?;
+    Ok(info)
+}
+
+/// Reads the public forms of the key stored as `name`
+fn load_info(dir: &Path, name: &str) -> Result<KeyInfo> {
+    let path = dir.join(format!("{name}.pub.json"));
+    let json = std::fs::read(&path).with_context(|| format!("No key named {name}"))?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+fn print_info(info: &KeyInfo) {
+    println!("Name: {}", info.name);
+    println!("Scheme: {:?}", info.scheme);
+    println!("Public Key: {}", info.pubkey);
+    println!("Public Key (uncompressed): {}", info.pubkey_uncompressed);
+    if let Some(address) = &info.address {
+        println!("Address: {}", address);
+    }
+    println!("Registry Leaf: {}", info.registry_leaf);
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    args.log.init()?;
+
+    match args.command {
+        Command::Generate { name, scheme } => {
+            let info = generate(&args.dir, &name, scheme, &password()?)?;
+            tracing::info!(path = %args.dir.join(&name).display(), "Saved the key");
+            print_info(&info);
+        }
+        Command::List => {
+            let mut names: Vec<String> = std::fs::read_dir(&args.dir)
+                .with_context(|| format!("Can't read {}", args.dir.display()))?
+                .filter_map(|entry| {
+                    let file_name = entry.ok()?.file_name().into_string().ok()?;
+                    Some(file_name.strip_suffix(".pub.json")?.to_string())
+                })
+                .collect();
+            names.sort();
+            for name in names {
+                let info = load_info(&args.dir, &name)?;
+                let id = info.address.as_ref().unwrap_or(&info.pubkey);
+                println!(
+                    "{:<20} {:<10} {}",
+                    info.name,
+                    format!("{:?}", info.scheme),
+                    id
+                );
+            }
+        }
+        Command::Show { name } => print_info(&load_info(&args.dir, &name)?),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_key_decrypts() {
+        let dir = std::env::temp_dir().join(format!("credence-keys-{}", std::process::id()));
+        let info = generate(&dir, "issuer", SignatureScheme::Secp256k1, "password").unwrap();
+        assert_eq!(load_info(&dir, "issuer").unwrap().pubkey, info.pubkey);
+        assert!(generate(&dir, "issuer", SignatureScheme::Secp256k1, "password").is_err());
+
+        let secret = eth_keystore::decrypt_key(dir.join("issuer"), "password").unwrap();
+        let derived = key_info("issuer", SignatureScheme::Secp256k1, &secret).unwrap();
+        assert_eq!(derived.address, info.address);
+        assert!(eth_keystore::decrypt_key(dir.join("issuer"), "wrong").is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,235 @@
+//! Verifies a saved proof locally
+//! Loads the SP1 proof from the envelope the `prove` binary saved, checks
+//! it against the program's verification key (and the `vkey` binary's
+//! artifact, when given, so the proof matches the deployed key) and logs
+//! the decoded public values, before any gas is spent. A `vkey --export`
+//! artifact carries the verifying key itself, so the program isn't set up
+//! at all
+
+use std::path::PathBuf;
+
+use alloy_sol_types::SolValue;
+use anyhow::{Context, Result};
+use borsh::BorshDeserialize;
+use clap::{Parser, ValueEnum};
+use credential_verifier_lib::{
+    BatchOutputs, CredentialOutput, NativeOutputs, PolicyOutputs, OUTPUT_VERSION,
+};
+use serde::Deserialize;
+use sp1_sdk::{HashableKey, ProverClient, SP1ProofWithPublicValues, SP1VerifyingKey};
+use tracing::{info, info_span};
+
+#[path = "../logging.rs"]
+mod logging;
+
+const ELF: &[u8] = include_bytes!("../../../program/elf/riscv32im-succinct-zkvm-elf");
+
+/// Layout the proof's public values were committed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Layout {
+    /// One credential's output
+    Single,
+    /// Outputs of a batch
+    Batch,
+    /// Outputs of a policy evaluation
+    Policy,
+    /// keccak256 of the outputs (`--digest`)
+    Digest,
+    /// Borsh-encoded outputs for Solana (`--solana`)
+    Solana,
+}
+
+/// CLI arguments
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Proof envelope saved by `prove`
+    #[arg(long, default_value = "proof.json")]
+    proof: PathBuf,
+
+    /// Verification key artifact from the `vkey` binary the proof must
+    /// match (or its `--export`, verified against directly)
+    #[arg(long)]
+    vkey: Option<PathBuf>,
+
+    /// Layout of the public values
+    #[arg(long, value_enum, default_value = "single")]
+    layout: Layout,
+
+    #[command(flatten)]
+    log: logging::LogArgs,
+}
+
+/// Fields of the proof envelope this binary reads
+#[derive(Deserialize)]
+struct Envelope {
+    envelope_version: u32,
+    program_version: String,
+    mode: String,
+    started_at: u64,
+    completed_at: u64,
+    sp1_proof: SP1ProofWithPublicValues,
+}
+
+/// Verification key artifact written by the `vkey` binary
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VKeyArtifact {
+    vkey: String,
+    /// Layout version, in exports
+    output_version: Option<u32>,
+    /// The verifying key itself, in exports
+    verifying_key: Option<SP1VerifyingKey>,
+}
+
+/// Decodes public values into their outputs and policy result
+fn decode(layout: Layout, values: &[u8]) -> Result<(Vec<CredentialOutput>, Option<bool>)> {
+    let (outputs, policy_satisfied) = match layout {
+        Layout::Single => (
+            vec![CredentialOutput::abi_decode_params(values, true)?],
+            None,
+        ),
+        Layout::Batch => (BatchOutputs::abi_decode_params(values, true)?.outputs, None),
+        Layout::Policy => {
+            let policy = PolicyOutputs::abi_decode_params(values, true)?;
+            (policy.outputs, Some(policy.policySatisfied))
+        }
+        Layout::Digest => anyhow::bail!("Digest proofs commit a hash, not outputs"),
+        Layout::Solana => {
+            let native = NativeOutputs::try_from_slice(values)?;
+            anyhow::ensure!(
+                native.version == OUTPUT_VERSION,
+                "Unsupported public values version {}",
+                native.version
+            );
+            let outputs = native.outputs.iter().map(Into::into).collect();
+            (outputs, native.policy.map(|(_, satisfied)| satisfied))
+        }
+    };
+    if let Some(output) = outputs
+        .iter()
+        .find(|output| output.version != OUTPUT_VERSION)
+    {
+        anyhow::bail!("Unsupported public values version {}", output.version);
+    }
+    Ok((outputs, policy_satisfied))
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    args.log.init()?;
+
+    let json = std::fs::read(&args.proof)
+        .with_context(|| format!("Can't read {}", args.proof.display()))?;
+    let envelope: Envelope = serde_json::from_slice(&json)
+        .with_context(|| format!("{} isn't a proof envelope", args.proof.display()))?;
+    anyhow::ensure!(
+        envelope.envelope_version == 1,
+        "Unsupported envelope version {}",
+        envelope.envelope_version
+    );
+    info!(
+        program_version = envelope.program_version,
+        mode = envelope.mode,
+        proving_secs = envelope.completed_at.saturating_sub(envelope.started_at),
+        "Loaded the proof"
+    );
+    let proof = envelope.sp1_proof;
+
+    let artifact = match &args.vkey {
+        Some(path) => {
+            let json =
+                std::fs::read(path).with_context(|| format!("Can't read {}", path.display()))?;
+            let artifact: VKeyArtifact = serde_json::from_slice(&json)?;
+            Some((path, artifact))
+        }
+        None => None,
+    };
+
+    let client = ProverClient::new();
+    let exported = artifact
+        .as_ref()
+        .and_then(|(_, artifact)| artifact.verifying_key.clone());
+    let vk = match exported {
+        Some(vk) => vk,
+        None => client.setup(ELF).1,
+    };
+    info!(vkey = vk.bytes32(), "Program verifying key");
+    if let Some((path, artifact)) = &artifact {
+        anyhow::ensure!(
+            artifact.vkey == vk.bytes32(),
+            "The verifying key {} doesn't match {} in {}",
+            vk.bytes32(),
+            artifact.vkey,
+            path.display()
+        );
+        if let Some(version) = artifact.output_version {
+            anyhow::ensure!(
+                version == OUTPUT_VERSION,
+                "{} was exported for public values version {}",
+                path.display(),
+                version
+            );
+        }
+    }
+
+    info_span!("verify")
+        .in_scope(|| client.verify(&proof, &vk))
+        .context("Proof verification failed")?;
+    info!("Proof verified");
+
+    let values = proof.public_values.to_vec();
+    info!(public_values = hex::encode(&values), "Public values");
+    if args.layout == Layout::Digest {
+        return Ok(());
+    }
+    let (outputs, policy_satisfied) = decode(args.layout, &values)?;
+    if let Some(satisfied) = policy_satisfied {
+        info!(satisfied, "Policy");
+    }
+    for (index, output) in outputs.iter().enumerate() {
+        // A chain id of 0 is unbound
+        info!(
+            index,
+            version = output.version,
+            subject = %output.subject,
+            credential_type = output.credentialType,
+            credential_hash = %output.credentialHash,
+            issued_at = output.issuedAt,
+            expires_at = output.expiresAt,
+            chain_id = output.chainId,
+            verifying_contract = %output.verifyingContract,
+            as_of = output.asOf,
+            nullifier = %output.holder.nullifier,
+            predicate_results = format!("{:#b}", output.predicateResults),
+            "Output"
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decodes_by_layout() {
+        let batch = BatchOutputs {
+            version: OUTPUT_VERSION,
+            outputs: vec![CredentialOutput {
+                version: OUTPUT_VERSION,
+                chainId: 1,
+                ..Default::default()
+            }],
+        };
+        let values = batch.abi_encode_params();
+        let (outputs, policy_satisfied) = decode(Layout::Batch, &values).unwrap();
+        assert_eq!(outputs, batch.outputs);
+        assert_eq!(policy_satisfied, None);
+
+        // Outputs of older layouts aren't printed as current ones
+        let mut stale = batch;
+        stale.outputs[0].version = OUTPUT_VERSION - 1;
+        assert!(decode(Layout::Batch, &stale.abi_encode_params()).is_err());
+    }
+}
@@ -0,0 +1,188 @@
+//! Prints the program verification key hash
+//! The bytes32 hash is what the on-chain SP1VerifierGateway and our
+//! verifier contracts are configured with; it is also written as JSON for
+//! deploy scripts, and optionally as a Solidity library with the public
+//! value layouts and credential types so contracts can't drift from the
+//! circuit. `--export` also writes the full verifying key, so verification
+//! services can check proofs without the ELF
+
+use std::path::PathBuf;
+
+use alloy_sol_types::SolStruct;
+use anyhow::Result;
+use clap::Parser;
+use credential_verifier_lib::{
+    BatchOutputs, CredentialOutput, PolicyOutputs, CREDENTIAL_TYPES, CREDENTIAL_TYPE_BITMASK,
+    OUTPUT_VERSION,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sp1_sdk::{HashableKey, ProverClient, SP1VerifyingKey};
+
+#[path = "../logging.rs"]
+mod logging;
+
+const ELF: &[u8] = include_bytes!("../../../program/elf/riscv32im-succinct-zkvm-elf");
+
+/// CLI arguments
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Output path for the vkey artifact
+    #[arg(short, long, default_value = "vkey.json")]
+    output: String,
+
+    /// Also generate the Solidity library at this path
+    #[arg(long)]
+    solidity: Option<PathBuf>,
+
+    /// Also export the full verifying key, with the ELF hash and program
+    /// version, to this path
+    #[arg(long)]
+    export: Option<PathBuf>,
+
+    #[command(flatten)]
+    log: logging::LogArgs,
+}
+
+/// Verification key artifact read by deploy scripts
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VKeyArtifact {
+    /// Hash of the program verification key (bytes32, hex encoded)
+    vkey: String,
+}
+
+/// Verifying key export for verification services without the ELF
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VKeyExport {
+    /// Hash of the program verification key (bytes32, hex encoded)
+    vkey: String,
+    /// SHA-256 of the program ELF the key was set up from (hex encoded)
+    elf_hash: String,
+    /// Version of the program crate (versioned with this crate)
+    program_version: String,
+    /// Layout version of the public values the program commits
+    output_version: u32,
+    /// The SP1 verifying key itself
+    verifying_key: SP1VerifyingKey,
+}
+
+/// Turns an EIP-712 type such as `Output(uint32 version,address subject)`
+/// into a Solidity struct declaration
+fn solidity_struct(eip712_type: &str) -> String {
+    let (name, fields) = eip712_type
+        .trim_end_matches(')')
+        .split_once('(')
+        .expect("EIP-712 types have a field list");
+    let mut declaration = format!("    struct {name} {{\n");
+    for field in fields.split(',') {
+        declaration.push_str(&format!("        {field};\n"));
+    }
+    declaration.push_str("    }\n");
+    declaration
+}
+
+/// Renders the `CredenceTypes` library from the Rust definitions
+fn solidity_library(vkey: &str) -> String {
+    let mut library = String::from(
+        "// SPDX-License-Identifier: MIT\n\
+         // Generated by `cargo run --bin vkey -- --solidity <path>`. Do not edit.\n\
+         pragma solidity ^0.8.24;\n\n\
+         /**\n \
+         * @title CredenceTypes\n \
+         * @notice Program vkey, public value layouts and credential types of the\n \
+         * Credence circuit\n \
+         * @dev A single credential's values are `abi.encode(version, subject, ...)`,\n \
+         * its fields as parameters rather than one `CredentialOutput` tuple\n \
+         */\n\
+         library CredenceTypes {\n",
+    );
+    library.push_str(&format!(
+        "    bytes32 internal constant PROGRAM_VKEY = {vkey};\n\n"
+    ));
+    library.push_str(&format!(
+        "    uint32 internal constant OUTPUT_VERSION = {OUTPUT_VERSION};\n\n"
+    ));
+    library.push_str(&format!(
+        "    uint32 internal constant CREDENTIAL_TYPE_BITMASK = {CREDENTIAL_TYPE_BITMASK:#010x};\n"
+    ));
+    for (name, credential_type) in CREDENTIAL_TYPES {
+        library.push_str(&format!(
+            "    uint32 internal constant CREDENTIAL_TYPE_{} = {credential_type};\n",
+            name.to_uppercase()
+        ));
+    }
+
+    let mut components = CredentialOutput::eip712_components();
+    components.sort_unstable();
+    components.dedup();
+    let structs = std::iter::once(CredentialOutput::eip712_root_type())
+        .chain(components)
+        .chain([
+            BatchOutputs::eip712_root_type(),
+            PolicyOutputs::eip712_root_type(),
+        ]);
+    for eip712_type in structs {
+        library.push('\n');
+        library.push_str(&solidity_struct(&eip712_type));
+    }
+    library.push_str("}\n");
+    library
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    args.log.init()?;
+
+    let client = ProverClient::new();
+    let (_, vk) = client.setup(ELF);
+    let artifact = VKeyArtifact { vkey: vk.bytes32() };
+    std::fs::write(&args.output, serde_json::to_string_pretty(&artifact)?)?;
+
+    if let Some(path) = &args.solidity {
+        std::fs::write(path, solidity_library(&artifact.vkey))?;
+    }
+
+    if let Some(path) = &args.export {
+        let export = VKeyExport {
+            vkey: artifact.vkey.clone(),
+            elf_hash: format!("0x{}", hex::encode(Sha256::digest(ELF))),
+            program_version: env!("CARGO_PKG_VERSION").to_string(),
+            output_version: OUTPUT_VERSION,
+            verifying_key: vk,
+        };
+        std::fs::write(path, serde_json::to_string_pretty(&export)?)?;
+    }
+
+    println!("{}", artifact.vkey);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solidity_library_declares_every_struct() {
+        assert_eq!(
+            solidity_struct("Output(uint32 version,bytes32[] claims)"),
+            "    struct Output {\n        uint32 version;\n        bytes32[] claims;\n    }\n"
+        );
+
+        let library = solidity_library("0x01");
+        for name in [
+            "CredentialOutput",
+            "IssuerOutput",
+            "HolderOutput",
+            "StatusOutput",
+            "BatchOutputs",
+            "PolicyOutputs",
+        ] {
+            assert_eq!(library.matches(&format!("struct {name} {{")).count(), 1);
+        }
+        assert!(library.contains("CREDENTIAL_TYPE_KYC = 1;"));
+        assert!(library.contains("CREDENTIAL_TYPE_BITMASK = 0x80000000;"));
+    }
+}
@@ -0,0 +1,156 @@
+//! RSA blind signing helpers for building test credentials
+//!
+//! Follows RFC 9474 (RSABSSA, randomized PSS variant) with SHA-256 in place
+//! of SHA-384, matching `rsa::verify_blind` in the program. The holder
+//! prepares and blinds the message, the issuer signs the blinded value
+//! without learning the message, and the holder unblinds the result.
+
+use num_bigint_dig::ModInverse;
+use rand::{CryptoRng, RngCore};
+use rsa::traits::{PrivateKeyParts, PublicKeyParts};
+use rsa::{BigUint, RsaPrivateKey, RsaPublicKey};
+use sha2::{Digest, Sha256};
+
+/// Size of the modulus and signature in bytes
+const MODULUS_BYTES: usize = 256;
+
+/// Length of a SHA-256 digest (also the PSS salt length)
+const HASH_LEN: usize = 32;
+
+/// Length of the message randomizer, must match the program
+pub const RANDOMIZER_BYTES: usize = 32;
+
+/// Holder-side state needed to unblind the issuer's response
+pub struct BlindingState {
+    /// Randomizer prefixed to the message before signing
+    pub randomizer: [u8; RANDOMIZER_BYTES],
+    /// Inverse of the blinding factor modulo `n`
+    inverse: BigUint,
+}
+
+/// MGF1 mask generation with SHA-256
+fn mgf1_sha256(seed: &[u8], len: usize) -> Vec<u8> {
+    let mut mask = Vec::with_capacity(len + HASH_LEN);
+    let mut counter: u32 = 0;
+    while mask.len() < len {
+        let mut hasher = Sha256::new();
+        hasher.update(seed);
+        hasher.update(counter.to_be_bytes());
+        mask.extend_from_slice(&hasher.finalize());
+        counter += 1;
+    }
+    mask.truncate(len);
+    mask
+}
+
+/// EMSA-PSS encoding for a 2048-bit modulus (emBits = 2047)
+fn pss_encode(message: &[u8], rng: &mut impl RngCore) -> Vec<u8> {
+    let mut salt = [0u8; HASH_LEN];
+    rng.fill_bytes(&mut salt);
+
+    let mut hasher = Sha256::new();
+    hasher.update([0u8; 8]);
+    hasher.update(Sha256::digest(message));
+    hasher.update(salt);
+    let h = hasher.finalize();
+
+    // DB = PS (zeros) || 0x01 || salt
+    let db_len = MODULUS_BYTES - HASH_LEN - 1;
+    let mut db = vec![0u8; db_len];
+    db[db_len - HASH_LEN - 1] = 0x01;
+    db[db_len - HASH_LEN..].copy_from_slice(&salt);
+    for (byte, mask) in db.iter_mut().zip(mgf1_sha256(&h, db_len)) {
+        *byte ^= mask;
+    }
+    db[0] &= 0x7f;
+
+    let mut encoded = db;
+    encoded.extend_from_slice(&h);
+    encoded.push(0xbc);
+    encoded
+}
+
+/// Prepares and blinds `message` for the issuer (holder side)
+///
+/// Returns the blinded value to send to the issuer and the state needed
+/// to finalize the signature.
+pub fn blind(
+    public_key: &RsaPublicKey,
+    message: &[u8],
+    rng: &mut (impl RngCore + CryptoRng),
+) -> (BigUint, BlindingState) {
+    let n = public_key.n();
+
+    let mut randomizer = [0u8; RANDOMIZER_BYTES];
+    rng.fill_bytes(&mut randomizer);
+    let mut prepared = randomizer.to_vec();
+    prepared.extend_from_slice(message);
+    let m = BigUint::from_bytes_be(&pss_encode(&prepared, rng));
+
+    // Sample blinding factors until one is invertible modulo n
+    let (r, inverse) = loop {
+        let mut bytes = [0u8; MODULUS_BYTES];
+        rng.fill_bytes(&mut bytes);
+        let r = BigUint::from_bytes_be(&bytes) % n;
+        if let Some(inverse) = r.clone().mod_inverse(n).and_then(|inv| inv.to_biguint()) {
+            break (r, inverse);
+        }
+    };
+
+    let blinded = (m * r.modpow(public_key.e(), n)) % n;
+    (
+        blinded,
+        BlindingState {
+            randomizer,
+            inverse,
+        },
+    )
+}
+
+/// Signs a blinded value with the raw RSA private operation (issuer side)
+pub fn blind_sign(private_key: &RsaPrivateKey, blinded: &BigUint) -> BigUint {
+    blinded.modpow(private_key.d(), private_key.n())
+}
+
+/// Unblinds the issuer's response (holder side)
+///
+/// Returns `randomizer || signature`, the form the program verifies.
+pub fn finalize(
+    public_key: &RsaPublicKey,
+    blind_signature: &BigUint,
+    state: &BlindingState,
+) -> Vec<u8> {
+    let signature = (blind_signature * &state.inverse) % public_key.n();
+    let signature = signature.to_bytes_be();
+
+    // Left-pad the signature to the modulus size
+    let mut output = state.randomizer.to_vec();
+    output.resize(RANDOMIZER_BYTES + MODULUS_BYTES - signature.len(), 0);
+    output.extend_from_slice(&signature);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa::signature::Verifier;
+
+    #[test]
+    fn test_unblinded_signature_verifies_as_pss() {
+        let mut rng = rand::thread_rng();
+        let private_key = RsaPrivateKey::new(&mut rng, 2048).unwrap();
+        let public_key = private_key.to_public_key();
+        let message = b"credence blind credential";
+
+        let (blinded, state) = blind(&public_key, message, &mut rng);
+        let signature = finalize(&public_key, &blind_sign(&private_key, &blinded), &state);
+
+        let (randomizer, signature) = signature.split_at(RANDOMIZER_BYTES);
+        let mut prepared = randomizer.to_vec();
+        prepared.extend_from_slice(message);
+        let signature = rsa::pss::Signature::try_from(signature).unwrap();
+        assert!(rsa::pss::VerifyingKey::<Sha256>::new(public_key)
+            .verify(&prepared, &signature)
+            .is_ok());
+    }
+}
@@ -0,0 +1,54 @@
+//! BLS12-381 signing helpers for building test credentials
+//!
+//! Mirrors the verifier in the program: min-pk keys (48-byte G1 pubkeys),
+//! 96-byte G2 signatures, and the basic-scheme ciphersuite tag.
+
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_381::{G1Affine, G2Affine, G2Projective, Scalar};
+
+/// Ciphersuite tag, must match `BLS_DST` in the program
+pub const BLS_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// Derives a deterministic secret key from a 64-byte seed
+pub fn secret_key_from_seed(seed: &[u8; 64]) -> Scalar {
+    Scalar::from_bytes_wide(seed)
+}
+
+/// Returns the compressed G1 public key for `secret_key`
+pub fn public_key(secret_key: &Scalar) -> [u8; 48] {
+    G1Affine::from(G1Affine::generator() * secret_key).to_compressed()
+}
+
+/// Signs `message`, returning the compressed G2 signature
+pub fn sign(secret_key: &Scalar, message: &[u8]) -> [u8; 96] {
+    let hashed =
+        <G2Projective as HashToCurve<ExpandMsgXmd<sha2_v0_9::Sha256>>>::hash_to_curve(
+            message, BLS_DST,
+        );
+    G2Affine::from(hashed * secret_key).to_compressed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bls12_381::pairing;
+
+    #[test]
+    fn test_sign_verifies_under_pairing() {
+        let secret_key = secret_key_from_seed(&[7u8; 64]);
+        let message = b"credence test credential";
+
+        let pubkey = G1Affine::from_compressed(&public_key(&secret_key)).unwrap();
+        let signature = G2Affine::from_compressed(&sign(&secret_key, message)).unwrap();
+        let hashed = G2Affine::from(
+            <G2Projective as HashToCurve<ExpandMsgXmd<sha2_v0_9::Sha256>>>::hash_to_curve(
+                message, BLS_DST,
+            ),
+        );
+
+        assert_eq!(
+            pairing(&pubkey, &hashed),
+            pairing(&G1Affine::generator(), &signature)
+        );
+    }
+}
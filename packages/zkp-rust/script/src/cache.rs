@@ -0,0 +1,163 @@
+//! Proof cache
+//!
+//! Holders often prove the same credential again within its validity
+//! window. With `--cache-dir`, proofs of single credentials are cached
+//! there and repeat requests get the cached proof instead of a new one.
+//! An entry is keyed by the credential's hash, every binding the proof
+//! commits to (the verifier nonce, chain, contract, predicates, status
+//! witnesses...), the program's vkey and the proof mode: everything in the
+//! program input but the time it runs at, unless a check besides the
+//! credential's own expiry depends on it. Predicates, key registries,
+//! revocation freshness, delegation chains and certificate chains are all
+//! evaluated as of that time, so their proofs are only reused at the same
+//! time. Otherwise a cached proof keeps the as-of time it was proved at,
+//! which contracts bound with their max proof age. Entries of expired
+//! credentials are dropped when looked up.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{CredentialInput, ProgramInput, ProofMode, ProofOutput};
+
+/// Cached proof, saved as `<key>.json` (borrowing the envelope to save it)
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry<E = ProofOutput> {
+    /// Hash of the credential, as the proof commits it (hex encoded)
+    credential_hash: String,
+    vkey: String,
+    mode: ProofMode,
+    /// Verifier nonce the proof is bound to (hex encoded)
+    nonce: String,
+    /// When the credential expires (0 if never)
+    expires_at: u64,
+    envelope: E,
+}
+
+/// Whether the proof checks more than the credential's validity window as
+/// of its `current_time`
+fn checked_as_of(credential: &CredentialInput) -> bool {
+    !credential.predicates.is_empty()
+        || credential.key_registry.is_some()
+        || credential.revocation_freshness.is_some()
+        || credential.delegation.is_some()
+        || !credential.issuer_cert_chain.is_empty()
+}
+
+/// Directory of cached proofs
+pub struct ProofCache {
+    dir: PathBuf,
+}
+
+impl ProofCache {
+    pub fn open(dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir).with_context(|| format!("Can't create {}", dir.display()))?;
+        Ok(ProofCache {
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    /// Key of a bound credential's proof
+    pub fn key(vkey: &str, mode: ProofMode, credential: &CredentialInput) -> String {
+        let mut credential = credential.clone();
+        if !checked_as_of(&credential) {
+            credential.current_time = 0;
+        }
+        let input = credential_verifier_lib::encode(&ProgramInput::Single(Box::new(credential)));
+        let mut hasher = Sha256::new();
+        hasher.update(vkey.as_bytes());
+        hasher.update(format!("{:?}", mode).as_bytes());
+        hasher.update(&input);
+        hex::encode(hasher.finalize())
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+
+    /// Cached proof under `key`, unless the credential expired by `now`
+    pub fn get(&self, key: &str, now: u64) -> Option<ProofOutput> {
+        let path = self.path(key);
+        let json = std::fs::read(&path).ok()?;
+        let entry: CacheEntry = serde_json::from_slice(&json).ok()?;
+        if entry.expires_at != 0 && now > entry.expires_at {
+            let _ = std::fs::remove_file(path);
+            return None;
+        }
+        Some(entry.envelope)
+    }
+
+    /// Caches the proof of a bound credential under `key`
+    pub fn put(
+        &self,
+        key: &str,
+        credential: &CredentialInput,
+        envelope: &ProofOutput,
+    ) -> Result<()> {
+        let entry = CacheEntry {
+            credential_hash: envelope.credential_hash.clone(),
+            vkey: envelope.vkey.clone(),
+            mode: envelope.mode,
+            nonce: format!("0x{}", hex::encode(credential.verifier_nonce)),
+            expires_at: credential.expires_at,
+            envelope,
+        };
+        let path = self.path(key);
+        let partial = path.with_extension("json.partial");
+        std::fs::write(&partial, serde_json::to_string(&entry)?)?;
+        std::fs::rename(&partial, &path).with_context(|| format!("Can't write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_ignore_the_time_only_without_time_checks() {
+        let mut credential = crate::create_sample_credential(
+            "0x1234567890123456789012345678901234567890",
+            1,
+            crate::SignatureScheme::Secp256k1,
+            crate::HashAlgorithm::Sha256,
+            crate::CredentialFormat::Binary,
+            crate::ClaimsVersion::V2,
+            crate::HolderBindings::default(),
+        )
+        .unwrap();
+        let key = ProofCache::key("0xab", ProofMode::Groth16, &credential);
+        credential.current_time += 60;
+        assert_eq!(
+            ProofCache::key("0xab", ProofMode::Groth16, &credential),
+            key
+        );
+        assert_ne!(ProofCache::key("0xab", ProofMode::Plonk, &credential), key);
+        assert_ne!(
+            ProofCache::key("0xcd", ProofMode::Groth16, &credential),
+            key
+        );
+        credential.verifier_nonce[0] ^= 1;
+        assert_ne!(
+            ProofCache::key("0xab", ProofMode::Groth16, &credential),
+            key
+        );
+
+        // A predicate proved yesterday may not hold today
+        credential
+            .predicates
+            .push(crate::Predicate::IssuedAtLeastDaysAgo(30));
+        let key = ProofCache::key("0xab", ProofMode::Groth16, &credential);
+        credential.current_time += 60;
+        assert_ne!(
+            ProofCache::key("0xab", ProofMode::Groth16, &credential),
+            key
+        );
+
+        let dir = std::env::temp_dir().join(format!("credence-cache-{}", std::process::id()));
+        let cache = ProofCache::open(&dir).unwrap();
+        assert!(cache.get(&key, 0).is_none());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
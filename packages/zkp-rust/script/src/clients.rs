@@ -0,0 +1,211 @@
+//! API keys of the proving services
+//!
+//! With `--api-keys <file>`, the REST API and gRPC service only take
+//! requests carrying a client's key, as `Authorization: Bearer <key>` (a
+//! header, or gRPC metadata). The file is a JSON array of clients:
+//!
+//! ```json
+//! [{ "name": "acme", "key": "…", "requests_per_minute": 60, "max_proofs": 2 }]
+//! ```
+//!
+//! so one integrator can't monopolize the prover. `requests_per_minute`
+//! limits how often a client calls the services (in bursts of up to that
+//! many), and `max_proofs` how many of its jobs are executed and proved at
+//! once; its other jobs stay queued meanwhile. Either can be left out for
+//! no limit.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// A client as the API keys file lists it
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ClientConfig {
+    name: String,
+    key: String,
+    requests_per_minute: Option<u32>,
+    max_proofs: Option<usize>,
+}
+
+/// A client of the proving services and what it has left of its limits
+pub struct Client {
+    pub name: String,
+    requests_per_minute: Option<u32>,
+    /// Requests the client can still make, and when that was counted
+    allowance: Mutex<(f64, Instant)>,
+    /// One permit per job of the client's executed or proved at once
+    provers: Option<Semaphore>,
+}
+
+impl Client {
+    /// Counts a request made at `now`, or says how long until the client
+    /// can make one
+    fn request(&self, now: Instant) -> Result<(), Duration> {
+        let Some(per_minute) = self.requests_per_minute else {
+            return Ok(());
+        };
+        let per_second = f64::from(per_minute) / 60.0;
+        let mut allowance = self.allowance.lock().expect("Limits aren't poisoned");
+        let (left, counted) = *allowance;
+        let left = (left + now.saturating_duration_since(counted).as_secs_f64() * per_second)
+            .min(f64::from(per_minute));
+        if left < 1.0 {
+            *allowance = (left, now);
+            return Err(Duration::from_secs_f64((1.0 - left) / per_second));
+        }
+        *allowance = (left - 1.0, now);
+        Ok(())
+    }
+
+    /// Waits until another of the client's jobs may run, if it's limited
+    pub async fn acquire(&self) -> Option<SemaphorePermit<'_>> {
+        let provers = self.provers.as_ref()?;
+        Some(provers.acquire().await.expect("The semaphore isn't closed"))
+    }
+}
+
+/// Why a request was refused
+#[derive(Debug, PartialEq)]
+pub enum Refusal {
+    NoKey,
+    UnknownKey,
+    /// The client made too many requests, and can retry after a while
+    RateLimited {
+        retry_after: Duration,
+    },
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::NoKey => write!(f, "An API key is required"),
+            Refusal::UnknownKey => write!(f, "Unknown API key"),
+            Refusal::RateLimited { retry_after } => write!(
+                f,
+                "Too many requests, retry in {} s",
+                retry_after.as_secs_f64().ceil()
+            ),
+        }
+    }
+}
+
+/// Clients of `--api-keys`
+pub struct Clients {
+    clients: Vec<Arc<Client>>,
+    /// Index of each key's client
+    keys: HashMap<String, usize>,
+}
+
+impl Clients {
+    /// Loads the clients listed in the API keys file at `path`
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read(path).with_context(|| format!("Can't read {}", path.display()))?;
+        let configs: Vec<ClientConfig> = serde_json::from_slice(&json)
+            .with_context(|| format!("{} isn't a list of API clients", path.display()))?;
+        let mut clients = Vec::new();
+        let mut keys = HashMap::new();
+        for config in configs {
+            anyhow::ensure!(!config.key.is_empty(), "Client {} has no key", config.name);
+            anyhow::ensure!(
+                config.requests_per_minute != Some(0) && config.max_proofs != Some(0),
+                "Client {}'s limits must be positive",
+                config.name
+            );
+            anyhow::ensure!(
+                clients
+                    .iter()
+                    .all(|client: &Arc<Client>| client.name != config.name),
+                "Client {} is listed twice",
+                config.name
+            );
+            anyhow::ensure!(
+                keys.insert(config.key, clients.len()).is_none(),
+                "Client {}'s key is already another client's",
+                config.name
+            );
+            let burst = config.requests_per_minute.map_or(0.0, f64::from);
+            clients.push(Arc::new(Client {
+                name: config.name,
+                requests_per_minute: config.requests_per_minute,
+                allowance: Mutex::new((burst, Instant::now())),
+                provers: config.max_proofs.map(Semaphore::new),
+            }));
+        }
+        Ok(Clients { clients, keys })
+    }
+
+    /// Client making a request with this `Authorization` value, if it's
+    /// within its rate limit
+    pub fn authorize(&self, authorization: Option<&str>) -> Result<Arc<Client>, Refusal> {
+        let key = authorization
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+            .ok_or(Refusal::NoKey)?;
+        let client = self.keys.get(key).ok_or(Refusal::UnknownKey)?;
+        let client = &self.clients[*client];
+        client
+            .request(Instant::now())
+            .map_err(|retry_after| Refusal::RateLimited { retry_after })?;
+        Ok(client.clone())
+    }
+
+    /// Client named `name`
+    pub fn get(&self, name: &str) -> Option<Arc<Client>> {
+        self.clients
+            .iter()
+            .find(|client| client.name == name)
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorizes_clients_within_their_limits() {
+        let path =
+            std::env::temp_dir().join(format!("credence-clients-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"[
+                { "name": "acme", "key": "acme-key", "requests_per_minute": 2, "max_proofs": 1 },
+                { "name": "internal", "key": "internal-key" }
+            ]"#,
+        )
+        .unwrap();
+        let clients = Clients::load(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(clients.authorize(None).err(), Some(Refusal::NoKey));
+        assert_eq!(
+            clients.authorize(Some("Bearer other")).err(),
+            Some(Refusal::UnknownKey)
+        );
+        for _ in 0..2 {
+            assert_eq!(
+                clients.authorize(Some("Bearer acme-key")).unwrap().name,
+                "acme"
+            );
+        }
+        assert!(matches!(
+            clients.authorize(Some("Bearer acme-key")),
+            Err(Refusal::RateLimited { .. })
+        ));
+        // Half a minute later, one more request is allowed
+        let acme = clients.get("acme").unwrap();
+        let later = Instant::now() + Duration::from_secs(30);
+        assert!(acme.request(later).is_ok());
+        assert!(acme.request(later).is_err());
+        for _ in 0..10 {
+            assert!(clients.authorize(Some("Bearer internal-key")).is_ok());
+        }
+    }
+}
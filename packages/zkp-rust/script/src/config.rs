@@ -0,0 +1,260 @@
+//! Settings of `credence.toml`
+//!
+//! The `prove` and `issue` binaries read settings from the TOML file of
+//! `--config`, or `credence.toml` in the working directory if it exists.
+//! Each key stands in for a flag (`[prover] mode` for `--mode`), so its
+//! value is parsed and checked as the flag's would be; flags given on the
+//! command line override the file, along with the keys of flags they
+//! conflict with. Paths are relative to the working directory.
+//!
+//! ```toml
+//! [prover]
+//! backend = "network"
+//! mode = "groth16"
+//! network_timeout = 600
+//! jobs = 8
+//!
+//! [trust]
+//! issuers = ["0x02a1b2..."]
+//!
+//! [rpc]
+//! url = "https://rpc.sepolia.mantle.xyz"
+//!
+//! [output]
+//! dir = "proofs"
+//! cache_dir = "cache"
+//!
+//! [issuer]
+//! keystore = "issuer.json"
+//! chain_id = 5003
+//! output = "credential.json"
+//!
+//! [log]
+//! format = "json"
+//! level = "info"
+//! ```
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Command, Parser};
+
+/// File read when `--config` isn't given
+pub const DEFAULT_PATH: &str = "credence.toml";
+
+/// Tables of the file, each read by the binaries with settings in it
+const TABLES: [&str; 6] = ["prover", "trust", "rpc", "output", "issuer", "log"];
+
+/// A key of the file and the id of the flag it stands in for
+pub struct Setting {
+    pub table: &'static str,
+    pub key: &'static str,
+    pub arg: &'static str,
+}
+
+pub const fn setting(table: &'static str, key: &'static str, arg: &'static str) -> Setting {
+    Setting { table, key, arg }
+}
+
+/// Parses the command line, adding the file's values of `settings` whose
+/// flags it doesn't give
+pub fn parse<T: Parser>(settings: &[Setting]) -> Result<T> {
+    parse_from(std::env::args_os().collect(), settings)
+}
+
+pub fn parse_from<T: Parser>(mut args: Vec<OsString>, settings: &[Setting]) -> Result<T> {
+    let mut command = T::command();
+    command.build();
+    // Which flags are given, before the file adds any
+    let Ok(given) = command
+        .clone()
+        .ignore_errors(true)
+        .try_get_matches_from(&args)
+    else {
+        // --help and --version
+        return Ok(T::parse_from(args));
+    };
+    let path = given.get_one::<PathBuf>("config").cloned();
+    let file = match path {
+        Some(path) => Some(read(&path)?),
+        None => {
+            let path = Path::new(DEFAULT_PATH);
+            path.exists().then(|| read(path)).transpose()?
+        }
+    };
+    if let Some((path, table)) = file {
+        let flags = flags(&command, &given, &table, settings)
+            .with_context(|| format!("Invalid config file {}", path.display()))?;
+        args.extend(flags);
+    }
+    Ok(T::parse_from(args))
+}
+
+/// Reads a config file
+fn read(path: &Path) -> Result<(PathBuf, toml::Table)> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Can't read config file {}", path.display()))?;
+    let table =
+        toml::from_str(&text).with_context(|| format!("Invalid config file {}", path.display()))?;
+    Ok((path.to_path_buf(), table))
+}
+
+/// Whether the command line gives `arg`
+fn is_given(given: &ArgMatches, arg: &str) -> bool {
+    given.value_source(arg) == Some(ValueSource::CommandLine)
+}
+
+/// Flags of the file's settings that the command line neither gives nor
+/// conflicts with
+fn flags(
+    command: &Command,
+    given: &ArgMatches,
+    file: &toml::Table,
+    settings: &[Setting],
+) -> Result<Vec<OsString>> {
+    for (name, table) in file {
+        anyhow::ensure!(TABLES.contains(&name.as_str()), "Unknown table [{name}]");
+        let table = table
+            .as_table()
+            .with_context(|| format!("{name} isn't a table"))?;
+        // Tables only other binaries read are theirs to check
+        if !settings.iter().any(|setting| setting.table == name) {
+            continue;
+        }
+        for key in table.keys() {
+            anyhow::ensure!(
+                settings
+                    .iter()
+                    .any(|setting| setting.table == name && setting.key == key),
+                "Unknown setting {name}.{key}"
+            );
+        }
+    }
+
+    let mut flags = Vec::new();
+    for setting in settings {
+        let Some(value) = file
+            .get(setting.table)
+            .and_then(|table| table.get(setting.key))
+        else {
+            continue;
+        };
+        let arg = command
+            .get_arguments()
+            .find(|arg| arg.get_id() == setting.arg)
+            .expect("Settings name flags of the command");
+        let overridden = is_given(given, setting.arg)
+            || command
+                .get_arguments()
+                .filter(|other| is_given(given, other.get_id().as_str()))
+                .any(|other| {
+                    command.get_arg_conflicts_with(arg).contains(&other)
+                        || command.get_arg_conflicts_with(other).contains(&arg)
+                });
+        if overridden {
+            continue;
+        }
+        let long = arg.get_long().expect("Settings name long flags");
+        let values = match value {
+            toml::Value::Array(values) => values.iter().collect(),
+            value => vec![value],
+        };
+        for value in values {
+            let value = match value {
+                toml::Value::String(text) => text.clone(),
+                toml::Value::Integer(number) => number.to_string(),
+                toml::Value::Float(number) => number.to_string(),
+                toml::Value::Boolean(true) => {
+                    flags.push(format!("--{long}").into());
+                    continue;
+                }
+                toml::Value::Boolean(false) => continue,
+                _ => anyhow::bail!("{}.{} isn't a value", setting.table, setting.key),
+            };
+            flags.push(format!("--{long}={value}").into());
+        }
+    }
+    Ok(flags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Parser, Debug)]
+    struct Args {
+        #[arg(long)]
+        config: Option<PathBuf>,
+        #[arg(long, default_value = "plonk")]
+        mode: String,
+        #[arg(long, default_value = "4")]
+        jobs: usize,
+        #[arg(long, default_value = "proof.json")]
+        output: String,
+        #[arg(long, conflicts_with = "output")]
+        output_dir: Option<PathBuf>,
+        #[arg(long)]
+        trusted_issuer: Vec<String>,
+        #[arg(long)]
+        no_fallback: bool,
+    }
+
+    const SETTINGS: &[Setting] = &[
+        setting("prover", "mode", "mode"),
+        setting("prover", "jobs", "jobs"),
+        setting("prover", "no_fallback", "no_fallback"),
+        setting("trust", "issuers", "trusted_issuer"),
+        setting("output", "dir", "output_dir"),
+    ];
+
+    #[test]
+    fn test_flags_override_the_file() {
+        let path =
+            std::env::temp_dir().join(format!("credence-config-{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"
+                [prover]
+                mode = "groth16"
+                jobs = 8
+                no_fallback = true
+
+                [trust]
+                issuers = ["0x02aa", "0x03bb"]
+
+                [output]
+                dir = "proofs"
+
+                [issuer]
+                key = "read by another binary"
+            "#,
+        )
+        .unwrap();
+        let parse = |flags: &[&str]| {
+            let mut args: Vec<OsString> =
+                vec!["prove".into(), "--config".into(), path.clone().into()];
+            args.extend(flags.iter().map(OsString::from));
+            parse_from::<Args>(args, SETTINGS)
+        };
+
+        let args = parse(&[]).unwrap();
+        assert_eq!(args.mode, "groth16");
+        assert_eq!(args.jobs, 8);
+        assert!(args.no_fallback);
+        assert_eq!(args.trusted_issuer, ["0x02aa", "0x03bb"]);
+        assert_eq!(args.output_dir, Some(PathBuf::from("proofs")));
+
+        // Flags win, and drop the settings they conflict with
+        let args = parse(&["--mode", "core", "--output", "out.json"]).unwrap();
+        assert_eq!(args.mode, "core");
+        assert_eq!(args.output, "out.json");
+        assert_eq!(args.output_dir, None);
+
+        std::fs::write(&path, "[prover]\nmodes = \"core\"\n").unwrap();
+        let error = parse(&[]).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(format!("{:#}", error).contains("Unknown setting prover.modes"));
+    }
+}
@@ -0,0 +1,191 @@
+//! Proof envelopes for CosmWasm verifier contracts
+//!
+//! CosmWasm contracts take JSON execute messages with binary fields
+//! base64-encoded, so the proof is wrapped as
+//!
+//! ```text
+//! {"verify_credential": {"proof": ..., "public_values": ..., ...}}
+//! ```
+//!
+//! with the decoded outputs alongside in the proto3 JSON mapping: bytes as
+//! standard base64 and 64-bit integers as decimal strings, so app-chains
+//! can declare the same fields as a protobuf message.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use credential_verifier_lib::CredentialOutput;
+use serde::{Deserialize, Serialize};
+
+/// Execute message verifying a proof
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    VerifyCredential(ProofEnvelope),
+}
+
+/// Proof, public values and decoded outputs for a CosmWasm verifier
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProofEnvelope {
+    /// Groth16 proof bytes, verifier selector included (base64)
+    pub proof: String,
+    /// Committed public values (base64)
+    pub public_values: String,
+    /// Hash of the program verification key (bytes32, hex encoded)
+    pub vkey: String,
+    /// One output per credential, in input order
+    pub outputs: Vec<CosmosOutput>,
+    /// Whether the credentials satisfied the policy (absent without one)
+    pub policy_satisfied: Option<bool>,
+}
+
+/// A `CredentialOutput` flattened into proto3 JSON
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CosmosOutput {
+    pub version: u32,
+    pub subject: String,
+    pub credential_type: u32,
+    pub credential_hash: String,
+    pub issued_at: String,
+    pub expires_at: String,
+    pub chain_id: String,
+    pub verifying_contract: String,
+    pub as_of: String,
+    pub hash_suite: u32,
+    pub timestamp_granularity: u32,
+    pub expiry_epoch: String,
+    pub credential_hash_blind: String,
+    pub disclosure_hash: String,
+    pub schema_hash: String,
+    pub predicates_hash: String,
+    pub predicate_results: u32,
+    pub withheld_claims_root: String,
+    pub issuer_address: String,
+    pub quorum_hash: String,
+    pub hmac_key_commitment: String,
+    pub root_ca_hash: String,
+    pub vrf_output_hash: String,
+    pub root_issuer_hash: String,
+    pub key_registry_version: u32,
+    pub trusted_issuers_root: String,
+    pub issuer_registry_root: String,
+    pub nullifier_scope: String,
+    pub nullifier: String,
+    pub verifier_nonce: String,
+    pub subject_commitment: String,
+    pub pseudonym: String,
+    pub ring_root: String,
+    pub ring_event: String,
+    pub ring_tag: String,
+    pub holder_bound: bool,
+    pub device_rp_id_hash: String,
+    pub biometric_bound: bool,
+    pub sanctions_root: String,
+    pub sanctions_list_version: u32,
+    pub serial_hash: String,
+    pub revocation_method: u32,
+    pub revocation_root: String,
+    pub revocation_version: u32,
+    pub revocation_max_age: String,
+    pub status_purpose: u32,
+    pub revealed_claims: Vec<String>,
+}
+
+impl From<&CredentialOutput> for CosmosOutput {
+    fn from(output: &CredentialOutput) -> Self {
+        let (issuer, holder, status) = (&output.issuer, &output.holder, &output.status);
+        CosmosOutput {
+            version: output.version,
+            subject: STANDARD.encode(output.subject),
+            credential_type: output.credentialType,
+            credential_hash: STANDARD.encode(output.credentialHash),
+            issued_at: output.issuedAt.to_string(),
+            expires_at: output.expiresAt.to_string(),
+            chain_id: output.chainId.to_string(),
+            verifying_contract: STANDARD.encode(output.verifyingContract),
+            as_of: output.asOf.to_string(),
+            hash_suite: output.hashSuite.into(),
+            timestamp_granularity: output.timestampGranularity.into(),
+            expiry_epoch: output.expiryEpoch.to_string(),
+            credential_hash_blind: STANDARD.encode(output.credentialHashBlind),
+            disclosure_hash: STANDARD.encode(output.disclosureHash),
+            schema_hash: STANDARD.encode(output.schemaHash),
+            predicates_hash: STANDARD.encode(output.predicatesHash),
+            predicate_results: output.predicateResults,
+            withheld_claims_root: STANDARD.encode(output.withheldClaimsRoot),
+            issuer_address: STANDARD.encode(issuer.issuerAddress),
+            quorum_hash: STANDARD.encode(issuer.quorumHash),
+            hmac_key_commitment: STANDARD.encode(issuer.hmacKeyCommitment),
+            root_ca_hash: STANDARD.encode(issuer.rootCaHash),
+            vrf_output_hash: STANDARD.encode(issuer.vrfOutputHash),
+            root_issuer_hash: STANDARD.encode(issuer.rootIssuerHash),
+            key_registry_version: issuer.keyRegistryVersion,
+            trusted_issuers_root: STANDARD.encode(issuer.trustedIssuersRoot),
+            issuer_registry_root: STANDARD.encode(issuer.issuerRegistryRoot),
+            nullifier_scope: STANDARD.encode(holder.nullifierScope),
+            nullifier: STANDARD.encode(holder.nullifier),
+            verifier_nonce: STANDARD.encode(holder.verifierNonce),
+            subject_commitment: STANDARD.encode(holder.subjectCommitment),
+            pseudonym: STANDARD.encode(holder.pseudonym),
+            ring_root: STANDARD.encode(holder.ringRoot),
+            ring_event: STANDARD.encode(holder.ringEvent),
+            ring_tag: STANDARD.encode(holder.ringTag),
+            holder_bound: holder.holderBound,
+            device_rp_id_hash: STANDARD.encode(holder.deviceRpIdHash),
+            biometric_bound: holder.biometricBound,
+            sanctions_root: STANDARD.encode(status.sanctionsRoot),
+            sanctions_list_version: status.sanctionsListVersion,
+            serial_hash: STANDARD.encode(status.serialHash),
+            revocation_method: status.revocationMethod.into(),
+            revocation_root: STANDARD.encode(status.revocationRoot),
+            revocation_version: status.revocationVersion,
+            revocation_max_age: status.revocationMaxAge.to_string(),
+            status_purpose: status.statusPurpose.into(),
+            revealed_claims: output
+                .revealedClaims
+                .iter()
+                .map(|claim| STANDARD.encode(claim))
+                .collect(),
+        }
+    }
+}
+
+/// Wraps a proof into the execute message verifying it
+pub fn execute_msg(
+    proof: &[u8],
+    public_values: &[u8],
+    vkey: String,
+    outputs: &[CredentialOutput],
+    policy_satisfied: Option<bool>,
+) -> ExecuteMsg {
+    ExecuteMsg::VerifyCredential(ProofEnvelope {
+        proof: STANDARD.encode(proof),
+        public_values: STANDARD.encode(public_values),
+        vkey,
+        outputs: outputs.iter().map(Into::into).collect(),
+        policy_satisfied,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execute_msg_uses_proto3_json() {
+        let output = CredentialOutput {
+            version: 1,
+            issuedAt: u64::MAX,
+            ..Default::default()
+        };
+        let msg = execute_msg(&[0xff], &[], "0x01".into(), &[output], None);
+        let json = serde_json::to_value(&msg).unwrap();
+
+        let envelope = &json["verify_credential"];
+        assert_eq!(envelope["proof"], "/w==");
+        assert_eq!(envelope["outputs"][0]["issued_at"], u64::MAX.to_string());
+        assert_eq!(
+            envelope["outputs"][0]["subject"],
+            STANDARD.encode([0u8; 20])
+        );
+    }
+}
@@ -0,0 +1,174 @@
+//! Prover daemon
+//!
+//! `prove --watch <inbox>` keeps running, e.g. as a systemd service, and
+//! proves the credential files that arrive in the inbox. A scan every
+//! `--poll-interval` seconds claims new files by moving them into
+//! `processing/`, and `--jobs` workers prove them into `--output-dir` like
+//! `--batch-dir` does. Each file then moves to `done/` or `failed/` with a
+//! `<name>.result.json` record beside it. Writers should create files
+//! elsewhere and move them into the inbox, so a file is never read half
+//! written. On SIGINT or SIGTERM the daemon stops claiming files, finishes
+//! the proofs under way and exits. Files a crash left in `processing/` are
+//! proven again on start.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use credential_verifier_lib::file;
+use tracing::{error, info, warn};
+
+use crate::batch::{self, BatchOptions, JobStatus};
+
+/// Subdirectory of the inbox holding the files being proven
+const PROCESSING: &str = "processing";
+/// Subdirectory of the inbox the proven files move to
+const DONE: &str = "done";
+/// Subdirectory of the inbox the files that failed move to
+const FAILED: &str = "failed";
+
+/// How often to check for a shutdown while waiting for the next scan
+const SHUTDOWN_CHECK: Duration = Duration::from_millis(200);
+
+/// Sets `shutdown` once the process receives SIGINT or SIGTERM
+pub async fn wait_for_signal(shutdown: Arc<AtomicBool>) -> Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    tokio::select! {
+        result = tokio::signal::ctrl_c() => result?,
+        _ = terminate.recv() => {}
+    }
+    info!("Shutting down once the proofs under way are saved");
+    shutdown.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Moves `path` into the inbox subdirectory `dir`, keeping its name
+fn move_to(inbox: &Path, dir: &str, path: &Path) -> Result<PathBuf> {
+    let name = path
+        .file_name()
+        .with_context(|| format!("{} has no file name", path.display()))?;
+    let destination = inbox.join(dir).join(name);
+    std::fs::rename(path, &destination)
+        .with_context(|| format!("Can't move {} to {}", path.display(), destination.display()))?;
+    Ok(destination)
+}
+
+/// Proves a claimed file and files it under `done/` or `failed/` with its
+/// result record
+fn process(inbox: &Path, output_dir: &Path, options: &BatchOptions, path: &Path) -> Result<()> {
+    let credential = file::load_credential(path).map_err(Into::into);
+    let mut entry = batch::prove_entry(options, path, &credential, output_dir);
+    let dir = match entry.status {
+        JobStatus::Proven => DONE,
+        JobStatus::Failed => FAILED,
+    };
+    entry.credential = move_to(inbox, dir, path)?;
+    let record = entry.credential.with_extension("result.json");
+    std::fs::write(&record, serde_json::to_string_pretty(&entry)?)
+        .with_context(|| format!("Can't write {}", record.display()))
+}
+
+/// Queues the files a crash left claimed, then claims and queues the files
+/// arriving in `inbox` until `shutdown` is set
+fn scan(
+    inbox: &Path,
+    queue: mpsc::Sender<PathBuf>,
+    poll_interval: Duration,
+    shutdown: &AtomicBool,
+) -> Result<()> {
+    for path in batch::credential_files(&inbox.join(PROCESSING))? {
+        info!(credential = %path.display(), "Resuming");
+        queue.send(path)?;
+    }
+    while !shutdown.load(Ordering::Relaxed) {
+        for path in batch::credential_files(inbox)? {
+            match move_to(inbox, PROCESSING, &path) {
+                Ok(claimed) => queue.send(claimed)?,
+                Err(error) => warn!(
+                    credential = %path.display(),
+                    error = format!("{:#}", error),
+                    "Skipping"
+                ),
+            }
+        }
+        let scanned = Instant::now();
+        while scanned.elapsed() < poll_interval && !shutdown.load(Ordering::Relaxed) {
+            std::thread::sleep(SHUTDOWN_CHECK);
+        }
+    }
+    Ok(())
+}
+
+/// Proves the files arriving in `inbox` with `options.jobs` workers until
+/// `shutdown` is set
+pub fn watch(
+    inbox: &Path,
+    output_dir: &Path,
+    options: &BatchOptions,
+    poll_interval: Duration,
+    shutdown: &AtomicBool,
+) -> Result<()> {
+    // Proofs are JSON too, and would be claimed as credentials
+    anyhow::ensure!(
+        inbox != output_dir,
+        "Save the proofs outside the watched directory"
+    );
+    for dir in [PROCESSING, DONE, FAILED] {
+        let dir = inbox.join(dir);
+        std::fs::create_dir_all(&dir).with_context(|| format!("Can't create {}", dir.display()))?;
+    }
+    std::fs::create_dir_all(output_dir)
+        .with_context(|| format!("Can't create {}", output_dir.display()))?;
+
+    let (sender, receiver) = mpsc::channel::<PathBuf>();
+    let receiver = Mutex::new(receiver);
+    std::thread::scope(|scope| {
+        for _ in 0..options.jobs.max(1) {
+            scope.spawn(|| loop {
+                // The channel closes once the scans stop and it's drained
+                let Ok(path) = receiver.lock().expect("Workers don't panic").recv() else {
+                    break;
+                };
+                if let Err(error) = process(inbox, output_dir, options, &path) {
+                    error!(
+                        credential = %path.display(),
+                        error = format!("{:#}", error),
+                        "Failed to file the credential"
+                    );
+                }
+            });
+        }
+
+        // Dropping the sender on any return lets the workers finish
+        scan(inbox, sender, poll_interval, shutdown)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claims_and_resumes_files() {
+        let inbox = std::env::temp_dir().join(format!("credence-daemon-{}", std::process::id()));
+        std::fs::create_dir_all(inbox.join(PROCESSING)).unwrap();
+        std::fs::write(inbox.join(PROCESSING).join("a.json"), "").unwrap();
+        std::fs::write(inbox.join("b.json"), "").unwrap();
+
+        let claimed = move_to(&inbox, PROCESSING, &inbox.join("b.json")).unwrap();
+        assert_eq!(claimed, inbox.join(PROCESSING).join("b.json"));
+        assert!(!inbox.join("b.json").exists());
+
+        // A stopped daemon still queues what a crash left claimed
+        let (sender, receiver) = mpsc::channel();
+        scan(&inbox, sender, Duration::ZERO, &AtomicBool::new(true)).unwrap();
+        let queued: Vec<PathBuf> = receiver.iter().collect();
+        assert_eq!(queued, [inbox.join(PROCESSING).join("a.json"), claimed]);
+        std::fs::remove_dir_all(inbox).unwrap();
+    }
+}
@@ -0,0 +1,86 @@
+//! Delegation chains for sample credentials
+//!
+//! Builds a two-link secp256k1 chain: a root issuer authorizes an
+//! intermediate issuer for every sample credential type, which authorizes
+//! the sample issuer for one type.
+
+use k256::ecdsa::{signature::Signer, Signature, SigningKey};
+
+use crate::{Delegation, DelegationChain};
+
+/// Domain tag prefixed to every signed delegation (mirrors the program)
+const DELEGATION_DOMAIN: &[u8] = b"CREDENCE_DELEGATION_V1";
+
+/// Builds the message a delegating key signs (mirrors the program)
+fn delegation_message(delegation: &Delegation) -> Vec<u8> {
+    let mut message = DELEGATION_DOMAIN.to_vec();
+    message.extend_from_slice(&(delegation.delegate_pubkey.len() as u32).to_be_bytes());
+    message.extend_from_slice(&delegation.delegate_pubkey);
+    message.extend_from_slice(&(delegation.credential_types.len() as u32).to_be_bytes());
+    for credential_type in &delegation.credential_types {
+        message.extend_from_slice(&credential_type.to_be_bytes());
+    }
+    message.extend_from_slice(&delegation.max_depth.to_be_bytes());
+    message.extend_from_slice(&delegation.expires_at.to_be_bytes());
+    message
+}
+
+/// Returns the compressed SEC1 encoding of a key's public half
+fn pubkey(key: &SigningKey) -> Vec<u8> {
+    key.verifying_key()
+        .to_encoded_point(true)
+        .as_bytes()
+        .to_vec()
+}
+
+/// Signs a delegation of `credential_types` to `delegate_pubkey`
+fn delegate(
+    delegator: &SigningKey,
+    delegate_pubkey: Vec<u8>,
+    credential_types: Vec<u32>,
+    max_depth: u32,
+    expires_at: u64,
+) -> Delegation {
+    let mut delegation = Delegation {
+        delegate_pubkey,
+        credential_types,
+        max_depth,
+        expires_at,
+        signature: Vec::new(),
+    };
+    let signature: Signature = delegator.sign(&delegation_message(&delegation));
+    delegation.signature = signature.to_vec();
+    delegation
+}
+
+/// Builds a root → intermediate → issuer chain for `credential_type`
+pub fn sample_chain(
+    issuer_pubkey: &[u8],
+    credential_type: u32,
+    expires_at: u64,
+) -> anyhow::Result<DelegationChain> {
+    let root = SigningKey::from_slice(&[0x33; 32])?;
+    let intermediate = SigningKey::from_slice(&[0x44; 32])?;
+
+    let links = vec![
+        delegate(
+            &root,
+            pubkey(&intermediate),
+            vec![1, 2, 3, 4, 5],
+            1,
+            expires_at,
+        ),
+        delegate(
+            &intermediate,
+            issuer_pubkey.to_vec(),
+            vec![credential_type],
+            0,
+            expires_at,
+        ),
+    ];
+
+    Ok(DelegationChain {
+        root_pubkey: pubkey(&root),
+        links,
+    })
+}
@@ -0,0 +1,148 @@
+//! Ethereum Attestation Service (EAS) attestations of verified proofs
+//!
+//! Once a proof verifies, its output can be attested to the subject under
+//! a Credence schema, so apps can read the result through EAS instead of
+//! verifying proofs themselves. The schema is the `CredentialAttestation`
+//! struct below, registered once with EAS's SchemaRegistry:
+//!
+//! ```text
+//! bytes32 credentialHash,uint32 credentialType,uint64 issuedAt,...
+//! ```
+
+use alloy_sol_types::{SolCall, SolStruct, SolValue};
+use anyhow::{Context, Result};
+use credential_verifier_lib::CredentialOutput;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, TransactionRequest, H256};
+
+alloy_sol_types::sol! {
+    /// Attested fields of a verified credential output
+    #[derive(Debug, PartialEq, Eq)]
+    struct CredentialAttestation {
+        bytes32 credentialHash;
+        uint32 credentialType;
+        uint64 issuedAt;
+        uint64 expiresAt;
+        bytes32 nullifier;
+        bytes32 issuerRegistryRoot;
+        bytes32 programVKey;
+    }
+
+    struct AttestationRequestData {
+        address recipient;
+        uint64 expirationTime;
+        bool revocable;
+        bytes32 refUID;
+        bytes data;
+        uint256 value;
+    }
+
+    struct AttestationRequest {
+        bytes32 schema;
+        AttestationRequestData data;
+    }
+
+    function attest(AttestationRequest request) external payable returns (bytes32);
+}
+
+/// Schema string to register the attestations under
+pub fn schema() -> String {
+    let root = CredentialAttestation::eip712_root_type();
+    let (_, fields) = root.split_once('(').expect("EIP-712 types have a field list");
+    fields.trim_end_matches(')').to_string()
+}
+
+/// Encodes the attestation data of a verified output
+pub fn attestation_data(output: &CredentialOutput, vkey: [u8; 32]) -> Vec<u8> {
+    CredentialAttestation {
+        credentialHash: output.credentialHash,
+        credentialType: output.credentialType,
+        issuedAt: output.issuedAt,
+        expiresAt: output.expiresAt,
+        nullifier: output.holder.nullifier,
+        issuerRegistryRoot: output.issuer.issuerRegistryRoot,
+        programVKey: vkey.into(),
+    }
+    .abi_encode_params()
+}
+
+/// Builds the `EAS.attest` calldata attesting `output` to its subject
+///
+/// The attestation expires with the credential and stays revocable, so it
+/// can be withdrawn if the credential is revoked.
+pub fn attest_calldata(schema: [u8; 32], output: &CredentialOutput, vkey: [u8; 32]) -> Vec<u8> {
+    attestCall {
+        request: AttestationRequest {
+            schema: schema.into(),
+            data: AttestationRequestData {
+                recipient: output.subject,
+                expirationTime: output.expiresAt,
+                revocable: true,
+                refUID: Default::default(),
+                data: attestation_data(output, vkey).into(),
+                value: Default::default(),
+            },
+        },
+    }
+    .abi_encode()
+}
+
+/// Sends the attestation to the EAS contract at `eas` and returns the
+/// transaction hash once it's mined
+pub async fn submit(
+    rpc_url: &str,
+    private_key: &str,
+    eas: [u8; 20],
+    calldata: Vec<u8>,
+) -> Result<H256> {
+    let provider = Provider::<Http>::try_from(rpc_url)?;
+    let chain_id = provider.get_chainid().await?.as_u64();
+    let wallet = private_key
+        .trim_start_matches("0x")
+        .parse::<LocalWallet>()?
+        .with_chain_id(chain_id);
+
+    let mut transaction: TypedTransaction = TransactionRequest::new()
+        .from(wallet.address())
+        .to(Address::from(eas))
+        .data(calldata)
+        .chain_id(chain_id)
+        .into();
+    provider.fill_transaction(&mut transaction, None).await?;
+    let signature = wallet.sign_transaction(&transaction).await?;
+
+    let pending = provider
+        .send_raw_transaction(transaction.rlp_signed(&signature))
+        .await?;
+    let receipt = pending.await?.context("Attestation transaction dropped")?;
+    anyhow::ensure!(
+        receipt.status == Some(1.into()),
+        "Attestation transaction reverted"
+    );
+    Ok(receipt.transaction_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attestation_matches_schema() {
+        assert!(schema().starts_with("bytes32 credentialHash,uint32 credentialType,"));
+
+        let output = CredentialOutput {
+            credentialType: 2,
+            expiresAt: 1_800_000_000,
+            ..Default::default()
+        };
+        let calldata = attest_calldata([0x11; 32], &output, [0x22; 32]);
+        let call = attestCall::abi_decode(&calldata, true).unwrap();
+        assert_eq!(call.request.data.expirationTime, 1_800_000_000);
+        let attestation =
+            CredentialAttestation::abi_decode_params(&call.request.data.data, true).unwrap();
+        assert_eq!(attestation.credentialType, 2);
+        assert_eq!(attestation.programVKey, [0x22; 32]);
+    }
+}
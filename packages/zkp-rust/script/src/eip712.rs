@@ -0,0 +1,17 @@
+//! Wallet signatures for signing sample credentials
+//!
+//! The digests come from the library's `eip712` module, which the program
+//! rebuilds them with.
+
+pub use credential_verifier_lib::eip712::{credential_digest, keccak256, personal_sign_digest};
+
+/// Signs `digest` the way a wallet does, returning `r || s || v` with v = 27/28
+pub fn sign_digest(
+    signing_key: &k256::ecdsa::SigningKey,
+    digest: &[u8; 32],
+) -> anyhow::Result<Vec<u8>> {
+    let (signature, recovery_id) = signing_key.sign_prehash_recoverable(digest)?;
+    let mut bytes = signature.to_vec();
+    bytes.push(27 + recovery_id.to_byte());
+    Ok(bytes)
+}
@@ -0,0 +1,107 @@
+//! Signed publication times of sample revocation registries
+//!
+//! Mirrors the program's `freshness` module: the issuer signs the
+//! registry's method, root, version and publication time.
+
+use anyhow::Result;
+use credential_verifier_lib::hash::message_digest;
+use credential_verifier_lib::{
+    HashAlgorithm, RevocationFreshness, RevocationMethod, SignatureScheme,
+};
+use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+
+use crate::eip712;
+
+/// Domain tag prefixed to every signed publication time
+const FRESHNESS_DOMAIN: &[u8] = b"CREDENCE_REVOCATION_FRESHNESS_V1";
+
+/// How long before the proof the sample issuer published its root
+const SAMPLE_ROOT_AGE: u64 = 3600;
+
+/// Builds the message the issuer signs (mirrors the program)
+fn freshness_message(
+    method: RevocationMethod,
+    root: &[u8; 32],
+    version: u32,
+    published_at: u64,
+) -> Vec<u8> {
+    let mut message = FRESHNESS_DOMAIN.to_vec();
+    message.push(method as u8);
+    message.extend_from_slice(root);
+    message.extend_from_slice(&version.to_be_bytes());
+    message.extend_from_slice(&published_at.to_be_bytes());
+    message
+}
+
+/// Has the sample secp256k1 issuer attest it published the registry `root`
+/// an hour before `current_time`, for a verifier accepting `max_age`
+///
+/// As a wallet, an EIP-712 issuer signs it with `personal_sign`.
+pub fn sample_freshness(
+    scheme: SignatureScheme,
+    method: RevocationMethod,
+    root: &[u8; 32],
+    version: u32,
+    hash: HashAlgorithm,
+    current_time: u64,
+    max_age: u64,
+) -> Result<RevocationFreshness> {
+    let published_at = current_time.saturating_sub(SAMPLE_ROOT_AGE);
+    let message = freshness_message(method, root, version, published_at);
+    let signing_key = SigningKey::from_slice(&[0x11; 32])?;
+    let signature = match scheme {
+        SignatureScheme::Secp256k1 => {
+            let signature: Signature = signing_key.sign_prehash(&message_digest(hash, &message))?;
+            signature.to_vec()
+        }
+        SignatureScheme::Eip712 => {
+            eip712::sign_digest(&signing_key, &eip712::personal_sign_digest(&message))?
+        }
+        scheme => anyhow::bail!("Sample publication times aren't signed with {:?}", scheme),
+    };
+    Ok(RevocationFreshness {
+        published_at,
+        signature,
+        max_age,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::ecdsa::{RecoveryId, VerifyingKey};
+
+    #[test]
+    fn test_wallet_issuers_personal_sign() {
+        let root = [0x42; 32];
+        let freshness = sample_freshness(
+            SignatureScheme::Eip712,
+            RevocationMethod::MerkleList,
+            &root,
+            3,
+            HashAlgorithm::Sha256,
+            1_700_003_600,
+            86_400,
+        )
+        .unwrap();
+
+        // The wallet signature recovers the issuer over the EIP-191 digest
+        // of the message, not a typed-data digest
+        let message = freshness_message(
+            RevocationMethod::MerkleList,
+            &root,
+            3,
+            freshness.published_at,
+        );
+        let signature = Signature::from_slice(&freshness.signature[..64]).unwrap();
+        let recovery_id = RecoveryId::from_byte(freshness.signature[64] - 27).unwrap();
+        let recovered = VerifyingKey::recover_from_prehash(
+            &eip712::personal_sign_digest(&message),
+            &signature,
+            recovery_id,
+        )
+        .unwrap();
+        let issuer = SigningKey::from_slice(&[0x11; 32]).unwrap();
+        assert_eq!(&recovered, issuer.verifying_key());
+    }
+}
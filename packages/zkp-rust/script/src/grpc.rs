@@ -0,0 +1,278 @@
+//! gRPC proving service
+//!
+//! `prove --grpc <port>` serves `proto/prover.proto` for internal services
+//! that prefer protobuf contracts to JSON. It mirrors the REST API of
+//! `prove --serve` over the same jobs, and adds `WatchJob`, which streams a
+//! job's status as it changes instead of being polled. With `--api-keys`,
+//! calls without a client's key fail as `UNAUTHENTICATED`, and those over
+//! its rate limit as `RESOURCE_EXHAUSTED`. Other clients' jobs are
+//! `NOT_FOUND`.
+
+use std::pin::Pin;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use credential_verifier_lib::file::{self, FileFormat};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::Stream;
+use tonic::service::Interceptor;
+use tonic::{Request, Response, Status};
+
+use crate::api::{self, ApiState};
+use crate::clients::{Client, Refusal};
+use crate::jobs::{Job, JobEvent, JobStatus};
+use crate::ProofOutput;
+
+pub mod pb {
+    tonic::include_proto!("credence.prover.v1");
+}
+
+use pb::credential_prover_server::{CredentialProver, CredentialProverServer};
+
+impl From<JobStatus> for pb::JobStatus {
+    fn from(status: JobStatus) -> Self {
+        match status {
+            JobStatus::Queued => pb::JobStatus::Queued,
+            JobStatus::Executing => pb::JobStatus::Executing,
+            JobStatus::Proving => pb::JobStatus::Proving,
+            JobStatus::Done => pb::JobStatus::Done,
+            JobStatus::Failed => pb::JobStatus::Failed,
+            JobStatus::Cancelled => pb::JobStatus::Cancelled,
+        }
+    }
+}
+
+impl From<Job> for pb::Job {
+    fn from(job: Job) -> Self {
+        pb::Job {
+            id: job.id,
+            status: pb::JobStatus::from(job.status).into(),
+            subject: job.subject,
+            submitted_at: job.submitted_at,
+            seconds: job.seconds,
+            error: job.error,
+            cycles: job.cycles,
+            shards: job.shards,
+            client: job.client,
+        }
+    }
+}
+
+/// Error for calls naming an unknown job
+fn no_job(id: &str) -> Status {
+    Status::not_found(format!("No job {id}"))
+}
+
+/// Name of the call's client, with `--api-keys`
+fn client_name<T>(request: &Request<T>) -> Option<&str> {
+    request
+        .extensions()
+        .get::<Arc<Client>>()
+        .map(|client| client.name.as_str())
+}
+
+/// Checks each call's API key, passing its client on to the service
+#[derive(Clone)]
+struct Authenticator {
+    state: Arc<ApiState>,
+}
+
+impl Interceptor for Authenticator {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let Some(clients) = &self.state.clients else {
+            return Ok(request);
+        };
+        let authorization = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok());
+        match clients.authorize(authorization) {
+            Ok(client) => {
+                request.extensions_mut().insert(client);
+                Ok(request)
+            }
+            Err(refusal @ Refusal::RateLimited { .. }) => {
+                Err(Status::resource_exhausted(refusal.to_string()))
+            }
+            Err(refusal) => Err(Status::unauthenticated(refusal.to_string())),
+        }
+    }
+}
+
+/// The service, over the REST API's state
+pub struct ProverService {
+    state: Arc<ApiState>,
+}
+
+type JobStream = Pin<Box<dyn Stream<Item = Result<pb::Job, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl CredentialProver for ProverService {
+    async fn prove(&self, request: Request<pb::ProveRequest>) -> Result<Response<pb::Job>, Status> {
+        let client = request.extensions().get::<Arc<Client>>().cloned();
+        let request = request.into_inner();
+        let format = match request.syntax() {
+            pb::CredentialSyntax::Json => FileFormat::Json,
+            pb::CredentialSyntax::Toml => FileFormat::Toml,
+        };
+        let credential = file::parse_credential(&request.credential, format)
+            .map_err(|error| Status::invalid_argument(error.to_string()))?;
+        let job = self
+            .state
+            .submit(credential, client.as_deref())
+            .map_err(|error| Status::internal(format!("Can't queue the job: {:#}", error)))?;
+        Ok(Response::new(job.into()))
+    }
+
+    async fn get_job(&self, request: Request<pb::JobRequest>) -> Result<Response<pb::Job>, Status> {
+        let id = &request.get_ref().id;
+        let job = self
+            .state
+            .jobs
+            .get_for(id, client_name(&request))
+            .ok_or_else(|| no_job(id))?;
+        Ok(Response::new(job.into()))
+    }
+
+    type WatchJobStream = JobStream;
+
+    async fn watch_job(
+        &self,
+        request: Request<pb::JobRequest>,
+    ) -> Result<Response<Self::WatchJobStream>, Status> {
+        let client = client_name(&request).map(str::to_owned);
+        let id = request.into_inner().id;
+        // Subscribe before reading the status, so no change is missed
+        let mut updates = self.state.jobs.subscribe();
+        let job = self
+            .state
+            .jobs
+            .get_for(&id, client.as_deref())
+            .ok_or_else(|| no_job(&id))?;
+        let state = self.state.clone();
+        let (sender, receiver) = mpsc::channel(16);
+        tokio::spawn(async move {
+            let mut job = job;
+            loop {
+                let done = job.status.is_final();
+                if sender.send(Ok(job.into())).await.is_err() || done {
+                    break;
+                }
+                // Proving progress isn't part of the contract
+                job = loop {
+                    match state.jobs.next_event(&mut updates, &id).await {
+                        Some(JobEvent::Update { job }) => break job,
+                        Some(JobEvent::Proving { .. }) => continue,
+                        None => return,
+                    }
+                };
+            }
+        });
+        Ok(Response::new(Box::pin(ReceiverStream::new(receiver))))
+    }
+
+    async fn cancel_job(
+        &self,
+        request: Request<pb::JobRequest>,
+    ) -> Result<Response<pb::Job>, Status> {
+        let id = &request.get_ref().id;
+        if self.state.jobs.get_for(id, client_name(&request)).is_none() {
+            return Err(no_job(id));
+        }
+        let job = self.state.jobs.cancel(id).ok_or_else(|| no_job(id))?;
+        if job.status != JobStatus::Cancelled {
+            return Err(Status::failed_precondition(api::not_running(&job)));
+        }
+        Ok(Response::new(job.into()))
+    }
+
+    async fn get_proof(
+        &self,
+        request: Request<pb::JobRequest>,
+    ) -> Result<Response<pb::ProofEnvelope>, Status> {
+        let id = &request.get_ref().id;
+        let job = self
+            .state
+            .jobs
+            .get_for(id, client_name(&request))
+            .ok_or_else(|| no_job(id))?;
+        let json = self
+            .state
+            .jobs
+            .proof(id)
+            .map_err(|error| Status::internal(format!("Can't read the proof: {:#}", error)))?
+            .ok_or_else(|| Status::failed_precondition(api::not_done(&job)))?;
+        Ok(Response::new(pb::ProofEnvelope { json }))
+    }
+
+    async fn verify(
+        &self,
+        request: Request<pb::VerifyRequest>,
+    ) -> Result<Response<pb::VerifyResponse>, Status> {
+        let envelope: ProofOutput = serde_json::from_str(&request.get_ref().envelope)
+            .map_err(|error| Status::invalid_argument(format!("Not a proof envelope: {error}")))?;
+        let subject = envelope.subject.clone();
+        let public_values = envelope.public_values.clone();
+        let response = match self.state.verify(envelope).await {
+            Ok(()) => pb::VerifyResponse {
+                verified: true,
+                vkey: self.state.vkey.clone(),
+                subject: Some(subject),
+                public_values: Some(public_values),
+                message: None,
+            },
+            Err(error) => pb::VerifyResponse {
+                verified: false,
+                vkey: self.state.vkey.clone(),
+                subject: None,
+                public_values: None,
+                message: Some(format!("{:#}", error)),
+            },
+        };
+        Ok(Response::new(response))
+    }
+}
+
+/// Serves the service on `port` until SIGINT, letting running calls finish
+pub async fn serve(state: Arc<ApiState>, port: u16) -> Result<()> {
+    let address = ([0, 0, 0, 0], port).into();
+    tracing::info!(port, "Serving the gRPC proving service");
+    let service = CredentialProverServer::with_interceptor(
+        ProverService {
+            state: state.clone(),
+        },
+        Authenticator { state },
+    );
+    tonic::transport::Server::builder()
+        .add_service(service)
+        .serve_with_shutdown(address, async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
+        .with_context(|| format!("Can't serve gRPC on port {}", port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_converts_jobs() {
+        let job = Job {
+            id: "ab".into(),
+            status: JobStatus::Failed,
+            subject: "0x12".into(),
+            submitted_at: 1,
+            seconds: Some(2.0),
+            error: Some("Expired".into()),
+            cycles: None,
+            shards: None,
+            client: None,
+        };
+        assert_eq!(api::not_running(&job), "Job ab already failed");
+        let converted = pb::Job::from(job);
+        assert_eq!(converted.status(), pb::JobStatus::Failed);
+        assert_eq!(converted.error.as_deref(), Some("Expired"));
+    }
+}
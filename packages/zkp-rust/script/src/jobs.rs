@@ -0,0 +1,398 @@
+//! Proving jobs
+//!
+//! A credential submitted to the REST API or gRPC service becomes a job that
+//! moves from `queued` through `executing` (the program runs without
+//! proving, so rejected credentials fail in seconds) and `proving` to
+//! `done` or `failed`. Until it's done or failed, a job can be cancelled; a
+//! proof already under way can't be interrupted, so it's discarded once
+//! generated. Each job is persisted in the [`Storage`] whenever it changes,
+//! and jobs a restart interrupted are queued again. The credential, which
+//! carries the holder's secrets, is only kept until the job is final, and
+//! never in databases: their interrupted jobs fail instead.
+//!
+//! Watchers receive every change as a [`JobEvent`], and progress events
+//! while proving. The SDK proves the shards and wraps the proof in one call
+//! without reporting back, so proving progress is estimated: execution
+//! counts the cycles, and so the shards, and the server times its proofs to
+//! learn how long a shard takes.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::storage::Storage;
+use crate::{unix_time, CredentialInput, ProofOutput};
+
+/// Where a proving job is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    /// Waiting for a free prover
+    Queued,
+    /// Running the program to check the credential before proving
+    Executing,
+    Proving,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub const ALL: [JobStatus; 6] = [
+        JobStatus::Queued,
+        JobStatus::Executing,
+        JobStatus::Proving,
+        JobStatus::Done,
+        JobStatus::Failed,
+        JobStatus::Cancelled,
+    ];
+
+    /// Name of the status, as serialized
+    pub fn name(self) -> &'static str {
+        match self {
+            JobStatus::Queued => "queued",
+            JobStatus::Executing => "executing",
+            JobStatus::Proving => "proving",
+            JobStatus::Done => "done",
+            JobStatus::Failed => "failed",
+            JobStatus::Cancelled => "cancelled",
+        }
+    }
+
+    /// Whether the job won't change any more
+    pub fn is_final(self) -> bool {
+        matches!(
+            self,
+            JobStatus::Done | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+}
+
+/// A submitted credential and how proving it went
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub id: String,
+    pub status: JobStatus,
+    /// Subject address
+    pub subject: String,
+    /// When the credential was submitted, in seconds since the Unix epoch
+    pub submitted_at: u64,
+    /// Seconds spent executing and proving (absent until done or failed)
+    pub seconds: Option<f64>,
+    /// Why the job failed (absent unless failed)
+    pub error: Option<String>,
+    /// Cycles the program ran for (absent until executed)
+    pub cycles: Option<u64>,
+    /// Shards the proof is estimated to span (absent until executed)
+    pub shards: Option<u64>,
+    /// Client that submitted the credential, with `--api-keys`
+    #[serde(default)]
+    pub client: Option<String>,
+}
+
+/// Something that happened to a job, as watchers receive it
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JobEvent {
+    /// The job changed status, or executing counted its cycles
+    Update { job: Job },
+    /// Proving is under way
+    Proving {
+        id: String,
+        elapsed_seconds: f64,
+        /// How long the whole proof should take, from the shards and the
+        /// time earlier proofs took per shard (absent before any proof)
+        estimated_seconds: Option<f64>,
+    },
+}
+
+impl JobEvent {
+    /// Job the event is about
+    pub fn id(&self) -> &str {
+        match self {
+            JobEvent::Update { job } => &job.id,
+            JobEvent::Proving { id, .. } => id,
+        }
+    }
+}
+
+/// Shards the prover splits this many cycles into, at SP1's default shard
+/// size of 2^22 cycles or `SHARD_SIZE`
+pub fn estimated_shards(cycles: u64) -> u64 {
+    let shard_size = std::env::var("SHARD_SIZE")
+        .ok()
+        .and_then(|size| size.parse::<u64>().ok())
+        .unwrap_or(1 << 22);
+    cycles.div_ceil(shard_size.max(1)).max(1)
+}
+
+/// A job as persisted, with what it needs to run again after a restart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRecord {
+    pub job: Job,
+    /// Credential to prove (absent once the job is final)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential: Option<CredentialInput>,
+    /// Where the proof was saved in `--output-dir` (absent until done)
+    pub proof: Option<PathBuf>,
+}
+
+/// Jobs of the proving services, persisted in a storage
+pub struct JobStore {
+    storage: Box<dyn Storage>,
+    records: Mutex<HashMap<String, JobRecord>>,
+    /// Every job as it changes, for streaming to watchers
+    updates: broadcast::Sender<JobEvent>,
+}
+
+impl JobStore {
+    /// Opens the jobs persisted in `storage`, returning the ids of those a
+    /// restart interrupted, queued again
+    ///
+    /// Interrupted jobs whose credential wasn't stored fail.
+    pub fn open(storage: Box<dyn Storage>) -> Result<(Self, Vec<String>)> {
+        let mut records = HashMap::new();
+        let mut interrupted = Vec::new();
+        for mut record in storage.load()? {
+            if !record.job.status.is_final() && record.credential.is_none() {
+                record.job.status = JobStatus::Failed;
+                record.job.error =
+                    Some("Interrupted by a restart, submit the credential again".to_string());
+                storage.save(&record)?;
+            } else if !record.job.status.is_final() {
+                record.job.status = JobStatus::Queued;
+                interrupted.push(record.job.id.clone());
+            } else if record.credential.take().is_some() {
+                // Stored before credentials were dropped from final jobs
+                storage.save(&record)?;
+            }
+            records.insert(record.job.id.clone(), record);
+        }
+        let store = JobStore {
+            storage,
+            records: Mutex::new(records),
+            updates: broadcast::channel(256).0,
+        };
+        Ok((store, interrupted))
+    }
+
+    fn records(&self) -> std::sync::MutexGuard<'_, HashMap<String, JobRecord>> {
+        self.records.lock().expect("Jobs aren't poisoned")
+    }
+
+    /// Stores a job's record and tells its watchers
+    fn persist(&self, record: &JobRecord) -> Result<()> {
+        self.storage.save(record)?;
+        self.report(JobEvent::Update {
+            job: record.job.clone(),
+        });
+        Ok(())
+    }
+
+    /// Queues a credential `client` submitted, returning its job
+    pub fn insert(&self, credential: CredentialInput, client: Option<String>) -> Result<Job> {
+        let mut id = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut id);
+        let job = Job {
+            id: hex::encode(id),
+            status: JobStatus::Queued,
+            subject: format!("0x{}", hex::encode(credential.subject)),
+            submitted_at: unix_time()?,
+            seconds: None,
+            error: None,
+            cycles: None,
+            shards: None,
+            client,
+        };
+        let record = JobRecord {
+            job: job.clone(),
+            credential: Some(credential),
+            proof: None,
+        };
+        self.persist(&record)?;
+        self.records().insert(job.id.clone(), record);
+        Ok(job)
+    }
+
+    pub fn get(&self, id: &str) -> Option<Job> {
+        self.records().get(id).map(|record| record.job.clone())
+    }
+
+    /// Job `id` if `client` submitted it, so clients can't see or cancel
+    /// each other's jobs
+    pub fn get_for(&self, id: &str, client: Option<&str>) -> Option<Job> {
+        self.get(id).filter(|job| job.client.as_deref() == client)
+    }
+
+    /// How many jobs have each status
+    pub fn counts(&self) -> Vec<(JobStatus, usize)> {
+        let records = self.records();
+        JobStatus::ALL
+            .into_iter()
+            .map(|status| {
+                let count = records
+                    .values()
+                    .filter(|record| record.job.status == status)
+                    .count();
+                (status, count)
+            })
+            .collect()
+    }
+
+    /// Credential a job proves, until it's final
+    pub fn credential(&self, id: &str) -> Option<CredentialInput> {
+        self.records()
+            .get(id)
+            .and_then(|record| record.credential.clone())
+    }
+
+    /// Stores the proof of job `id`
+    pub fn save_proof(&self, id: &str, envelope: &ProofOutput) -> Result<()> {
+        self.storage.save_proof(id, envelope)
+    }
+
+    /// Proof envelope of a done job, as JSON
+    pub fn proof(&self, id: &str) -> Result<Option<String>> {
+        if let Some(json) = self.storage.proof(id)? {
+            return Ok(Some(json));
+        }
+        // Jobs done before proofs were stored only have the saved file
+        let Some(path) = self
+            .records()
+            .get(id)
+            .and_then(|record| record.proof.clone())
+        else {
+            return Ok(None);
+        };
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("Can't read {}", path.display()))?;
+        Ok(Some(json))
+    }
+
+    /// Moves a job on with `update`, unless it's already final (e.g.
+    /// cancelled), dropping its credential once it's final. Returns whether
+    /// it moved on
+    pub fn advance(&self, id: &str, update: impl FnOnce(&mut JobRecord)) -> bool {
+        let mut records = self.records();
+        let Some(record) = records.get_mut(id) else {
+            return false;
+        };
+        if record.job.status.is_final() {
+            return false;
+        }
+        update(record);
+        if record.job.status.is_final() {
+            record.credential = None;
+        }
+        if let Err(error) = self.persist(record) {
+            tracing::error!(
+                job = id,
+                error = format!("{:#}", error),
+                "Failed to persist the job"
+            );
+        }
+        true
+    }
+
+    /// Cancels a job unless it's already done or failed, returning it
+    pub fn cancel(&self, id: &str) -> Option<Job> {
+        self.advance(id, |record| record.job.status = JobStatus::Cancelled);
+        self.get(id)
+    }
+
+    /// Tells a job's watchers about an event
+    pub fn report(&self, event: JobEvent) {
+        // Nobody may be watching
+        let _ = self.updates.send(event);
+    }
+
+    /// Receives every job's events
+    pub fn subscribe(&self) -> broadcast::Receiver<JobEvent> {
+        self.updates.subscribe()
+    }
+
+    /// Waits for the next event of job `id`, catching up on events the
+    /// receiver fell behind on by reading the job again. None once the
+    /// store is gone
+    pub async fn next_event(
+        &self,
+        updates: &mut broadcast::Receiver<JobEvent>,
+        id: &str,
+    ) -> Option<JobEvent> {
+        loop {
+            match updates.recv().await {
+                Ok(event) if event.id() == id => return Some(event),
+                Ok(_) => continue,
+                Err(RecvError::Lagged(_)) => {
+                    return self.get(id).map(|job| JobEvent::Update { job })
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::FileStorage;
+
+    #[test]
+    fn test_persists_and_requeues_jobs() {
+        let dir = std::env::temp_dir().join(format!("credence-jobs-{}", std::process::id()));
+        let credential = crate::create_sample_credential(
+            "0x1234567890123456789012345678901234567890",
+            1,
+            crate::SignatureScheme::Secp256k1,
+            crate::HashAlgorithm::Sha256,
+            crate::CredentialFormat::Binary,
+            crate::ClaimsVersion::V2,
+            crate::HolderBindings::default(),
+        )
+        .unwrap();
+        let storage = || Box::new(FileStorage::open(&dir).unwrap());
+        let (store, interrupted) = JobStore::open(storage()).unwrap();
+        assert!(interrupted.is_empty());
+        let proving = store.insert(credential.clone(), None).unwrap();
+        let cancelled = store.insert(credential, Some("acme".into())).unwrap();
+        assert!(store.advance(&proving.id, |record| record.job.status = JobStatus::Proving));
+        assert_eq!(
+            store.cancel(&cancelled.id).unwrap().status,
+            JobStatus::Cancelled
+        );
+        // Cancelled jobs don't move on, nor keep the credential
+        assert!(!store.advance(&cancelled.id, |record| record.job.status = JobStatus::Done));
+        assert!(store.credential(&cancelled.id).is_none());
+        let stored = std::fs::read_to_string(dir.join(format!("{}.json", cancelled.id))).unwrap();
+        assert!(!stored.contains("credential"));
+
+        let (store, interrupted) = JobStore::open(storage()).unwrap();
+        assert_eq!(interrupted, [proving.id.as_str()]);
+        assert!(store.counts().contains(&(JobStatus::Cancelled, 1)));
+        assert_eq!(store.get(&proving.id).unwrap().status, JobStatus::Queued);
+        assert!(store.credential(&proving.id).is_some());
+        // Only the submitting client sees its job
+        assert!(store.get_for(&cancelled.id, Some("acme")).is_some());
+        assert!(store.get_for(&cancelled.id, Some("globex")).is_none());
+        assert!(store.get_for(&cancelled.id, None).is_none());
+        assert!(store.get_for(&proving.id, None).is_some());
+        assert_eq!(
+            store.get(&cancelled.id).unwrap().status,
+            JobStatus::Cancelled
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+
+        let event = JobEvent::Proving {
+            id: proving.id,
+            elapsed_seconds: 2.0,
+            estimated_seconds: None,
+        };
+        assert_eq!(serde_json::to_value(&event).unwrap()["event"], "proving");
+        assert_eq!(estimated_shards(0), 1);
+    }
+}
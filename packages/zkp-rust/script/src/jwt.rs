@@ -0,0 +1,92 @@
+//! ES256K JWT helpers for building test credentials
+//!
+//! Produces the compact JWS the program parses in `CredentialFormat::Jwt`
+//! mode: the subject, timestamps and credential type travel as
+//! `sub`/`iat`/`exp`/`vct` claims and the signature is the 64-byte `r || s` over `header.payload`. SD-JWTs
+//! add salted `_sd` digests and append the disclosures being presented.
+
+use anyhow::Result;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use credential_verifier_lib::credential_type_name;
+use k256::ecdsa::{signature::Signer, Signature, SigningKey};
+use rand::RngCore;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::CredentialInput;
+
+/// Builds the registered claims shared by JWT and SD-JWT credentials
+fn registered_claims(credential: &CredentialInput) -> Value {
+    let mut claims = json!({
+        "sub": format!("0x{}", hex::encode(credential.subject)),
+        "iat": credential.issued_at,
+        "vct": credential_type_name(credential.credential_type),
+    });
+    if credential.expires_at > 0 {
+        claims["exp"] = credential.expires_at.into();
+    }
+    claims
+}
+
+/// Signs `claims` as a compact ES256K JWS
+fn sign_jws(typ: &str, claims: &Value, signing_key: &SigningKey) -> Result<String> {
+    let header = json!({ "alg": "ES256K", "typ": typ });
+    let signing_input = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(&header)?),
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(claims)?),
+    );
+    let signature: Signature = signing_key.sign(signing_input.as_bytes());
+
+    Ok(format!(
+        "{}.{}",
+        signing_input,
+        URL_SAFE_NO_PAD.encode(signature.to_bytes())
+    ))
+}
+
+/// Encodes the credential's subject, timestamps and type as an ES256K JWT
+pub fn encode_credential(credential: &CredentialInput, signing_key: &SigningKey) -> Result<String> {
+    sign_jws("JWT", &registered_claims(credential), signing_key)
+}
+
+/// Encodes an SD-JWT with selectively disclosable claims
+///
+/// Every entry of `hidden` becomes a salted disclosure; the presentation
+/// includes only those named in `disclosed`, and no key-binding JWT.
+pub fn encode_sd_credential(
+    credential: &CredentialInput,
+    signing_key: &SigningKey,
+    hidden: &[(&str, Value)],
+    disclosed: &[&str],
+) -> Result<String> {
+    let mut digests = Vec::new();
+    let mut presented = Vec::new();
+    for (name, value) in hidden {
+        let mut salt = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let disclosure = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&json!([
+            URL_SAFE_NO_PAD.encode(salt),
+            name,
+            value
+        ]))?);
+
+        digests.push(URL_SAFE_NO_PAD.encode(Sha256::digest(disclosure.as_bytes())));
+        if disclosed.contains(name) {
+            presented.push(disclosure);
+        }
+    }
+
+    let mut claims = registered_claims(credential);
+    claims["_sd"] = digests.into();
+    claims["_sd_alg"] = "sha-256".into();
+
+    let mut token = sign_jws("dc+sd-jwt", &claims, signing_key)?;
+    for disclosure in presented {
+        token.push('~');
+        token.push_str(&disclosure);
+    }
+    token.push('~');
+    Ok(token)
+}
@@ -0,0 +1,58 @@
+//! Key registries for sample credentials
+//!
+//! Builds a secp256k1 registry in which a long-term root key lists a
+//! retired signing key alongside the sample issuer's current one.
+
+use k256::ecdsa::{signature::Signer, Signature, SigningKey};
+
+use crate::{KeyRegistry, RegistryKey};
+
+/// Domain tag prefixed to every signed key registry (mirrors the program)
+const KEY_REGISTRY_DOMAIN: &[u8] = b"CREDENCE_KEY_REGISTRY_V1";
+
+/// Builds the message the root key signs (mirrors the program)
+fn registry_message(registry: &KeyRegistry) -> Vec<u8> {
+    let mut message = KEY_REGISTRY_DOMAIN.to_vec();
+    message.extend_from_slice(&registry.version.to_be_bytes());
+    message.extend_from_slice(&(registry.keys.len() as u32).to_be_bytes());
+    for key in &registry.keys {
+        message.extend_from_slice(&(key.pubkey.len() as u32).to_be_bytes());
+        message.extend_from_slice(&key.pubkey);
+        message.extend_from_slice(&key.retired_at.to_be_bytes());
+    }
+    message
+}
+
+/// Returns the compressed SEC1 encoding of a key's public half
+fn pubkey(key: &SigningKey) -> Vec<u8> {
+    key.verifying_key()
+        .to_encoded_point(true)
+        .as_bytes()
+        .to_vec()
+}
+
+/// Builds a version 2 registry whose root key retired the issuer's first
+/// key in favour of `issuer_pubkey`
+pub fn sample_registry(issuer_pubkey: &[u8]) -> anyhow::Result<KeyRegistry> {
+    let root = SigningKey::from_slice(&[0x66; 32])?;
+    let retired = SigningKey::from_slice(&[0x10; 32])?;
+
+    let mut registry = KeyRegistry {
+        root_pubkey: pubkey(&root),
+        version: 2,
+        keys: vec![
+            RegistryKey {
+                pubkey: pubkey(&retired),
+                retired_at: 1_700_000_000,
+            },
+            RegistryKey {
+                pubkey: issuer_pubkey.to_vec(),
+                retired_at: 0,
+            },
+        ],
+        signature: Vec::new(),
+    };
+    let signature: Signature = root.sign(&registry_message(&registry));
+    registry.signature = signature.to_vec();
+    Ok(registry)
+}
@@ -0,0 +1,113 @@
+//! Key-value (v3) credential data for sample credentials
+//!
+//! Mirrors the program's `keyed` decoder: `version = 3 || claim_count ||
+//! claim*` with each claim `key_len (BE u16) || key || tag || value`.
+
+use credential_verifier_lib::{base_credential_types, CREDENTIAL_TYPE_BITMASK};
+use sha2::{Digest, Sha256};
+
+/// A typed claim value
+pub enum Value {
+    /// Unsigned integer (tag 1)
+    U64(u64),
+    /// Boolean flag (tag 2)
+    Bool(bool),
+    /// String committed by its SHA-256 (tag 3)
+    String(&'static str),
+    /// Unix timestamp in seconds (tag 4)
+    Date(i64),
+    /// Quantity in `10^-precision` of a 3-letter unit (tag 5)
+    Amount {
+        unit: &'static str,
+        precision: u8,
+        value: u64,
+    },
+}
+
+/// Encodes a value as its tag followed by its bytes
+pub fn encode_value(value: &Value) -> Vec<u8> {
+    match value {
+        Value::U64(number) => [&[1][..], &number.to_be_bytes()].concat(),
+        Value::Bool(flag) => vec![2, *flag as u8],
+        Value::String(text) => [&[3][..], &Sha256::digest(text.as_bytes())].concat(),
+        Value::Date(seconds) => [&[4][..], &seconds.to_be_bytes()].concat(),
+        Value::Amount {
+            unit,
+            precision,
+            value,
+        } => [
+            &[5][..],
+            unit.as_bytes(),
+            &[*precision],
+            &value.to_be_bytes(),
+        ]
+        .concat(),
+    }
+}
+
+/// Encodes key-value claims as v3 credential data
+pub fn encode(claims: &[(&str, Value)]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&3u32.to_be_bytes());
+    data.extend_from_slice(&(claims.len() as u32).to_be_bytes());
+    for (key, value) in claims {
+        data.extend_from_slice(&(key.len() as u16).to_be_bytes());
+        data.extend_from_slice(key.as_bytes());
+        data.extend_from_slice(&encode_value(value));
+    }
+    data
+}
+
+/// Builds the sample claims the program requires for `credential_type`
+pub fn sample_claims(credential_type: u32, issued_at: u64) -> Vec<(&'static str, Value)> {
+    // Bitmask types carry the union of their base types' claims
+    if credential_type & CREDENTIAL_TYPE_BITMASK != 0 {
+        let mut claims: Vec<(&str, Value)> = Vec::new();
+        for base in base_credential_types(credential_type) {
+            for claim in sample_claims(base, issued_at) {
+                if !claims.iter().any(|(key, _)| *key == claim.0) {
+                    claims.push(claim);
+                }
+            }
+        }
+        return claims;
+    }
+
+    let verified_at = Value::Date(issued_at as i64 - 3600);
+    let mut claims = match credential_type {
+        1 => vec![("kycLevel", Value::U64(2)), ("verifiedAt", verified_at)],
+        // Accredited by income: $250,000.00
+        2 => vec![
+            ("kycLevel", Value::U64(2)),
+            (
+                "annualIncome",
+                Value::Amount {
+                    unit: "USD",
+                    precision: 2,
+                    value: 25_000_000,
+                },
+            ),
+        ],
+        3 => vec![
+            ("kycLevel", Value::U64(2)),
+            ("qualifiedPurchaser", Value::Bool(true)),
+        ],
+        4 => vec![
+            ("kycLevel", Value::U64(2)),
+            ("entityRegistration", Value::String("LEI-SAMPLE")),
+            ("accredited", Value::Bool(true)),
+        ],
+        5 => vec![
+            ("amlCleared", Value::Bool(true)),
+            ("screenedAt", verified_at),
+        ],
+        _ => vec![("fullName", Value::String("Sample Holder"))],
+    };
+    // Every sample carries a date of birth and residence for predicates
+    claims.push(("dateOfBirth", Value::Date(crate::SAMPLE_DATE_OF_BIRTH)));
+    claims.push((
+        "residenceCountry",
+        Value::String(crate::SAMPLE_RESIDENCE_COUNTRY),
+    ));
+    claims
+}
@@ -0,0 +1,118 @@
+//! Linked-data proof helpers for building test credentials
+//!
+//! Produces the N-Quads envelope the program parses in
+//! `CredentialFormat::LdProof` mode, as a JSON-LD processor would expand a
+//! VC and its `ecdsa-rdfc-2019` proof configuration, and signs the verify
+//! data with ES256.
+
+use anyhow::Result;
+use credential_verifier_lib::credential_type_name;
+use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::{vc, CredentialInput};
+
+const RDF_TYPE: &str = "<http://www.w3.org/1999/02/22-rdf-syntax-ns#type>";
+const XSD_DATE_TIME: &str = "<http://www.w3.org/2001/XMLSchema#dateTime>";
+
+/// Expands the credential's subject, type and validity period to N-Quads,
+/// with the credential as the blank node `label`
+fn document(credential: &CredentialInput, label: &str) -> Result<Vec<String>> {
+    let cred = |term: &str| format!("<https://www.w3.org/2018/credentials#{}>", term);
+    let mut quads = vec![
+        format!("{} {} {} .", label, RDF_TYPE, cred("VerifiableCredential")),
+        format!(
+            "{} {} <{}> .",
+            label,
+            RDF_TYPE,
+            credential_type_name(credential.credential_type)
+        ),
+        format!(
+            "{} {} <did:pkh:eip155:1:0x{}> .",
+            label,
+            cred("credentialSubject"),
+            hex::encode(credential.subject)
+        ),
+        format!("{} {} <{}> .", label, cred("issuer"), vc::SAMPLE_ISSUER),
+        format!(
+            "{} {} \"{}\"^^{} .",
+            label,
+            cred("issuanceDate"),
+            vc::rfc3339(credential.issued_at)?,
+            XSD_DATE_TIME
+        ),
+    ];
+    if credential.expires_at > 0 {
+        quads.push(format!(
+            "{} {} \"{}\"^^{} .",
+            label,
+            cred("expirationDate"),
+            vc::rfc3339(credential.expires_at)?,
+            XSD_DATE_TIME
+        ));
+    }
+    Ok(quads)
+}
+
+/// Expands the `ecdsa-rdfc-2019` proof configuration to N-Quads, with the
+/// proof as the blank node `label`
+fn proof(credential: &CredentialInput, label: &str) -> Result<Vec<String>> {
+    let sec = |term: &str| format!("<https://w3id.org/security#{}>", term);
+    Ok(vec![
+        format!("{} {} {} .", label, RDF_TYPE, sec("DataIntegrityProof")),
+        format!(
+            "{} {} \"ecdsa-rdfc-2019\"^^{} .",
+            label,
+            sec("cryptosuite"),
+            sec("cryptosuiteString")
+        ),
+        format!(
+            "{} {} \"{}\"^^{} .",
+            label,
+            "<http://purl.org/dc/terms/created>",
+            vc::rfc3339(credential.issued_at)?,
+            XSD_DATE_TIME
+        ),
+        format!(
+            "{} {} {} .",
+            label,
+            sec("proofPurpose"),
+            sec("assertionMethod")
+        ),
+        format!(
+            "{} {} <{}#key-1> .",
+            label,
+            sec("verificationMethod"),
+            vc::SAMPLE_ISSUER
+        ),
+    ])
+}
+
+/// Canonical form of a dataset with a single blank node: URDNA2015 labels
+/// it `_:c14n0` and sorts the quads
+fn canonical(quads: Vec<String>) -> String {
+    let mut lines: Vec<String> = quads.into_iter().map(|quad| quad + "\n").collect();
+    lines.sort();
+    lines.concat()
+}
+
+/// Encodes the credential as an N-Quads envelope and signs its verify data
+///
+/// Returns the envelope and the ES256 signature. The envelope keeps the
+/// host's own blank node labels and quad order so the program has to
+/// canonicalize it.
+pub fn encode_credential(
+    credential: &CredentialInput,
+    signing_key: &SigningKey,
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    let mut verify_data = Sha256::digest(canonical(proof(credential, "_:c14n0")?)).to_vec();
+    verify_data.extend_from_slice(&Sha256::digest(canonical(document(credential, "_:c14n0")?)));
+    let signature: Signature = signing_key.sign(&verify_data);
+
+    let envelope = json!({
+        "document": document(credential, "_:b0")?.join("\n"),
+        "proof": proof(credential, "_:b1")?.join("\n"),
+    });
+    Ok((serde_json::to_vec(&envelope)?, signature.to_vec()))
+}
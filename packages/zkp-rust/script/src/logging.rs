@@ -0,0 +1,69 @@
+//! Logs of the binaries
+//!
+//! The binaries report what they do as `tracing` events on stderr, within
+//! spans around executing, proving and verifying (whose durations are
+//! logged as they close), so the proving services can be followed in
+//! production. `--log-format json` writes one JSON object per event, with
+//! the fields of its spans, for log pipelines; `--log-level` picks what's
+//! shown.
+
+use anyhow::Result;
+use clap::ValueEnum;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::EnvFilter;
+
+/// Crates too chatty to log, as in the SP1 SDK's own logger
+const SILENCED: [&str; 5] = [
+    "hyper=off",
+    "p3_keccak_air=off",
+    "p3_fri=off",
+    "p3_dft=off",
+    "p3_challenger=off",
+];
+
+/// How logs are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// A line of text per event
+    Text,
+    /// A JSON object per event
+    Json,
+}
+
+/// Logging flags of every binary
+#[derive(clap::Args, Debug)]
+pub struct LogArgs {
+    /// Format of the logs written to stderr
+    #[arg(long, value_enum, default_value = "text")]
+    pub log_format: LogFormat,
+
+    /// Logs to show: a level (error, warn, info, debug or trace) or
+    /// `RUST_LOG` directives such as `info,sp1_prover=debug` (defaults to
+    /// `RUST_LOG`, else info)
+    #[arg(long)]
+    pub log_level: Option<String>,
+}
+
+impl LogArgs {
+    /// Installs the subscriber writing the logs
+    pub fn init(&self) -> Result<()> {
+        let directives = match &self.log_level {
+            Some(directives) => directives.clone(),
+            None => std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string()),
+        };
+        let mut filter = EnvFilter::try_new(&directives)
+            .map_err(|error| anyhow::anyhow!("Invalid log level {}: {}", directives, error))?;
+        for directive in SILENCED {
+            filter = filter.add_directive(directive.parse()?);
+        }
+        let subscriber = tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_writer(std::io::stderr)
+            .with_span_events(FmtSpan::CLOSE);
+        match self.log_format {
+            LogFormat::Text => subscriber.compact().try_init(),
+            LogFormat::Json => subscriber.json().try_init(),
+        }
+        .map_err(|error| anyhow::anyhow!("Can't set up logging: {}", error))
+    }
+}
@@ -3,11 +3,68 @@
 //! This script generates zero-knowledge proofs for credential verification
 //! that can be verified on-chain using the SP1 verifier.
 
-use anyhow::Result;
-use clap::Parser;
+mod accumulator;
+mod anoncreds;
+mod api;
+mod batch;
+mod bbs;
+mod bcs;
+mod blind;
+mod bls;
+mod cache;
+mod clients;
+mod config;
+mod cosmwasm;
+mod daemon;
+mod delegation;
+mod eas;
+mod eip712;
+mod freshness;
+mod grpc;
+mod jobs;
+mod jwt;
+mod key_registry;
+mod keyed;
+mod ld;
+mod logging;
+mod mdl;
+mod merkle;
+mod metrics;
+mod outputs;
+mod policy;
+mod prover;
+mod revocation;
+mod ring;
+mod sanctions;
+mod schema;
+mod smt;
+mod solana;
+mod status_list;
+mod storage;
+mod trusted;
+mod vc;
+mod vrf;
+mod webauthn;
+
+use anyhow::{Context, Result};
+use clap::{Parser, ValueEnum};
+use credential_verifier_lib::credential_type_name;
+use credential_verifier_lib::file;
+use credential_verifier_lib::hash::message_digest;
+use credential_verifier_lib::message::{self, signing_message};
+pub use credential_verifier_lib::{
+    BbsInput, ClaimSchema, CredentialFormat, CredentialInput, CredentialOutput, Delegation,
+    DelegationChain, Eip712Domain, FieldType, HashAlgorithm, IssuerQuorum, KeyRegistry,
+    NullifierInput, Policy, PolicyInput, Predicate, ProgramInput, QuorumSignature, RegistryKey,
+    RevocationMethod, SchemaField, SignatureScheme, StatusPurpose, TimestampGranularity,
+    CREDENTIAL_TYPES, CREDENTIAL_TYPE_BITMASK,
+};
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
-use sp1_sdk::{HashableKey, ProverClient, SP1Stdin};
+use sha2::Sha256;
+use sp1_sdk::{HashableKey, SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin, SP1VerifyingKey};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{info, info_span};
 
 /// The ELF binary of the credential verifier program
 /// This is generated by building the program package
@@ -17,48 +74,471 @@ const ELF: &[u8] = include_bytes!("../../program/elf/riscv32im-succinct-zkvm-elf
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Path to the credential JSON file
-    #[arg(short, long)]
-    credential: String,
+    /// Path to a JSON or TOML credential file (byte fields as `0x` hex or
+    /// arrays), or `sample` for a generated sample credential
+    #[arg(short, long, required_unless_present_any = ["batch_dir", "watch", "serve", "grpc"])]
+    credential: Option<String>,
+
+    /// Read settings from this TOML file rather than `credence.toml` (flags
+    /// override its values)
+    #[arg(long)]
+    config: Option<PathBuf>,
 
     /// Output path for the proof
     #[arg(short, long, default_value = "proof.json")]
     output: String,
 
-    /// Whether to generate a PLONK proof (for on-chain verification)
-    #[arg(long, default_value = "true")]
-    plonk: bool,
+    /// Save the proof in this directory as `0x<subject>.json` instead,
+    /// where the `gateway` binary serves it from
+    #[arg(long, conflicts_with = "output")]
+    output_dir: Option<PathBuf>,
+
+    /// Prove every JSON or TOML credential file in this directory, each
+    /// as its own proof saved in `--output-dir` with a `manifest.json` of
+    /// per-credential status, proof paths and timing
+    #[arg(
+        long,
+        requires = "output_dir",
+        conflicts_with_all = [
+            "credential", "batch", "policy", "digest", "solana", "move_outputs", "cosmwasm_output",
+            "eas",
+        ]
+    )]
+    batch_dir: Option<PathBuf>,
+
+    /// Run as a daemon proving the credential files moved into this
+    /// directory into `--output-dir`, until SIGINT or SIGTERM
+    #[arg(
+        long,
+        requires = "output_dir",
+        conflicts_with_all = [
+            "credential", "batch_dir", "batch", "policy", "digest", "solana", "move_outputs",
+            "cosmwasm_output", "eas",
+        ]
+    )]
+    watch: Option<PathBuf>,
+
+    /// Serve the REST proving API on this port, saving proofs in
+    /// `--output-dir`
+    #[arg(
+        long,
+        requires = "output_dir",
+        conflicts_with_all = [
+            "credential", "batch_dir", "watch", "batch", "policy", "digest", "solana",
+            "move_outputs", "cosmwasm_output", "eas",
+        ]
+    )]
+    serve: Option<u16>,
+
+    /// Serve the gRPC proving service on this port, saving proofs in
+    /// `--output-dir` (with `--serve`, both share the jobs)
+    #[arg(
+        long,
+        requires = "output_dir",
+        conflicts_with_all = [
+            "credential", "batch_dir", "watch", "batch", "policy", "digest", "solana",
+            "move_outputs", "cosmwasm_output", "eas",
+        ]
+    )]
+    grpc: Option<u16>,
+
+    /// Seconds between scans of the `--watch` directory
+    #[arg(long, default_value = "5")]
+    poll_interval: u64,
+
+    /// Only serve the clients listed in this JSON file with `--serve` and
+    /// `--grpc`, each with its API key and limits (anyone can call them
+    /// without)
+    #[arg(long)]
+    api_keys: Option<PathBuf>,
+
+    /// Keep the jobs of `--serve` and `--grpc` and their proofs in this
+    /// database (`sqlite://<path>` or `postgres://...`) rather than in
+    /// `--output-dir`'s `jobs/` directory
+    #[arg(long)]
+    database: Option<String>,
+
+    /// Reuse proofs cached in this directory for credentials proven before
+    /// with `--batch-dir`, `--watch`, `--serve` or `--grpc`, and cache new
+    /// ones there
+    #[arg(long)]
+    cache_dir: Option<PathBuf>,
+
+    /// Proofs generated at once with `--batch-dir`, `--watch`, `--serve` or
+    /// `--grpc`
+    #[arg(long, default_value = "4")]
+    jobs: usize,
+
+    /// Kind of proof to generate, trading proving time against on-chain
+    /// verification cost
+    #[arg(long, value_enum, default_value = "plonk")]
+    mode: ProofMode,
+
+    /// Prover to generate the proof with (defaults to `SP1_PROVER`, else
+    /// local)
+    #[arg(long, value_enum)]
+    prover: Option<prover::ProverBackend>,
+
+    /// Seconds to wait for the prover network to fulfill the request
+    #[arg(long)]
+    network_timeout: Option<u64>,
+
+    /// Fail rather than prove locally when the prover network fails
+    #[arg(long)]
+    no_fallback: bool,
+
+    /// Only prove credentials of this issuer key (hex encoded, as the
+    /// credential encodes it), or of a delegate or registry under it;
+    /// repeatable
+    #[arg(long)]
+    trusted_issuer: Vec<String>,
+
+    /// Commit only the keccak256 of the public values, cutting calldata on
+    /// L2s; the full values are saved for the contract to re-hash
+    #[arg(long)]
+    digest: bool,
+
+    /// Commit borsh-encoded outputs for the SP1 Solana verifier and save
+    /// the instruction data verifying the proof (needs `--mode groth16`)
+    #[arg(long, conflicts_with = "digest")]
+    solana: bool,
+
+    /// Commit BCS-encoded outputs for Move verifiers on Aptos and Sui
+    #[arg(long = "move", conflicts_with_all = ["digest", "solana"])]
+    move_outputs: bool,
+
+    /// Also save a CosmWasm execute message verifying the proof, with the
+    /// proof base64-encoded and the outputs in proto3 JSON (needs `--mode
+    /// groth16`)
+    #[arg(long, conflicts_with_all = ["digest", "solana", "move_outputs"])]
+    cosmwasm_output: Option<String>,
+
+    /// Credential type of the sample: a number, a name (kyc, accredited,
+    /// qualified, institutional, aml) or names joined with `+` for a
+    /// combined bitmask type (e.g., `kyc+aml`)
+    #[arg(long, default_value = "accredited", value_parser = policy::parse_credential_type)]
+    credential_type: u32,
+
+    /// Signature scheme used for the sample credential
+    #[arg(long, value_enum, default_value = "secp256k1")]
+    scheme: SignatureScheme,
+
+    /// Hash suite for the sample's credential hash and ECDSA digest
+    #[arg(long, value_enum, default_value = "sha256")]
+    hash: HashAlgorithm,
+
+    /// Encoding of the sample's credential data (jwt and sd-jwt require
+    /// secp256k1, mdl requires secp256r1)
+    #[arg(long, value_enum, default_value = "binary")]
+    format: CredentialFormat,
+
+    /// Layout of the sample's binary claims
+    #[arg(long, value_enum, default_value = "v2")]
+    claims: ClaimsVersion,
+
+    /// Attach an issuer VRF proof to the sample (requires secp256r1)
+    #[arg(long)]
+    vrf: bool,
+
+    /// Issue the sample through a root → intermediate delegation chain
+    /// (requires secp256k1)
+    #[arg(long)]
+    delegate: bool,
+
+    /// List the sample issuer's key in a sample root-signed key registry
+    /// (requires secp256k1)
+    #[arg(long)]
+    key_registry: bool,
+
+    /// Also prove a KYC credential for the same subject in the same proof
+    /// (sample only)
+    #[arg(long)]
+    batch: bool,
+
+    /// Evaluate the credentials against a policy such as
+    /// `accredited & (kyc | aml)`
+    #[arg(long)]
+    policy: Option<String>,
+
+    /// Attach the claim schema describing the sample's claims (binary
+    /// format only)
+    #[arg(long)]
+    schema: bool,
+
+    /// Derive a nullifier for this verifier scope (SHA-256 of the text)
+    /// from a sample holder secret
+    #[arg(long)]
+    scope: Option<String>,
+
+    /// Verifier challenge to bind the proof to (32 bytes, hex encoded)
+    #[arg(long, value_parser = parse_hex32)]
+    nonce: Option<[u8; 32]>,
+
+    /// Chain ID of the verifier contract, so the proof can't be replayed
+    /// on another chain (e.g., 11155111 for Sepolia)
+    #[arg(long)]
+    chain_id: Option<u64>,
+
+    /// Address of the verifier contract, so another dapp's contract can't
+    /// accept the proof
+    #[arg(long, value_parser = parse_address)]
+    verifying_contract: Option<[u8; 20]>,
+
+    /// Commit a salted hash of the subject instead of its address, so a
+    /// relayer can submit the proof (the salt is saved with the proof)
+    #[arg(long)]
+    hide_subject: bool,
+
+    /// Prove the issuer belongs to a sample trusted-issuer set instead of
+    /// revealing it
+    #[arg(long)]
+    anonymous_issuer: bool,
+
+    /// Prove the issuer, or the root key behind a delegate or key registry,
+    /// is listed in a sample on-chain issuer registry
+    #[arg(long, conflicts_with = "anonymous_issuer")]
+    issuer_registry: bool,
+
+    /// Prove the subject is at least this many years old without revealing
+    /// the date of birth
+    #[arg(long)]
+    min_age: Option<u32>,
+
+    /// Prove a numeric claim lies within bounds without revealing it, as
+    /// `claim:min:max` (e.g., `kycLevel:2:3`) or, for amounts,
+    /// `claim:min:max:unit:precision` (an empty max is unbounded)
+    #[arg(long, value_parser = parse_range)]
+    range: Vec<Predicate>,
+
+    /// Prove the credential was issued at least this many days ago (pair
+    /// with a coarse --timestamp-granularity to hide the issuance date)
+    #[arg(long)]
+    min_credential_age_days: Option<u32>,
+
+    /// Reveal the binary claims at these positions (v2 or v3 claims),
+    /// committing the rest only as a root (sample credentials get a
+    /// disclosure seed to salt them with)
+    #[arg(long, value_delimiter = ',')]
+    reveal: Vec<u32>,
+
+    /// Prove the country of residence is among these ISO 3166-1 alpha-2
+    /// codes without revealing which
+    #[arg(long, value_delimiter = ',')]
+    allowed_countries: Vec<String>,
+
+    /// Prove the subject is not on a sample sanctions list
+    #[arg(long)]
+    sanctions_screen: bool,
+
+    /// Prove the credential is not on a sample issuer revocation list (any
+    /// hash suite but Poseidon)
+    #[arg(long, conflicts_with_all = ["accumulator_check", "status_index"])]
+    revocation_check: bool,
+
+    /// Prove the credential is not in a sample RSA revocation accumulator
+    /// (any hash suite but Poseidon)
+    #[arg(long, conflicts_with = "status_index")]
+    accumulator_check: bool,
+
+    /// Give the sample this entry in a sample StatusList2021 revocation
+    /// list, whose revoked entries are 7 and 42 (secp256k1 only)
+    #[arg(long)]
+    status_index: Option<u64>,
+
+    /// Put the status entry in the sample suspension list instead, whose
+    /// suspended entry is 3 (requires --revocation-max-age)
+    #[arg(long, requires_all = ["status_index", "revocation_max_age"])]
+    suspension: bool,
+
+    /// Require the revocation registry root to be at most this many seconds
+    /// old, with a publication time signed by the sample issuer (requires
+    /// a revocation check and secp256k1)
+    #[arg(long)]
+    revocation_max_age: Option<u64>,
+
+    /// Replace the subject with a pseudonym for this verifier (SHA-256 of
+    /// the text) derived from the holder secret (requires --scope)
+    #[arg(long)]
+    verifier_id: Option<String>,
+
+    /// Round the committed issuance and expiry times down to this
+    /// granularity (expiry is still checked exactly)
+    #[arg(long, value_enum, default_value = "exact")]
+    timestamp_granularity: TimestampGranularity,
+
+    /// Verifier blind to hash the committed credential hash with (32
+    /// bytes, hex encoded)
+    #[arg(long, value_parser = parse_hex32)]
+    blind: Option<[u8; 32]>,
+
+    /// Bind the sample to a sample passkey, whose assertion over the nonce
+    /// the proof then needs (requires --nonce)
+    #[arg(long)]
+    device_bound: bool,
+
+    /// Bind the sample to a sample biometric template and prove a matching
+    /// capture
+    #[arg(long)]
+    biometric: bool,
+
+    /// Make the sample single-use with this issuer-signed serial number
+    /// (32 bytes, hex encoded), committing only its hash
+    #[arg(long, value_parser = parse_hex32)]
+    serial: Option<[u8; 32]>,
+
+    /// Prove the prover controls the subject address by signing the nonce
+    /// with a sample subject key (requires --nonce)
+    #[arg(long)]
+    holder_binding: bool,
+
+    /// Prove the subject is one of a sample ring of eligible holders, with
+    /// a linkability tag for this event (SHA-256 of the text; requires
+    /// --blind)
+    #[arg(long)]
+    ring_event: Option<String>,
+
+    /// After the proof verifies, attest its first output to the subject
+    /// through the EAS contract at this address, signed with the key in
+    /// `EAS_PRIVATE_KEY`
+    #[arg(long, value_parser = parse_address, requires_all = ["eas_schema", "rpc_url"])]
+    eas: Option<[u8; 20]>,
+
+    /// UID of the registered Credence attestation schema
+    #[arg(long, value_parser = parse_hex32)]
+    eas_schema: Option<[u8; 32]>,
+
+    /// JSON-RPC endpoint attestations are sent through
+    #[arg(long)]
+    rpc_url: Option<String>,
+
+    #[command(flatten)]
+    log: logging::LogArgs,
 }
 
-/// Credential input data
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CredentialInput {
-    /// The subject's Ethereum address (20 bytes as hex string)
-    pub subject: [u8; 20],
-    /// The credential type
-    pub credential_type: u32,
-    /// Raw credential data
-    pub credential_data: Vec<u8>,
-    /// Issuer's signature
-    pub signature: Vec<u8>,
-    /// Issuer's public key
-    pub issuer_pubkey: Vec<u8>,
-    /// Issuance timestamp
-    pub issued_at: u64,
-    /// Expiration timestamp
-    pub expires_at: u64,
-    /// Current timestamp
-    pub current_time: u64,
-}
-
-/// Proof output for serialization
+/// `credence.toml` keys of the arguments
+const SETTINGS: &[config::Setting] = &[
+    config::setting("prover", "backend", "prover"),
+    config::setting("prover", "mode", "mode"),
+    config::setting("prover", "network_timeout", "network_timeout"),
+    config::setting("prover", "no_fallback", "no_fallback"),
+    config::setting("prover", "jobs", "jobs"),
+    config::setting("trust", "issuers", "trusted_issuer"),
+    config::setting("rpc", "url", "rpc_url"),
+    config::setting("output", "path", "output"),
+    config::setting("output", "dir", "output_dir"),
+    config::setting("output", "cache_dir", "cache_dir"),
+    config::setting("output", "database", "database"),
+    config::setting("log", "format", "log_format"),
+    config::setting("log", "level", "log_level"),
+];
+
+/// Parses a hex-encoded 32-byte value such as a verifier challenge
+fn parse_hex32(value: &str) -> Result<[u8; 32], String> {
+    let bytes = hex::decode(value.trim_start_matches("0x")).map_err(|err| err.to_string())?;
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| format!("expected 32 bytes, got {}", bytes.len()))
+}
+
+/// Parses a hex-encoded 20-byte address
+fn parse_address(value: &str) -> Result<[u8; 20], String> {
+    let bytes = hex::decode(value.trim_start_matches("0x")).map_err(|err| err.to_string())?;
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| format!("expected 20 bytes, got {}", bytes.len()))
+}
+
+/// Parses a range predicate from `claim:min:max[:unit:precision]`
+fn parse_range(value: &str) -> Result<Predicate, String> {
+    let bound = |text: &str, default: u64| match text {
+        "" => Ok(default),
+        _ => text.parse::<u64>().map_err(|err| err.to_string()),
+    };
+    let (claim, min, max, unit, precision) = match value.split(':').collect::<Vec<_>>()[..] {
+        [claim, min, max] => (claim, min, max, None, 0),
+        [claim, min, max, unit, precision] => {
+            let unit: [u8; 3] = unit
+                .as_bytes()
+                .try_into()
+                .map_err(|_| format!("expected a 3-letter unit, got {unit}"))?;
+            let precision = precision.parse::<u8>().map_err(|err| err.to_string())?;
+            (claim, min, max, Some(unit), precision)
+        }
+        _ => return Err("expected claim:min:max[:unit:precision]".to_string()),
+    };
+    if claim.is_empty() {
+        return Err("missing claim name".to_string());
+    }
+    Ok(Predicate::InRange {
+        claim: claim.to_string(),
+        unit,
+        precision,
+        min: bound(min, 0)?,
+        max: bound(max, u64::MAX)?,
+    })
+}
+
+/// Kind of proof the prover generates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofMode {
+    /// PLONK wrapped proof for the on-chain SP1 verifier, with no trusted
+    /// setup of its own
+    Plonk,
+    /// Groth16 wrapped proof for the on-chain SP1 verifier, the cheapest to
+    /// verify
+    Groth16,
+    /// Constant-size STARK proof, verifiable off-chain or wrapped later
+    Compressed,
+    /// Uncompressed STARK proof, the fastest to generate, verifiable
+    /// off-chain only
+    Core,
+}
+
+/// Layout of binary credential data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ClaimsVersion {
+    /// Typed TLV claims from the claim-type registry
+    V2,
+    /// Key-value claims with typed values
+    V3,
+    /// Merkle root of v2 claims, with the type's claims proven and the
+    /// holder's name left unproven
+    V4,
+    /// Sparse Merkle tree of key-value claims, with the type's keys
+    /// proven present and `sanctioned` proven absent
+    V5,
+}
+
+/// Version of the proof envelope's schema, bumped when fields change
+/// meaning or are removed
+pub const ENVELOPE_VERSION: u32 = 1;
+
+/// Proof envelope, the single artifact a proof is saved as
+///
+/// Other services read the proof bytes, public values and metadata; the
+/// full SP1 proof is in the SDK's own format, for verifying off-chain.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProofOutput {
+    /// Version of this schema
+    pub envelope_version: u32,
+    /// Version of the program that generated the proof (the script and
+    /// program crates are versioned together)
+    pub program_version: String,
+    /// Layout version of the public values
+    pub output_version: u32,
+    /// Kind of proof
+    pub mode: ProofMode,
+    /// When proving started, in seconds since the Unix epoch
+    pub started_at: u64,
+    /// When the proof was generated, in seconds since the Unix epoch
+    pub completed_at: u64,
     /// The proof bytes (hex encoded)
     pub proof: String,
     /// The public values (hex encoded)
     pub public_values: String,
-    /// The program verification key (hex encoded)
+    /// Hash of the program verification key, as registered with the
+    /// on-chain SP1 verifier (bytes32, hex encoded)
     pub vkey: String,
     /// Subject address
     pub subject: String,
@@ -66,44 +546,157 @@ pub struct ProofOutput {
     pub credential_type: u32,
     /// Credential hash (hex encoded)
     pub credential_hash: String,
+    /// Hashes of every credential in the proof, in input order (hex
+    /// encoded)
+    pub credential_hashes: Vec<String>,
+    /// Whether the credentials satisfied `--policy` (absent without one)
+    pub policy_satisfied: Option<bool>,
+    /// Salt opening the subject commitment (absent unless `--hide-subject`)
+    pub subject_salt: Option<String>,
+    /// Whether every predicate held (absent without predicates)
+    pub predicates_satisfied: Option<bool>,
+    /// Revocation registry the credential was checked against (absent if
+    /// unchecked)
+    pub revocation: Option<RevocationOutput>,
+    /// Public values the committed digest hashes, passed to the contract
+    /// with the proof (hex encoded; absent unless `--digest`)
+    pub outputs: Option<String>,
+    /// Instruction data for the SP1 Solana verifier (hex encoded; absent
+    /// unless `--solana`)
+    pub instruction_data: Option<String>,
+    /// The full SP1 proof, which the `verify` binary checks
+    pub sp1_proof: SP1ProofWithPublicValues,
+}
+
+/// Committed revocation registry of a proof
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevocationOutput {
+    /// Kind of registry
+    pub method: RevocationMethod,
+    /// Registry root (hex encoded)
+    pub root: String,
+    /// Registry version
+    pub version: u32,
+    /// Oldest the root was allowed to be, in seconds (0 if unbounded)
+    pub max_age: u64,
+    /// Status the registry ruled out
+    pub purpose: StatusPurpose,
+}
+
+/// Date of birth of every sample holder (1990-01-01)
+const SAMPLE_DATE_OF_BIRTH: i64 = 631_152_000;
+
+/// Country of residence of every sample holder
+const SAMPLE_RESIDENCE_COUNTRY: &str = "CH";
+
+/// Enrolled biometric template of sample holders
+const SAMPLE_BIOMETRIC_TEMPLATE: &[u8] = b"sample fingerprint template";
+
+/// Secp256k1 key behind the subject of samples that prove control of it
+const SAMPLE_SUBJECT_KEY: [u8; 32] = [0x77; 32];
+
+/// Holder secret behind sample nullifiers
+const SAMPLE_HOLDER_SECRET: [u8; 32] = [0x55; 32];
+
+/// Computes the SHA-256 credential hash revocation checks key on, whatever
+/// the credential's suite
+fn sha256_credential_hash(credential: &CredentialInput) -> [u8; 32] {
+    credential_verifier_lib::hash::credential_hash(
+        HashAlgorithm::Sha256,
+        &credential.subject,
+        credential.credential_type,
+        &credential.credential_data,
+        &credential.issuer_pubkey,
+    )
+}
+
+/// Sample v2 claims `(claim type, value)` for each credential type
+///
+/// Claim types: 1 full name, 2 date of birth, 5 KYC level, 6 accreditation status,
+/// 8 investor class, 9 entity registration, 10 AML screening
+fn sample_claims(credential_type: u32) -> Vec<(u16, Vec<u8>)> {
+    // Bitmask types carry the union of their base types' claims
+    if credential_type & CREDENTIAL_TYPE_BITMASK != 0 {
+        let mut claims: Vec<(u16, Vec<u8>)> = Vec::new();
+        for base in credential_verifier_lib::base_credential_types(credential_type) {
+            for claim in sample_claims(base) {
+                if !claims.iter().any(|(claim_type, _)| *claim_type == claim.0) {
+                    claims.push(claim);
+                }
+            }
+        }
+        return claims;
+    }
+
+    let mut claims = match credential_type {
+        1 => vec![(5, vec![2])],                                  // KYC
+        2 => vec![(5, vec![2]), (6, vec![1])],                    // Accredited
+        3 => vec![(5, vec![2]), (8, vec![1])],                    // Qualified
+        4 => vec![(5, vec![2]), (9, b"LEI-SAMPLE".to_vec()), (6, vec![1])], // Institutional
+        5 => vec![(10, vec![1])],                                 // AML
+        _ => vec![(1, b"Sample Holder".to_vec())],
+    };
+    // Every sample carries a date of birth and residence for predicates
+    claims.push((2, SAMPLE_DATE_OF_BIRTH.to_be_bytes().to_vec()));
+    claims.push((4, SAMPLE_RESIDENCE_COUNTRY.as_bytes().to_vec()));
+    claims
+}
+
+/// Returns the Ethereum address of `SAMPLE_SUBJECT_KEY`
+fn sample_subject_address() -> Result<[u8; 20]> {
+    let key = k256::ecdsa::SigningKey::from_slice(&SAMPLE_SUBJECT_KEY)?;
+    let uncompressed = key.verifying_key().to_encoded_point(false);
+    let hash = eip712::keccak256(&uncompressed.as_bytes()[1..]);
+    Ok(hash[12..].try_into().expect("20-byte address"))
+}
+
+/// Signs `nonce` with `SAMPLE_SUBJECT_KEY` as EIP-191 `personal_sign`
+fn sign_holder_binding(nonce: &[u8; 32]) -> Result<Vec<u8>> {
+    let key = k256::ecdsa::SigningKey::from_slice(&SAMPLE_SUBJECT_KEY)?;
+    let mut prefixed = b"\x19Ethereum Signed Message:\n32".to_vec();
+    prefixed.extend_from_slice(nonce);
+    let (signature, recovery_id) = key.sign_prehash_recoverable(&eip712::keccak256(&prefixed))?;
+    let mut encoded = signature.to_bytes().to_vec();
+    encoded.push(27 + recovery_id.to_byte());
+    Ok(encoded)
+}
+
+/// Holder bindings, serial number, status entry and disclosure seed the
+/// issuer signs into a sample credential
+#[derive(Debug, Clone, Copy, Default)]
+struct HolderBindings<'a> {
+    /// Verifier scope (SHA-256 of the text) to derive a nullifier for
+    scope: Option<&'a str>,
+    /// Bind the credential to a sample passkey
+    device_bound: bool,
+    /// Bind the credential to a sample biometric template
+    biometric: bool,
+    /// Serial number making the credential single-use
+    serial: Option<[u8; 32]>,
+    /// Entry in the sample StatusList2021 list of that purpose
+    status_entry: Option<(u64, StatusPurpose)>,
+    /// Seed of the salts of claims the holder may reveal
+    disclosure_seed: Option<[u8; 32]>,
 }
 
 /// Creates a sample credential for testing
 fn create_sample_credential(
     subject_hex: &str,
     credential_type: u32,
+    scheme: SignatureScheme,
+    hash: HashAlgorithm,
+    format: CredentialFormat,
+    claims_version: ClaimsVersion,
+    bindings: HolderBindings,
 ) -> Result<CredentialInput> {
     // Parse subject address
     let subject_bytes = hex::decode(subject_hex.trim_start_matches("0x"))?;
     let mut subject = [0u8; 20];
     subject.copy_from_slice(&subject_bytes);
 
-    // Create sample credential data
-    // Format: version (4 bytes) + claim_count (4 bytes) + claims
-    let version: u32 = 1;
-    let claim_count: u32 = match credential_type {
-        1 => 1, // KYC
-        2 => 2, // Accredited
-        3 => 2, // Qualified
-        4 => 3, // Institutional
-        5 => 1, // AML
-        _ => 1,
-    };
-
-    let mut credential_data = Vec::new();
-    credential_data.extend_from_slice(&version.to_be_bytes());
-    credential_data.extend_from_slice(&claim_count.to_be_bytes());
-
-    // Add dummy claim data
-    for i in 0..claim_count {
-        credential_data.extend_from_slice(&[i as u8; 32]); // 32-byte claim
-    }
-
-    // Create sample signature (64 bytes)
-    let signature = vec![0u8; 64];
-
-    // Create sample public key (33 bytes compressed)
-    let issuer_pubkey = vec![0x02; 33];
+    // Create sample credential data in the v2 typed TLV format
+    let claims = sample_claims(credential_type);
+    let credential_data = credential_verifier_lib::claims::encode(&claims);
 
     // Timestamps
     let current_time = std::time::SystemTime::now()
@@ -112,113 +705,1054 @@ fn create_sample_credential(
     let issued_at = current_time - 86400; // Issued 1 day ago
     let expires_at = current_time + 365 * 86400; // Expires in 1 year
 
-    Ok(CredentialInput {
+    // Scoped nullifiers come from a sample holder secret
+    let nullifier = bindings.scope.map(|scope| {
+        use sha2::Digest;
+        NullifierInput {
+            scope: Sha256::digest(scope.as_bytes()).into(),
+            secret: SAMPLE_HOLDER_SECRET,
+        }
+    });
+
+    // Device-bound samples embed the sample passkey
+    let device_pubkey = if bindings.device_bound {
+        Some(webauthn::sample_device_pubkey()?)
+    } else {
+        None
+    };
+
+    // A fresh capture of the sample template hashes like the enrolled one
+    let biometric_template_hash = bindings.biometric.then(|| {
+        use sha2::Digest;
+        Sha256::digest(SAMPLE_BIOMETRIC_TEMPLATE).into()
+    });
+
+    // Status entries point into the sample issuer's signed status list
+    let status_list = match bindings.status_entry {
+        Some((index, purpose)) => {
+            anyhow::ensure!(
+                scheme == SignatureScheme::Secp256k1,
+                "The sample status list is signed with secp256k1 (--scheme secp256k1)"
+            );
+            Some(status_list::sample_entry(index, purpose, hash)?)
+        }
+        None => None,
+    };
+
+    let mut credential = CredentialInput {
         subject,
         credential_type,
         credential_data,
-        signature,
-        issuer_pubkey,
+        credential_format: format,
+        signature: Vec::new(),
+        issuer_pubkey: Vec::new(),
+        signature_scheme: scheme,
+        eip712_domain: None,
+        hash_algorithm: hash,
+        bbs: None,
+        quorum: None,
+        hmac_key: None,
+        link_secret: None,
+        issuer_cert_chain: Vec::new(),
+        vrf_proof: None,
+        claim_schema: None,
+        delegation: None,
+        claim_proofs: Vec::new(),
+        sparse_claim_proofs: Vec::new(),
+        nullifier,
+        verifier_nonce: [0u8; 32],
+        subject_salt: None,
+        issuer_membership: None,
+        predicates: Vec::new(),
+        revealed_claims: Vec::new(),
+        disclosure_seed: bindings.disclosure_seed,
+        sanctions_exclusion: None,
+        verifier_id: None,
+        timestamp_granularity: TimestampGranularity::Exact,
+        credential_hash_blind: None,
+        ring: None,
+        holder_signature: None,
+        device_pubkey,
+        device_assertion: None,
+        biometric_template_hash,
+        serial_number: bindings.serial,
+        revocation_exclusion: None,
+        status_list,
+        accumulator_witness: None,
+        key_registry: None,
+        revocation_freshness: None,
+        issuer_registry: None,
+        chain_id: None,
+        verifying_contract: None,
         issued_at,
         expires_at,
         current_time,
-    })
-}
+    };
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // Initialize logging
-    sp1_sdk::utils::setup_logger();
+    // W3C documents replace the binary claims before signing
+    if format == CredentialFormat::W3cVc {
+        credential.credential_data = vc::encode_credential(&credential)?;
+    }
+
+    // As do key-value claims
+    if claims_version == ClaimsVersion::V3 {
+        anyhow::ensure!(
+            format == CredentialFormat::Binary,
+            "Key-value claims are a binary layout (--format binary)"
+        );
+        let claims = keyed::sample_claims(credential_type, issued_at);
+        credential.credential_data = keyed::encode(&claims);
+    }
 
-    let args = Args::parse();
+    // And Merkle roots, which the holder opens only for the type's claims
+    if claims_version == ClaimsVersion::V4 {
+        anyhow::ensure!(
+            format == CredentialFormat::Binary,
+            "Merkle claims are a binary layout (--format binary)"
+        );
+        let revealed: Vec<usize> = (0..claims.len()).collect();
+        let mut leaves = claims;
+        if !leaves.iter().any(|(claim_type, _)| *claim_type == 1) {
+            leaves.push((1, b"Sample Holder".to_vec()));
+        }
+        let (credential_data, claim_proofs) = merkle::encode(&leaves, &revealed);
+        credential.credential_data = credential_data;
+        credential.claim_proofs = claim_proofs;
+    }
 
-    println!("SP1 Credential Verifier Proof Generator");
-    println!("========================================");
+    // And sparse trees, which can also show a key was never issued
+    if claims_version == ClaimsVersion::V5 {
+        anyhow::ensure!(
+            format == CredentialFormat::Binary,
+            "Sparse Merkle claims are a binary layout (--format binary)"
+        );
+        let claims = keyed::sample_claims(credential_type, issued_at);
+        let mut proven: Vec<&str> = claims.iter().map(|(key, _)| *key).collect();
+        proven.push("sanctioned");
+        let (credential_data, sparse_claim_proofs) = smt::encode(&claims, &proven);
+        credential.credential_data = credential_data;
+        credential.sparse_claim_proofs = sparse_claim_proofs;
+    }
 
-    // Load or create credential
-    let credential: CredentialInput = if args.credential == "sample" {
-        println!("Creating sample credential...");
-        create_sample_credential(
-            "0x1234567890123456789012345678901234567890",
-            2, // Accredited investor
-        )?
-    } else {
-        println!("Loading credential from: {}", args.credential);
-        let content = std::fs::read_to_string(&args.credential)?;
-        serde_json::from_str(&content)?
+    // Sign with a fixed test key for the requested scheme
+    let message = signing_message(&credential);
+    let (signature, issuer_pubkey) = match scheme {
+        SignatureScheme::Secp256k1 => {
+            use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+
+            let signing_key = SigningKey::from_slice(&[0x11; 32])?;
+            let signature: Signature =
+                signing_key.sign_prehash(&message_digest(hash, &message))?;
+            let pubkey = signing_key.verifying_key().to_encoded_point(true);
+            (signature.to_vec(), pubkey.as_bytes().to_vec())
+        }
+        SignatureScheme::Secp256r1 => {
+            use p256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+
+            let signing_key = SigningKey::from_slice(&[0x11; 32])?;
+            let signature: Signature =
+                signing_key.sign_prehash(&message_digest(hash, &message))?;
+            let pubkey = signing_key.verifying_key().to_encoded_point(true);
+            (signature.to_vec(), pubkey.as_bytes().to_vec())
+        }
+        SignatureScheme::Bls12381 => {
+            let secret_key = bls::secret_key_from_seed(&[0x11; 64]);
+            (
+                bls::sign(&secret_key, &message).to_vec(),
+                bls::public_key(&secret_key).to_vec(),
+            )
+        }
+        SignatureScheme::Rsa2048Pkcs1v15 | SignatureScheme::Rsa2048Pss => {
+            use rsa::signature::{RandomizedSigner, SignatureEncoding, Signer};
+            use rsa::traits::PublicKeyParts;
+
+            // RSA keys can't be derived from a fixed seed cheaply, so the
+            // sample generates a fresh 2048-bit key each run
+            let mut rng = rand::thread_rng();
+            let private_key = rsa::RsaPrivateKey::new(&mut rng, 2048)?;
+            let modulus = private_key.n().to_bytes_be();
+            let signature = if scheme == SignatureScheme::Rsa2048Pss {
+                rsa::pss::BlindedSigningKey::<Sha256>::new(private_key)
+                    .sign_with_rng(&mut rng, &message)
+                    .to_vec()
+            } else {
+                rsa::pkcs1v15::SigningKey::<Sha256>::new(private_key)
+                    .sign(&message)
+                    .to_vec()
+            };
+            (signature, modulus)
+        }
+        SignatureScheme::Eip712 => {
+            let domain = Eip712Domain {
+                name: "Credence".to_string(),
+                version: "1".to_string(),
+                chain_id: 5003, // Mantle Sepolia
+                verifying_contract: [0u8; 20],
+            };
+            let signing_key = k256::ecdsa::SigningKey::from_slice(&[0x11; 32])?;
+            let digest = eip712::credential_digest(&credential, &domain);
+            credential.eip712_domain = Some(domain);
+            let pubkey = signing_key.verifying_key().to_encoded_point(true);
+            (
+                eip712::sign_digest(&signing_key, &digest)?,
+                pubkey.as_bytes().to_vec(),
+            )
+        }
+        SignatureScheme::BbsPlus => {
+            let secret_key = bls::secret_key_from_seed(&[0x11; 64]);
+            let messages = vec![
+                message,
+                b"given_name=Alice".to_vec(),
+                b"country=US".to_vec(),
+            ];
+            let signature = bbs::sign(&secret_key, &messages, b"sample");
+            credential.bbs = Some(BbsInput {
+                messages,
+                disclosed: vec![2], // reveal only the country
+            });
+            (signature.to_vec(), bbs::public_key(&secret_key).to_vec())
+        }
+        SignatureScheme::HmacSha256 => {
+            use hmac::{Hmac, Mac};
+            use sha2::Digest;
+
+            let key = vec![0x22; 32];
+            let mut mac = Hmac::<Sha256>::new_from_slice(&key)?;
+            mac.update(&message);
+            let tag = mac.finalize().into_bytes().to_vec();
+
+            // Public commitment to the key, mirrors `mac::key_commitment`
+            let mut hasher = Sha256::new();
+            hasher.update(b"CREDENCE_HMAC_KEY_V1");
+            hasher.update(&key);
+            let commitment = hasher.finalize().to_vec();
+
+            credential.hmac_key = Some(key);
+            (tag, commitment)
+        }
+        SignatureScheme::Rsa2048Blind => {
+            use rsa::traits::PublicKeyParts;
+
+            let mut rng = rand::thread_rng();
+            let private_key = rsa::RsaPrivateKey::new(&mut rng, 2048)?;
+            let public_key = private_key.to_public_key();
+
+            // The holder blinds the message so the issuer signs without
+            // ever seeing the credential
+            let (blinded, state) = blind::blind(&public_key, &message, &mut rng);
+            let blind_signature = blind::blind_sign(&private_key, &blinded);
+            (
+                blind::finalize(&public_key, &blind_signature, &state),
+                public_key.n().to_bytes_be(),
+            )
+        }
+        SignatureScheme::AnonCredsCl => {
+            anyhow::ensure!(
+                format == CredentialFormat::AnonCreds,
+                "CL signatures are only used by AnonCreds credentials (--format anon-creds)"
+            );
+            let mut rng = rand::thread_rng();
+            // The subject, type and validity period travel as attributes
+            let bound = [
+                format!("0x{}", hex::encode(credential.subject)),
+                credential_type_name(credential.credential_type),
+                credential.issued_at.to_string(),
+                credential.expires_at.to_string(),
+            ];
+            let values = [
+                ("subject", bound[0].as_str()),
+                ("credential_type", bound[1].as_str()),
+                ("issued_at", bound[2].as_str()),
+                ("expires_at", bound[3].as_str()),
+                ("name", "Alice"),
+                ("age", "30"),
+            ];
+            let attributes: Vec<&str> = values.iter().map(|(name, _)| *name).collect();
+            let key = anoncreds::generate_key(&attributes, &mut rng)?;
+            let link_secret = rsa::BigUint::from_bytes_be(&[0x33; 32]);
+            let issued = anoncreds::issue(&key, &values, &link_secret, &mut rng)?;
+
+            credential.credential_data = serde_json::to_vec(&issued)?;
+            credential.link_secret = Some(link_secret.to_bytes_be());
+            (
+                Vec::new(),
+                serde_json::to_vec(&anoncreds::public_key_json(&key))?,
+            )
+        }
     };
+    credential.signature = signature;
+    credential.issuer_pubkey = issuer_pubkey;
 
-    println!("Subject: 0x{}", hex::encode(credential.subject));
-    println!("Credential Type: {}", credential.credential_type);
-    println!("Issued At: {}", credential.issued_at);
-    println!("Expires At: {}", credential.expires_at);
+    // JWTs carry the claims and signature in the token itself
+    if matches!(format, CredentialFormat::Jwt | CredentialFormat::SdJwt) {
+        anyhow::ensure!(
+            scheme == SignatureScheme::Secp256k1,
+            "JWT credentials are signed with ES256K (--scheme secp256k1)"
+        );
+        let signing_key = k256::ecdsa::SigningKey::from_slice(&[0x11; 32])?;
+        let token = if format == CredentialFormat::SdJwt {
+            let hidden = [
+                ("given_name", serde_json::json!("Alice")),
+                ("country", serde_json::json!("US")),
+            ];
+            // Reveal only the country
+            jwt::encode_sd_credential(&credential, &signing_key, &hidden, &["country"])?
+        } else {
+            jwt::encode_credential(&credential, &signing_key)?
+        };
+        credential.credential_data = token.into_bytes();
+        credential.signature = Vec::new();
+    }
 
-    // Initialize the prover
-    println!("\nInitializing SP1 prover...");
-    let client = ProverClient::new();
+    // mDLs carry the validity period and signature in the issuer's MSO
+    if format == CredentialFormat::Mdl {
+        anyhow::ensure!(
+            scheme == SignatureScheme::Secp256r1,
+            "mDL credentials are signed with ES256 (--scheme secp256r1)"
+        );
+        let signing_key = p256::ecdsa::SigningKey::from_slice(&[0x11; 32])?;
+        let elements = [
+            ("family_name", coset::cbor::value::Value::Text("Doe".to_string())),
+            ("age_over_18", coset::cbor::value::Value::Bool(true)),
+        ];
+        // The MSO binds the holder by their device key
+        let holder_key = k256::ecdsa::SigningKey::from_slice(&SAMPLE_SUBJECT_KEY)?;
+        anyhow::ensure!(
+            credential.subject == sample_subject_address()?,
+            "mDL samples are held by the sample subject"
+        );
+        // Present only the age attestation
+        credential.credential_data = mdl::encode_credential(
+            &credential,
+            &signing_key,
+            holder_key.verifying_key(),
+            &elements,
+            &["age_over_18"],
+        )?;
+        credential.signature = Vec::new();
+    }
 
-    // Setup the program
-    let (pk, vk) = client.setup(ELF);
-    println!("Program VKey: 0x{}", hex::encode(vk.bytes32()));
+    // Linked-data credentials sign the canonical dataset's verify data
+    if format == CredentialFormat::LdProof {
+        anyhow::ensure!(
+            scheme == SignatureScheme::Secp256r1,
+            "ecdsa-rdfc-2019 proofs are signed with ES256 (--scheme secp256r1)"
+        );
+        let signing_key = p256::ecdsa::SigningKey::from_slice(&[0x11; 32])?;
+        let (credential_data, signature) = ld::encode_credential(&credential, &signing_key)?;
+        credential.credential_data = credential_data;
+        credential.signature = signature;
+    }
 
-    // Prepare inputs
-    let mut stdin = SP1Stdin::new();
-    stdin.write(&credential);
+    Ok(credential)
+}
 
-    println!("\nGenerating proof (this may take a while)...");
+/// Sets up the prover and keys `--batch-dir`, `--watch`, `--serve` and
+/// `--grpc` share
+fn batch_prover(args: &Args) -> Result<(prover::Prover, SP1ProvingKey, SP1VerifyingKey)> {
+    let prover = prover::Prover::new(
+        args.prover,
+        args.network_timeout.map(Duration::from_secs),
+        !args.no_fallback,
+    )?;
+    let (pk, vk) = prover.client().setup(ELF);
+    info!(backend = ?prover.backend(), vkey = vk.bytes32(), "Set up the prover");
+    Ok((prover, pk, vk))
+}
 
-    // Generate the proof
-    let proof = if args.plonk {
-        println!("Generating PLONK proof for on-chain verification...");
-        client.prove(&pk, stdin).plonk().run()?
-    } else {
-        println!("Generating core proof...");
-        client.prove(&pk, stdin).run()?
+/// Batch settings of the arguments
+fn batch_options<'a>(
+    args: &Args,
+    prover: &'a prover::Prover,
+    pk: &'a SP1ProvingKey,
+    vkey: String,
+    cache: Option<&'a cache::ProofCache>,
+    trusted_issuers: &'a [Vec<u8>],
+) -> batch::BatchOptions<'a> {
+    batch::BatchOptions {
+        prover,
+        pk,
+        vkey,
+        mode: args.mode,
+        jobs: args.jobs,
+        nonce: args.nonce,
+        chain_id: args.chain_id,
+        verifying_contract: args.verifying_contract,
+        cache,
+        trusted_issuers,
+    }
+}
+
+/// Cache of `--cache-dir`
+fn proof_cache(args: &Args) -> Result<Option<cache::ProofCache>> {
+    args.cache_dir
+        .as_deref()
+        .map(cache::ProofCache::open)
+        .transpose()
+}
+
+/// Proves every credential file in `dir` (`--batch-dir`)
+fn prove_batch(args: &Args, dir: &Path, output_dir: &Path) -> Result<()> {
+    info!(dir = %dir.display(), "Proving the credentials");
+    let (prover, pk, vk) = batch_prover(args)?;
+    let cache = proof_cache(args)?;
+    let trusted_issuers = trusted::parse_keys(&args.trusted_issuer)?;
+    let options = batch_options(
+        args,
+        &prover,
+        &pk,
+        vk.bytes32(),
+        cache.as_ref(),
+        &trusted_issuers,
+    );
+    let manifest = batch::prove_dir(dir, output_dir, &options)?;
+
+    info!(
+        proven = manifest.proven,
+        failed = manifest.failed,
+        seconds = manifest.seconds,
+        manifest = %output_dir.join(batch::MANIFEST).display(),
+        "Batch proving complete"
+    );
+    Ok(())
+}
+
+/// Proves the credential files arriving in `inbox` until stopped (`--watch`)
+fn watch(args: &Args, inbox: &Path, output_dir: &Path) -> Result<()> {
+    let (prover, pk, vk) = batch_prover(args)?;
+    let cache = proof_cache(args)?;
+    let trusted_issuers = trusted::parse_keys(&args.trusted_issuer)?;
+    let options = batch_options(
+        args,
+        &prover,
+        &pk,
+        vk.bytes32(),
+        cache.as_ref(),
+        &trusted_issuers,
+    );
+
+    // The signal handler runs on the runtime's workers while this thread
+    // scans and proves
+    let shutdown = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    tokio::spawn(daemon::wait_for_signal(shutdown.clone()));
+    info!(inbox = %inbox.display(), "Watching for credentials");
+    daemon::watch(
+        inbox,
+        output_dir,
+        &options,
+        Duration::from_secs(args.poll_interval),
+        &shutdown,
+    )?;
+    info!("Daemon stopped");
+    Ok(())
+}
+
+/// Serves the REST proving API and gRPC service until stopped (`--serve`
+/// and `--grpc`)
+async fn serve(args: &Args, output_dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(output_dir)
+        .with_context(|| format!("Can't create {}", output_dir.display()))?;
+    let storage = storage::open(args.database.as_deref(), &output_dir.join("jobs"))?;
+    let (jobs, interrupted) = jobs::JobStore::open(storage)?;
+    let (prover, pk, vk) = batch_prover(args)?;
+    let state = api::ApiState {
+        prover,
+        pk,
+        vkey: vk.bytes32(),
+        vk,
+        mode: args.mode,
+        output_dir: output_dir.to_path_buf(),
+        nonce: args.nonce,
+        chain_id: args.chain_id,
+        verifying_contract: args.verifying_contract,
+        cache: proof_cache(args)?,
+        trusted_issuers: trusted::parse_keys(&args.trusted_issuer)?,
+        clients: args
+            .api_keys
+            .as_deref()
+            .map(clients::Clients::load)
+            .transpose()?,
+        provers: tokio::sync::Semaphore::new(args.jobs.max(1)),
+        jobs,
+        proven: Default::default(),
+        metrics: Default::default(),
+    };
+    let state = std::sync::Arc::new(state);
+    state.resume(interrupted);
+    let rest = async {
+        match args.serve {
+            Some(port) => api::serve(state.clone(), port).await,
+            None => Ok(()),
+        }
     };
+    let grpc = async {
+        match args.grpc {
+            Some(port) => grpc::serve(state.clone(), port).await,
+            None => Ok(()),
+        }
+    };
+    tokio::try_join!(rest, grpc)?;
+    Ok(())
+}
+
+/// Seconds since the Unix epoch
+fn unix_time() -> Result<u64> {
+    Ok(std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs())
+}
 
-    println!("Proof generated successfully!");
+/// Reads a proof's outputs back from its public values (or, for digest
+/// proofs, the executed `full_values` they commit to) and wraps the proof
+/// in its envelope
+fn proof_output(
+    proof: &SP1ProofWithPublicValues,
+    vkey: String,
+    mode: ProofMode,
+    started_at: u64,
+    input: &ProgramInput,
+    credentials: &[CredentialInput],
+    full_values: Option<&[u8]>,
+) -> Result<(ProofOutput, Vec<CredentialOutput>)> {
+    let credential = &credentials[0];
 
     // Extract public values
     let public_values = proof.public_values.to_vec();
-    println!("Public values length: {} bytes", public_values.len());
+    tracing::debug!(bytes = public_values.len(), "Public values");
+    let values = match full_values {
+        Some(values) => {
+            anyhow::ensure!(
+                public_values == eip712::keccak256(values),
+                "Public values don't commit to the executed outputs"
+            );
+            values
+        }
+        None => &public_values,
+    };
+
+    // Read the credential hashes back from the committed values rather
+    // than recomputing them, so they always match the circuit's hash
+    // selection
+    let outputs::DecodedOutputs {
+        outputs,
+        policy_satisfied,
+    } = outputs::decode(input, values)?;
+    anyhow::ensure!(
+        outputs.len() == credentials.len(),
+        "Public values don't hold an output per credential"
+    );
+    let credential_hashes: Vec<String> = outputs
+        .iter()
+        .map(|output| format!("0x{}", hex::encode(output.credentialHash)))
+        .collect();
 
-    // Compute credential hash for output
-    let mut hasher = Sha256::new();
-    hasher.update(&credential.subject);
-    hasher.update(credential.credential_type.to_be_bytes());
-    hasher.update(&credential.credential_data);
-    hasher.update(&credential.issuer_pubkey);
-    let credential_hash = hasher.finalize();
+    let predicates_satisfied = (!credential.predicates.is_empty())
+        .then(|| outputs[0].predicateResults.count_ones() as usize == credential.predicates.len());
+
+    let status = &outputs[0].status;
+    let revocation = RevocationMethod::from_u8(status.revocationMethod)
+        .filter(|method| *method != RevocationMethod::None)
+        .map(|method| RevocationOutput {
+            method,
+            root: format!("0x{}", hex::encode(status.revocationRoot)),
+            version: status.revocationVersion,
+            max_age: status.revocationMaxAge,
+            purpose: StatusPurpose::from_u8(status.statusPurpose).expect("Unknown status purpose"),
+        });
 
-    // Create output
     let output = ProofOutput {
+        envelope_version: ENVELOPE_VERSION,
+        program_version: env!("CARGO_PKG_VERSION").to_string(),
+        output_version: outputs[0].version,
+        mode,
+        started_at,
+        completed_at: unix_time()?,
         proof: hex::encode(proof.bytes()),
         public_values: hex::encode(&public_values),
-        vkey: hex::encode(vk.bytes32()),
+        vkey,
         subject: format!("0x{}", hex::encode(credential.subject)),
         credential_type: credential.credential_type,
-        credential_hash: format!("0x{}", hex::encode(credential_hash)),
+        credential_hash: credential_hashes[0].clone(),
+        credential_hashes,
+        policy_satisfied,
+        subject_salt: credential
+            .subject_salt
+            .map(|salt| format!("0x{}", hex::encode(salt))),
+        predicates_satisfied,
+        revocation,
+        outputs: full_values.map(hex::encode),
+        instruction_data: matches!(input, ProgramInput::Solana(_))
+            .then(|| hex::encode(solana::instruction_data(&proof.bytes(), &public_values))),
+        sp1_proof: proof.clone(),
+    };
+    Ok((output, outputs))
+}
+
+/// Saves the proof envelope as JSON at `path`
+fn save_proof(output: &ProofOutput, path: &Path) -> Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(output)?)
+        .with_context(|| format!("Can't write {}", path.display()))
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args: Args = config::parse(SETTINGS)?;
+    args.log.init()?;
+    anyhow::ensure!(
+        !args.solana || args.mode == ProofMode::Groth16,
+        "The SP1 Solana verifier only verifies Groth16 proofs"
+    );
+    anyhow::ensure!(
+        args.cosmwasm_output.is_none() || args.mode == ProofMode::Groth16,
+        "CosmWasm verifiers only verify Groth16 proofs"
+    );
+
+    if let (Some(dir), Some(output_dir)) = (&args.batch_dir, &args.output_dir) {
+        return prove_batch(&args, dir, output_dir);
+    }
+    if let (Some(inbox), Some(output_dir)) = (&args.watch, &args.output_dir) {
+        return watch(&args, inbox, output_dir);
+    }
+    if args.serve.is_some() || args.grpc.is_some() {
+        let output_dir = args
+            .output_dir
+            .as_deref()
+            .expect("clap requires --output-dir with --serve or --grpc");
+        return serve(&args, output_dir).await;
+    }
+    let credential_path = args
+        .credential
+        .as_deref()
+        .expect("clap requires --credential without --batch-dir, --watch, --serve or --grpc");
+
+    let bindings = HolderBindings {
+        scope: args.scope.as_deref(),
+        device_bound: args.device_bound,
+        biometric: args.biometric,
+        serial: args.serial,
+        status_entry: args.status_index.map(|index| {
+            let purpose = if args.suspension {
+                StatusPurpose::Suspension
+            } else {
+                StatusPurpose::Revocation
+            };
+            (index, purpose)
+        }),
+        disclosure_seed: (!args.reveal.is_empty()).then(rand::random),
+    };
+
+    // Load or create credential
+    let mut credential: CredentialInput = if credential_path == "sample" {
+        info!("Creating a sample credential");
+        // Ring proofs, holder binding and mDLs need a subject whose key the
+        // sample holds
+        let subject = if args.ring_event.is_some()
+            || args.holder_binding
+            || args.format == CredentialFormat::Mdl
+        {
+            hex::encode(sample_subject_address()?)
+        } else {
+            "0x1234567890123456789012345678901234567890".to_string()
+        };
+        let mut credential = create_sample_credential(
+            &subject,
+            args.credential_type,
+            args.scheme,
+            args.hash,
+            args.format,
+            args.claims,
+            bindings,
+        )?;
+        if args.vrf {
+            anyhow::ensure!(
+                args.scheme == SignatureScheme::Secp256r1,
+                "VRF proofs are evaluated with a P-256 key (--scheme secp256r1)"
+            );
+            let secret_key = p256::SecretKey::from_slice(&[0x11; 32])?;
+            let message = signing_message(&credential);
+            credential.vrf_proof =
+                Some(vrf::prove(&secret_key, &message, &mut rand::thread_rng()));
+        }
+        if args.delegate {
+            anyhow::ensure!(
+                args.scheme == SignatureScheme::Secp256k1,
+                "Sample delegation chains use secp256k1 keys (--scheme secp256k1)"
+            );
+            credential.delegation = Some(delegation::sample_chain(
+                &credential.issuer_pubkey,
+                credential.credential_type,
+                credential.expires_at,
+            )?);
+        }
+        if args.key_registry {
+            anyhow::ensure!(
+                args.scheme == SignatureScheme::Secp256k1,
+                "Sample key registries use secp256k1 keys (--scheme secp256k1)"
+            );
+            credential.key_registry =
+                Some(key_registry::sample_registry(&credential.issuer_pubkey)?);
+        }
+        if args.schema {
+            anyhow::ensure!(
+                args.format == CredentialFormat::Binary && args.claims == ClaimsVersion::V2,
+                "Claim schemas describe v2 binary claims (--format binary --claims v2)"
+            );
+            let claims = sample_claims(credential.credential_type);
+            credential.claim_schema = Some(schema::describe(&claims));
+        }
+        credential
+    } else {
+        info!(path = credential_path, "Loading the credential");
+        file::load_credential(Path::new(credential_path))
+            .with_context(|| format!("Invalid credential file {}", credential_path))?
+    };
+
+    // Bind the proof to the verifier's challenge
+    if let Some(nonce) = args.nonce {
+        credential.verifier_nonce = nonce;
+    }
+
+    // Bind the proof to the verifier's chain and contract
+    if args.chain_id.is_some() {
+        credential.chain_id = args.chain_id;
+    }
+    if args.verifying_contract.is_some() {
+        credential.verifying_contract = args.verifying_contract;
+    }
+
+    // Hide the subject behind a fresh salt
+    if args.hide_subject {
+        credential.subject_salt = Some(rand::random());
+    }
+
+    // Ask for predicates over claims instead of the claims themselves
+    if let Some(years) = args.min_age {
+        credential.predicates.push(Predicate::AgeAtLeast(years));
+    }
+    credential.predicates.extend(args.range);
+    if let Some(days) = args.min_credential_age_days {
+        credential
+            .predicates
+            .push(Predicate::IssuedAtLeastDaysAgo(days));
+    }
+    if !args.allowed_countries.is_empty() {
+        use sha2::Digest;
+        let mut members: Vec<[u8; 32]> = args
+            .allowed_countries
+            .iter()
+            .map(|country| Sha256::digest(country.as_bytes()).into())
+            .collect();
+        members.sort_unstable();
+        members.dedup();
+        credential.predicates.push(Predicate::InSet {
+            claim: "residenceCountry".to_string(),
+            members,
+        });
+    }
+
+    // Reveal chosen claims and withhold the rest, handing the verifier the
+    // salts of the revealed ones
+    credential.revealed_claims = args.reveal;
+    if let Some(seed) = &credential.disclosure_seed {
+        for &position in &credential.revealed_claims {
+            let salt = message::claim_salt(seed, position);
+            info!(position, salt = hex::encode(salt), "Revealed claim salt");
+        }
+    }
+
+    // Hide the issuer among a trusted set
+    if args.anonymous_issuer {
+        let (root, membership) =
+            trusted::sample_membership(credential.signature_scheme, &credential.issuer_pubkey);
+        info!(root = hex::encode(root), "Trusted issuers");
+        credential.issuer_membership = Some(membership);
+    }
+
+    // List the accountable issuer in an on-chain registry
+    if args.issuer_registry {
+        let issuer_key = trusted::accountable_key(&credential);
+        let (root, path) = trusted::sample_membership(credential.signature_scheme, issuer_key);
+        info!(root = hex::encode(root), "Issuer registry");
+        credential.issuer_registry = Some(path);
+    }
+
+    // Screen the subject against a sanctions list
+    if args.sanctions_screen {
+        let (root, exclusion) = sanctions::sample_exclusion(&credential.subject)?;
+        info!(root = hex::encode(root), "Sanctions list");
+        credential.sanctions_exclusion = Some(exclusion);
+    }
+
+    // Show the issuer hasn't revoked the credential, keeping the committed
+    // registry for its publication time
+    let mut revocation_registry = credential.status_list.as_ref().map(|entry| {
+        let root = message_digest(credential.hash_algorithm, &entry.list_credential);
+        (RevocationMethod::StatusList2021, root, entry.list_version)
+    });
+    if args.revocation_check {
+        let (root, exclusion) = revocation::sample_exclusion(&sha256_credential_hash(&credential))?;
+        info!(root = hex::encode(root), "Revocation list");
+        revocation_registry = Some((RevocationMethod::MerkleList, root, exclusion.list_version));
+        credential.revocation_exclusion = Some(exclusion);
+    }
+    let accumulator = if args.accumulator_check {
+        let accumulator = accumulator::SampleAccumulator::new()?;
+        let witness = accumulator.witness(&sha256_credential_hash(&credential))?;
+        let root = accumulator::accumulator_hash(&witness, credential.hash_algorithm);
+        revocation_registry = Some((RevocationMethod::Accumulator, root, witness.version));
+        credential.accumulator_witness = Some(witness);
+        Some(accumulator)
+    } else {
+        None
     };
 
+    // Have the issuer attest how recent that registry's root is
+    if let Some(max_age) = args.revocation_max_age {
+        anyhow::ensure!(
+            matches!(
+                args.scheme,
+                SignatureScheme::Secp256k1 | SignatureScheme::Eip712
+            ),
+            "Sample publication times are signed with secp256k1 (--scheme secp256k1 or eip712)"
+        );
+        anyhow::ensure!(
+            !matches!(credential.hash_algorithm, HashAlgorithm::Poseidon),
+            "Poseidon registry roots are only computed in the circuit"
+        );
+        let (method, root, version) = revocation_registry
+            .ok_or_else(|| anyhow::anyhow!("--revocation-max-age requires a revocation check"))?;
+        credential.revocation_freshness = Some(freshness::sample_freshness(
+            args.scheme,
+            method,
+            &root,
+            version,
+            credential.hash_algorithm,
+            credential.current_time,
+            max_age,
+        )?);
+    }
+
+    // Appear under a per-verifier pseudonym
+    if let Some(verifier_id) = &args.verifier_id {
+        use sha2::Digest;
+        anyhow::ensure!(credential.nullifier.is_some(), "--verifier-id requires --scope");
+        credential.verifier_id = Some(Sha256::digest(verifier_id.as_bytes()).into());
+    }
+
+    // Coarsen the committed times
+    credential.timestamp_granularity = args.timestamp_granularity;
+
+    // Blind the committed hash for this verifier
+    credential.credential_hash_blind = args.blind;
+
+    // Answer the verifier's nonce from the bound passkey
+    if credential.device_pubkey.is_some() {
+        let nonce = args
+            .nonce
+            .ok_or_else(|| anyhow::anyhow!("--device-bound requires --nonce"))?;
+        credential.device_assertion = Some(webauthn::sample_assertion(&nonce)?);
+    }
+
+    // Sign the verifier's nonce with the subject key
+    if args.holder_binding {
+        let nonce = args
+            .nonce
+            .ok_or_else(|| anyhow::anyhow!("--holder-binding requires --nonce"))?;
+        credential.holder_signature = Some(sign_holder_binding(&nonce)?);
+    }
+
+    // Act anonymously within a ring of eligible holders
+    if let Some(event) = &args.ring_event {
+        use sha2::Digest;
+        anyhow::ensure!(args.blind.is_some(), "--ring-event requires --blind");
+        let (root, membership) = ring::sample_membership(Sha256::digest(event.as_bytes()).into())?;
+        info!(root = hex::encode(root), "Ring");
+        credential.ring = Some(membership);
+    }
+
+    // Batches verify a KYC credential for the same subject alongside it
+    let mut credentials = vec![credential.clone()];
+    if args.batch {
+        anyhow::ensure!(
+            credential_path == "sample",
+            "--batch requires --credential sample"
+        );
+        let mut kyc = create_sample_credential(
+            &hex::encode(credential.subject),
+            1, // KYC
+            args.scheme,
+            args.hash,
+            args.format,
+            args.claims,
+            // The KYC credential has no serial or status entry of its own
+            HolderBindings {
+                serial: None,
+                status_entry: None,
+                ..bindings
+            },
+        )?;
+        kyc.verifier_nonce = credential.verifier_nonce;
+        kyc.chain_id = credential.chain_id;
+        kyc.verifying_contract = credential.verifying_contract;
+        kyc.subject_salt = credential.subject_salt;
+        kyc.issuer_membership = credential.issuer_membership.clone();
+        if args.issuer_registry {
+            let (_, path) = trusted::sample_membership(kyc.signature_scheme, &kyc.issuer_pubkey);
+            kyc.issuer_registry = Some(path);
+        }
+        kyc.sanctions_exclusion = credential.sanctions_exclusion.clone();
+        kyc.verifier_id = credential.verifier_id;
+        kyc.timestamp_granularity = credential.timestamp_granularity;
+        kyc.credential_hash_blind = credential.credential_hash_blind;
+        kyc.ring = credential.ring.clone();
+        kyc.holder_signature = credential.holder_signature.clone();
+        kyc.device_assertion = credential.device_assertion.clone();
+        if args.revocation_check {
+            let (_, exclusion) = revocation::sample_exclusion(&sha256_credential_hash(&kyc))?;
+            kyc.revocation_exclusion = Some(exclusion);
+        }
+        if let Some(accumulator) = &accumulator {
+            kyc.accumulator_witness = Some(accumulator.witness(&sha256_credential_hash(&kyc))?);
+        }
+        // Both credentials share the list or accumulator, and so its
+        // publication time
+        if kyc.revocation_exclusion.is_some() || kyc.accumulator_witness.is_some() {
+            kyc.revocation_freshness = credential.revocation_freshness.clone();
+        }
+        credentials.push(kyc);
+    }
+
+    let trusted_issuers = trusted::parse_keys(&args.trusted_issuer)?;
+    for credential in &credentials {
+        trusted::check(&trusted_issuers, credential)?;
+    }
+
+    info!(
+        subject = hex::encode(credential.subject),
+        credential_type = credential.credential_type,
+        credentials = credentials.len(),
+        scheme = ?credential.signature_scheme,
+        issued_at = credential.issued_at,
+        expires_at = credential.expires_at,
+        "Credential"
+    );
+
+    // Initialize the prover
+    let prover = prover::Prover::new(
+        args.prover,
+        args.network_timeout.map(Duration::from_secs),
+        !args.no_fallback,
+    )?;
+    let client = prover.client();
+
+    // Setup the program
+    let (pk, vk) = client.setup(ELF);
+    info!(backend = ?prover.backend(), vkey = vk.bytes32(), "Set up the prover");
+
+    // Prepare inputs in the program's borsh layout
+    let input = match &args.policy {
+        Some(expression) => ProgramInput::Policy(PolicyInput {
+            policy: policy::parse(expression)?,
+            credentials: credentials.clone(),
+        }),
+        None if credentials.len() == 1 => ProgramInput::Single(Box::new(credential.clone())),
+        None => ProgramInput::Batch(credentials.clone()),
+    };
+    let input = if args.solana {
+        ProgramInput::Solana(Box::new(input))
+    } else if args.move_outputs {
+        ProgramInput::Move(Box::new(input))
+    } else {
+        input
+    };
+
+    // A digest proof commits only the hash of the public values, so execute
+    // the input first for the values the contract is passed
+    let (proven_input, full_values) = if args.digest {
+        let mut stdin = SP1Stdin::new();
+        stdin.write_vec(credential_verifier_lib::encode(&input));
+        let (values, _) = info_span!("execute").in_scope(|| client.execute(ELF, stdin).run())?;
+        let digest = ProgramInput::Digest(Box::new(input.clone()));
+        (digest, Some(values.to_vec()))
+    } else {
+        (input.clone(), None)
+    };
+    let mut stdin = SP1Stdin::new();
+    stdin.write_vec(credential_verifier_lib::encode(&proven_input));
+
+    let started_at = unix_time()?;
+    let proof = prover.prove(&pk, stdin, args.mode)?;
+
+    let (output, outputs) = proof_output(
+        &proof,
+        vk.bytes32(),
+        args.mode,
+        started_at,
+        &input,
+        &credentials,
+        full_values.as_deref(),
+    )?;
+
     // Save proof
-    let output_json = serde_json::to_string_pretty(&output)?;
-    std::fs::write(&args.output, &output_json)?;
-    println!("\nProof saved to: {}", args.output);
+    let output_path = match &args.output_dir {
+        Some(dir) => {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Can't create {}", dir.display()))?;
+            dir.join(format!("{}.json", output.subject))
+        }
+        None => PathBuf::from(&args.output),
+    };
+    save_proof(&output, &output_path)?;
+    info!(path = %output_path.display(), "Saved the proof");
+
+    if let Some(path) = &args.cosmwasm_output {
+        let msg = cosmwasm::execute_msg(
+            &proof.bytes(),
+            proof.public_values.as_slice(),
+            vk.bytes32(),
+            &outputs,
+            output.policy_satisfied,
+        );
+        std::fs::write(path, serde_json::to_string_pretty(&msg)?)?;
+        info!(path, "Saved the CosmWasm message");
+    }
 
     // Verify the proof locally
-    println!("\nVerifying proof locally...");
-    client.verify(&proof, &vk)?;
-    println!("Local verification successful!");
-
-    // Print summary
-    println!("\n========================================");
-    println!("Proof Generation Complete!");
-    println!("========================================");
-    println!("VKey: 0x{}", hex::encode(vk.bytes32()));
-    println!("Subject: {}", output.subject);
-    println!("Credential Type: {}", output.credential_type);
-    println!("Credential Hash: {}", output.credential_hash);
-    println!("\nTo verify on-chain, call SP1CredentialVerifier.verifyCredential()");
-    println!("with the public values and proof bytes from {}", args.output);
+    info_span!("verify").in_scope(|| client.verify(&proof, &vk))?;
+    info!("Verified the proof locally");
+
+    if let (Some(eas), Some(schema), Some(rpc_url)) = (args.eas, args.eas_schema, &args.rpc_url) {
+        info!(
+            schema = eas::schema(),
+            "Attesting the credential through EAS"
+        );
+        let private_key = std::env::var("EAS_PRIVATE_KEY").context("EAS_PRIVATE_KEY is not set")?;
+        let vkey = parse_hex32(&vk.bytes32()).map_err(anyhow::Error::msg)?;
+        let calldata = eas::attest_calldata(schema, &outputs[0], vkey);
+        let transaction = eas::submit(rpc_url, &private_key, eas, calldata).await?;
+        info!(transaction = ?transaction, "Attested the credential");
+    }
+
+    if let Some(revocation) = &output.revocation {
+        info!(
+            method = ?revocation.method,
+            version = revocation.version,
+            root = revocation.root,
+            purpose = ?revocation.purpose,
+            "Revocation registry"
+        );
+    }
+    // SP1CredentialVerifier.verifyCredential() takes the public values and
+    // proof bytes of the envelope
+    info!(
+        vkey = vk.bytes32(),
+        subject = output.subject,
+        credential_type = output.credential_type,
+        credential_hash = output.credential_hash,
+        policy_satisfied = output.policy_satisfied,
+        predicates_satisfied = output.predicates_satisfied,
+        "Proof generation complete"
+    );
 
     Ok(())
 }
@@ -232,6 +1766,11 @@ mod tests {
         let credential = create_sample_credential(
             "0x1234567890123456789012345678901234567890",
             1,
+            SignatureScheme::Secp256k1,
+            HashAlgorithm::Sha256,
+            CredentialFormat::Binary,
+            ClaimsVersion::V2,
+            HolderBindings::default(),
         )
         .unwrap();
 
@@ -240,4 +1779,88 @@ mod tests {
         assert_eq!(credential.signature.len(), 64);
         assert_eq!(credential.issuer_pubkey.len(), 33);
     }
+
+    #[test]
+    fn test_create_sample_bls_credential() {
+        let credential = create_sample_credential(
+            "0x1234567890123456789012345678901234567890",
+            2,
+            SignatureScheme::Bls12381,
+            HashAlgorithm::Sha256,
+            CredentialFormat::Binary,
+            ClaimsVersion::V2,
+            HolderBindings::default(),
+        )
+        .unwrap();
+
+        assert_eq!(credential.signature.len(), 96);
+        assert_eq!(credential.issuer_pubkey.len(), 48);
+    }
+
+    #[test]
+    fn test_parse_hex32() {
+        assert_eq!(
+            parse_hex32(&format!("0x{}", "ab".repeat(32))),
+            Ok([0xab; 32])
+        );
+        assert!(parse_hex32("abcd").is_err());
+        assert!(parse_hex32("zz").is_err());
+    }
+
+    #[test]
+    fn test_holder_binding_recovers_subject() {
+        use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+        let nonce = [0x42; 32];
+        let signature = sign_holder_binding(&nonce).unwrap();
+        let mut prefixed = b"\x19Ethereum Signed Message:\n32".to_vec();
+        prefixed.extend_from_slice(&nonce);
+        let recovered = VerifyingKey::recover_from_prehash(
+            &eip712::keccak256(&prefixed),
+            &Signature::from_slice(&signature[..64]).unwrap(),
+            RecoveryId::from_byte(signature[64] - 27).unwrap(),
+        )
+        .unwrap();
+        let uncompressed = recovered.to_encoded_point(false);
+        let address = &eip712::keccak256(&uncompressed.as_bytes()[1..])[12..];
+        assert_eq!(address, sample_subject_address().unwrap());
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(
+            parse_range("annualIncome:20000000::USD:2"),
+            Ok(Predicate::InRange {
+                claim: "annualIncome".to_string(),
+                unit: Some(*b"USD"),
+                precision: 2,
+                min: 20_000_000,
+                max: u64::MAX,
+            })
+        );
+        assert!(parse_range("kycLevel:2").is_err());
+        assert!(parse_range("netWorth:1:2:DOLLARS:0").is_err());
+    }
+
+    #[test]
+    fn test_revocation_exclusion_brackets_credential_hash() {
+        let mut credential = create_sample_credential(
+            "0x1234567890123456789012345678901234567890",
+            1,
+            SignatureScheme::Secp256k1,
+            HashAlgorithm::Keccak256,
+            CredentialFormat::Binary,
+            ClaimsVersion::V2,
+            HolderBindings::default(),
+        )
+        .unwrap();
+
+        let hash = sha256_credential_hash(&credential);
+        let (_, exclusion) = revocation::sample_exclusion(&hash).unwrap();
+        assert!(exclusion.lower < hash && hash < exclusion.upper);
+
+        // Switching suites doesn't move the credential off the list
+        credential.hash_algorithm = HashAlgorithm::Poseidon;
+        assert_eq!(sha256_credential_hash(&credential), hash);
+    }
 }
@@ -0,0 +1,132 @@
+//! ISO 18013-5 mDL helpers for building test credentials
+//!
+//! Produces the CBOR `IssuerSigned` structure the program parses in
+//! `CredentialFormat::Mdl` mode: a minimal Mobile Security Object signed
+//! with ES256 in a COSE_Sign1, plus the presented data elements. The MSO's
+//! `docType` names the credential type and its device key is the holder's
+//! secp256k1 key.
+
+use anyhow::Result;
+use coset::cbor::value::Value;
+use coset::{iana, AsCborValue, CoseKeyBuilder, CoseSign1Builder, HeaderBuilder};
+use credential_verifier_lib::credential_type_name;
+use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+use crate::{vc, CredentialInput};
+
+/// Namespace of the standard mDL data elements
+pub const NAMESPACE: &str = "org.iso.18013.5.1";
+
+/// Builds a CBOR text value
+fn text(value: &str) -> Value {
+    Value::Text(value.to_string())
+}
+
+/// Encodes a value as `#6.24(bstr .cbor value)`
+fn embed(value: &Value) -> Result<Value> {
+    let mut encoded = Vec::new();
+    coset::cbor::into_writer(value, &mut encoded)?;
+    Ok(Value::Tag(24, Box::new(Value::Bytes(encoded))))
+}
+
+/// Encodes Unix seconds as a `#6.0(tdate)` value
+fn tdate(timestamp: u64) -> Result<Value> {
+    Ok(Value::Tag(
+        0,
+        Box::new(Value::Text(vc::rfc3339(timestamp)?)),
+    ))
+}
+
+/// Encodes an mDL over `elements` for the holder's `device_key`,
+/// presenting only those in `disclosed`
+pub fn encode_credential(
+    credential: &CredentialInput,
+    signing_key: &SigningKey,
+    device_key: &k256::ecdsa::VerifyingKey,
+    elements: &[(&str, Value)],
+    disclosed: &[&str],
+) -> Result<Vec<u8>> {
+    let mut digests = Vec::new();
+    let mut presented = Vec::new();
+    for (digest_id, (identifier, value)) in elements.iter().enumerate() {
+        let mut random = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut random);
+        let item = embed(&Value::Map(vec![
+            (text("digestID"), Value::Integer((digest_id as u64).into())),
+            (text("random"), Value::Bytes(random.to_vec())),
+            (text("elementIdentifier"), text(identifier)),
+            (text("elementValue"), value.clone()),
+        ]))?;
+
+        let mut encoded = Vec::new();
+        coset::cbor::into_writer(&item, &mut encoded)?;
+        digests.push((
+            Value::Integer((digest_id as u64).into()),
+            Value::Bytes(Sha256::digest(&encoded).to_vec()),
+        ));
+        if disclosed.contains(identifier) {
+            presented.push(item);
+        }
+    }
+
+    let point = device_key.to_encoded_point(false);
+    let device_key = CoseKeyBuilder::new_ec2_pub_key(
+        iana::EllipticCurve::Secp256k1,
+        point.x().expect("uncompressed point").to_vec(),
+        point.y().expect("uncompressed point").to_vec(),
+    )
+    .build();
+
+    let mso = Value::Map(vec![
+        (text("version"), text("1.0")),
+        (text("digestAlgorithm"), text("SHA-256")),
+        (
+            text("valueDigests"),
+            Value::Map(vec![(text(NAMESPACE), Value::Map(digests))]),
+        ),
+        (
+            text("deviceKeyInfo"),
+            Value::Map(vec![(text("deviceKey"), device_key.to_cbor_value()?)]),
+        ),
+        (
+            text("docType"),
+            text(&credential_type_name(credential.credential_type)),
+        ),
+        (
+            text("validityInfo"),
+            Value::Map(vec![
+                (text("signed"), tdate(credential.issued_at)?),
+                (text("validFrom"), tdate(credential.issued_at)?),
+                (text("validUntil"), tdate(credential.expires_at)?),
+            ]),
+        ),
+    ]);
+    let mut payload = Vec::new();
+    coset::cbor::into_writer(&embed(&mso)?, &mut payload)?;
+
+    let issuer_auth = CoseSign1Builder::new()
+        .protected(
+            HeaderBuilder::new()
+                .algorithm(iana::Algorithm::ES256)
+                .build(),
+        )
+        .payload(payload)
+        .create_signature(&[], |tbs| {
+            let signature: Signature = signing_key.sign(tbs);
+            signature.to_vec()
+        })
+        .build();
+
+    let issuer_signed = Value::Map(vec![
+        (
+            text("nameSpaces"),
+            Value::Map(vec![(text(NAMESPACE), Value::Array(presented))]),
+        ),
+        (text("issuerAuth"), issuer_auth.to_cbor_value()?),
+    ]);
+    let mut encoded = Vec::new();
+    coset::cbor::into_writer(&issuer_signed, &mut encoded)?;
+    Ok(encoded)
+}
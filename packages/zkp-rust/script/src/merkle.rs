@@ -0,0 +1,110 @@
+//! Merkle root (v4) credential data for sample credentials
+//!
+//! Mirrors the program's `merkle` verifier: the issuer signs
+//! `version = 4 || depth || root` over SHA-256 leaves
+//! `0x00 || claim_type || value` and nodes `0x01 || left || right`, padded
+//! with zero leaves to a power of two.
+
+use credential_verifier_lib::ClaimProof;
+use sha2::{Digest, Sha256};
+
+/// Hashes a claim into a leaf
+fn leaf_hash(claim_type: u16, value: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(claim_type.to_be_bytes());
+    hasher.update(value);
+    hasher.finalize().into()
+}
+
+/// Hashes two children into their parent
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Builds a tree over `leaves`, padded with zero leaves to a power of two
+///
+/// `levels[0]` holds the leaves and the last level the root.
+pub fn levels(leaves: Vec<[u8; 32]>) -> Vec<Vec<[u8; 32]>> {
+    let width = leaves.len().next_power_of_two();
+    let mut levels = vec![leaves
+        .into_iter()
+        .chain(std::iter::repeat([0u8; 32]))
+        .take(width)
+        .collect::<Vec<_>>()];
+    while levels.last().map_or(0, Vec::len) > 1 {
+        let level = levels.last().expect("non-empty tree");
+        let parents = level
+            .chunks(2)
+            .map(|pair| node_hash(&pair[0], &pair[1]))
+            .collect();
+        levels.push(parents);
+    }
+    levels
+}
+
+/// Returns the sibling hashes from leaf `index` up to the root
+pub fn path(levels: &[Vec<[u8; 32]>], index: usize) -> Vec<[u8; 32]> {
+    levels[..levels.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(level, nodes)| nodes[(index >> level) ^ 1])
+        .collect()
+}
+
+/// Builds the tree over `claims` and returns the v4 credential data and
+/// inclusion proofs for the claims at `revealed` positions
+pub fn encode(claims: &[(u16, Vec<u8>)], revealed: &[usize]) -> (Vec<u8>, Vec<ClaimProof>) {
+    let levels = levels(
+        claims
+            .iter()
+            .map(|(claim_type, value)| leaf_hash(*claim_type, value))
+            .collect(),
+    );
+    let depth = levels.len() as u32 - 1;
+    let root = levels.last().expect("non-empty tree")[0];
+
+    let proofs = revealed
+        .iter()
+        .map(|&index| ClaimProof {
+            claim_type: claims[index].0,
+            value: claims[index].1.clone(),
+            index: index as u32,
+            siblings: path(&levels, index),
+        })
+        .collect();
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&4u32.to_be_bytes());
+    data.extend_from_slice(&depth.to_be_bytes());
+    data.extend_from_slice(&root);
+    (data, proofs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proofs_open_root() {
+        let claims: Vec<(u16, Vec<u8>)> = (1..=5).map(|claim_type| (claim_type, vec![1])).collect();
+        let (data, proofs) = encode(&claims, &[0, 2, 4]);
+        assert_eq!(u32::from_be_bytes(data[4..8].try_into().unwrap()), 3);
+
+        for proof in &proofs {
+            let mut node = leaf_hash(proof.claim_type, &proof.value);
+            for (level, sibling) in proof.siblings.iter().enumerate() {
+                node = if proof.index >> level & 1 == 0 {
+                    node_hash(&node, sibling)
+                } else {
+                    node_hash(sibling, &node)
+                };
+            }
+            assert_eq!(node, data[8..]);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Prometheus metrics of the proving services
+//!
+//! `GET /metrics` on the REST API's port serves, in Prometheus' text format:
+//!
+//! - `credence_jobs{status}`: jobs by status, including those persisted
+//!   before a restart
+//! - `credence_queue_depth`: jobs waiting for a prover
+//! - `credence_job_failures_total{reason}`: jobs failed since the start,
+//!   `rejected` by the program or failed while `proving`
+//! - `credence_proving_seconds` and `credence_job_cycles`: summaries of the
+//!   time proofs took and the cycles jobs ran for, with quantiles over the
+//!   last [`WINDOW`] jobs
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write;
+use std::sync::Mutex;
+
+use crate::jobs::{JobStatus, JobStore};
+
+/// Observations the quantiles of a summary are computed over
+pub const WINDOW: usize = 1000;
+
+/// Quantiles summaries report
+const QUANTILES: [f64; 4] = [0.5, 0.9, 0.95, 0.99];
+
+/// Why a job failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FailureReason {
+    /// The program rejected the credential while executing
+    Rejected,
+    /// Proving, or saving the proof, failed
+    Proving,
+}
+
+impl FailureReason {
+    fn label(self) -> &'static str {
+        match self {
+            FailureReason::Rejected => "rejected",
+            FailureReason::Proving => "proving",
+        }
+    }
+}
+
+/// Recent observations of a value, with totals over all of them
+#[derive(Debug, Default)]
+struct Summary {
+    recent: VecDeque<f64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Summary {
+    fn observe(&mut self, value: f64) {
+        if self.recent.len() == WINDOW {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(value);
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} summary");
+        let mut sorted: Vec<f64> = self.recent.iter().copied().collect();
+        sorted.sort_by(f64::total_cmp);
+        for quantile in QUANTILES {
+            let value = match sorted.len() {
+                0 => f64::NAN,
+                len => sorted[(quantile * (len - 1) as f64).round() as usize],
+            };
+            let _ = writeln!(out, "{name}{{quantile=\"{quantile}\"}} {value}");
+        }
+        let _ = writeln!(out, "{name}_sum {}", self.sum);
+        let _ = writeln!(out, "{name}_count {}", self.count);
+    }
+}
+
+#[derive(Debug, Default)]
+struct Observed {
+    failures: BTreeMap<FailureReason, u64>,
+    proving_seconds: Summary,
+    cycles: Summary,
+}
+
+/// Metrics the services observe as jobs run
+#[derive(Debug, Default)]
+pub struct Metrics {
+    observed: Mutex<Observed>,
+}
+
+impl Metrics {
+    fn observed(&self) -> std::sync::MutexGuard<'_, Observed> {
+        self.observed.lock().expect("Metrics aren't poisoned")
+    }
+
+    pub fn observe_failure(&self, reason: FailureReason) {
+        *self.observed().failures.entry(reason).or_default() += 1;
+    }
+
+    pub fn observe_proof(&self, seconds: f64) {
+        self.observed().proving_seconds.observe(seconds);
+    }
+
+    pub fn observe_cycles(&self, cycles: u64) {
+        self.observed().cycles.observe(cycles as f64);
+    }
+
+    /// The metrics in Prometheus' text format, with the jobs of `jobs`
+    pub fn render(&self, jobs: &JobStore) -> String {
+        let counts = jobs.counts();
+        let mut out = String::new();
+        out.push_str("# HELP credence_jobs Jobs by status\n# TYPE credence_jobs gauge\n");
+        for (status, count) in &counts {
+            let _ = writeln!(out, "credence_jobs{{status=\"{}\"}} {count}", status.name());
+        }
+        let queued = counts
+            .iter()
+            .find(|(status, _)| *status == JobStatus::Queued)
+            .map_or(0, |(_, count)| *count);
+        out.push_str("# HELP credence_queue_depth Jobs waiting for a prover\n");
+        out.push_str("# TYPE credence_queue_depth gauge\n");
+        let _ = writeln!(out, "credence_queue_depth {queued}");
+
+        let observed = self.observed();
+        out.push_str("# HELP credence_job_failures_total Jobs failed, by reason\n");
+        out.push_str("# TYPE credence_job_failures_total counter\n");
+        for reason in [FailureReason::Rejected, FailureReason::Proving] {
+            let count = observed.failures.get(&reason).copied().unwrap_or(0);
+            let _ = writeln!(
+                out,
+                "credence_job_failures_total{{reason=\"{}\"}} {count}",
+                reason.label()
+            );
+        }
+        observed.proving_seconds.render(
+            &mut out,
+            "credence_proving_seconds",
+            "Seconds proofs took to generate",
+        );
+        observed
+            .cycles
+            .render(&mut out, "credence_job_cycles", "Cycles jobs ran for");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renders_summaries() {
+        let mut summary = Summary::default();
+        for value in 1..=WINDOW + 100 {
+            summary.observe(value as f64);
+        }
+        let mut out = String::new();
+        summary.render(&mut out, "credence_test", "Test values");
+        assert!(out.contains("# TYPE credence_test summary\n"));
+        // Quantiles only cover the window, totals every observation
+        assert!(out.contains("credence_test{quantile=\"0.5\"} 601\n"));
+        assert!(out.contains("credence_test{quantile=\"0.99\"} 1090\n"));
+        assert!(out.contains("credence_test_count 1100\n"));
+        assert!(out.contains("credence_test_sum 605550\n"));
+    }
+}
@@ -0,0 +1,461 @@
+//! Decoders for each version of the program's public values
+//!
+//! Every ABI layout starts with its version word, so the decoder is picked
+//! from that before anything else is read. Version 4 is the current
+//! layout and decodes straight into the library's structs; the structs of
+//! versions 1 to 3, from before outputs committed a chain ID, a verifying
+//! contract and the time they were checked as of, live here and their
+//! decoders convert them. Solana and Move values are borsh- and
+//! BCS-encoded and lead with the version as a little-endian `u32` instead;
+//! only the current version of those is decoded.
+
+use alloy_sol_types::SolValue;
+use anyhow::Result;
+use borsh::BorshDeserialize;
+use credential_verifier_lib::{
+    BatchOutputs, CredentialOutput, HolderOutput, IssuerOutput, NativeOutputs, PolicyOutputs,
+    ProgramInput, StatusOutput,
+};
+
+/// Layouts of earlier versions
+mod legacy {
+    alloy_sol_types::sol! {
+        /// Version 1 output, without `chainId`
+        #[derive(Default)]
+        struct CredentialOutputV1 {
+            uint32 version;
+            address subject;
+            uint32 credentialType;
+            bytes32 credentialHash;
+            uint64 issuedAt;
+            uint64 expiresAt;
+            uint8 hashSuite;
+            uint8 timestampGranularity;
+            uint64 expiryEpoch;
+            bytes32 credentialHashBlind;
+            bytes32 disclosureHash;
+            bytes32 schemaHash;
+            bytes32 predicatesHash;
+            uint32 predicateResults;
+            bytes32 withheldClaimsRoot;
+            IssuerOutput issuer;
+            HolderOutput holder;
+            StatusOutput status;
+            bytes32[] revealedClaims;
+        }
+
+        /// Version 2 output, without `verifyingContract`
+        #[derive(Default)]
+        struct CredentialOutputV2 {
+            uint32 version;
+            address subject;
+            uint32 credentialType;
+            bytes32 credentialHash;
+            uint64 issuedAt;
+            uint64 expiresAt;
+            uint64 chainId;
+            uint8 hashSuite;
+            uint8 timestampGranularity;
+            uint64 expiryEpoch;
+            bytes32 credentialHashBlind;
+            bytes32 disclosureHash;
+            bytes32 schemaHash;
+            bytes32 predicatesHash;
+            uint32 predicateResults;
+            bytes32 withheldClaimsRoot;
+            IssuerOutput issuer;
+            HolderOutput holder;
+            StatusOutput status;
+            bytes32[] revealedClaims;
+        }
+
+        /// Version 3 output, without `asOf`
+        #[derive(Default)]
+        struct CredentialOutputV3 {
+            uint32 version;
+            address subject;
+            uint32 credentialType;
+            bytes32 credentialHash;
+            uint64 issuedAt;
+            uint64 expiresAt;
+            uint64 chainId;
+            address verifyingContract;
+            uint8 hashSuite;
+            uint8 timestampGranularity;
+            uint64 expiryEpoch;
+            bytes32 credentialHashBlind;
+            bytes32 disclosureHash;
+            bytes32 schemaHash;
+            bytes32 predicatesHash;
+            uint32 predicateResults;
+            bytes32 withheldClaimsRoot;
+            IssuerOutput issuer;
+            HolderOutput holder;
+            StatusOutput status;
+            bytes32[] revealedClaims;
+        }
+
+        #[derive(Default)]
+        struct IssuerOutput {
+            address issuerAddress;
+            bytes32 quorumHash;
+            bytes32 hmacKeyCommitment;
+            bytes32 rootCaHash;
+            bytes32 vrfOutputHash;
+            bytes32 rootIssuerHash;
+            uint32 keyRegistryVersion;
+            bytes32 trustedIssuersRoot;
+            bytes32 issuerRegistryRoot;
+        }
+
+        #[derive(Default)]
+        struct HolderOutput {
+            bytes32 nullifierScope;
+            bytes32 nullifier;
+            bytes32 verifierNonce;
+            bytes32 subjectCommitment;
+            bytes32 pseudonym;
+            bytes32 ringRoot;
+            bytes32 ringEvent;
+            bytes32 ringTag;
+            bool holderBound;
+            bytes32 deviceRpIdHash;
+            bool biometricBound;
+        }
+
+        #[derive(Default)]
+        struct StatusOutput {
+            bytes32 sanctionsRoot;
+            uint32 sanctionsListVersion;
+            bytes32 serialHash;
+            uint8 revocationMethod;
+            bytes32 revocationRoot;
+            uint32 revocationVersion;
+            uint64 revocationMaxAge;
+            uint8 statusPurpose;
+        }
+
+        struct BatchOutputsV1 {
+            uint32 version;
+            CredentialOutputV1[] outputs;
+        }
+
+        struct PolicyOutputsV1 {
+            uint32 version;
+            bytes32 policyHash;
+            bool policySatisfied;
+            CredentialOutputV1[] outputs;
+        }
+
+        struct BatchOutputsV2 {
+            uint32 version;
+            CredentialOutputV2[] outputs;
+        }
+
+        struct PolicyOutputsV2 {
+            uint32 version;
+            bytes32 policyHash;
+            bool policySatisfied;
+            CredentialOutputV2[] outputs;
+        }
+
+        struct BatchOutputsV3 {
+            uint32 version;
+            CredentialOutputV3[] outputs;
+        }
+
+        struct PolicyOutputsV3 {
+            uint32 version;
+            bytes32 policyHash;
+            bool policySatisfied;
+            CredentialOutputV3[] outputs;
+        }
+    }
+}
+
+/// Outputs of a proof, whichever layout they were committed in
+#[derive(Debug)]
+pub struct DecodedOutputs {
+    /// One output per credential, in input order
+    pub outputs: Vec<CredentialOutput>,
+    /// Whether the credentials satisfied the policy (absent without one)
+    pub policy_satisfied: Option<bool>,
+}
+
+/// Reads the layout version from the first word of `values`
+pub fn version(values: &[u8]) -> Result<u32> {
+    let word = values
+        .get(..32)
+        .ok_or_else(|| anyhow::anyhow!("Public values too short"))?;
+    anyhow::ensure!(
+        word[..28].iter().all(|&byte| byte == 0),
+        "Malformed version word"
+    );
+    Ok(u32::from_be_bytes(word[28..].try_into().expect("4 bytes")))
+}
+
+/// Decodes the public values the program committed for `input`
+pub fn decode(input: &ProgramInput, values: &[u8]) -> Result<DecodedOutputs> {
+    if let ProgramInput::Solana(_) | ProgramInput::Move(_) = input {
+        return decode_native(input, values);
+    }
+    match version(values)? {
+        1 => decode_v1(input, values),
+        2 => decode_v2(input, values),
+        3 => decode_v3(input, values),
+        4 => decode_v4(input, values),
+        version => anyhow::bail!("Unsupported public values version {}", version),
+    }
+}
+
+/// Decodes borsh-encoded Solana or BCS-encoded Move values
+fn decode_native(input: &ProgramInput, values: &[u8]) -> Result<DecodedOutputs> {
+    let version = values
+        .get(..4)
+        .ok_or_else(|| anyhow::anyhow!("Public values too short"))?;
+    let native = match u32::from_le_bytes(version.try_into().expect("4 bytes")) {
+        4 if matches!(input, ProgramInput::Move(_)) => crate::bcs::decode(values)?,
+        4 => NativeOutputs::try_from_slice(values)?,
+        version => anyhow::bail!("Unsupported public values version {}", version),
+    };
+    Ok(DecodedOutputs {
+        outputs: native.outputs.iter().map(Into::into).collect(),
+        policy_satisfied: native.policy.map(|(_, satisfied)| satisfied),
+    })
+}
+
+/// Decodes version 1 values, as unbound to any chain
+fn decode_v1(input: &ProgramInput, values: &[u8]) -> Result<DecodedOutputs> {
+    let (outputs, policy_satisfied) = match input {
+        ProgramInput::Single(_) => {
+            let output = legacy::CredentialOutputV1::abi_decode_params(values, true)?;
+            (vec![output], None)
+        }
+        ProgramInput::Batch(_) => {
+            let batch = legacy::BatchOutputsV1::abi_decode_params(values, true)?;
+            (batch.outputs, None)
+        }
+        ProgramInput::Policy(_) => {
+            let policy = legacy::PolicyOutputsV1::abi_decode_params(values, true)?;
+            (policy.outputs, Some(policy.policySatisfied))
+        }
+        _ => return decode_v4(input, values),
+    };
+    let outputs = outputs
+        .into_iter()
+        .map(upgrade_v1)
+        .map(upgrade_v2)
+        .map(upgrade_v3);
+    Ok(DecodedOutputs {
+        outputs: outputs.collect::<Result<_>>()?,
+        policy_satisfied,
+    })
+}
+
+/// Decodes version 2 values, as unbound to any verifying contract
+fn decode_v2(input: &ProgramInput, values: &[u8]) -> Result<DecodedOutputs> {
+    let (outputs, policy_satisfied) = match input {
+        ProgramInput::Single(_) => {
+            let output = legacy::CredentialOutputV2::abi_decode_params(values, true)?;
+            (vec![output], None)
+        }
+        ProgramInput::Batch(_) => {
+            let batch = legacy::BatchOutputsV2::abi_decode_params(values, true)?;
+            (batch.outputs, None)
+        }
+        ProgramInput::Policy(_) => {
+            let policy = legacy::PolicyOutputsV2::abi_decode_params(values, true)?;
+            (policy.outputs, Some(policy.policySatisfied))
+        }
+        _ => return decode_v4(input, values),
+    };
+    let outputs = outputs.into_iter().map(upgrade_v2).map(upgrade_v3);
+    Ok(DecodedOutputs {
+        outputs: outputs.collect::<Result<_>>()?,
+        policy_satisfied,
+    })
+}
+
+/// Decodes version 3 values, as checked at an unknown time
+fn decode_v3(input: &ProgramInput, values: &[u8]) -> Result<DecodedOutputs> {
+    let (outputs, policy_satisfied) = match input {
+        ProgramInput::Single(_) => {
+            let output = legacy::CredentialOutputV3::abi_decode_params(values, true)?;
+            (vec![output], None)
+        }
+        ProgramInput::Batch(_) => {
+            let batch = legacy::BatchOutputsV3::abi_decode_params(values, true)?;
+            (batch.outputs, None)
+        }
+        ProgramInput::Policy(_) => {
+            let policy = legacy::PolicyOutputsV3::abi_decode_params(values, true)?;
+            (policy.outputs, Some(policy.policySatisfied))
+        }
+        _ => return decode_v4(input, values),
+    };
+    Ok(DecodedOutputs {
+        outputs: outputs.into_iter().map(upgrade_v3).collect::<Result<_>>()?,
+        policy_satisfied,
+    })
+}
+
+/// Converts a version 1 output to version 2, unbound to any chain
+fn upgrade_v1(output: legacy::CredentialOutputV1) -> legacy::CredentialOutputV2 {
+    legacy::CredentialOutputV2 {
+        version: output.version,
+        subject: output.subject,
+        credentialType: output.credentialType,
+        credentialHash: output.credentialHash,
+        issuedAt: output.issuedAt,
+        expiresAt: output.expiresAt,
+        chainId: 0,
+        hashSuite: output.hashSuite,
+        timestampGranularity: output.timestampGranularity,
+        expiryEpoch: output.expiryEpoch,
+        credentialHashBlind: output.credentialHashBlind,
+        disclosureHash: output.disclosureHash,
+        schemaHash: output.schemaHash,
+        predicatesHash: output.predicatesHash,
+        predicateResults: output.predicateResults,
+        withheldClaimsRoot: output.withheldClaimsRoot,
+        issuer: output.issuer,
+        holder: output.holder,
+        status: output.status,
+        revealedClaims: output.revealedClaims,
+    }
+}
+
+/// Converts a version 2 output to version 3, unbound to any verifying
+/// contract
+fn upgrade_v2(output: legacy::CredentialOutputV2) -> legacy::CredentialOutputV3 {
+    legacy::CredentialOutputV3 {
+        version: output.version,
+        subject: output.subject,
+        credentialType: output.credentialType,
+        credentialHash: output.credentialHash,
+        issuedAt: output.issuedAt,
+        expiresAt: output.expiresAt,
+        chainId: output.chainId,
+        verifyingContract: Default::default(),
+        hashSuite: output.hashSuite,
+        timestampGranularity: output.timestampGranularity,
+        expiryEpoch: output.expiryEpoch,
+        credentialHashBlind: output.credentialHashBlind,
+        disclosureHash: output.disclosureHash,
+        schemaHash: output.schemaHash,
+        predicatesHash: output.predicatesHash,
+        predicateResults: output.predicateResults,
+        withheldClaimsRoot: output.withheldClaimsRoot,
+        issuer: output.issuer,
+        holder: output.holder,
+        status: output.status,
+        revealedClaims: output.revealedClaims,
+    }
+}
+
+/// Converts a version 3 output, whose grouped structs are unchanged, with
+/// a zero `asOf`
+fn upgrade_v3(output: legacy::CredentialOutputV3) -> Result<CredentialOutput> {
+    Ok(CredentialOutput {
+        version: output.version,
+        subject: output.subject,
+        credentialType: output.credentialType,
+        credentialHash: output.credentialHash,
+        issuedAt: output.issuedAt,
+        expiresAt: output.expiresAt,
+        chainId: output.chainId,
+        verifyingContract: output.verifyingContract,
+        asOf: 0,
+        hashSuite: output.hashSuite,
+        timestampGranularity: output.timestampGranularity,
+        expiryEpoch: output.expiryEpoch,
+        credentialHashBlind: output.credentialHashBlind,
+        disclosureHash: output.disclosureHash,
+        schemaHash: output.schemaHash,
+        predicatesHash: output.predicatesHash,
+        predicateResults: output.predicateResults,
+        withheldClaimsRoot: output.withheldClaimsRoot,
+        issuer: IssuerOutput::abi_decode(&output.issuer.abi_encode(), true)?,
+        holder: HolderOutput::abi_decode(&output.holder.abi_encode(), true)?,
+        status: StatusOutput::abi_decode(&output.status.abi_encode(), true)?,
+        revealedClaims: output.revealedClaims,
+    })
+}
+
+/// Decodes version 4 values
+fn decode_v4(input: &ProgramInput, values: &[u8]) -> Result<DecodedOutputs> {
+    let (outputs, policy_satisfied) = match input {
+        ProgramInput::Single(_) => {
+            let output = CredentialOutput::abi_decode_params(values, true)?;
+            (vec![output], None)
+        }
+        ProgramInput::Batch(_) => {
+            let batch = BatchOutputs::abi_decode_params(values, true)?;
+            (batch.outputs, None)
+        }
+        ProgramInput::Policy(_) => {
+            let policy = PolicyOutputs::abi_decode_params(values, true)?;
+            (policy.outputs, Some(policy.policySatisfied))
+        }
+        ProgramInput::Digest(_) => anyhow::bail!("Digest proofs commit a hash, not outputs"),
+        ProgramInput::Solana(_) | ProgramInput::Move(_) => {
+            anyhow::bail!("Solana and Move proofs don't commit ABI")
+        }
+    };
+    Ok(DecodedOutputs {
+        outputs,
+        policy_satisfied,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use credential_verifier_lib::OUTPUT_VERSION;
+
+    #[test]
+    fn test_decodes_by_version() {
+        let batch = BatchOutputs {
+            version: OUTPUT_VERSION,
+            outputs: vec![CredentialOutput {
+                version: OUTPUT_VERSION,
+                credentialType: 1,
+                ..Default::default()
+            }],
+        };
+        let mut values = batch.abi_encode_params();
+        let decoded = decode(&ProgramInput::Batch(Vec::new()), &values).unwrap();
+        assert_eq!(decoded.outputs, batch.outputs);
+
+        values[31] = 0xff;
+        assert!(decode(&ProgramInput::Batch(Vec::new()), &values).is_err());
+    }
+
+    #[test]
+    fn test_decodes_v1_as_unbound() {
+        let output = legacy::CredentialOutputV1 {
+            version: 1,
+            credentialType: 2,
+            expiresAt: 9,
+            holder: legacy::HolderOutput {
+                holderBound: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let batch = legacy::BatchOutputsV1 {
+            version: 1,
+            outputs: vec![output],
+        };
+        let values = batch.abi_encode_params();
+        let decoded = decode(&ProgramInput::Batch(Vec::new()), &values).unwrap();
+        let output = &decoded.outputs[0];
+        assert_eq!(
+            (output.version, output.expiresAt, output.chainId),
+            (1, 9, 0)
+        );
+        assert_eq!(output.verifyingContract, [0; 20]);
+        assert_eq!(output.asOf, 0);
+        assert!(output.holder.holderBound);
+    }
+}
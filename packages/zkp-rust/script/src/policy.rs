@@ -0,0 +1,160 @@
+//! Parser for verifier policy expressions
+//!
+//! Policies combine credential types with `&` (all) and `|` (any), with
+//! `&` binding tighter and parentheses for grouping, e.g. `kyc & (aml | 3)`.
+//! Types are numbers or the names of the sample credential types, which
+//! the `--credential-type` flag also accepts.
+
+use anyhow::{bail, Context, Result};
+
+use crate::{Policy, CREDENTIAL_TYPES, CREDENTIAL_TYPE_BITMASK};
+
+/// Parses a policy expression
+pub fn parse(expression: &str) -> Result<Policy> {
+    let tokens = tokenize(expression)?;
+    let mut position = 0;
+    let policy = parse_any(&tokens, &mut position)?;
+    if position != tokens.len() {
+        bail!("Unexpected `{}` in policy", tokens[position]);
+    }
+    Ok(policy)
+}
+
+/// Splits an expression into operators, parentheses and operands
+fn tokenize(expression: &str) -> Result<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if "&|()".contains(c) {
+            tokens.push(c.to_string());
+            chars.next();
+        } else if c.is_ascii_alphanumeric() {
+            let mut operand = String::new();
+            while let Some(&c) = chars.peek().filter(|c| c.is_ascii_alphanumeric()) {
+                operand.push(c);
+                chars.next();
+            }
+            tokens.push(operand);
+        } else {
+            bail!("Unexpected `{c}` in policy");
+        }
+    }
+    Ok(tokens)
+}
+
+/// Parses `term ('|' term)*`
+fn parse_any(tokens: &[String], position: &mut usize) -> Result<Policy> {
+    let mut terms = vec![parse_all(tokens, position)?];
+    while tokens.get(*position).is_some_and(|token| token == "|") {
+        *position += 1;
+        terms.push(parse_all(tokens, position)?);
+    }
+    Ok(if terms.len() == 1 {
+        terms.remove(0)
+    } else {
+        Policy::Any(terms)
+    })
+}
+
+/// Parses `factor ('&' factor)*`
+fn parse_all(tokens: &[String], position: &mut usize) -> Result<Policy> {
+    let mut factors = vec![parse_factor(tokens, position)?];
+    while tokens.get(*position).is_some_and(|token| token == "&") {
+        *position += 1;
+        factors.push(parse_factor(tokens, position)?);
+    }
+    Ok(if factors.len() == 1 {
+        factors.remove(0)
+    } else {
+        Policy::All(factors)
+    })
+}
+
+/// Parses a credential type or a parenthesized expression
+fn parse_factor(tokens: &[String], position: &mut usize) -> Result<Policy> {
+    let token = tokens.get(*position).context("Policy ends unexpectedly")?;
+    *position += 1;
+
+    if token == "(" {
+        let policy = parse_any(tokens, position)?;
+        if tokens.get(*position).map(String::as_str) != Some(")") {
+            bail!("Unclosed `(` in policy");
+        }
+        *position += 1;
+        return Ok(policy);
+    }
+
+    Ok(Policy::Credential(base_credential_type(token)?))
+}
+
+/// Looks up a credential type by name or number
+fn base_credential_type(token: &str) -> Result<u32> {
+    match CREDENTIAL_TYPES.iter().find(|(name, _)| *name == token) {
+        Some((_, credential_type)) => Ok(*credential_type),
+        None => token
+            .parse()
+            .with_context(|| format!("Unknown credential type `{token}`")),
+    }
+}
+
+/// Parses a credential type such as `accredited`, `2` or `kyc+aml`
+///
+/// Types joined with `+` become a bitmask type attesting all of them.
+pub fn parse_credential_type(value: &str) -> Result<u32> {
+    if !value.contains('+') {
+        return base_credential_type(value.trim());
+    }
+
+    let mut credential_type = CREDENTIAL_TYPE_BITMASK;
+    for token in value.split('+') {
+        let base = base_credential_type(token.trim())?;
+        if !(1..=31).contains(&base) {
+            bail!("Credential type {base} can't be combined into a bitmask");
+        }
+        credential_type |= 1 << (base - 1);
+    }
+    Ok(credential_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_precedence() {
+        assert_eq!(
+            parse("kyc & aml | 3").unwrap(),
+            Policy::Any(vec![
+                Policy::All(vec![Policy::Credential(1), Policy::Credential(5)]),
+                Policy::Credential(3),
+            ])
+        );
+        assert_eq!(
+            parse("kyc & (aml | 3)").unwrap(),
+            Policy::All(vec![
+                Policy::Credential(1),
+                Policy::Any(vec![Policy::Credential(5), Policy::Credential(3)]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_bitmask_credential_type() {
+        assert_eq!(parse_credential_type("accredited").unwrap(), 2);
+        assert_eq!(
+            parse_credential_type("kyc+aml").unwrap(),
+            CREDENTIAL_TYPE_BITMASK | 0b10001
+        );
+        assert!(parse_credential_type("kyc+0").is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed() {
+        assert!(parse("kyc &").is_err());
+        assert!(parse("(kyc | aml").is_err());
+        assert!(parse("kyc aml").is_err());
+        assert!(parse("passport").is_err());
+    }
+}
@@ -0,0 +1,147 @@
+//! Prover backends
+//!
+//! Proofs are generated on this machine, mocked, or on the Succinct prover
+//! network. The network is picked with `--prover network` or
+//! `SP1_PROVER=network` and authenticates with the requester key in
+//! `SP1_PRIVATE_KEY` (and `PROVER_NETWORK_RPC` for another RPC than the
+//! default). The SDK submits the request and polls it until a prover
+//! fulfills it; if the network fails or doesn't fulfill it within the
+//! timeout, the proof is generated locally instead.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use sp1_sdk::{ProverClient, SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin};
+use tracing::{info, info_span, warn};
+
+use crate::ProofMode;
+
+/// Where proofs are generated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProverBackend {
+    /// Prove on this machine
+    Local,
+    /// Skip proving and return a mock proof, for testing the pipeline
+    Mock,
+    /// Prove on the Succinct prover network (needs `SP1_PRIVATE_KEY`)
+    Network,
+}
+
+impl ProverBackend {
+    /// Backend named by `SP1_PROVER`, local if unset
+    pub fn from_env() -> Result<Self> {
+        match std::env::var("SP1_PROVER") {
+            Ok(name) => ProverBackend::from_str(&name, true)
+                .map_err(|_| anyhow::anyhow!("Unknown SP1_PROVER {}", name)),
+            Err(_) => Ok(ProverBackend::Local),
+        }
+    }
+}
+
+/// Prover of the chosen backend, with a local prover to fall back on
+pub struct Prover {
+    backend: ProverBackend,
+    client: ProverClient,
+    /// How long to wait for the network to fulfill a request
+    timeout: Option<Duration>,
+    /// Whether to prove locally when the network fails
+    fallback: bool,
+}
+
+impl Prover {
+    /// Initializes the backend, or the one `SP1_PROVER` names
+    pub fn new(
+        backend: Option<ProverBackend>,
+        timeout: Option<Duration>,
+        fallback: bool,
+    ) -> Result<Self> {
+        let backend = match backend {
+            Some(backend) => backend,
+            None => ProverBackend::from_env()?,
+        };
+        let client = match backend {
+            ProverBackend::Local => ProverClient::local(),
+            ProverBackend::Mock => ProverClient::mock(),
+            ProverBackend::Network => {
+                std::env::var("SP1_PRIVATE_KEY")
+                    .context("The prover network needs a requester key in SP1_PRIVATE_KEY")?;
+                ProverClient::network()
+            }
+        };
+        Ok(Prover {
+            backend,
+            client,
+            timeout,
+            fallback,
+        })
+    }
+
+    pub fn backend(&self) -> ProverBackend {
+        self.backend
+    }
+
+    /// Client for setting up programs and verifying proofs
+    pub fn client(&self) -> &ProverClient {
+        &self.client
+    }
+
+    /// Generates a proof of `stdin` of the given kind, proving locally if
+    /// the network fails
+    pub fn prove(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+        mode: ProofMode,
+    ) -> Result<SP1ProofWithPublicValues> {
+        let _span = info_span!("prove", backend = ?self.backend, mode = ?mode).entered();
+        if self.backend != ProverBackend::Network {
+            return generate(&self.client, pk, stdin, mode, None);
+        }
+        info!("Requesting the proof from the prover network");
+        match generate(&self.client, pk, stdin.clone(), mode, self.timeout) {
+            Ok(proof) => Ok(proof),
+            Err(error) if self.fallback => {
+                warn!(
+                    error = format!("{:#}", error),
+                    "Prover network failed, proving locally instead"
+                );
+                generate(&ProverClient::local(), pk, stdin, mode, None)
+            }
+            Err(error) => Err(error.context("Prover network failed")),
+        }
+    }
+}
+
+/// Generates a proof of `stdin` of the given kind with `client`
+fn generate(
+    client: &ProverClient,
+    pk: &SP1ProvingKey,
+    stdin: SP1Stdin,
+    mode: ProofMode,
+    timeout: Option<Duration>,
+) -> Result<SP1ProofWithPublicValues> {
+    let mut request = client.prove(pk, stdin);
+    if let Some(timeout) = timeout {
+        request = request.timeout(timeout);
+    }
+    let proof = match mode {
+        ProofMode::Plonk => {
+            info!("Generating a PLONK proof for on-chain verification");
+            request.plonk().run()?
+        }
+        ProofMode::Groth16 => {
+            info!("Generating a Groth16 proof for on-chain verification");
+            request.groth16().run()?
+        }
+        ProofMode::Compressed => {
+            info!("Generating a compressed proof");
+            request.compressed().run()?
+        }
+        ProofMode::Core => {
+            info!("Generating a core proof");
+            request.core().run()?
+        }
+    };
+    Ok(proof)
+}
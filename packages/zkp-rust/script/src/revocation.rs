@@ -0,0 +1,46 @@
+//! Sample issuer revocation lists
+//!
+//! Mirrors the program's `revocation` module: leaves
+//! `SHA-256(0x00 || credential_hash)` over the revoked SHA-256 credential
+//! hashes in ascending order between the `0x00..00` and `0xff..ff`
+//! sentinels, in a tree hashed like v4 claim trees.
+
+use anyhow::Result;
+use credential_verifier_lib::RevocationExclusion;
+use sha2::{Digest, Sha256};
+
+use crate::merkle;
+
+/// Version of the sample list
+const SAMPLE_LIST_VERSION: u32 = 1;
+
+/// Hashes a revoked credential hash into a leaf
+fn revoked_leaf(credential_hash: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(credential_hash);
+    hasher.finalize().into()
+}
+
+/// Builds a sample list of three revoked credentials and returns its root
+/// and the neighbours bracketing `credential_hash`
+///
+/// Fails if the credential is revoked or is a sentinel.
+pub fn sample_exclusion(credential_hash: &[u8; 32]) -> Result<([u8; 32], RevocationExclusion)> {
+    let list: Vec<[u8; 32]> = vec![[0x00; 32], [0x2a; 32], [0x95; 32], [0xd4; 32], [0xff; 32]];
+    let index = list
+        .windows(2)
+        .position(|pair| pair[0] < *credential_hash && *credential_hash < pair[1])
+        .ok_or_else(|| anyhow::anyhow!("Credential is on the sample revocation list"))?;
+
+    let levels = merkle::levels(list.iter().map(revoked_leaf).collect());
+    let exclusion = RevocationExclusion {
+        list_version: SAMPLE_LIST_VERSION,
+        index: index as u32,
+        lower: list[index],
+        lower_siblings: merkle::path(&levels, index),
+        upper: list[index + 1],
+        upper_siblings: merkle::path(&levels, index + 1),
+    };
+    Ok((levels.last().expect("non-empty tree")[0], exclusion))
+}
@@ -0,0 +1,39 @@
+//! Sample rings of eligible holders for linkable ring proofs
+//!
+//! Mirrors the program's `ring` module: leaves `SHA-256(0x00 || address)`
+//! in a tree hashed like v4 claim trees, with the member proving control
+//! of their address by its secp256k1 key.
+
+use anyhow::Result;
+use credential_verifier_lib::RingMembership;
+use sha2::{Digest, Sha256};
+
+use crate::merkle;
+
+/// Hashes an eligible address into a ring leaf
+fn address_leaf(address: &[u8; 20]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(address);
+    hasher.finalize().into()
+}
+
+/// Places the sample subject among three other eligible holders and
+/// returns the ring's root and the subject's proof for `event`
+pub fn sample_membership(event: [u8; 32]) -> Result<([u8; 32], RingMembership)> {
+    let mut leaves: Vec<[u8; 32]> = [[0xb1; 20], [0xb2; 20], [0xb3; 20]]
+        .iter()
+        .map(address_leaf)
+        .collect();
+    let index = 1;
+    leaves.insert(index, address_leaf(&crate::sample_subject_address()?));
+
+    let levels = merkle::levels(leaves);
+    let membership = RingMembership {
+        subject_key: crate::SAMPLE_SUBJECT_KEY,
+        event,
+        index: index as u32,
+        siblings: merkle::path(&levels, index),
+    };
+    Ok((levels.last().expect("non-empty tree")[0], membership))
+}
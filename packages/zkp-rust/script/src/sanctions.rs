@@ -0,0 +1,46 @@
+//! Sample sanctions lists for screening proofs
+//!
+//! Mirrors the program's `sanctions` module: leaves
+//! `SHA-256(0x00 || address)` over the listed addresses in ascending order
+//! between the `0x00..00` and `0xff..ff` sentinels, in a tree hashed like
+//! v4 claim trees.
+
+use anyhow::Result;
+use credential_verifier_lib::SanctionsExclusion;
+use sha2::{Digest, Sha256};
+
+use crate::merkle;
+
+/// Version of the sample list
+const SAMPLE_LIST_VERSION: u32 = 1;
+
+/// Hashes a listed address into a leaf
+fn address_leaf(address: &[u8; 20]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(address);
+    hasher.finalize().into()
+}
+
+/// Builds a sample list of three sanctioned addresses and returns its root
+/// and the neighbours bracketing `subject`
+///
+/// Fails if the subject is listed or is a sentinel.
+pub fn sample_exclusion(subject: &[u8; 20]) -> Result<([u8; 32], SanctionsExclusion)> {
+    let list: Vec<[u8; 20]> = vec![[0x00; 20], [0x3c; 20], [0x7e; 20], [0xc1; 20], [0xff; 20]];
+    let index = list
+        .windows(2)
+        .position(|pair| pair[0] < *subject && *subject < pair[1])
+        .ok_or_else(|| anyhow::anyhow!("Subject is on the sample sanctions list"))?;
+
+    let levels = merkle::levels(list.iter().map(address_leaf).collect());
+    let exclusion = SanctionsExclusion {
+        list_version: SAMPLE_LIST_VERSION,
+        index: index as u32,
+        lower: list[index],
+        lower_siblings: merkle::path(&levels, index),
+        upper: list[index + 1],
+        upper_siblings: merkle::path(&levels, index + 1),
+    };
+    Ok((levels.last().expect("non-empty tree")[0], exclusion))
+}
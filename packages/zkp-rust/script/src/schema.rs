@@ -0,0 +1,50 @@
+//! Claim schemas for sample credentials
+//!
+//! Mirrors the program's v2 claim-type registry so the sample issuer can
+//! publish a schema covering exactly the claims it issued.
+
+use crate::{ClaimSchema, FieldType, SchemaField};
+
+/// Schema name of the sample issuer
+pub const SAMPLE_SCHEMA: &str = "credence.sample";
+
+/// Field name, value type and bounds of each registered claim type
+fn field_spec(claim_type: u16) -> (&'static str, FieldType, u64, u64) {
+    match claim_type {
+        1 => ("fullName", FieldType::Utf8, 1, 256),
+        2 => ("dateOfBirth", FieldType::Bytes, 8, 8),
+        3 => ("nationality", FieldType::Utf8, 2, 2),
+        4 => ("residenceCountry", FieldType::Utf8, 2, 2),
+        5 => ("kycLevel", FieldType::Uint, 1, 3),
+        6 => ("accreditationStatus", FieldType::Uint, 0, 1),
+        7 => ("netWorth", FieldType::Uint, 0, u64::MAX),
+        8 => ("investorClass", FieldType::Uint, 1, 3),
+        9 => ("entityRegistration", FieldType::Utf8, 1, 64),
+        10 => ("amlScreening", FieldType::Uint, 0, 1),
+        _ => ("unknown", FieldType::Bytes, 0, u64::MAX),
+    }
+}
+
+/// Builds a schema requiring every claim in `claims`
+pub fn describe(claims: &[(u16, Vec<u8>)]) -> ClaimSchema {
+    let fields = claims
+        .iter()
+        .map(|(claim_type, _)| {
+            let (name, field_type, min, max) = field_spec(*claim_type);
+            SchemaField {
+                name: name.to_string(),
+                claim_type: *claim_type,
+                field_type,
+                required: true,
+                min,
+                max,
+            }
+        })
+        .collect();
+
+    ClaimSchema {
+        name: SAMPLE_SCHEMA.to_string(),
+        version: 1,
+        fields,
+    }
+}
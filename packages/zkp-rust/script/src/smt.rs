@@ -0,0 +1,151 @@
+//! Sparse Merkle tree (v5) credential data for sample credentials
+//!
+//! Mirrors the program's `smt` verifier: a 256-level SHA-256 tree with
+//! each claim at the path `SHA-256(key)`, leaves
+//! `0x00 || path || tag || value`, nodes `0x01 || left || right` and empty
+//! subtrees of 32 zero bytes.
+
+use credential_verifier_lib::SparseClaimProof;
+use sha2::{Digest, Sha256};
+
+use crate::keyed::{self, Value};
+
+/// Levels below the root
+const DEPTH: usize = 256;
+
+/// The hash of an empty leaf or subtree
+const EMPTY: [u8; 32] = [0u8; 32];
+
+/// A claim placed in the tree, as `(path, leaf hash)`
+type Leaf = ([u8; 32], [u8; 32]);
+
+/// Returns bit `level` of `path`, counted from the root
+fn bit(path: &[u8; 32], level: usize) -> u8 {
+    path[level / 8] >> (7 - level % 8) & 1
+}
+
+/// Hashes two children into their parent
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    if *left == EMPTY && *right == EMPTY {
+        return EMPTY;
+    }
+    let mut hasher = Sha256::new();
+    hasher.update([0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Hashes the subtree at `level` holding `leaves`
+fn subtree(leaves: &[&Leaf], level: usize) -> [u8; 32] {
+    match leaves {
+        [] => EMPTY,
+        [(_, leaf)] if level == DEPTH => *leaf,
+        _ => {
+            let (left, right): (Vec<&Leaf>, Vec<&Leaf>) =
+                leaves.iter().partition(|(path, _)| bit(path, level) == 0);
+            node_hash(&subtree(&left, level + 1), &subtree(&right, level + 1))
+        }
+    }
+}
+
+/// Builds the tree over `claims` and returns the v5 credential data and
+/// proofs for `proven` keys, present or absent
+pub fn encode(claims: &[(&str, Value)], proven: &[&str]) -> (Vec<u8>, Vec<SparseClaimProof>) {
+    let path = |key: &str| -> [u8; 32] { Sha256::digest(key.as_bytes()).into() };
+    let leaves: Vec<Leaf> = claims
+        .iter()
+        .map(|(key, value)| {
+            let mut hasher = Sha256::new();
+            hasher.update([0x00]);
+            hasher.update(path(key));
+            hasher.update(keyed::encode_value(value));
+            (path(key), hasher.finalize().into())
+        })
+        .collect();
+    let all: Vec<&Leaf> = leaves.iter().collect();
+
+    let proofs = proven
+        .iter()
+        .map(|key| {
+            let key_path = path(key);
+            let mut occupied = [0u8; 32];
+            let mut siblings = Vec::new();
+            for height in 0..DEPTH {
+                // The sibling subtree shares the path above `level` and
+                // branches the other way at it
+                let level = DEPTH - 1 - height;
+                let branch: Vec<&Leaf> = all
+                    .iter()
+                    .copied()
+                    .filter(|(path, _)| {
+                        (0..level).all(|above| bit(path, above) == bit(&key_path, above))
+                            && bit(path, level) != bit(&key_path, level)
+                    })
+                    .collect();
+                let sibling = subtree(&branch, level + 1);
+                if sibling != EMPTY {
+                    occupied[height / 8] |= 1 << (height % 8);
+                    siblings.push(sibling);
+                }
+            }
+            SparseClaimProof {
+                key: key.to_string(),
+                value: claims
+                    .iter()
+                    .find(|(claim_key, _)| claim_key == key)
+                    .map(|(_, value)| keyed::encode_value(value)),
+                occupied,
+                siblings,
+            }
+        })
+        .collect();
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&5u32.to_be_bytes());
+    data.extend_from_slice(&subtree(&all, 0));
+    (data, proofs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proofs_open_root() {
+        let claims = [
+            ("kycLevel", Value::U64(2)),
+            ("accredited", Value::Bool(true)),
+            ("residence", Value::String("CH")),
+        ];
+        let (data, proofs) = encode(&claims, &["accredited", "sanctioned"]);
+        assert!(proofs[0].value.is_some() && proofs[1].value.is_none());
+
+        for proof in &proofs {
+            let key_path: [u8; 32] = Sha256::digest(proof.key.as_bytes()).into();
+            let mut node = match &proof.value {
+                Some(value) => {
+                    let mut hasher = Sha256::new();
+                    hasher.update([0x00]);
+                    hasher.update(key_path);
+                    hasher.update(value);
+                    hasher.finalize().into()
+                }
+                None => EMPTY,
+            };
+            let mut siblings = proof.siblings.iter();
+            for height in 0..DEPTH {
+                let sibling = match proof.occupied[height / 8] >> (height % 8) & 1 {
+                    1 => *siblings.next().unwrap(),
+                    _ => EMPTY,
+                };
+                node = match bit(&key_path, DEPTH - 1 - height) {
+                    0 => node_hash(&node, &sibling),
+                    _ => node_hash(&sibling, &node),
+                };
+            }
+            assert!(siblings.next().is_none());
+            assert_eq!(node, data[4..]);
+        }
+    }
+}
@@ -0,0 +1,40 @@
+//! Instruction data for the SP1 Solana verifier
+//!
+//! Solana programs verify SP1 Groth16 proofs with the `sp1-solana` crate,
+//! which takes the proof and its public inputs borsh-encoded as the
+//! instruction data. Proofs for Solana commit `NativeOutputs`, so the
+//! program can deserialize the public inputs with borsh too.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Instruction data layout of the `sp1-solana` example programs
+#[derive(Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct SP1Groth16Proof {
+    /// Groth16 proof bytes, verifier selector included
+    pub proof: Vec<u8>,
+    /// Committed public values
+    pub sp1_public_inputs: Vec<u8>,
+}
+
+/// Builds the instruction data verifying `proof` over `public_values`
+pub fn instruction_data(proof: &[u8], public_values: &[u8]) -> Vec<u8> {
+    let instruction = SP1Groth16Proof {
+        proof: proof.to_vec(),
+        sp1_public_inputs: public_values.to_vec(),
+    };
+    borsh::to_vec(&instruction).expect("Vec writes are infallible")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instruction_data_round_trips() {
+        let data = instruction_data(&[0xaa; 4], &[1, 2, 3]);
+        // Each field is a little-endian u32 length and its bytes
+        assert_eq!(&data[..8], &[4, 0, 0, 0, 0xaa, 0xaa, 0xaa, 0xaa]);
+        let instruction = SP1Groth16Proof::try_from_slice(&data).unwrap();
+        assert_eq!(instruction.sp1_public_inputs, vec![1, 2, 3]);
+    }
+}
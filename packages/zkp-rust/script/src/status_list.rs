@@ -0,0 +1,130 @@
+//! Sample W3C StatusList2021 credentials
+//!
+//! Mirrors the program's `status_list` module: the issuer signs
+//! `STATUS_LIST_DOMAIN || document` with its credential key, and the
+//! credential's signing message covers a commitment to its entry,
+//! including whether it's in the revocation or the suspension list.
+
+use anyhow::Result;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use credential_verifier_lib::hash::message_digest;
+use credential_verifier_lib::{HashAlgorithm, StatusListEntry, StatusPurpose};
+use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+use serde_json::json;
+
+use crate::vc;
+
+/// Domain tag for status list signatures
+const STATUS_LIST_DOMAIN: &[u8] = b"CREDENCE_STATUS_LIST_V1";
+
+/// URL of the sample revocation list credential
+const SAMPLE_LIST_URL: &str = "https://issuer.credence.example/status/1";
+
+/// URL of the sample suspension list credential
+const SAMPLE_SUSPENSION_LIST_URL: &str = "https://issuer.credence.example/status/2";
+
+/// Version of the sample lists
+const SAMPLE_LIST_VERSION: u32 = 1;
+
+/// Indices the sample issuer has revoked
+const SAMPLE_REVOKED: &[u64] = &[7, 42];
+
+/// Indices the sample issuer has suspended
+const SAMPLE_SUSPENDED: &[u64] = &[3];
+
+/// Bitstring length, the spec's minimum of 131,072 entries
+const LIST_BYTES: usize = 16 * 1024;
+
+/// CRC-32 (IEEE) for the GZIP trailer
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// Compresses `data` into a single GZIP member without optional fields
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut member = vec![0x1f, 0x8b, 0x08, 0, 0, 0, 0, 0, 0, 0xff];
+    member.extend_from_slice(&miniz_oxide::deflate::compress_to_vec(data, 6));
+    member.extend_from_slice(&crc32(data).to_le_bytes());
+    member.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    member
+}
+
+/// The `statusPurpose` of lists for `purpose`
+pub fn purpose_name(purpose: StatusPurpose) -> &'static str {
+    match purpose {
+        StatusPurpose::None => "none",
+        StatusPurpose::Revocation => "revocation",
+        StatusPurpose::Suspension => "suspension",
+    }
+}
+
+/// Builds the sample issuer's status list for `purpose` and the entry at
+/// `index`, signed with the sample secp256k1 issuer key under `hash`
+pub fn sample_entry(
+    index: u64,
+    purpose: StatusPurpose,
+    hash: HashAlgorithm,
+) -> Result<StatusListEntry> {
+    let (list_url, set) = match purpose {
+        StatusPurpose::Suspension => (SAMPLE_SUSPENSION_LIST_URL, SAMPLE_SUSPENDED),
+        _ => (SAMPLE_LIST_URL, SAMPLE_REVOKED),
+    };
+    let mut bits = vec![0u8; LIST_BYTES];
+    for &index in set {
+        bits[(index / 8) as usize] |= 0x80 >> (index % 8);
+    }
+
+    let document = json!({
+        "@context": [
+            "https://www.w3.org/2018/credentials/v1",
+            "https://w3id.org/vc/status-list/2021/v1",
+        ],
+        "id": list_url,
+        "type": ["VerifiableCredential", "StatusList2021Credential"],
+        "issuer": vc::SAMPLE_ISSUER,
+        "issuanceDate": vc::rfc3339(1_700_000_000)?,
+        "credentialSubject": {
+            "id": format!("{list_url}#list"),
+            "type": "StatusList2021",
+            "statusPurpose": purpose_name(purpose),
+            "encodedList": URL_SAFE_NO_PAD.encode(gzip(&bits)),
+        },
+    });
+    let list_credential = serde_json::to_vec(&document)?;
+
+    let mut message = STATUS_LIST_DOMAIN.to_vec();
+    message.extend_from_slice(&list_credential);
+    let signature: Signature =
+        SigningKey::from_slice(&[0x11; 32])?.sign_prehash(&message_digest(hash, &message))?;
+
+    Ok(StatusListEntry {
+        list_url: list_url.to_string(),
+        index,
+        purpose,
+        list_version: SAMPLE_LIST_VERSION,
+        list_credential,
+        list_signature: signature.to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gzip_round_trips() {
+        let data = b"status list bits".repeat(64);
+        let member = gzip(&data);
+        let inflated = miniz_oxide::inflate::decompress_to_vec(&member[10..]).unwrap();
+        assert_eq!(inflated, data);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+}
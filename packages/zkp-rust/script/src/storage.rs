@@ -0,0 +1,475 @@
+//! Storage of the proving services' jobs
+//!
+//! Jobs and their proofs are kept behind [`Storage`], so a restart loses
+//! neither the jobs under way (they run again) nor the finished ones. By
+//! default each job is a `<id>.json` file in `--output-dir`'s `jobs/`
+//! directory, next to its proof as `<id>.proof.json`. With `--database`,
+//! they're kept in SQLite (`sqlite://<path>`) or Postgres
+//! (`postgres://...`) instead: the `jobs` table has the job's status,
+//! subject, client and times, its record as JSON, and once done its proof
+//! envelope, public values, vkey and proof mode. Databases also keep an
+//! audit trail of every status a job went through, in `job_audit`.
+//!
+//! Databases never get the credential, which carries the holder's secrets,
+//! so jobs a restart interrupts fail there rather than run again.
+
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex};
+
+use anyhow::{Context, Result};
+
+use crate::jobs::JobRecord;
+use crate::{unix_time, ProofOutput};
+
+/// Where jobs and their proofs are kept
+pub trait Storage: Send + Sync {
+    /// Every job stored
+    fn load(&self) -> Result<Vec<JobRecord>>;
+
+    /// Stores a job as it is now
+    fn save(&self, record: &JobRecord) -> Result<()>;
+
+    /// Stores a job's proof
+    fn save_proof(&self, id: &str, envelope: &ProofOutput) -> Result<()>;
+
+    /// A job's stored proof envelope, as JSON
+    fn proof(&self, id: &str) -> Result<Option<String>>;
+}
+
+/// Opens the `--database` storage, or the files in `jobs_dir` without one
+pub fn open(database: Option<&str>, jobs_dir: &Path) -> Result<Box<dyn Storage>> {
+    let Some(url) = database else {
+        return Ok(Box::new(FileStorage::open(jobs_dir)?));
+    };
+    if let Some(path) = url
+        .strip_prefix("sqlite://")
+        .or_else(|| url.strip_prefix("sqlite:"))
+    {
+        return Ok(Box::new(SqliteStorage::open(Path::new(path))?));
+    }
+    if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+        return Ok(Box::new(PostgresStorage::connect(url)?));
+    }
+    anyhow::bail!("Unsupported database {url}: expected sqlite:// or postgres://")
+}
+
+/// Jobs as JSON files in a directory
+pub struct FileStorage {
+    dir: PathBuf,
+}
+
+impl FileStorage {
+    pub fn open(dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir).with_context(|| format!("Can't create {}", dir.display()))?;
+        Ok(FileStorage {
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    /// Writes `contents` to `path` atomically
+    fn write(path: &Path, contents: String) -> Result<()> {
+        let partial = path.with_extension("json.partial");
+        std::fs::write(&partial, contents)?;
+        std::fs::rename(&partial, path).with_context(|| format!("Can't write {}", path.display()))
+    }
+
+    fn proof_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}.proof.json"))
+    }
+}
+
+impl Storage for FileStorage {
+    fn load(&self) -> Result<Vec<JobRecord>> {
+        let mut records = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default();
+            if !name.ends_with(".json") || name.ends_with(".proof.json") {
+                continue;
+            }
+            let json =
+                std::fs::read(&path).with_context(|| format!("Can't read {}", path.display()))?;
+            let record = serde_json::from_slice(&json)
+                .with_context(|| format!("{} isn't a job", path.display()))?;
+            records.push(record);
+        }
+        Ok(records)
+    }
+
+    fn save(&self, record: &JobRecord) -> Result<()> {
+        let path = self.dir.join(format!("{}.json", record.job.id));
+        Self::write(&path, serde_json::to_string_pretty(record)?)
+    }
+
+    fn save_proof(&self, id: &str, envelope: &ProofOutput) -> Result<()> {
+        Self::write(
+            &self.proof_path(id),
+            serde_json::to_string_pretty(envelope)?,
+        )
+    }
+
+    fn proof(&self, id: &str) -> Result<Option<String>> {
+        let path = self.proof_path(id);
+        match std::fs::read_to_string(&path) {
+            Ok(json) => Ok(Some(json)),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error).with_context(|| format!("Can't read {}", path.display())),
+        }
+    }
+}
+
+/// Tables of both databases
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS jobs (
+    id TEXT PRIMARY KEY,
+    status TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    client TEXT,
+    submitted_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL,
+    record TEXT NOT NULL,
+    proof TEXT,
+    public_values TEXT,
+    vkey TEXT,
+    proof_mode TEXT
+);
+CREATE TABLE IF NOT EXISTS job_audit (
+    job_id TEXT NOT NULL,
+    status TEXT NOT NULL,
+    at BIGINT NOT NULL,
+    error TEXT
+);
+CREATE INDEX IF NOT EXISTS job_audit_job_id ON job_audit (job_id);
+";
+
+/// Columns a job's row has besides its proof, in the order the statements
+/// bind them
+struct JobRow {
+    id: String,
+    status: &'static str,
+    subject: String,
+    client: Option<String>,
+    submitted_at: i64,
+    updated_at: i64,
+    record: String,
+    error: Option<String>,
+}
+
+impl JobRow {
+    fn new(record: &JobRecord) -> Result<Self> {
+        let job = &record.job;
+        let stored = JobRecord {
+            job: job.clone(),
+            credential: None,
+            proof: record.proof.clone(),
+        };
+        Ok(JobRow {
+            id: job.id.clone(),
+            status: job.status.name(),
+            subject: job.subject.clone(),
+            client: job.client.clone(),
+            submitted_at: job.submitted_at as i64,
+            updated_at: unix_time()? as i64,
+            record: serde_json::to_string(&stored)?,
+            error: job.error.clone(),
+        })
+    }
+}
+
+/// Proof columns of a done job's row
+struct ProofRow {
+    proof: String,
+    public_values: String,
+    vkey: String,
+    proof_mode: String,
+}
+
+impl ProofRow {
+    fn new(envelope: &ProofOutput) -> Result<Self> {
+        Ok(ProofRow {
+            proof: serde_json::to_string(envelope)?,
+            public_values: envelope.public_values.clone(),
+            vkey: envelope.vkey.clone(),
+            proof_mode: format!("{:?}", envelope.mode).to_lowercase(),
+        })
+    }
+}
+
+fn parse_records(rows: Vec<String>) -> Result<Vec<JobRecord>> {
+    rows.iter()
+        .map(|json| serde_json::from_str(json).context("A stored job is corrupted"))
+        .collect()
+}
+
+/// Jobs in a SQLite database
+pub struct SqliteStorage {
+    connection: Mutex<rusqlite::Connection>,
+}
+
+impl SqliteStorage {
+    pub fn open(path: &Path) -> Result<Self> {
+        let connection = rusqlite::Connection::open(path)
+            .with_context(|| format!("Can't open the database {}", path.display()))?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(SqliteStorage {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn connection(&self) -> std::sync::MutexGuard<'_, rusqlite::Connection> {
+        self.connection.lock().expect("The database isn't poisoned")
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn load(&self) -> Result<Vec<JobRecord>> {
+        let connection = self.connection();
+        let mut statement = connection.prepare("SELECT record FROM jobs ORDER BY submitted_at")?;
+        let rows = statement
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        parse_records(rows)
+    }
+
+    fn save(&self, record: &JobRecord) -> Result<()> {
+        let row = JobRow::new(record)?;
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+        transaction.execute(
+            "INSERT INTO jobs (id, status, subject, client, submitted_at, updated_at, record)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT (id) DO UPDATE SET status = excluded.status,
+                 updated_at = excluded.updated_at, record = excluded.record",
+            rusqlite::params![
+                row.id,
+                row.status,
+                row.subject,
+                row.client,
+                row.submitted_at,
+                row.updated_at,
+                row.record
+            ],
+        )?;
+        transaction.execute(
+            "INSERT INTO job_audit (job_id, status, at, error) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![row.id, row.status, row.updated_at, row.error],
+        )?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn save_proof(&self, id: &str, envelope: &ProofOutput) -> Result<()> {
+        let row = ProofRow::new(envelope)?;
+        self.connection().execute(
+            "UPDATE jobs SET proof = ?2, public_values = ?3, vkey = ?4, proof_mode = ?5
+             WHERE id = ?1",
+            rusqlite::params![id, row.proof, row.public_values, row.vkey, row.proof_mode],
+        )?;
+        Ok(())
+    }
+
+    fn proof(&self, id: &str) -> Result<Option<String>> {
+        let connection = self.connection();
+        let mut statement = connection.prepare("SELECT proof FROM jobs WHERE id = ?1")?;
+        let mut rows = statement.query([id])?;
+        match rows.next()? {
+            Some(row) => Ok(row.get(0)?),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Query run on the Postgres connection's thread
+type Query = Box<dyn FnOnce(&mut postgres::Client) + Send>;
+
+/// Jobs in a Postgres database
+///
+/// The blocking client runs a runtime of its own, which can't be started
+/// from the services' runtime, so the connection lives on its own thread.
+pub struct PostgresStorage {
+    queries: Mutex<mpsc::Sender<Query>>,
+}
+
+impl PostgresStorage {
+    pub fn connect(url: &str) -> Result<Self> {
+        let (queries, received) = mpsc::channel::<Query>();
+        let (connected, connection) = mpsc::channel();
+        let url = url.to_string();
+        std::thread::spawn(move || {
+            let client = postgres::Client::connect(&url, postgres::NoTls)
+                .context("Can't connect to the database")
+                .and_then(|mut client| {
+                    client.batch_execute(SCHEMA)?;
+                    Ok(client)
+                });
+            let mut client = match client {
+                Ok(client) => {
+                    let _ = connected.send(Ok(()));
+                    client
+                }
+                Err(error) => {
+                    let _ = connected.send(Err(error));
+                    return;
+                }
+            };
+            for query in received {
+                query(&mut client);
+            }
+        });
+        connection
+            .recv()
+            .context("The database connection closed")??;
+        Ok(PostgresStorage {
+            queries: Mutex::new(queries),
+        })
+    }
+
+    /// Runs `query` on the connection, waiting for its result
+    fn run<T: Send + 'static>(
+        &self,
+        query: impl FnOnce(&mut postgres::Client) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        let (reply, result) = mpsc::channel();
+        self.queries
+            .lock()
+            .expect("The database isn't poisoned")
+            .send(Box::new(move |client| {
+                let _ = reply.send(query(client));
+            }))
+            .map_err(|_| anyhow::anyhow!("The database connection closed"))?;
+        result.recv().context("The database connection closed")?
+    }
+}
+
+impl Storage for PostgresStorage {
+    fn load(&self) -> Result<Vec<JobRecord>> {
+        let rows = self.run(|client| {
+            let rows = client.query("SELECT record FROM jobs ORDER BY submitted_at", &[])?;
+            Ok(rows.iter().map(|row| row.get(0)).collect())
+        })?;
+        parse_records(rows)
+    }
+
+    fn save(&self, record: &JobRecord) -> Result<()> {
+        let row = JobRow::new(record)?;
+        self.run(move |client| {
+            let mut transaction = client.transaction()?;
+            transaction.execute(
+                "INSERT INTO jobs (id, status, subject, client, submitted_at, updated_at, record)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 ON CONFLICT (id) DO UPDATE SET status = excluded.status,
+                     updated_at = excluded.updated_at, record = excluded.record",
+                &[
+                    &row.id,
+                    &row.status,
+                    &row.subject,
+                    &row.client,
+                    &row.submitted_at,
+                    &row.updated_at,
+                    &row.record,
+                ],
+            )?;
+            transaction.execute(
+                "INSERT INTO job_audit (job_id, status, at, error) VALUES ($1, $2, $3, $4)",
+                &[&row.id, &row.status, &row.updated_at, &row.error],
+            )?;
+            transaction.commit()?;
+            Ok(())
+        })
+    }
+
+    fn save_proof(&self, id: &str, envelope: &ProofOutput) -> Result<()> {
+        let row = ProofRow::new(envelope)?;
+        let id = id.to_string();
+        self.run(move |client| {
+            client.execute(
+                "UPDATE jobs SET proof = $2, public_values = $3, vkey = $4, proof_mode = $5
+                 WHERE id = $1",
+                &[
+                    &id,
+                    &row.proof,
+                    &row.public_values,
+                    &row.vkey,
+                    &row.proof_mode,
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn proof(&self, id: &str) -> Result<Option<String>> {
+        let id = id.to_string();
+        self.run(move |client| {
+            let row = client.query_opt("SELECT proof FROM jobs WHERE id = $1", &[&id])?;
+            Ok(row.and_then(|row| row.get(0)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::{Job, JobStatus, JobStore};
+
+    #[test]
+    fn test_stores_jobs_in_sqlite() {
+        let credential = crate::create_sample_credential(
+            "0x1234567890123456789012345678901234567890",
+            1,
+            crate::SignatureScheme::Secp256k1,
+            crate::HashAlgorithm::Sha256,
+            crate::CredentialFormat::Binary,
+            crate::ClaimsVersion::V2,
+            crate::HolderBindings::default(),
+        )
+        .unwrap();
+        let mut record = JobRecord {
+            job: Job {
+                id: "ab".into(),
+                status: JobStatus::Queued,
+                subject: "0x12".into(),
+                submitted_at: 1,
+                seconds: None,
+                error: None,
+                cycles: None,
+                shards: None,
+                client: Some("acme".into()),
+            },
+            credential: Some(credential),
+            proof: None,
+        };
+        let storage = SqliteStorage::open(Path::new(":memory:")).unwrap();
+        storage.save(&record).unwrap();
+        record.job.status = JobStatus::Failed;
+        record.job.error = Some("Expired".into());
+        storage.save(&record).unwrap();
+
+        let records = storage.load().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].job.status, JobStatus::Failed);
+        assert_eq!(records[0].job.client.as_deref(), Some("acme"));
+        assert!(records[0].credential.is_none());
+        assert_eq!(storage.proof("ab").unwrap(), None);
+        let audited: i64 = storage
+            .connection()
+            .query_row(
+                "SELECT COUNT(*) FROM job_audit WHERE job_id = 'ab'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(audited, 2);
+        assert!(open(Some("mysql://localhost"), Path::new("jobs")).is_err());
+
+        // Without its credential, an interrupted job can't run again
+        let storage = SqliteStorage::open(Path::new(":memory:")).unwrap();
+        record.job.status = JobStatus::Proving;
+        storage.save(&record).unwrap();
+        let (store, interrupted) = JobStore::open(Box::new(storage)).unwrap();
+        assert!(interrupted.is_empty());
+        assert_eq!(store.get("ab").unwrap().status, JobStatus::Failed);
+    }
+}
@@ -0,0 +1,74 @@
+//! Trusted-issuer sets and issuer registries for sample issuers
+//!
+//! Mirrors the program's `trusted` module: leaves
+//! `SHA-256(0x00 || scheme || issuer_pubkey)` in a tree hashed like v4
+//! claim trees, for anonymous issuers and on-chain issuer registries
+//! alike. The prover can also refuse credentials from issuers outside the
+//! keys of `--trusted-issuer`, before spending a proof on them.
+
+use anyhow::{Context, Result};
+use credential_verifier_lib::{CredentialInput, IssuerMembership, SignatureScheme};
+use sha2::{Digest, Sha256};
+
+use crate::merkle;
+
+/// Hashes an issuer key into a trusted-set leaf
+fn issuer_leaf(scheme: SignatureScheme, issuer_pubkey: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00, scheme as u8]);
+    hasher.update(issuer_pubkey);
+    hasher.finalize().into()
+}
+
+/// Places the issuer among three other sample issuers and returns the
+/// trusted set's root and the issuer's membership path
+pub fn sample_membership(
+    scheme: SignatureScheme,
+    issuer_pubkey: &[u8],
+) -> ([u8; 32], IssuerMembership) {
+    let mut leaves: Vec<[u8; 32]> = [[0xa1; 33], [0xa2; 33], [0xa3; 33]]
+        .iter()
+        .map(|pubkey| issuer_leaf(scheme, pubkey))
+        .collect();
+    let index = 2;
+    leaves.insert(index, issuer_leaf(scheme, issuer_pubkey));
+
+    let levels = merkle::levels(leaves);
+    let membership = IssuerMembership {
+        index: index as u32,
+        siblings: merkle::path(&levels, index),
+    };
+    (levels.last().expect("non-empty tree")[0], membership)
+}
+
+/// Key the credential's issuer is accountable under: the root key behind a
+/// delegate or key registry, else the issuer key
+pub fn accountable_key(credential: &CredentialInput) -> &[u8] {
+    match (&credential.delegation, &credential.key_registry) {
+        (Some(chain), _) => &chain.root_pubkey,
+        (None, Some(registry)) => &registry.root_pubkey,
+        (None, None) => &credential.issuer_pubkey,
+    }
+}
+
+/// Parses the hex-encoded keys of `--trusted-issuer`
+pub fn parse_keys(keys: &[String]) -> Result<Vec<Vec<u8>>> {
+    keys.iter()
+        .map(|key| {
+            hex::decode(key.trim_start_matches("0x"))
+                .with_context(|| format!("Invalid trusted issuer key {}", key))
+        })
+        .collect()
+}
+
+/// Fails unless the credential's accountable key is one of `trusted`, as
+/// the credential encodes it (any issuer is trusted when none are listed)
+pub fn check(trusted: &[Vec<u8>], credential: &CredentialInput) -> Result<()> {
+    let key = accountable_key(credential);
+    anyhow::ensure!(
+        trusted.is_empty() || trusted.iter().any(|trusted| trusted == key),
+        "Issuer 0x{} isn't trusted",
+        hex::encode(key)
+    );
+    Ok(())
+}
@@ -0,0 +1,48 @@
+//! W3C Verifiable Credential helpers for building test credentials
+//!
+//! Produces the VC JSON document the program parses in
+//! `CredentialFormat::W3cVc` mode, with the subject as a `did:pkh` id and
+//! the validity period as RFC 3339 dates.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, SecondsFormat};
+use serde_json::json;
+
+use crate::{status_list, CredentialInput};
+
+/// Issuer id used by sample credentials
+pub const SAMPLE_ISSUER: &str = "did:web:issuer.credence.example";
+
+/// Formats Unix seconds as an RFC 3339 UTC date-time
+pub fn rfc3339(timestamp: u64) -> Result<String> {
+    let time = DateTime::from_timestamp(timestamp as i64, 0)
+        .ok_or_else(|| anyhow!("timestamp out of range: {}", timestamp))?;
+    Ok(time.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+/// Encodes the credential's subject and validity period as a VC document
+pub fn encode_credential(credential: &CredentialInput) -> Result<Vec<u8>> {
+    let mut document = json!({
+        "@context": ["https://www.w3.org/2018/credentials/v1"],
+        "type": ["VerifiableCredential", "CredenceCredential"],
+        "issuer": SAMPLE_ISSUER,
+        "issuanceDate": rfc3339(credential.issued_at)?,
+        "credentialSubject": {
+            "id": format!("did:pkh:eip155:1:0x{}", hex::encode(credential.subject)),
+            "credentialType": credential.credential_type,
+        },
+    });
+    if credential.expires_at > 0 {
+        document["expirationDate"] = rfc3339(credential.expires_at)?.into();
+    }
+    if let Some(entry) = &credential.status_list {
+        document["credentialStatus"] = json!({
+            "id": format!("{}#{}", entry.list_url, entry.index),
+            "type": "StatusList2021Entry",
+            "statusPurpose": status_list::purpose_name(entry.purpose),
+            "statusListIndex": entry.index.to_string(),
+            "statusListCredential": entry.list_url,
+        });
+    }
+    Ok(serde_json::to_vec(&document)?)
+}
@@ -0,0 +1,80 @@
+//! ECVRF proving helpers for building test credentials
+//!
+//! Mirrors the ECVRF-P256-SHA256-TAI verifier in the program. The nonce is
+//! drawn at random rather than via RFC 6979, which the verifier can't tell
+//! apart.
+
+use p256::elliptic_curve::ff::PrimeField;
+use p256::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+use p256::{AffinePoint, EncodedPoint, NonZeroScalar, ProjectivePoint, Scalar, SecretKey};
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+
+/// RFC 9381 suite string for ECVRF-P256-SHA256-TAI
+const SUITE: u8 = 0x01;
+
+/// Challenge length in bytes
+const CHALLENGE_BYTES: usize = 16;
+
+/// Encodes a point in compressed SEC1 form
+fn encode_point(point: &ProjectivePoint) -> Vec<u8> {
+    point.to_affine().to_encoded_point(true).as_bytes().to_vec()
+}
+
+/// Hashes `alpha` to a curve point by try-and-increment
+fn encode_to_curve(pubkey: &[u8], alpha: &[u8]) -> ProjectivePoint {
+    (0u8..=255)
+        .find_map(|counter| {
+            let mut hasher = Sha256::new();
+            hasher.update([SUITE, 0x01]);
+            hasher.update(pubkey);
+            hasher.update(alpha);
+            hasher.update([counter, 0x00]);
+
+            let mut candidate = [0x02u8; 33];
+            candidate[1..].copy_from_slice(&hasher.finalize());
+            let encoded = EncodedPoint::from_bytes(candidate).ok()?;
+            Option::<AffinePoint>::from(AffinePoint::from_encoded_point(&encoded))
+        })
+        .expect("try-and-increment found no point")
+        .into()
+}
+
+/// Computes the truncated challenge over five points
+fn challenge(points: [&ProjectivePoint; 5]) -> [u8; CHALLENGE_BYTES] {
+    let mut hasher = Sha256::new();
+    hasher.update([SUITE, 0x02]);
+    for point in points {
+        hasher.update(encode_point(point));
+    }
+    hasher.update([0x00]);
+
+    let mut c = [0u8; CHALLENGE_BYTES];
+    c.copy_from_slice(&hasher.finalize()[..CHALLENGE_BYTES]);
+    c
+}
+
+/// Produces a VRF proof `Gamma || c || s` for `alpha`
+pub fn prove(
+    secret_key: &SecretKey,
+    alpha: &[u8],
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Vec<u8> {
+    let x = *secret_key.to_nonzero_scalar();
+    let y = ProjectivePoint::GENERATOR * x;
+
+    let h = encode_to_curve(&encode_point(&y), alpha);
+    let gamma = h * x;
+    let k = *NonZeroScalar::random(rng);
+
+    let c_bytes = challenge([&y, &h, &gamma, &(ProjectivePoint::GENERATOR * k), &(h * k)]);
+    let mut c_repr = p256::FieldBytes::default();
+    c_repr[32 - CHALLENGE_BYTES..].copy_from_slice(&c_bytes);
+    let c = Scalar::from_repr_vartime(c_repr).expect("challenge is below the group order");
+    let s = k + c * x;
+
+    let mut proof = encode_point(&gamma);
+    proof.extend_from_slice(&c_bytes);
+    proof.extend_from_slice(&s.to_bytes());
+    proof
+}
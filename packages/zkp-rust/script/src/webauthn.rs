@@ -0,0 +1,49 @@
+//! Sample passkey assertions for device-bound credentials
+//!
+//! Mirrors the program's `webauthn` module: the passkey signs
+//! `authenticatorData || SHA-256(clientDataJSON)` with ES256, and the
+//! client data's challenge is the base64url verifier nonce.
+
+use anyhow::Result;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use credential_verifier_lib::DeviceAssertion;
+use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+use sha2::{Digest, Sha256};
+
+/// P-256 key of the sample passkey
+const SAMPLE_DEVICE_KEY: [u8; 32] = [0x33; 32];
+
+/// Relying party the sample passkey is registered with
+const SAMPLE_RP_ID: &str = "credence.example";
+
+/// Returns the sample passkey's compressed SEC1 public key
+pub fn sample_device_pubkey() -> Result<Vec<u8>> {
+    let key = SigningKey::from_slice(&SAMPLE_DEVICE_KEY)?;
+    let pubkey = key.verifying_key().to_encoded_point(true);
+    Ok(pubkey.as_bytes().to_vec())
+}
+
+/// Has the sample passkey assert the verifier's `nonce`, with the user
+/// present and verified
+pub fn sample_assertion(nonce: &[u8; 32]) -> Result<DeviceAssertion> {
+    let mut authenticator_data = Sha256::digest(SAMPLE_RP_ID.as_bytes()).to_vec();
+    authenticator_data.push(0x05); // user present and verified
+    authenticator_data.extend_from_slice(&0u32.to_be_bytes()); // signCount
+
+    let client_data_json = serde_json::to_vec(&serde_json::json!({
+        "type": "webauthn.get",
+        "challenge": URL_SAFE_NO_PAD.encode(nonce),
+        "origin": format!("https://{SAMPLE_RP_ID}"),
+    }))?;
+
+    let mut signed = authenticator_data.clone();
+    signed.extend_from_slice(&Sha256::digest(&client_data_json));
+    let signature: Signature = SigningKey::from_slice(&SAMPLE_DEVICE_KEY)?.sign(&signed);
+
+    Ok(DeviceAssertion {
+        authenticator_data,
+        client_data_json,
+        signature: signature.to_der().as_bytes().to_vec(),
+    })
+}
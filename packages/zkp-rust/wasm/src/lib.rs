@@ -0,0 +1,243 @@
+//! WebAssembly bindings of the credential byte layouts
+//!
+//! Browser wallets build credential inputs and read proof outputs through
+//! this module rather than re-implementing the layouts in TypeScript: it
+//! encodes claims as v2 `credential_data`, computes the credential hash the
+//! program commits and decodes the ABI-encoded public values of a proof.
+//! Bytes cross the boundary as `0x`-prefixed hex strings, as in credential
+//! files. Build it with `wasm-pack build --target web`.
+//!
+//! ```js
+//! const data = encodeCredentialData([{ claimType: 1, value: "0x02" }]);
+//! const hash = credentialHash("Keccak256", subject, 1, data, issuerPubkey);
+//! const { outputs } = decodePublicValues("single", publicValues);
+//! ```
+
+use alloy_sol_types::SolValue;
+use credential_verifier_lib::{
+    claims, hash, BatchOutputs, CredentialOutput, HashAlgorithm, PolicyOutputs, OUTPUT_VERSION,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use wasm_bindgen::prelude::*;
+
+/// A v2 claim as JS passes it
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Claim {
+    claim_type: u16,
+    /// Hex-encoded value
+    value: String,
+}
+
+/// Decodes a hex field, with or without its `0x` prefix
+fn bytes(field: &str, value: &str) -> Result<Vec<u8>, String> {
+    hex::decode(value.trim_start_matches("0x")).map_err(|error| format!("Invalid {field}: {error}"))
+}
+
+/// Decodes a hex field of exactly `N` bytes
+fn fixed<const N: usize>(field: &str, value: &str) -> Result<[u8; N], String> {
+    bytes(field, value)?
+        .try_into()
+        .map_err(|_| format!("{field} must be {N} bytes"))
+}
+
+/// Parses a hash suite by its name in credential files (e.g., `Keccak256`)
+fn hash_algorithm(name: &str) -> Result<HashAlgorithm, String> {
+    serde_json::from_value(Value::String(name.to_string()))
+        .map_err(|_| format!("Unknown hash algorithm {name}"))
+}
+
+/// Hex of the credential hash: `subject || credential_type (BE) ||
+/// credential_data || issuer_pubkey` under `algorithm`
+fn credential_hash(
+    algorithm: &str,
+    subject: &str,
+    credential_type: u32,
+    credential_data: &str,
+    issuer_pubkey: &str,
+) -> Result<String, String> {
+    let hash = hash::credential_hash(
+        hash_algorithm(algorithm)?,
+        &fixed::<20>("subject", subject)?,
+        credential_type,
+        &bytes("credential data", credential_data)?,
+        &bytes("issuer public key", issuer_pubkey)?,
+    );
+    Ok(format!("0x{}", hex::encode(hash)))
+}
+
+/// An output as a JS object, with the Solidity struct's field names
+fn output_json(output: &CredentialOutput) -> Value {
+    let issuer = &output.issuer;
+    let holder = &output.holder;
+    let status = &output.status;
+    json!({
+        "version": output.version,
+        "subject": output.subject.to_string(),
+        "credentialType": output.credentialType,
+        "credentialHash": output.credentialHash.to_string(),
+        "issuedAt": output.issuedAt,
+        "expiresAt": output.expiresAt,
+        "chainId": output.chainId,
+        "verifyingContract": output.verifyingContract.to_string(),
+        "asOf": output.asOf,
+        "hashSuite": output.hashSuite,
+        "timestampGranularity": output.timestampGranularity,
+        "expiryEpoch": output.expiryEpoch,
+        "credentialHashBlind": output.credentialHashBlind.to_string(),
+        "disclosureHash": output.disclosureHash.to_string(),
+        "schemaHash": output.schemaHash.to_string(),
+        "predicatesHash": output.predicatesHash.to_string(),
+        "predicateResults": output.predicateResults,
+        "withheldClaimsRoot": output.withheldClaimsRoot.to_string(),
+        "issuer": {
+            "issuerAddress": issuer.issuerAddress.to_string(),
+            "quorumHash": issuer.quorumHash.to_string(),
+            "hmacKeyCommitment": issuer.hmacKeyCommitment.to_string(),
+            "rootCaHash": issuer.rootCaHash.to_string(),
+            "vrfOutputHash": issuer.vrfOutputHash.to_string(),
+            "rootIssuerHash": issuer.rootIssuerHash.to_string(),
+            "keyRegistryVersion": issuer.keyRegistryVersion,
+            "trustedIssuersRoot": issuer.trustedIssuersRoot.to_string(),
+            "issuerRegistryRoot": issuer.issuerRegistryRoot.to_string(),
+        },
+        "holder": {
+            "nullifierScope": holder.nullifierScope.to_string(),
+            "nullifier": holder.nullifier.to_string(),
+            "verifierNonce": holder.verifierNonce.to_string(),
+            "subjectCommitment": holder.subjectCommitment.to_string(),
+            "pseudonym": holder.pseudonym.to_string(),
+            "ringRoot": holder.ringRoot.to_string(),
+            "ringEvent": holder.ringEvent.to_string(),
+            "ringTag": holder.ringTag.to_string(),
+            "holderBound": holder.holderBound,
+            "deviceRpIdHash": holder.deviceRpIdHash.to_string(),
+            "biometricBound": holder.biometricBound,
+        },
+        "status": {
+            "sanctionsRoot": status.sanctionsRoot.to_string(),
+            "sanctionsListVersion": status.sanctionsListVersion,
+            "serialHash": status.serialHash.to_string(),
+            "revocationMethod": status.revocationMethod,
+            "revocationRoot": status.revocationRoot.to_string(),
+            "revocationVersion": status.revocationVersion,
+            "revocationMaxAge": status.revocationMaxAge,
+            "statusPurpose": status.statusPurpose,
+        },
+        "revealedClaims": output
+            .revealedClaims
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Decodes public values committed in `layout` (`single`, `batch` or
+/// `policy`) at the current [`OUTPUT_VERSION`]
+fn decode_public_values(layout: &str, values: &str) -> Result<Value, String> {
+    let values = bytes("public values", values)?;
+    let version = values
+        .get(28..32)
+        .map(|word| u32::from_be_bytes(word.try_into().expect("4 bytes")))
+        .ok_or("Public values too short")?;
+    if version != OUTPUT_VERSION {
+        return Err(format!("Unsupported public values version {version}"));
+    }
+    let decoded = match layout {
+        "single" => CredentialOutput::abi_decode_params(&values, true)
+            .map(|output| json!({ "outputs": [output_json(&output)] })),
+        "batch" => BatchOutputs::abi_decode_params(&values, true).map(
+            |batch| json!({ "outputs": batch.outputs.iter().map(output_json).collect::<Vec<_>>() }),
+        ),
+        "policy" => PolicyOutputs::abi_decode_params(&values, true).map(|policy| {
+            json!({
+                "policyHash": policy.policyHash.to_string(),
+                "policySatisfied": policy.policySatisfied,
+                "outputs": policy.outputs.iter().map(output_json).collect::<Vec<_>>(),
+            })
+        }),
+        layout => return Err(format!("Unknown layout {layout}")),
+    };
+    decoded.map_err(|error| format!("Malformed public values: {error}"))
+}
+
+/// Encodes `[{ claimType, value }]` claims as hex v2 `credential_data`
+#[wasm_bindgen(js_name = encodeCredentialData)]
+pub fn encode_credential_data(claims: JsValue) -> Result<String, JsError> {
+    let claims: Vec<Claim> = serde_wasm_bindgen::from_value(claims)?;
+    let claims = claims
+        .into_iter()
+        .map(|claim| Ok((claim.claim_type, bytes("claim value", &claim.value)?)))
+        .collect::<Result<Vec<_>, String>>()
+        .map_err(|error| JsError::new(&error))?;
+    Ok(format!("0x{}", hex::encode(claims::encode(&claims))))
+}
+
+/// Hex of the credential hash the program commits (before any blind)
+#[wasm_bindgen(js_name = credentialHash)]
+pub fn js_credential_hash(
+    hash_algorithm: &str,
+    subject: &str,
+    credential_type: u32,
+    credential_data: &str,
+    issuer_pubkey: &str,
+) -> Result<String, JsError> {
+    credential_hash(
+        hash_algorithm,
+        subject,
+        credential_type,
+        credential_data,
+        issuer_pubkey,
+    )
+    .map_err(|error| JsError::new(&error))
+}
+
+/// Decodes hex public values as `{ policyHash?, policySatisfied?, outputs }`
+#[wasm_bindgen(js_name = decodePublicValues)]
+pub fn js_decode_public_values(layout: &str, values: &str) -> Result<JsValue, JsError> {
+    let decoded = decode_public_values(layout, values).map_err(|error| JsError::new(&error))?;
+    Ok(decoded.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encodes_and_decodes_layouts() {
+        let data = claims::encode(&[(1, vec![2]), (3, b"US".to_vec())]);
+        assert_eq!(
+            hex::encode(&data),
+            "0000000200000002000100000001020003000000025553"
+        );
+
+        let subject = "0x1234567890123456789012345678901234567890";
+        let hash = credential_hash("Keccak256", subject, 1, &hex::encode(&data), "0x02ab").unwrap();
+        let mut preimage = hex::decode(&subject[2..]).unwrap();
+        preimage.extend_from_slice(&1u32.to_be_bytes());
+        preimage.extend_from_slice(&data);
+        preimage.extend_from_slice(&[0x02, 0xab]);
+        assert_eq!(
+            hash,
+            alloy_sol_types::private::keccak256(&preimage).to_string()
+        );
+        assert!(credential_hash("Md5", subject, 1, "", "").is_err());
+
+        let output = CredentialOutput {
+            version: OUTPUT_VERSION,
+            credentialType: 1,
+            chainId: 5003,
+            ..Default::default()
+        };
+        let values = hex::encode(output.abi_encode_params());
+        let decoded = decode_public_values("single", &values).unwrap();
+        assert_eq!(decoded["outputs"][0]["credentialType"], 1);
+        assert_eq!(decoded["outputs"][0]["chainId"], 5003);
+        assert_eq!(
+            decoded["outputs"][0]["holder"]["holderBound"],
+            Value::Bool(false)
+        );
+        assert!(decode_public_values("solana", &values).is_err());
+    }
+}